@@ -0,0 +1,1171 @@
+// 命令行版本，供 CI 和脚本使用：直接同步调用 release-assistant-core，不依赖 Tauri 和桌面界面
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::Read;
+use std::path::Path;
+use std::str::FromStr;
+
+use release_assistant_core::advisory::{self, VulnSeverity};
+use release_assistant_core::apk::{self, PackageInfo};
+use release_assistant_core::channel::{self, ChannelFormat};
+use release_assistant_core::device_compat::{self, CheckStatus};
+use release_assistant_core::file_info;
+use release_assistant_core::generators::{self, PasswordOptions, UuidVersion};
+use release_assistant_core::icons::{self, IconOptions};
+use release_assistant_core::image::{self, OutputFormat, ResizeMode, DEFAULT_JPEG_QUALITY};
+use release_assistant_core::manifest_audit::{self, Severity};
+use release_assistant_core::manifest_editor::{self, ManifestEdits};
+use release_assistant_core::play_policy::{self, ReleaseType, RuleResult, RuleStatus};
+use release_assistant_core::retrace::{self, Mapping};
+use release_assistant_core::signing::{self, SignOptions};
+use release_assistant_core::{
+    build_apks, channel_batch, library, network_security, permissions, sbom, secret_scan, store_compliance,
+    symbolicate, zip_integrity, zipalign, AnalysisError,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// 执行成功
+const EXIT_OK: i32 = 0;
+/// 检查未通过（签名错误、政策不通过、发现高危漏洞等）
+const EXIT_CHECK_FAILED: i32 = 1;
+/// 参数错误
+const EXIT_USAGE: i32 = 2;
+/// 执行失败（文件不存在、解析失败等）
+const EXIT_ERROR: i32 = 3;
+
+const USAGE: &str = "\
+release-assistant-cli <命令> [参数] [--json]
+
+文件与安装包:
+  info <文件>                             文件大小与 MD5 / SHA1 / SHA256
+  parse <安装包>                          解析 APK / XAPK / IPA / HAP 包信息
+  verify-sig <apk>                        校验 v1/v2/v3/v4 签名
+
+图片与生成器:
+  icons <图片> --out-dir <目录> [--sizes 48,72,96,144,192,512] [--radius 0] [--padding 0] [--format png|jpg|webp]
+  resize <图片> --width <宽> --height <高> --output <文件> [--mode fit|fill|stretch] [--quality 90]
+  uuid [--count 1] [--version v4|v7] [--uppercase] [--no-hyphens]
+  password [--length 16] [--count 1] [--no-uppercase] [--no-lowercase] [--no-numbers] [--no-symbols] [--exclude-ambiguous]
+
+检查:
+  audit <apk>                             Manifest 安全审计
+  permissions <apk>                       权限分类
+  network <apk>                           网络安全配置与深度链接
+  secrets <apk>                           硬编码密钥扫描
+  channel <apk>                           读取渠道号
+  zip-integrity <apk>                     ZIP 结构完整性检查
+  zipalign <apk> [--page-size 16384]      检查条目对齐
+  play-policy <apk> [--rules <文件>] [--release new|update]
+                                          Google Play 政策检查
+  store-compliance <apk> [--profile <文件>] [--report <md 文件>]
+                                          国内应用市场合规检查
+  sbom <apk> [--format cyclonedx|spdx] [--output <文件>]
+  vuln-import --db <数据库> <来源>...     导入 OSV 漏洞数据
+  vuln-scan <apk> --db <数据库> [--fail-on low|medium|high|critical]
+  device-compat <apk|apks|aab> --device <device-spec.json>
+                                          设备兼容性检查
+  library <aar|jar>                       检查 SDK 库文件
+
+打包与修改:
+  channel-write <apk> --channel <渠道> --output <文件> [--format walle|vasdolly|comment] [--extras k=v,k2=v2]
+  channel-batch <apk> --channels <列表文件> --out-dir <目录> [--name-template <模板>] [--format walle|vasdolly|comment]
+  manifest-edit <apk> --edits <JSON 文件> --output <文件> [签名选项]
+  align <apk> --output <文件> [--page-size 16384]
+                                          重新对齐（会移除 v2 及以上签名）
+  build-apks <aab> --out-dir <目录> [--mode device|universal] [--device <device-spec.json>] [签名选项]
+
+堆栈还原:
+  retrace <堆栈文件|-> --mapping <mapping.txt> [--apk <apk>]
+  symbolicate <backtrace 文件|-> --symbols <目录> [--apk <apk>]
+
+签名选项: --keystore <文件> --ks-pass <密码|env:变量名> [--key-alias <别名>] [--key-pass <密码|env:变量名>] [--apksigner <路径>]
+所有命令均支持 --json 输出 JSON 结果。
+退出码: 0 成功, 1 检查未通过, 2 参数错误, 3 执行失败";
+
+#[derive(Debug)]
+enum CliError {
+    Usage(String),
+    Failed(String),
+}
+
+impl From<String> for CliError {
+    fn from(message: String) -> Self {
+        CliError::Failed(message)
+    }
+}
+
+impl From<AnalysisError> for CliError {
+    fn from(error: AnalysisError) -> Self {
+        CliError::Failed(error.to_string())
+    }
+}
+
+type CliResult = Result<i32, CliError>;
+
+/// 解析后的命令行参数
+struct Args {
+    positional: Vec<String>,
+    options: HashMap<String, String>,
+    flags: HashSet<String>,
+}
+
+impl Args {
+    /// options 为需要取值的选项，flags 为开关选项（--json 始终可用）
+    fn parse(tokens: &[String], options: &[&str], flags: &[&str]) -> Result<Self, CliError> {
+        let mut args = Args {
+            positional: Vec::new(),
+            options: HashMap::new(),
+            flags: HashSet::new(),
+        };
+        let mut iter = tokens.iter();
+        while let Some(token) = iter.next() {
+            let Some(name) = token.strip_prefix("--") else {
+                args.positional.push(token.clone());
+                continue;
+            };
+            let (name, inline_value) = match name.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (name, None),
+            };
+            if name == "json" || flags.contains(&name) {
+                args.flags.insert(name.to_string());
+            } else if options.contains(&name) {
+                let value = match inline_value {
+                    Some(value) => value,
+                    None => iter
+                        .next()
+                        .cloned()
+                        .ok_or_else(|| CliError::Usage(format!("选项 --{} 缺少参数值", name)))?,
+                };
+                args.options.insert(name.to_string(), value);
+            } else {
+                return Err(CliError::Usage(format!("未知选项: --{}", name)));
+            }
+        }
+        Ok(args)
+    }
+
+    fn json(&self) -> bool {
+        self.flags.contains("json")
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.flags.contains(name)
+    }
+
+    fn value(&self, name: &str) -> Option<&str> {
+        self.options.get(name).map(String::as_str)
+    }
+
+    fn required(&self, name: &str) -> Result<&str, CliError> {
+        self.value(name)
+            .ok_or_else(|| CliError::Usage(format!("缺少选项 --{}", name)))
+    }
+
+    fn parsed<T: FromStr>(&self, name: &str, default: T) -> Result<T, CliError> {
+        match self.value(name) {
+            Some(value) => value
+                .parse()
+                .map_err(|_| CliError::Usage(format!("选项 --{} 的值无效: {}", name, value))),
+            None => Ok(default),
+        }
+    }
+
+    /// 取唯一的位置参数（输入文件）
+    fn input(&self) -> Result<String, CliError> {
+        match self.positional.as_slice() {
+            [input] => Ok(input.clone()),
+            [] => Err(CliError::Usage("缺少输入文件".to_string())),
+            _ => Err(CliError::Usage(format!("多余的参数: {}", self.positional[1..].join(" ")))),
+        }
+    }
+
+    fn no_input(&self) -> Result<(), CliError> {
+        match self.positional.first() {
+            Some(extra) => Err(CliError::Usage(format!("多余的参数: {}", extra))),
+            None => Ok(()),
+        }
+    }
+}
+
+fn main() {
+    let args = std::env::args().skip(1).collect();
+    std::process::exit(run(args))
+}
+
+/// 运行命令行，参数不含程序名，返回进程退出码
+fn run(argv: Vec<String>) -> i32 {
+    let Some((command, rest)) = argv.split_first() else {
+        eprintln!("{}", USAGE);
+        return EXIT_USAGE;
+    };
+
+    let result = match command.as_str() {
+        "help" | "-h" | "--help" => {
+            println!("{}", USAGE);
+            Ok(EXIT_OK)
+        }
+        "info" => cmd_info(rest),
+        "parse" => cmd_parse(rest),
+        "verify-sig" => cmd_verify_sig(rest),
+        "icons" => cmd_icons(rest),
+        "resize" => cmd_resize(rest),
+        "uuid" => cmd_uuid(rest),
+        "password" => cmd_password(rest),
+        "audit" => cmd_audit(rest),
+        "permissions" => cmd_permissions(rest),
+        "network" => cmd_network(rest),
+        "secrets" => cmd_secrets(rest),
+        "channel" => cmd_channel(rest),
+        "zip-integrity" => cmd_zip_integrity(rest),
+        "zipalign" => cmd_zipalign(rest),
+        "play-policy" => cmd_play_policy(rest),
+        "store-compliance" => cmd_store_compliance(rest),
+        "sbom" => cmd_sbom(rest),
+        "vuln-import" => cmd_vuln_import(rest),
+        "vuln-scan" => cmd_vuln_scan(rest),
+        "device-compat" => cmd_device_compat(rest),
+        "library" => cmd_library(rest),
+        "channel-write" => cmd_channel_write(rest),
+        "channel-batch" => cmd_channel_batch(rest),
+        "manifest-edit" => cmd_manifest_edit(rest),
+        "align" => cmd_align(rest),
+        "build-apks" => cmd_build_apks(rest),
+        "retrace" => cmd_retrace(rest),
+        "symbolicate" => cmd_symbolicate(rest),
+        other => Err(CliError::Usage(format!("未知命令: {}", other))),
+    };
+
+    match result {
+        Ok(code) => code,
+        Err(CliError::Usage(message)) => {
+            eprintln!("错误: {}", message);
+            eprintln!("运行 release-assistant-cli help 查看用法");
+            EXIT_USAGE
+        }
+        Err(CliError::Failed(message)) => {
+            eprintln!("错误: {}", message);
+            EXIT_ERROR
+        }
+    }
+}
+
+// ==================== 输出辅助 ====================
+
+/// --json 时输出 JSON，否则调用 human 输出可读文本
+fn emit<T: Serialize>(args: &Args, value: &T, human: impl FnOnce(&T)) -> Result<(), CliError> {
+    if args.json() {
+        let json = serde_json::to_string_pretty(value).map_err(|e| format!("JSON 序列化失败: {}", e))?;
+        println!("{}", json);
+    } else {
+        human(value);
+    }
+    Ok(())
+}
+
+/// 枚举的序列化名称（与前端看到的一致）
+fn label<T: Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(s)) => s,
+        _ => String::new(),
+    }
+}
+
+fn print_warnings(warnings: &[String]) {
+    for warning in warnings {
+        println!("⚠️  {}", warning);
+    }
+}
+
+fn print_rule_results(results: &[RuleResult]) {
+    for result in results {
+        let mark = match result.status {
+            RuleStatus::Pass => "✅",
+            RuleStatus::Fail => "❌",
+            RuleStatus::Review => "⚠️ ",
+            RuleStatus::NotApplicable => "➖",
+        };
+        println!("{} [{}] {}: {}", mark, result.id, result.title, result.detail);
+        for item in &result.items {
+            println!("     - {}", item);
+        }
+    }
+}
+
+/// 命令行只接受明确的图片格式，不像桌面端那样回退到 PNG
+fn output_format(name: &str) -> Result<OutputFormat, CliError> {
+    match name.to_ascii_lowercase().as_str() {
+        "png" | "jpg" | "jpeg" | "webp" => Ok(OutputFormat::from_name(name)),
+        other => Err(CliError::Usage(format!("不支持的图片格式: {}", other))),
+    }
+}
+
+fn read_image(path: &str) -> Result<Vec<u8>, CliError> {
+    Ok(std::fs::read(path).map_err(|e| format!("无法读取图片 {}: {}", path, e))?)
+}
+
+fn write_image(path: &Path, data: &[u8]) -> Result<(), CliError> {
+    Ok(std::fs::write(path, data).map_err(|e| format!("写入 {} 失败: {}", path.display(), e))?)
+}
+
+/// 读取文本输入，"-" 表示标准输入
+fn read_text(path: &str) -> Result<String, CliError> {
+    if path == "-" {
+        let mut text = String::new();
+        std::io::stdin()
+            .read_to_string(&mut text)
+            .map_err(|e| format!("读取标准输入失败: {}", e))?;
+        return Ok(text);
+    }
+    Ok(std::fs::read_to_string(path).map_err(|e| format!("无法读取 {}: {}", path, e))?)
+}
+
+/// 按序列化名称解析枚举选项，与前端传入的值一致
+fn parse_enum<T: DeserializeOwned>(args: &Args, name: &str) -> Result<Option<T>, CliError> {
+    args.value(name)
+        .map(|value| {
+            serde_json::from_value(serde_json::Value::String(value.to_string()))
+                .map_err(|_| CliError::Usage(format!("选项 --{} 的值无效: {}", name, value)))
+        })
+        .transpose()
+}
+
+/// 密码支持 env:变量名，避免在 CI 日志和进程列表中出现明文
+fn password(args: &Args, name: &str) -> Result<Option<String>, CliError> {
+    match args.value(name) {
+        Some(value) => match value.strip_prefix("env:") {
+            Some(var) => std::env::var(var)
+                .map(Some)
+                .map_err(|_| CliError::Usage(format!("环境变量 {} 未设置", var))),
+            None => Ok(Some(value.to_string())),
+        },
+        None => Ok(None),
+    }
+}
+
+/// 签名相关选项名，需要签名的命令合并到自己的选项列表中
+const SIGN_OPTIONS: &[&str] = &["keystore", "ks-pass", "key-alias", "key-pass", "apksigner"];
+
+fn sign_options(args: &Args) -> Result<Option<SignOptions>, CliError> {
+    let Some(keystore) = args.value("keystore") else {
+        return Ok(None);
+    };
+    let keystore_password = password(args, "ks-pass")?.ok_or_else(|| CliError::Usage("缺少选项 --ks-pass".to_string()))?;
+    Ok(Some(SignOptions {
+        keystore_path: keystore.to_string(),
+        keystore_password,
+        key_alias: args.value("key-alias").map(String::from),
+        key_password: password(args, "key-pass")?,
+        apksigner_path: args.value("apksigner").map(String::from),
+    }))
+}
+
+fn parse_package(path: &str) -> Result<PackageInfo, CliError> {
+    Ok(apk::parse_package(Path::new(path)).map_err(|e| e.to_string())?)
+}
+
+/// 政策、合规和 SBOM 只支持 Android APK
+fn parse_android_package(path: &str) -> Result<PackageInfo, CliError> {
+    let info = parse_package(path)?;
+    if info.platform != "android" {
+        return Err(CliError::Failed("仅支持 Android APK".to_string()));
+    }
+    Ok(info)
+}
+
+// ==================== 文件与安装包 ====================
+
+fn cmd_info(tokens: &[String]) -> CliResult {
+    let args = Args::parse(tokens, &[], &[])?;
+    let info = file_info::file_info(Path::new(&args.input()?))?;
+    emit(&args, &info, |info| {
+        println!("文件名:   {}", info.file_name);
+        println!("路径:     {}", info.file_path);
+        println!("大小:     {} ({} 字节)", info.file_size_readable, info.file_size);
+        println!("MD5:      {}", info.md5);
+        println!("SHA1:     {}", info.sha1);
+        println!("SHA256:   {}", info.sha256);
+    })?;
+    Ok(EXIT_OK)
+}
+
+fn cmd_parse(tokens: &[String]) -> CliResult {
+    let args = Args::parse(tokens, &[], &[])?;
+    let info = parse_package(&args.input()?)?;
+    emit(&args, &info, |info| {
+        println!("平台:       {}", info.platform);
+        println!("包名:       {}", info.package_name);
+        println!("版本:       {} ({})", info.version_name, info.version_code);
+        println!("最低版本:   {}", info.min_sdk_version);
+        println!("目标版本:   {}", info.target_sdk_version);
+        println!("编译版本:   {}", info.compile_sdk_version);
+        println!("文件大小:   {}", info.file_size_readable);
+        println!(
+            "组件:       {} Activity, {} Service, {} Receiver, {} Provider",
+            info.activities.len(),
+            info.services.len(),
+            info.receivers.len(),
+            info.providers.len()
+        );
+        if !info.frameworks.is_empty() {
+            let names: Vec<&str> = info.frameworks.iter().map(|f| f.name.as_str()).collect();
+            println!("框架:       {}", names.join(", "));
+        }
+        println!("权限 ({}):", info.permissions.len());
+        for permission in &info.permissions {
+            println!("  {}", permission);
+        }
+    })?;
+    Ok(EXIT_OK)
+}
+
+fn cmd_verify_sig(tokens: &[String]) -> CliResult {
+    let args = Args::parse(tokens, &[], &[])?;
+    let result = signing::verify_apk(Path::new(&args.input()?)).map_err(|e| e.to_string())?;
+    emit(&args, &result, |result| {
+        println!("{} ({})", result.file_name, result.file_size);
+        let signatures: BTreeMap<_, _> = result.signatures.iter().collect();
+        for (scheme, signature) in signatures {
+            let mark = if signature.present { "✅" } else { "➖" };
+            match &signature.details {
+                Some(details) => println!("{} {}: {}", mark, scheme, details),
+                None => println!("{} {}", mark, scheme),
+            }
+            if let Some(cert) = &signature.certificate {
+                println!("     主题: {}", cert.subject);
+                println!("     有效期: {} ~ {}", cert.valid_from, cert.valid_to);
+            }
+        }
+        print_warnings(&result.warnings);
+        for error in &result.errors {
+            println!("❌ {}", error);
+        }
+    })?;
+    Ok(if result.errors.is_empty() { EXIT_OK } else { EXIT_CHECK_FAILED })
+}
+
+// ==================== 图片与生成器 ====================
+
+fn cmd_icons(tokens: &[String]) -> CliResult {
+    let args = Args::parse(tokens, &["out-dir", "sizes", "radius", "padding", "format"], &[])?;
+    let source = read_image(&args.input()?)?;
+    let out_dir = Path::new(args.required("out-dir")?);
+    let sizes = match args.value("sizes") {
+        Some(list) => list
+            .split(',')
+            .map(|s| s.trim().parse::<u32>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| CliError::Usage(format!("选项 --sizes 的值无效: {}", list)))?,
+        None => vec![48, 72, 96, 144, 192, 512],
+    };
+    let options = IconOptions {
+        sizes,
+        radius_percent: args.parsed("radius", 0u32)?,
+        padding_percent: args.parsed("padding", 0u32)?,
+        format: output_format(args.value("format").unwrap_or("png"))?,
+    };
+
+    let icons = icons::generate_icons(&source, &options).map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(out_dir).map_err(|e| format!("无法创建输出目录: {}", e))?;
+
+    #[derive(Serialize)]
+    struct IconFile {
+        size: u32,
+        path: String,
+    }
+    let mut files = Vec::new();
+    for icon in &icons {
+        let path = out_dir.join(format!("icon_{}.{}", icon.size, options.format.extension()));
+        write_image(&path, &icon.data)?;
+        files.push(IconFile { size: icon.size, path: path.display().to_string() });
+    }
+    emit(&args, &files, |files| {
+        for file in files {
+            println!("{:>4}px  {}", file.size, file.path);
+        }
+    })?;
+    Ok(EXIT_OK)
+}
+
+fn cmd_resize(tokens: &[String]) -> CliResult {
+    let args = Args::parse(tokens, &["width", "height", "output", "mode", "quality"], &[])?;
+    let source = read_image(&args.input()?)?;
+    let width = args.parsed("width", 0u32)?;
+    let height = args.parsed("height", 0u32)?;
+    if width == 0 || height == 0 {
+        return Err(CliError::Usage("需要指定 --width 和 --height".to_string()));
+    }
+    let output = Path::new(args.required("output")?);
+    let mode = args.value("mode").unwrap_or("fit");
+    let resize_mode: ResizeMode = mode.parse().map_err(|_| CliError::Usage(format!("不支持的模式: {}", mode)))?;
+    let quality = args.parsed("quality", DEFAULT_JPEG_QUALITY)?;
+    // 输出格式取自输出文件扩展名
+    let format = output_format(output.extension().and_then(|e| e.to_str()).unwrap_or("png"))?;
+
+    let data = image::resize(&source, width, height, resize_mode, format, quality).map_err(|e| e.to_string())?;
+    write_image(output, &data)?;
+
+    #[derive(Serialize)]
+    struct ResizeOutput {
+        path: String,
+        width: u32,
+        height: u32,
+        mode: String,
+    }
+    let result = ResizeOutput {
+        path: output.display().to_string(),
+        width,
+        height,
+        mode: mode.to_string(),
+    };
+    emit(&args, &result, |result| {
+        println!("{}x{} ({})  {}", result.width, result.height, result.mode, result.path);
+    })?;
+    Ok(EXIT_OK)
+}
+
+fn cmd_uuid(tokens: &[String]) -> CliResult {
+    let args = Args::parse(tokens, &["count", "version"], &["uppercase", "no-hyphens"])?;
+    args.no_input()?;
+    let version: UuidVersion = args.parsed("version", UuidVersion::V4)?;
+    let uuids = generators::generate_uuids(
+        args.parsed("count", 1u32)?,
+        version,
+        args.flag("uppercase"),
+        !args.flag("no-hyphens"),
+    );
+    emit(&args, &uuids, |uuids| uuids.iter().for_each(|uuid| println!("{}", uuid)))?;
+    Ok(EXIT_OK)
+}
+
+fn cmd_password(tokens: &[String]) -> CliResult {
+    let args = Args::parse(
+        tokens,
+        &["length", "count"],
+        &["no-uppercase", "no-lowercase", "no-numbers", "no-symbols", "exclude-ambiguous"],
+    )?;
+    args.no_input()?;
+    let options = PasswordOptions {
+        length: args.parsed("length", 16u32)?,
+        uppercase: !args.flag("no-uppercase"),
+        lowercase: !args.flag("no-lowercase"),
+        numbers: !args.flag("no-numbers"),
+        symbols: !args.flag("no-symbols"),
+        exclude_ambiguous: args.flag("exclude-ambiguous"),
+    };
+    let passwords = generators::generate_passwords(&options, args.parsed("count", 1u32)?)
+        .map_err(|e| CliError::Usage(e.to_string()))?;
+    emit(&args, &passwords, |passwords| passwords.iter().for_each(|p| println!("{}", p)))?;
+    Ok(EXIT_OK)
+}
+
+// ==================== 检查 ====================
+
+fn cmd_audit(tokens: &[String]) -> CliResult {
+    let args = Args::parse(tokens, &[], &[])?;
+    let result = manifest_audit::audit_apk_manifest(Path::new(&args.input()?))?;
+    emit(&args, &result, |result| {
+        println!("{}  发现 {} 项", result.package_name, result.findings.len());
+        for finding in &result.findings {
+            println!("[{}] {} {}", label(&finding.severity), finding.id, finding.title);
+            if let Some(component) = &finding.component {
+                println!("     {}", component);
+            }
+        }
+        print_warnings(&result.warnings);
+        for error in &result.errors {
+            println!("❌ {}", error);
+        }
+    })?;
+    let high = result.findings.iter().any(|f| f.severity == Severity::High);
+    Ok(if high || !result.errors.is_empty() { EXIT_CHECK_FAILED } else { EXIT_OK })
+}
+
+fn cmd_permissions(tokens: &[String]) -> CliResult {
+    let args = Args::parse(tokens, &[], &[])?;
+    let report = permissions::classify_apk_permissions(Path::new(&args.input()?))?;
+    emit(&args, &report, |report| {
+        println!("{}  申请 {} 项，敏感 {} 项", report.package_name, report.requested.len(), report.sensitive.len());
+        for permission in &report.requested {
+            let mark = if permission.sensitive { "⚠️ " } else { "  " };
+            println!("{} {:<10} {}", mark, label(&permission.protection_level), permission.name);
+        }
+        for permission in &report.declared {
+            println!("   {:<10} {} (自定义)", label(&permission.protection_level), permission.name);
+        }
+    })?;
+    Ok(EXIT_OK)
+}
+
+fn cmd_network(tokens: &[String]) -> CliResult {
+    let args = Args::parse(tokens, &[], &[])?;
+    let report = network_security::analyze_apk_network(Path::new(&args.input()?))?;
+    emit(&args, &report, |report| {
+        println!("{}", report.package_name);
+        let cleartext = match report.uses_cleartext_traffic {
+            Some(true) => "允许",
+            Some(false) => "禁止",
+            None => "未声明",
+        };
+        println!("明文流量:   {}", cleartext);
+        println!("安全配置:   {}", if report.network_security_config.is_some() { "有" } else { "无" });
+        println!("深度链接 ({}):", report.deep_links.len());
+        for link in &report.deep_links {
+            let kind = if link.is_app_link { "App Link" } else { "Deep Link" };
+            println!("  [{}] {}", kind, link.component);
+            for url in &link.urls {
+                println!("     {}", url);
+            }
+        }
+        print_warnings(&report.warnings);
+    })?;
+    Ok(EXIT_OK)
+}
+
+fn cmd_secrets(tokens: &[String]) -> CliResult {
+    let args = Args::parse(tokens, &[], &[])?;
+    let result = secret_scan::scan_apk_secrets(Path::new(&args.input()?))?;
+    emit(&args, &result, |result| {
+        println!("{}  扫描 {} 个文件", result.file_name, result.scanned_files);
+        for file in &result.files {
+            println!("{} ({})", file.file, file.source);
+            for secret in &file.matches {
+                println!("  [{}] {}: {} ×{}", label(&secret.severity), secret.kind, secret.value, secret.occurrences);
+            }
+        }
+        if !result.endpoints.is_empty() {
+            println!("URL / IP ({}):", result.endpoints.len());
+            for endpoint in &result.endpoints {
+                println!("  {}", endpoint);
+            }
+        }
+        print_warnings(&result.warnings);
+    })?;
+    let high = result
+        .files
+        .iter()
+        .flat_map(|f| &f.matches)
+        .any(|m| m.severity == Severity::High);
+    Ok(if high { EXIT_CHECK_FAILED } else { EXIT_OK })
+}
+
+fn cmd_channel(tokens: &[String]) -> CliResult {
+    let args = Args::parse(tokens, &[], &[])?;
+    let info = channel::read_apk_channel(Path::new(&args.input()?))?;
+    emit(&args, &info, |info| {
+        match (&info.channel, &info.format) {
+            (Some(channel), Some(format)) => println!("渠道:   {} ({})", channel, label(format)),
+            (Some(channel), None) => println!("渠道:   {}", channel),
+            _ => println!("渠道:   未写入"),
+        }
+        for (key, value) in &info.extras {
+            println!("  {} = {}", key, value);
+        }
+        print_warnings(&info.warnings);
+    })?;
+    Ok(EXIT_OK)
+}
+
+fn cmd_zip_integrity(tokens: &[String]) -> CliResult {
+    let args = Args::parse(tokens, &[], &[])?;
+    let report = zip_integrity::check_zip_integrity(Path::new(&args.input()?))?;
+    emit(&args, &report, |report| {
+        println!("{}  {} 个条目", report.file_name, report.entry_count);
+        for finding in &report.findings {
+            println!("[{}] {} {}", label(&finding.severity), finding.id, finding.title);
+        }
+        print_warnings(&report.warnings);
+        for error in &report.errors {
+            println!("❌ {}", error);
+        }
+        println!("{}", if report.safe { "✅ 未发现异常" } else { "❌ ZIP 结构存在风险" });
+    })?;
+    Ok(if report.safe { EXIT_OK } else { EXIT_CHECK_FAILED })
+}
+
+fn cmd_zipalign(tokens: &[String]) -> CliResult {
+    let args = Args::parse(tokens, &["page-size"], &[])?;
+    let page_size = match args.value("page-size") {
+        Some(_) => Some(args.parsed("page-size", 0u64)?),
+        None => None,
+    };
+    let report = zipalign::check_alignment(Path::new(&args.input()?), page_size)?;
+    emit(&args, &report, |report| {
+        println!(
+            "{}  {} 个条目，{} 个未压缩，.so 页大小 {} KB",
+            report.file_name,
+            report.total_entries,
+            report.stored_entries,
+            report.page_size / 1024
+        );
+        for entry in &report.misaligned {
+            println!("❌ {} (偏移 {}，需要 {} 字节对齐)", entry.name, entry.data_offset, entry.required_alignment);
+        }
+        for lib in &report.compressed_native_libs {
+            println!("⚠️  原生库被压缩: {}", lib);
+        }
+        println!("{}", if report.aligned { "✅ 已对齐" } else { "❌ 未对齐" });
+    })?;
+    Ok(if report.aligned { EXIT_OK } else { EXIT_CHECK_FAILED })
+}
+
+fn cmd_play_policy(tokens: &[String]) -> CliResult {
+    let args = Args::parse(tokens, &["rules", "release"], &[])?;
+    let release = match args.value("release").unwrap_or("update") {
+        "new" => ReleaseType::New,
+        "update" => ReleaseType::Update,
+        other => return Err(CliError::Usage(format!("选项 --release 的值无效: {}", other))),
+    };
+    let input = args.input()?;
+    let rules = play_policy::load_rules(args.value("rules").map(Path::new))?;
+    let info = parse_android_package(&input)?;
+    let report = play_policy::check_policy(&info, Path::new(&input), &rules, release)?;
+    emit(&args, &report, |report| {
+        println!("{}  规则 {} ({})", report.package_name, report.rules_version, report.rules_updated);
+        print_rule_results(&report.results);
+        print_warnings(&report.warnings);
+        println!(
+            "{} 不通过 {} 项，需声明 {} 项",
+            if report.passed { "✅" } else { "❌" },
+            report.failed,
+            report.review
+        );
+    })?;
+    Ok(if report.passed { EXIT_OK } else { EXIT_CHECK_FAILED })
+}
+
+fn cmd_store_compliance(tokens: &[String]) -> CliResult {
+    let args = Args::parse(tokens, &["profile", "report"], &[])?;
+    let input = args.input()?;
+    let profile = store_compliance::load_profile(args.value("profile").map(Path::new))?;
+    let info = parse_android_package(&input)?;
+    let report = store_compliance::check_compliance(&info, Path::new(&input), &profile)?;
+    if let Some(path) = args.value("report") {
+        std::fs::write(path, &report.markdown).map_err(|e| format!("写入报告失败: {}", e))?;
+    }
+    emit(&args, &report, |report| {
+        println!(
+            "{} {}  {} {} ({})",
+            report.package_name, report.version_name, report.profile_name, report.profile_version, report.profile_updated
+        );
+        print_rule_results(&report.results);
+        print_warnings(&report.warnings);
+        println!(
+            "{} 不通过 {} 项，需人工确认 {} 项",
+            if report.passed { "✅" } else { "❌" },
+            report.failed,
+            report.review
+        );
+    })?;
+    Ok(if report.passed { EXIT_OK } else { EXIT_CHECK_FAILED })
+}
+
+fn cmd_sbom(tokens: &[String]) -> CliResult {
+    let args = Args::parse(tokens, &["format", "output"], &[])?;
+    let input = args.input()?;
+    let format = args.value("format").unwrap_or("cyclonedx");
+    let info = parse_android_package(&input)?;
+    let result = sbom::generate_sbom(&info, Path::new(&input), format, args.value("output").map(Path::new))?;
+    emit(&args, &result, |result| match &result.output_path {
+        Some(path) => println!("{} 个组件，已写入 {}", result.components.len(), path),
+        // 未指定输出文件时直接输出 SBOM 文档，便于重定向
+        None => println!("{}", result.document),
+    })?;
+    Ok(EXIT_OK)
+}
+
+fn cmd_vuln_import(tokens: &[String]) -> CliResult {
+    let args = Args::parse(tokens, &["db"], &[])?;
+    let database = Path::new(args.required("db")?);
+    if args.positional.is_empty() {
+        return Err(CliError::Usage("缺少漏洞数据来源".to_string()));
+    }
+    let sources: Vec<&Path> = args.positional.iter().map(Path::new).collect();
+    let result = advisory::import_advisories(&sources, database)?;
+    emit(&args, &result, |result| {
+        println!(
+            "导入 {} 条，跳过 {} 条，数据库共 {} 条: {}",
+            result.imported, result.skipped, result.total, result.database_path
+        );
+        print_warnings(&result.warnings);
+    })?;
+    Ok(EXIT_OK)
+}
+
+fn cmd_vuln_scan(tokens: &[String]) -> CliResult {
+    let args = Args::parse(tokens, &["db", "fail-on"], &[])?;
+    let database = Path::new(args.required("db")?);
+    let fail_on: VulnSeverity = match args.value("fail-on").unwrap_or("high") {
+        "low" => VulnSeverity::Low,
+        "medium" => VulnSeverity::Medium,
+        "high" => VulnSeverity::High,
+        "critical" => VulnSeverity::Critical,
+        other => return Err(CliError::Usage(format!("选项 --fail-on 的值无效: {}", other))),
+    };
+    let report = advisory::scan(Path::new(&args.input()?), database)?;
+    emit(&args, &report, |report| {
+        println!(
+            "检查 {} 个组件，漏洞库 {} 条 (更新于 {})",
+            report.components_checked, report.advisory_count, report.database_updated
+        );
+        for m in &report.matches {
+            let score = m.score.map(|s| format!(" {:.1}", s)).unwrap_or_default();
+            let id = m.cves.first().unwrap_or(&m.advisory_id);
+            println!("[{}{}] {}  {}", label(&m.severity), score, m.component, id);
+            println!("     {}", m.summary);
+            if !m.fixed_versions.is_empty() {
+                println!("     修复版本: {}", m.fixed_versions.join(", "));
+            }
+        }
+        if report.matches.is_empty() {
+            println!("✅ 未发现已知漏洞");
+        }
+    })?;
+    let failed = report.matches.iter().any(|m| m.severity >= fail_on);
+    Ok(if failed { EXIT_CHECK_FAILED } else { EXIT_OK })
+}
+
+fn cmd_device_compat(tokens: &[String]) -> CliResult {
+    let args = Args::parse(tokens, &["device"], &[])?;
+    let input = args.input()?;
+    let device_spec = read_text(args.required("device")?)?;
+    let report = device_compat::check_compatibility(Path::new(&input), &device_spec)?;
+    emit(&args, &report, |report| {
+        println!("{} ({})", report.package_name, report.package_type);
+        for check in &report.checks {
+            let mark = match check.status {
+                CheckStatus::Pass => "✅",
+                CheckStatus::Fail => "❌",
+                CheckStatus::Unknown => "❔",
+            };
+            println!("{} [{}] {}: {}", mark, label(&check.impact), check.title, check.detail);
+        }
+        if !report.splits.is_empty() {
+            println!("拆分 APK ({}):", report.splits.len());
+            for split in &report.splits {
+                println!("  {}:{}  {}", split.module, split.split, split.reason);
+            }
+        }
+        print_warnings(&report.warnings);
+        println!(
+            "{} 可安装: {}，Play 可见: {}",
+            if report.installable { "✅" } else { "❌" },
+            if report.installable { "是" } else { "否" },
+            if report.play_available { "是" } else { "否" }
+        );
+    })?;
+    Ok(if report.installable { EXIT_OK } else { EXIT_CHECK_FAILED })
+}
+
+fn cmd_library(tokens: &[String]) -> CliResult {
+    let args = Args::parse(tokens, &[], &[])?;
+    let info = library::inspect_library(Path::new(&args.input()?))?;
+    emit(&args, &info, |info| {
+        println!("{} ({}, {} 字节)", info.file_name, info.kind, info.file_size);
+        if let Some(manifest) = &info.manifest {
+            println!("包名:       {}", manifest.package_name);
+            if let Some(min_sdk) = &manifest.min_sdk_version {
+                println!("最低版本:   {}", min_sdk);
+            }
+            println!("权限 ({}):", manifest.permissions.len());
+            for permission in &manifest.permissions {
+                println!("  {}", permission.name);
+            }
+        }
+        if let Some(classes) = &info.classes {
+            let java = classes.java_version.as_deref().unwrap_or("未知");
+            println!("类:         {} 个，Java {}", classes.class_count, java);
+        }
+        if !info.abis.is_empty() {
+            println!("ABI:        {}", info.abis.join(", "));
+        }
+        if info.resource_count > 0 {
+            println!("资源:       {} 个", info.resource_count);
+        }
+        print_warnings(&info.consumer_rule_warnings);
+        print_warnings(&info.warnings);
+    })?;
+    Ok(EXIT_OK)
+}
+
+// ==================== 打包与修改 ====================
+
+fn cmd_channel_write(tokens: &[String]) -> CliResult {
+    let args = Args::parse(tokens, &["channel", "output", "format", "extras"], &[])?;
+    let input = args.input()?;
+    let extras = match args.value("extras") {
+        Some(list) => list
+            .split(',')
+            .filter(|pair| !pair.trim().is_empty())
+            .map(|pair| {
+                pair.split_once('=')
+                    .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+                    .ok_or_else(|| CliError::Usage(format!("选项 --extras 的值无效: {}", pair)))
+            })
+            .collect::<Result<BTreeMap<_, _>, _>>()?,
+        None => BTreeMap::new(),
+    };
+    let format: Option<ChannelFormat> = parse_enum(&args, "format")?;
+    let info = channel::write_apk_channel(
+        Path::new(&input),
+        Path::new(args.required("output")?),
+        args.required("channel")?,
+        &extras,
+        format,
+    )?;
+    emit(&args, &info, |info| {
+        match (&info.channel, &info.format) {
+            (Some(channel), Some(format)) => println!("✅ 已写入渠道 {} ({}): {}", channel, label(format), info.file_name),
+            _ => println!("✅ 已写入: {}", info.file_name),
+        }
+        print_warnings(&info.warnings);
+    })?;
+    Ok(EXIT_OK)
+}
+
+fn cmd_channel_batch(tokens: &[String]) -> CliResult {
+    let args = Args::parse(tokens, &["channels", "out-dir", "name-template", "format"], &[])?;
+    let input = args.input()?;
+    let format: Option<ChannelFormat> = parse_enum(&args, "format")?;
+    let result = channel_batch::package_channels(
+        Path::new(&input),
+        Path::new(args.required("channels")?),
+        Path::new(args.required("out-dir")?),
+        args.value("name-template"),
+        format,
+        // 进度输出到 stderr，不影响 --json 的输出
+        |progress| {
+            let mark = if progress.success { "✅" } else { "❌" };
+            eprintln!("[{}/{}] {} {}", progress.index, progress.total, mark, progress.channel);
+        },
+    )?;
+    emit(&args, &result, |result| {
+        println!("{} {} ({})", result.package_name, result.version_name, result.version_code);
+        for package in &result.packages {
+            match &package.error {
+                Some(error) => println!("❌ {}: {}", package.channel, error),
+                None => println!("{} {}  {}", if package.verified { "✅" } else { "⚠️ " }, package.channel, package.file_path),
+            }
+        }
+        if let Some(checksum_file) = &result.checksum_file {
+            println!("校验和: {}", checksum_file);
+        }
+        println!("成功 {} 个，失败 {} 个", result.succeeded, result.failed);
+    })?;
+    Ok(if result.failed == 0 { EXIT_OK } else { EXIT_CHECK_FAILED })
+}
+
+fn cmd_manifest_edit(tokens: &[String]) -> CliResult {
+    let options: Vec<&str> = ["edits", "output"].iter().chain(SIGN_OPTIONS).copied().collect();
+    let args = Args::parse(tokens, &options, &[])?;
+    let input = args.input()?;
+    let output = args.required("output")?;
+    let sign = sign_options(&args)?;
+    let edits_path = args.required("edits")?;
+    let edits: ManifestEdits = serde_json::from_str(&read_text(edits_path)?)
+        .map_err(|e| format!("修改内容格式错误 {}: {}", edits_path, e))?;
+    let result = manifest_editor::edit_apk_manifest(Path::new(&input), Path::new(output), &edits, sign.as_ref())?;
+    emit(&args, &result, |result| {
+        for applied in &result.applied {
+            println!("✅ {}", applied);
+        }
+        print_warnings(&result.warnings);
+        println!("{}{}", result.output_path, if result.signed { " (已签名)" } else { " (未签名)" });
+    })?;
+    Ok(EXIT_OK)
+}
+
+fn cmd_align(tokens: &[String]) -> CliResult {
+    let args = Args::parse(tokens, &["output", "page-size"], &[])?;
+    let page_size = match args.value("page-size") {
+        Some(_) => Some(args.parsed("page-size", 0u64)?),
+        None => None,
+    };
+    let result = zipalign::align_apk(Path::new(&args.input()?), Path::new(args.required("output")?), page_size)?;
+    emit(&args, &result, |result| {
+        println!("调整 {} 个条目: {}", result.adjusted_entries, result.output_path);
+        if result.signature_removed {
+            println!("⚠️  已移除 v2/v3 签名块，需要重新签名");
+        }
+        print_warnings(&result.warnings);
+        println!("{}", if result.report.aligned { "✅ 已对齐" } else { "❌ 未对齐" });
+    })?;
+    Ok(if result.report.aligned { EXIT_OK } else { EXIT_CHECK_FAILED })
+}
+
+fn cmd_build_apks(tokens: &[String]) -> CliResult {
+    let options: Vec<&str> = ["out-dir", "mode", "device"].iter().chain(SIGN_OPTIONS).copied().collect();
+    let args = Args::parse(tokens, &options, &[])?;
+    let input = args.input()?;
+    let output_dir = args.required("out-dir")?;
+    let mode = args.value("mode").unwrap_or("device");
+    let sign = sign_options(&args)?;
+    let device_spec = args.value("device").map(read_text).transpose()?;
+    let result = build_apks::build_apks(Path::new(&input), Path::new(output_dir), mode, device_spec.as_deref(), sign.as_ref())?;
+    emit(&args, &result, |result| {
+        for apk in &result.apks {
+            println!("{}:{}  {} ({} 字节)", apk.module, apk.split, apk.file_path, apk.file_size);
+        }
+        print_warnings(&result.warnings);
+        println!("{}", result.install_command);
+    })?;
+    Ok(EXIT_OK)
+}
+
+// ==================== 堆栈还原 ====================
+
+fn cmd_retrace(tokens: &[String]) -> CliResult {
+    let args = Args::parse(tokens, &["mapping", "apk"], &[])?;
+    let mapping_path = args.required("mapping")?;
+    let stack_trace = read_text(&args.input()?)?;
+    let mapping = Mapping::load(Path::new(mapping_path))?;
+    let result = retrace::retrace(&mapping, &stack_trace, args.value("apk").map(Path::new))?;
+    emit(&args, &result, |result| {
+        println!("{}", result.output);
+        print_warnings(&result.warnings);
+    })?;
+    // 提供 APK 时 mapping 与 APK 不匹配视为检查未通过
+    let mismatched = result.map_id_check.as_ref().is_some_and(|check| !check.matched);
+    Ok(if mismatched { EXIT_CHECK_FAILED } else { EXIT_OK })
+}
+
+fn cmd_symbolicate(tokens: &[String]) -> CliResult {
+    let args = Args::parse(tokens, &["symbols", "apk"], &[])?;
+    let symbol_dir = args.required("symbols")?;
+    let backtrace = read_text(&args.input()?)?;
+    let result = symbolicate::symbolicate(
+        &backtrace,
+        Path::new(symbol_dir),
+        args.value("apk").map(Path::new),
+    )?;
+    emit(&args, &result, |result| {
+        println!("{}", result.output);
+        print_warnings(&result.warnings);
+    })?;
+    let mismatched = result.libraries.iter().any(|lib| lib.apk_matches == Some(false));
+    Ok(if mismatched { EXIT_CHECK_FAILED } else { EXIT_OK })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn tokens(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn run_args(list: &[&str]) -> i32 {
+        run(tokens(list))
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("release_assistant_cli_{}_{}", std::process::id(), name))
+    }
+
+    // 只有一个空的未压缩条目 a.txt 的 ZIP，数据偏移 30 + 5 = 35，不满足 4 字节对齐
+    fn write_unaligned_zip(path: &Path) {
+        let name = b"a.txt";
+        let mut data = Vec::new();
+        data.extend_from_slice(&0x04034b50u32.to_le_bytes());
+        data.extend_from_slice(&[20, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        data.extend_from_slice(&[0; 12]);
+        data.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        data.extend_from_slice(&0u16.to_le_bytes());
+        data.extend_from_slice(name);
+
+        let central_offset = data.len() as u32;
+        data.extend_from_slice(&0x02014b50u32.to_le_bytes());
+        data.extend_from_slice(&[20, 0, 20, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        data.extend_from_slice(&[0; 12]);
+        data.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        data.extend_from_slice(&[0; 12]);
+        data.extend_from_slice(&0u32.to_le_bytes());
+        data.extend_from_slice(name);
+        let central_size = data.len() as u32 - central_offset;
+
+        data.extend_from_slice(&0x06054b50u32.to_le_bytes());
+        data.extend_from_slice(&[0, 0, 0, 0, 1, 0, 1, 0]);
+        data.extend_from_slice(&central_size.to_le_bytes());
+        data.extend_from_slice(&central_offset.to_le_bytes());
+        data.extend_from_slice(&0u16.to_le_bytes());
+        std::fs::write(path, data).unwrap();
+    }
+
+    #[test]
+    fn parses_inline_and_separate_option_values() {
+        let args = Args::parse(&tokens(&["app.apk", "--count=3", "--version", "v7", "--json"]), &["count", "version"], &[])
+            .unwrap();
+        assert_eq!(args.positional, vec!["app.apk"]);
+        assert_eq!(args.value("count"), Some("3"));
+        assert_eq!(args.value("version"), Some("v7"));
+        assert!(args.json());
+    }
+
+    #[test]
+    fn option_without_value_is_usage_error() {
+        let result = Args::parse(&tokens(&["--count"]), &["count"], &[]);
+        assert!(matches!(result, Err(CliError::Usage(message)) if message.contains("--count")));
+    }
+
+    #[test]
+    fn unknown_option_is_usage_error() {
+        let result = Args::parse(&tokens(&["--verbose"]), &["count"], &["uppercase"]);
+        assert!(matches!(result, Err(CliError::Usage(message)) if message.contains("--verbose")));
+    }
+
+    #[test]
+    fn flags_do_not_take_values() {
+        let args = Args::parse(&tokens(&["--uppercase", "x"]), &[], &["uppercase"]).unwrap();
+        assert!(args.flag("uppercase"));
+        assert_eq!(args.positional, vec!["x"]);
+    }
+
+    #[test]
+    fn usage_errors_exit_with_two() {
+        assert_eq!(run_args(&[]), EXIT_USAGE);
+        assert_eq!(run_args(&["frobnicate"]), EXIT_USAGE);
+        assert_eq!(run_args(&["parse"]), EXIT_USAGE);
+        assert_eq!(run_args(&["parse", "a.apk", "b.apk"]), EXIT_USAGE);
+        assert_eq!(run_args(&["uuid", "--version", "v9"]), EXIT_USAGE);
+        assert_eq!(run_args(&["uuid", "--count=abc"]), EXIT_USAGE);
+        assert_eq!(run_args(&["play-policy", "a.apk", "--release", "beta"]), EXIT_USAGE);
+        assert_eq!(run_args(&["channel-write", "a.apk", "--format", "zip"]), EXIT_USAGE);
+        assert_eq!(run_args(&["retrace", "trace.txt"]), EXIT_USAGE);
+        assert_eq!(run_args(&["symbolicate", "backtrace.txt"]), EXIT_USAGE);
+        // 指定了 keystore 但没有密码
+        assert_eq!(run_args(&["manifest-edit", "a.apk", "--output", "b.apk", "--keystore", "release.jks"]), EXIT_USAGE);
+        assert_eq!(run_args(&["build-apks", "app.aab", "--out-dir", "out", "--keystore", "release.jks"]), EXIT_USAGE);
+    }
+
+    #[test]
+    fn successful_commands_exit_with_zero() {
+        assert_eq!(run_args(&["help"]), EXIT_OK);
+        assert_eq!(run_args(&["uuid", "--count=2", "--version", "v7", "--json"]), EXIT_OK);
+        assert_eq!(run_args(&["password", "--length", "8"]), EXIT_OK);
+
+        let path = temp_path("info.txt");
+        std::fs::write(&path, b"abc").unwrap();
+        let code = run_args(&["info", path.to_str().unwrap(), "--json"]);
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(code, EXIT_OK);
+    }
+
+    #[test]
+    fn failed_checks_exit_with_one() {
+        let path = temp_path("unaligned.zip");
+        write_unaligned_zip(&path);
+        let code = run_args(&["zipalign", path.to_str().unwrap()]);
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(code, EXIT_CHECK_FAILED);
+    }
+
+    #[test]
+    fn execution_errors_exit_with_three() {
+        assert_eq!(run_args(&["info", "/nonexistent/app.apk"]), EXIT_ERROR);
+        assert_eq!(run_args(&["zip-integrity", "/nonexistent/app.apk"]), EXIT_ERROR);
+        assert_eq!(run_args(&["library", "/nonexistent/lib.aar"]), EXIT_ERROR);
+    }
+}
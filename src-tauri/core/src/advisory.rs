@@ -0,0 +1,839 @@
+// ==================== 离线漏洞匹配 ====================
+// 导入 OSV 格式的漏洞数据（osv.dev 按生态导出的 all.zip、目录或单个 JSON），精简后保存为本地数据库，
+// 再与 SBOM 识别出的组件比对：Maven 组件按 groupId:artifactId 匹配，原生库组件（OpenSSL、curl 等）按包名匹配。
+// 只使用 SEMVER / ECOSYSTEM 版本区间和 versions 列表，GIT 提交区间无法与二进制版本对应，导入时丢弃
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use zip::ZipArchive;
+
+use crate::error::AnalysisError;
+use crate::sbom::{self, SbomComponent};
+
+// 原生组件名与 OSV 中常见包名的对应关系
+const NATIVE_PACKAGE_NAMES: &[(&str, &[&str])] = &[
+    ("openssl", &["openssl"]),
+    ("curl", &["curl", "libcurl"]),
+    ("sqlite", &["sqlite", "sqlite3"]),
+    ("ffmpeg", &["ffmpeg"]),
+    ("zlib", &["zlib"]),
+    ("libpng", &["libpng"]),
+    ("libjpeg-turbo", &["libjpeg-turbo"]),
+    ("mbedtls", &["mbedtls", "mbed-tls"]),
+];
+// 原生组件只在这些生态中按名称匹配，npm、PyPI 等同名包和各发行版打过补丁的包版本不适用
+const NATIVE_ECOSYSTEMS: &[&str] = &["OSS-Fuzz", "ConanCenter"];
+// 预发布版本标识，排在正式版之前
+const PRE_RELEASE_QUALIFIERS: &[&str] =
+    &["alpha", "beta", "milestone", "rc", "cr", "pre", "preview", "dev", "snapshot"];
+// 单字母缩写只在后面紧跟数字时才是预发布（Maven 的 1.0-b2），OpenSSL 的 1.1.1a 是补丁后缀
+const SHORT_PRE_RELEASE_QUALIFIERS: &[&str] = &["a", "b", "m"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VulnSeverity {
+    Unknown,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl VulnSeverity {
+    fn from_score(score: f64) -> Self {
+        match score {
+            s if s >= 9.0 => VulnSeverity::Critical,
+            s if s >= 7.0 => VulnSeverity::High,
+            s if s >= 4.0 => VulnSeverity::Medium,
+            s if s > 0.0 => VulnSeverity::Low,
+            _ => VulnSeverity::Unknown,
+        }
+    }
+
+    // GitHub 安全公告的 database_specific.severity
+    fn from_label(label: &str) -> Self {
+        match label.to_ascii_uppercase().as_str() {
+            "CRITICAL" => VulnSeverity::Critical,
+            "HIGH" => VulnSeverity::High,
+            "MODERATE" | "MEDIUM" => VulnSeverity::Medium,
+            "LOW" => VulnSeverity::Low,
+            _ => VulnSeverity::Unknown,
+        }
+    }
+}
+
+// ==================== OSV 格式 ====================
+
+#[derive(Debug, Deserialize)]
+struct OsvRecord {
+    id: String,
+    #[serde(default)]
+    modified: String,
+    #[serde(default)]
+    aliases: Vec<String>,
+    #[serde(default)]
+    summary: Option<String>,
+    #[serde(default)]
+    details: Option<String>,
+    #[serde(default)]
+    severity: Vec<OsvSeverity>,
+    #[serde(default)]
+    affected: Vec<OsvAffected>,
+    #[serde(default)]
+    references: Vec<OsvReference>,
+    #[serde(default)]
+    database_specific: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OsvSeverity {
+    #[serde(rename = "type")]
+    kind: String,
+    score: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OsvAffected {
+    package: Option<OsvPackage>,
+    #[serde(default)]
+    ranges: Vec<OsvRange>,
+    #[serde(default)]
+    versions: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OsvPackage {
+    #[serde(default)]
+    ecosystem: String,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OsvRange {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    events: Vec<RangeEvent>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OsvReference {
+    url: String,
+}
+
+// ==================== 本地数据库 ====================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RangeEvent {
+    Introduced(String),
+    Fixed(String),
+    LastAffected(String),
+    Limit(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AffectedPackage {
+    pub ecosystem: String,
+    pub name: String,
+    /// 每个区间的事件列表
+    pub ranges: Vec<Vec<RangeEvent>>,
+    /// 明确列出的受影响版本
+    pub versions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Advisory {
+    pub id: String,
+    pub aliases: Vec<String>,
+    pub summary: String,
+    pub modified: String,
+    pub severity: VulnSeverity,
+    /// CVSS v3 基础分
+    pub score: Option<f64>,
+    pub affected: Vec<AffectedPackage>,
+    pub references: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct AdvisoryDatabase {
+    pub updated: String,
+    pub advisories: Vec<Advisory>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportResult {
+    pub database_path: String,
+    /// 新增或更新的条目
+    pub imported: usize,
+    /// 没有可用版本区间的条目
+    pub skipped: usize,
+    pub total: usize,
+    pub warnings: Vec<String>,
+}
+
+// 将 OSV 记录精简为本地格式，没有可匹配的版本信息时返回 None
+fn convert_record(record: OsvRecord) -> Option<Advisory> {
+    let affected: Vec<AffectedPackage> = record
+        .affected
+        .into_iter()
+        .filter_map(|a| {
+            let package = a.package?;
+            let ranges: Vec<Vec<RangeEvent>> = a
+                .ranges
+                .into_iter()
+                .filter(|r| r.kind == "SEMVER" || r.kind == "ECOSYSTEM")
+                .map(|r| r.events)
+                .filter(|events| !events.is_empty())
+                .collect();
+            (!ranges.is_empty() || !a.versions.is_empty()).then_some(AffectedPackage {
+                ecosystem: package.ecosystem,
+                name: package.name,
+                ranges,
+                versions: a.versions,
+            })
+        })
+        .collect();
+    if affected.is_empty() {
+        return None;
+    }
+
+    let score = record
+        .severity
+        .iter()
+        .filter(|s| s.kind == "CVSS_V3")
+        .find_map(|s| cvss3_base_score(&s.score));
+    let severity = match score {
+        Some(score) => VulnSeverity::from_score(score),
+        None => record
+            .database_specific
+            .as_ref()
+            .and_then(|d| d.get("severity"))
+            .and_then(|s| s.as_str())
+            .map(VulnSeverity::from_label)
+            .unwrap_or(VulnSeverity::Unknown),
+    };
+    let summary = record
+        .summary
+        .filter(|s| !s.is_empty())
+        .or_else(|| record.details.map(|d| d.lines().next().unwrap_or_default().to_string()))
+        .unwrap_or_default();
+
+    Some(Advisory {
+        id: record.id,
+        aliases: record.aliases,
+        summary,
+        modified: record.modified,
+        severity,
+        score,
+        affected,
+        references: record.references.into_iter().take(3).map(|r| r.url).collect(),
+    })
+}
+
+// 单个 JSON 可能是一条记录，也可能是记录数组
+fn parse_records(data: &[u8]) -> Result<Vec<OsvRecord>, String> {
+    let value: serde_json::Value = serde_json::from_slice(data).map_err(|e| e.to_string())?;
+    match value {
+        serde_json::Value::Array(items) => items
+            .into_iter()
+            .map(|item| serde_json::from_value(item).map_err(|e| e.to_string()))
+            .collect(),
+        value => Ok(vec![serde_json::from_value(value).map_err(|e| e.to_string())?]),
+    }
+}
+
+fn read_source(path: &Path, records: &mut Vec<OsvRecord>, warnings: &mut Vec<String>) -> Result<(), String> {
+    let display = path.display();
+    let metadata = fs::symlink_metadata(path).map_err(|e| format!("无法读取 {}: {}", display, e))?;
+    if metadata.file_type().is_symlink() && path.is_dir() {
+        // 不跟随目录符号链接，避免链接成环时无限递归
+        warnings.push(format!("跳过目录符号链接: {}", display));
+    } else if metadata.is_dir() {
+        let mut entries: Vec<_> = fs::read_dir(path)
+            .map_err(|e| format!("无法读取目录 {}: {}", display, e))?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .collect();
+        entries.sort();
+        for entry in entries {
+            let is_data = entry.extension().is_some_and(|ext| ext == "json" || ext == "zip");
+            if entry.is_dir() || is_data {
+                read_source(&entry, records, warnings)?;
+            }
+        }
+    } else if path.extension().is_some_and(|ext| ext == "zip") {
+        let file = File::open(path).map_err(|e| format!("无法打开文件: {}", e))?;
+        let mut archive = ZipArchive::new(file).map_err(|e| format!("无法解析 ZIP 文件: {}", e))?;
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i).map_err(|e| format!("读取 {} 失败: {}", display, e))?;
+            if !entry.name().ends_with(".json") {
+                continue;
+            }
+            let mut data = Vec::new();
+            entry
+                .read_to_end(&mut data)
+                .map_err(|e| format!("读取 {} 失败: {}", entry.name(), e))?;
+            match parse_records(&data) {
+                Ok(parsed) => records.extend(parsed),
+                Err(e) => warnings.push(format!("{}/{} 格式错误: {}", display, entry.name(), e)),
+            }
+        }
+    } else {
+        let data = fs::read(path).map_err(|e| format!("无法读取 {}: {}", display, e))?;
+        match parse_records(&data) {
+            Ok(parsed) => records.extend(parsed),
+            Err(e) => warnings.push(format!("{} 格式错误: {}", display, e)),
+        }
+    }
+    Ok(())
+}
+
+/// 读取本地漏洞数据库，文件不存在时返回空数据库
+pub(crate) fn load_database(path: &Path) -> Result<AdvisoryDatabase, String> {
+    if !path.exists() {
+        return Ok(AdvisoryDatabase::default());
+    }
+    let data = fs::read(path).map_err(|e| format!("无法读取漏洞数据库: {}", e))?;
+    serde_json::from_slice(&data).map_err(|e| format!("漏洞数据库格式错误: {}", e))
+}
+
+/// 导入 OSV 数据并合并到本地数据库，相同 ID 的条目保留 modified 较新的版本
+pub fn import_advisories(sources: &[&Path], database_path: &Path) -> Result<ImportResult, AnalysisError> {
+    let mut records = Vec::new();
+    let mut warnings = Vec::new();
+    for source in sources {
+        if !source.exists() {
+            return Err(format!("文件不存在: {}", source.display()).into());
+        }
+        read_source(source, &mut records, &mut warnings)?;
+    }
+
+    let mut database = load_database(database_path)?;
+    let mut by_id: BTreeMap<String, Advisory> =
+        database.advisories.into_iter().map(|a| (a.id.clone(), a)).collect();
+    let mut imported = 0;
+    let mut skipped = 0;
+    for record in records {
+        let Some(advisory) = convert_record(record) else {
+            skipped += 1;
+            continue;
+        };
+        // modified 为 RFC 3339 时间，按字符串比较即可
+        if by_id.get(&advisory.id).is_some_and(|old| old.modified >= advisory.modified) {
+            continue;
+        }
+        by_id.insert(advisory.id.clone(), advisory);
+        imported += 1;
+    }
+
+    database.advisories = by_id.into_values().collect();
+    database.updated = sbom::now_utc_timestamp();
+    let data = serde_json::to_vec(&database).map_err(|e| format!("序列化漏洞数据库失败: {}", e))?;
+    fs::write(database_path, data).map_err(|e| format!("写入漏洞数据库失败: {}", e))?;
+
+    Ok(ImportResult {
+        database_path: database_path.to_string_lossy().to_string(),
+        imported,
+        skipped,
+        total: database.advisories.len(),
+        warnings,
+    })
+}
+
+// ==================== CVSS ====================
+
+// CVSS v3.x 规范中的 Roundup：保留一位小数并向上取整，避免浮点误差
+fn roundup(value: f64) -> f64 {
+    let int_input = (value * 100_000.0).round() as i64;
+    if int_input % 10_000 == 0 {
+        int_input as f64 / 100_000.0
+    } else {
+        ((int_input / 10_000) + 1) as f64 / 10.0
+    }
+}
+
+/// 根据 CVSS v3 向量（CVSS:3.1/AV:N/AC:L/...）计算基础分
+pub fn cvss3_base_score(vector: &str) -> Option<f64> {
+    let rest = vector.strip_prefix("CVSS:3.0/").or_else(|| vector.strip_prefix("CVSS:3.1/"))?;
+    let metrics: BTreeMap<&str, &str> = rest.split('/').filter_map(|m| m.split_once(':')).collect();
+    let changed = match *metrics.get("S")? {
+        "U" => false,
+        "C" => true,
+        _ => return None,
+    };
+    let av = match *metrics.get("AV")? {
+        "N" => 0.85,
+        "A" => 0.62,
+        "L" => 0.55,
+        "P" => 0.2,
+        _ => return None,
+    };
+    let ac = match *metrics.get("AC")? {
+        "L" => 0.77,
+        "H" => 0.44,
+        _ => return None,
+    };
+    let pr = match (*metrics.get("PR")?, changed) {
+        ("N", _) => 0.85,
+        ("L", false) => 0.62,
+        ("L", true) => 0.68,
+        ("H", false) => 0.27,
+        ("H", true) => 0.5,
+        _ => return None,
+    };
+    let ui = match *metrics.get("UI")? {
+        "N" => 0.85,
+        "R" => 0.62,
+        _ => return None,
+    };
+    let cia = |key: &str| match *metrics.get(key)? {
+        "H" => Some(0.56),
+        "L" => Some(0.22),
+        "N" => Some(0.0),
+        _ => None,
+    };
+    let iss: f64 = 1.0 - (1.0 - cia("C")?) * (1.0 - cia("I")?) * (1.0 - cia("A")?);
+    let impact: f64 = if changed {
+        7.52 * (iss - 0.029) - 3.25 * (iss - 0.02).powi(15)
+    } else {
+        6.42 * iss
+    };
+    let exploitability = 8.22 * av * ac * pr * ui;
+
+    if impact <= 0.0 {
+        return Some(0.0);
+    }
+    let score = if changed {
+        roundup((1.08 * (impact + exploitability)).min(10.0))
+    } else {
+        roundup((impact + exploitability).min(10.0))
+    };
+    Some(score)
+}
+
+// ==================== 版本比较 ====================
+
+#[derive(Debug, PartialEq, Eq)]
+enum VersionToken {
+    Number(u64),
+    /// alpha、rc 等预发布标识，小于正式版
+    PreRelease(String),
+    /// 其他字母后缀，如 OpenSSL 的 1.1.1k，大于正式版
+    Suffix(String),
+}
+
+fn tokenize(version: &str) -> Vec<VersionToken> {
+    let version = version.trim().trim_start_matches(['v', 'V']);
+    let mut tokens = Vec::new();
+    let mut chars = version.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_ascii_digit() {
+            let mut digits = String::new();
+            while let Some(&d) = chars.peek().filter(|d| d.is_ascii_digit()) {
+                digits.push(d);
+                chars.next();
+            }
+            tokens.push(VersionToken::Number(digits.parse().unwrap_or(u64::MAX)));
+        } else if c.is_ascii_alphabetic() {
+            let mut word = String::new();
+            while let Some(&d) = chars.peek().filter(|d| d.is_ascii_alphabetic()) {
+                word.push(d.to_ascii_lowercase());
+                chars.next();
+            }
+            let followed_by_digit = chars.peek().is_some_and(|d| d.is_ascii_digit());
+            if PRE_RELEASE_QUALIFIERS.contains(&word.as_str())
+                || (followed_by_digit && SHORT_PRE_RELEASE_QUALIFIERS.contains(&word.as_str()))
+            {
+                tokens.push(VersionToken::PreRelease(word));
+            } else {
+                tokens.push(VersionToken::Suffix(word));
+            }
+        } else {
+            chars.next();
+        }
+    }
+    tokens
+}
+
+fn token_rank(token: Option<&VersionToken>) -> (u8, u64, &str) {
+    match token {
+        Some(VersionToken::PreRelease(word)) => (0, 0, word),
+        None => (1, 0, ""),
+        Some(VersionToken::Suffix(word)) => (2, 0, word),
+        Some(VersionToken::Number(n)) => (3, *n, ""),
+    }
+}
+
+/// 比较两个版本号，兼容 semver、Maven 和 OpenSSL 风格
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let (a, b) = (tokenize(a), tokenize(b));
+    for i in 0..a.len().max(b.len()) {
+        let (x, y) = (a.get(i), b.get(i));
+        // 缺失的段视为 0，如 1.2 等于 1.2.0
+        let ordering = match (x, y) {
+            (Some(VersionToken::Number(n)), None) => n.cmp(&0),
+            (None, Some(VersionToken::Number(n))) => 0.cmp(n),
+            _ => token_rank(x).cmp(&token_rank(y)),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
+
+fn event_version(event: &RangeEvent) -> &str {
+    match event {
+        RangeEvent::Introduced(v) | RangeEvent::Fixed(v) | RangeEvent::LastAffected(v) | RangeEvent::Limit(v) => v,
+    }
+}
+
+fn in_range(version: &str, events: &[RangeEvent]) -> bool {
+    // OSV 规定事件按版本排序后依次判断，"0" 表示最低版本
+    let mut sorted: Vec<&RangeEvent> = events.iter().collect();
+    sorted.sort_by(|a, b| compare_versions(event_version(a), event_version(b)));
+    let mut affected = false;
+    for event in sorted {
+        match event {
+            RangeEvent::Introduced(v) => {
+                if v == "0" || compare_versions(version, v) != Ordering::Less {
+                    affected = true;
+                }
+            }
+            RangeEvent::Fixed(v) | RangeEvent::Limit(v) => {
+                if compare_versions(version, v) != Ordering::Less {
+                    affected = false;
+                }
+            }
+            RangeEvent::LastAffected(v) => {
+                if compare_versions(version, v) == Ordering::Greater {
+                    affected = false;
+                }
+            }
+        }
+    }
+    affected
+}
+
+fn is_affected(package: &AffectedPackage, version: &str) -> bool {
+    package.versions.iter().any(|v| v == version) || package.ranges.iter().any(|events| in_range(version, events))
+}
+
+// ==================== 匹配 ====================
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VulnerabilityMatch {
+    pub component: String,
+    pub version: String,
+    /// 组件所在文件
+    pub source: String,
+    pub advisory_id: String,
+    pub cves: Vec<String>,
+    pub summary: String,
+    pub severity: VulnSeverity,
+    pub score: Option<f64>,
+    /// 修复版本
+    pub fixed_versions: Vec<String>,
+    pub references: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VulnerabilityReport {
+    pub database_updated: String,
+    pub advisory_count: usize,
+    pub components_checked: usize,
+    /// 没有版本号或坐标、无法匹配的组件
+    pub components_skipped: Vec<String>,
+    pub matches: Vec<VulnerabilityMatch>,
+    pub count_by_severity: BTreeMap<VulnSeverity, usize>,
+}
+
+// 组件对应的 (生态, 包名)，生态为空表示原生组件，只匹配 NATIVE_ECOSYSTEMS 中的生态
+fn component_packages(component: &SbomComponent) -> Vec<(Option<&'static str>, String)> {
+    let Some(purl) = &component.purl else { return Vec::new() };
+    if purl.starts_with("pkg:maven/") {
+        let Some(group) = &component.group else { return Vec::new() };
+        return vec![(Some("Maven"), format!("{}:{}", group, component.name))];
+    }
+    if purl.starts_with("pkg:generic/") {
+        let names = NATIVE_PACKAGE_NAMES
+            .iter()
+            .find(|(name, _)| *name == component.name)
+            .map(|(_, names)| names.to_vec())
+            .unwrap_or_default();
+        return names.into_iter().map(|n| (None, n.to_string())).collect();
+    }
+    Vec::new()
+}
+
+fn package_matches(package: &AffectedPackage, ecosystem: Option<&str>, name: &str) -> bool {
+    match ecosystem {
+        Some(ecosystem) => package.ecosystem == ecosystem && package.name == name,
+        None => NATIVE_ECOSYSTEMS.contains(&package.ecosystem.as_str()) && package.name.eq_ignore_ascii_case(name),
+    }
+}
+
+/// 用本地漏洞数据库检查 APK 中的组件
+pub fn scan(path: &Path, database_path: &Path) -> Result<VulnerabilityReport, AnalysisError> {
+    if !database_path.exists() {
+        return Err("漏洞数据库不存在，请先导入 OSV 数据".into());
+    }
+    let database = load_database(database_path)?;
+    let components = sbom::collect_components(path)?;
+
+    let mut matches = Vec::new();
+    let mut components_checked = 0;
+    let mut components_skipped = Vec::new();
+    for component in &components {
+        let packages = component_packages(component);
+        let Some(version) = component.version.as_deref().filter(|_| !packages.is_empty()) else {
+            // 原生库文件本身只有哈希，版本由从中识别出的开源组件承载，不算作跳过
+            let native_file = component.purl.is_none() && component.name.ends_with(".so");
+            if !native_file {
+                components_skipped.push(component.name.clone());
+            }
+            continue;
+        };
+        components_checked += 1;
+
+        for advisory in &database.advisories {
+            let affected: Vec<&AffectedPackage> = advisory
+                .affected
+                .iter()
+                .filter(|p| packages.iter().any(|(eco, name)| package_matches(p, *eco, name)))
+                .filter(|p| is_affected(p, version))
+                .collect();
+            if affected.is_empty() {
+                continue;
+            }
+            let mut fixed_versions: Vec<String> = affected
+                .iter()
+                .flat_map(|p| p.ranges.iter().flatten())
+                .filter_map(|e| match e {
+                    RangeEvent::Fixed(v) => Some(v.clone()),
+                    _ => None,
+                })
+                .collect();
+            fixed_versions.sort_by(|a, b| compare_versions(a, b));
+            fixed_versions.dedup();
+            let cves = std::iter::once(&advisory.id)
+                .chain(&advisory.aliases)
+                .filter(|id| id.starts_with("CVE-"))
+                .cloned()
+                .collect();
+            matches.push(VulnerabilityMatch {
+                component: component.purl.clone().unwrap_or_else(|| component.name.clone()),
+                version: version.to_string(),
+                source: component.source.clone(),
+                advisory_id: advisory.id.clone(),
+                cves,
+                summary: advisory.summary.clone(),
+                severity: advisory.severity,
+                score: advisory.score,
+                fixed_versions,
+                references: advisory.references.clone(),
+            });
+        }
+    }
+
+    // 严重程度从高到低
+    matches.sort_by(|a, b| b.severity.cmp(&a.severity).then_with(|| a.component.cmp(&b.component)));
+    let mut count_by_severity = BTreeMap::new();
+    for m in &matches {
+        *count_by_severity.entry(m.severity).or_insert(0) += 1;
+    }
+
+    Ok(VulnerabilityReport {
+        database_updated: database.updated,
+        advisory_count: database.advisories.len(),
+        components_checked,
+        components_skipped,
+        matches,
+        count_by_severity,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn package(ecosystem: &str, name: &str) -> AffectedPackage {
+        AffectedPackage {
+            ecosystem: ecosystem.to_string(),
+            name: name.to_string(),
+            ranges: Vec::new(),
+            versions: vec!["1.0".to_string()],
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("advisory_{}_{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    const RECORD: &str = r#"{"id":"OSV-1","modified":"2025-01-01T00:00:00Z","affected":[{"package":{"ecosystem":"OSS-Fuzz","name":"zlib"},"versions":["1.2.11"]}]}"#;
+
+    fn range(events: &[(&str, &str)]) -> Vec<RangeEvent> {
+        events
+            .iter()
+            .map(|&(kind, version)| {
+                let version = version.to_string();
+                match kind {
+                    "introduced" => RangeEvent::Introduced(version),
+                    "fixed" => RangeEvent::Fixed(version),
+                    "last_affected" => RangeEvent::LastAffected(version),
+                    "limit" => RangeEvent::Limit(version),
+                    other => panic!("未知事件 {}", other),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn compares_semver_maven_and_openssl_versions() {
+        assert_eq!(compare_versions("1.2", "1.2.0"), Ordering::Equal);
+        assert_eq!(compare_versions("v2.10.0", "2.9.9"), Ordering::Greater);
+        assert_eq!(compare_versions("1.0-rc1", "1.0"), Ordering::Less);
+        assert_eq!(compare_versions("1.0-beta-2", "1.0-beta-10"), Ordering::Less);
+        assert_eq!(compare_versions("1.0-b2", "1.0"), Ordering::Less);
+        assert_eq!(compare_versions("2.0.0-SNAPSHOT", "2.0.0"), Ordering::Less);
+
+        // OpenSSL 字母后缀是补丁版本，排在正式版之后
+        assert_eq!(compare_versions("1.1.1a", "1.1.1"), Ordering::Greater);
+        assert_eq!(compare_versions("1.1.1b", "1.1.1a"), Ordering::Greater);
+        assert_eq!(compare_versions("1.1.1w", "1.1.2"), Ordering::Less);
+        assert_eq!(compare_versions("1.0.2m", "1.0.2"), Ordering::Greater);
+    }
+
+    #[test]
+    fn openssl_letter_releases_fall_inside_range() {
+        let events = range(&[("introduced", "1.1.1"), ("fixed", "1.1.1c")]);
+        assert!(in_range("1.1.1", &events));
+        assert!(in_range("1.1.1a", &events));
+        assert!(in_range("1.1.1b", &events));
+        assert!(!in_range("1.1.1c", &events));
+        assert!(!in_range("1.1.0l", &events));
+    }
+
+    #[test]
+    fn multiple_introduced_fixed_pairs() {
+        let events = range(&[
+            ("introduced", "2.0.0"),
+            ("fixed", "2.3.1"),
+            ("introduced", "1.0.0"),
+            ("fixed", "1.2.0"),
+        ]);
+        assert!(!in_range("0.9", &events));
+        assert!(in_range("1.0.0", &events));
+        assert!(in_range("1.1.9", &events));
+        assert!(!in_range("1.2.0", &events));
+        assert!(!in_range("1.5", &events));
+        assert!(in_range("2.3.0", &events));
+        assert!(!in_range("2.3.1", &events));
+    }
+
+    #[test]
+    fn last_affected_is_inclusive() {
+        let events = range(&[("introduced", "0"), ("last_affected", "3.4.2")]);
+        assert!(in_range("0.1", &events));
+        assert!(in_range("3.4.2", &events));
+        assert!(!in_range("3.4.3", &events));
+    }
+
+    #[test]
+    fn limit_ends_range() {
+        let events = range(&[("introduced", "1.0"), ("limit", "2.0")]);
+        assert!(in_range("1.9.9", &events));
+        assert!(!in_range("2.0", &events));
+        assert!(!in_range("2.5", &events));
+    }
+
+    #[test]
+    fn explicit_versions_are_affected() {
+        let zlib = package("OSS-Fuzz", "zlib");
+        assert!(is_affected(&zlib, "1.0"));
+        assert!(!is_affected(&zlib, "1.0.1"));
+    }
+
+    #[test]
+    fn cvss3_scores_match_reference_vectors() {
+        let score = |vector: &str| cvss3_base_score(vector).unwrap();
+        assert_eq!(score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H"), 9.8);
+        assert_eq!(score("CVSS:3.0/AV:L/AC:L/PR:L/UI:N/S:U/C:H/I:H/A:H"), 7.8);
+        // 作用域改变
+        assert_eq!(score("CVSS:3.1/AV:N/AC:L/PR:N/UI:R/S:C/C:L/I:L/A:N"), 6.1);
+        assert_eq!(score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:C/C:H/I:H/A:H"), 10.0);
+        // Heartbleed (CVE-2014-0160)
+        assert_eq!(score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:N/A:N"), 7.5);
+        assert_eq!(score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:N/I:N/A:N"), 0.0);
+        assert_eq!(VulnSeverity::from_score(9.8), VulnSeverity::Critical);
+    }
+
+    #[test]
+    fn invalid_cvss_vectors_are_rejected() {
+        assert_eq!(cvss3_base_score("AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H"), None);
+        assert_eq!(cvss3_base_score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:X/C:H/I:H/A:H"), None);
+        assert_eq!(cvss3_base_score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H"), None);
+    }
+
+    #[test]
+    fn maven_matches_exact_coordinates() {
+        let okhttp = package("Maven", "com.squareup.okhttp3:okhttp");
+        assert!(package_matches(&okhttp, Some("Maven"), "com.squareup.okhttp3:okhttp"));
+        assert!(!package_matches(&okhttp, Some("Maven"), "com.squareup.okhttp3:OKHTTP"));
+        assert!(!package_matches(&okhttp, None, "com.squareup.okhttp3:okhttp"));
+    }
+
+    #[test]
+    fn native_components_match_only_allowed_ecosystems() {
+        assert!(package_matches(&package("OSS-Fuzz", "OpenSSL"), None, "openssl"));
+        assert!(package_matches(&package("ConanCenter", "libcurl"), None, "libcurl"));
+
+        // 同名的语言包和发行版软件包不算
+        assert!(!package_matches(&package("npm", "sqlite3"), None, "sqlite3"));
+        assert!(!package_matches(&package("PyPI", "curl"), None, "curl"));
+        assert!(!package_matches(&package("Debian:12", "openssl"), None, "openssl"));
+        assert!(!package_matches(&package("Alpine:v3.19", "zlib"), None, "zlib"));
+    }
+
+    #[test]
+    fn reads_nested_directories_and_skips_bad_files() {
+        let dir = temp_dir("nested");
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(dir.join("sub/a.json"), RECORD).unwrap();
+        fs::write(dir.join("broken.json"), "{").unwrap();
+        fs::write(dir.join("notes.txt"), "ignored").unwrap();
+
+        let mut records = Vec::new();
+        let mut warnings = Vec::new();
+        read_source(&dir, &mut records, &mut warnings).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].id, "OSV-1");
+        assert_eq!(warnings.len(), 1, "{:?}", warnings);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn directory_symlink_loop_is_not_followed() {
+        let dir = temp_dir("symlink");
+        fs::write(dir.join("a.json"), RECORD).unwrap();
+        std::os::unix::fs::symlink(&dir, dir.join("loop")).unwrap();
+
+        let mut records = Vec::new();
+        let mut warnings = Vec::new();
+        read_source(&dir, &mut records, &mut warnings).unwrap();
+        assert_eq!(records.len(), 1);
+        assert!(warnings.iter().any(|w| w.contains("符号链接")), "{:?}", warnings);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,593 @@
+// ==================== 安装包解析 ====================
+// 解析 APK / XAPK / IPA / HAP 的基本信息，并附带加固、ZIP 结构、框架和构建溯源检测结果
+
+use std::fmt;
+use std::io::{Cursor, Read};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use zip::ZipArchive;
+
+use crate::framework::{self, FrameworkMatch};
+use crate::harmony::{self, HarmonyInfo};
+use crate::ipa::{self, IpaInfo};
+use crate::manifest::clean_attr_value;
+use crate::protection::{self, ProtectionInfo};
+use crate::provenance::{self, BuildProvenance};
+use crate::zip_integrity::{self, ZipIntegrityReport};
+
+#[derive(Debug)]
+pub enum ApkError {
+    /// 文件不存在
+    NotFound,
+    /// 读取文件失败
+    Io(std::io::Error),
+    /// 无法解析 ZIP 结构
+    Zip(zip::result::ZipError),
+    /// APK 中没有 AndroidManifest.xml
+    MissingManifest,
+    /// 二进制 AndroidManifest.xml 解析失败
+    Manifest(String),
+    /// XAPK 中没有 APK，附带 XAPK 的文件列表
+    NoApkInXapk(Vec<String>),
+    /// IPA / HAP 等格式的解析错误
+    Package(String),
+}
+
+impl fmt::Display for ApkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApkError::NotFound => write!(f, "文件不存在"),
+            ApkError::Io(e) => write!(f, "读取文件失败: {}", e),
+            ApkError::Zip(e) => write!(f, "无法解析 ZIP 文件: {}", e),
+            ApkError::MissingManifest => write!(f, "未找到 AndroidManifest.xml 文件"),
+            ApkError::Manifest(e) => write!(f, "解析 AndroidManifest.xml 失败: {}", e),
+            ApkError::NoApkInXapk(files) => {
+                write!(f, "XAPK 文件中未找到任何 APK 文件。文件列表:")?;
+                for (i, name) in files.iter().take(30).enumerate() {
+                    write!(f, "\n  {}. {}", i + 1, name)?;
+                }
+                Ok(())
+            }
+            ApkError::Package(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for ApkError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ApkError::Io(e) => Some(e),
+            ApkError::Zip(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ApkError {
+    fn from(e: std::io::Error) -> Self {
+        ApkError::Io(e)
+    }
+}
+
+impl From<zip::result::ZipError> for ApkError {
+    fn from(e: zip::result::ZipError) -> Self {
+        ApkError::Zip(e)
+    }
+}
+
+// IPA / HAP 解析模块返回的错误信息
+impl From<String> for ApkError {
+    fn from(message: String) -> Self {
+        ApkError::Package(message)
+    }
+}
+
+/// 安装包信息（Android、iOS 与 HarmonyOS 共用）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PackageInfo {
+    pub package_name: String,
+    pub version_name: String,
+    pub version_code: String,
+    pub min_sdk_version: String,
+    pub target_sdk_version: String,
+    pub compile_sdk_version: String,
+    pub permissions: Vec<String>,
+    pub activities: Vec<String>,
+    pub services: Vec<String>,
+    pub receivers: Vec<String>,
+    pub providers: Vec<String>,
+    pub file_size: u64,
+    pub file_size_readable: String,
+    pub icon_base64: Option<String>,  // Base64 编码的图标
+    pub protection: Option<ProtectionInfo>,  // 加固与混淆检测结果
+    pub zip_integrity: Option<ZipIntegrityReport>,  // ZIP 结构完整性检查结果
+    pub frameworks: Vec<FrameworkMatch>,  // 跨平台框架（Flutter、React Native、Unity 等）
+    pub build_provenance: Option<BuildProvenance>,  // 构建溯源（AGP、R8、Kotlin 版本等）
+    pub platform: String,  // android / ios / harmonyos
+    pub ios: Option<IpaInfo>,  // iOS 专有信息（描述文件、设备类型等）
+    pub harmony: Option<HarmonyInfo>,  // HarmonyOS 专有信息（模块、签名等）
+}
+
+
+/// 格式化文件大小，如 1.50 MB
+pub fn format_file_size(size: u64) -> String {
+    const KB: u64 = 1024;
+    const MB: u64 = KB * 1024;
+    const GB: u64 = MB * 1024;
+    
+    if size >= GB {
+        format!("{:.2} GB", size as f64 / GB as f64)
+    } else if size >= MB {
+        format!("{:.2} MB", size as f64 / MB as f64)
+    } else if size >= KB {
+        format!("{:.2} KB", size as f64 / KB as f64)
+    } else {
+        format!("{} Bytes", size)
+    }
+}
+
+/// 解析安装包，按扩展名区分 APK、XAPK、IPA 和 HAP / APP
+pub fn parse_package(path: &Path) -> Result<PackageInfo, ApkError> {
+    if !path.exists() {
+        return Err(ApkError::NotFound);
+    }
+
+    // 获取文件扩展名，判断是否是 XAPK
+    let extension = path.extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+
+    // 如果是 XAPK 文件，先提取 base.apk
+    if extension == "xapk" {
+        return parse_xapk_file(path);
+    }
+
+    if extension == "ipa" {
+        return parse_ipa_file(path);
+    }
+
+    if extension == "hap" || extension == "app" {
+        return parse_harmony_file(path);
+    }
+
+    // 获取文件大小
+    let file_size = std::fs::metadata(path)?.len();
+    let file_size_readable = format_file_size(file_size);
+
+    // 打开 ZIP 文件
+    let mut archive = ZipArchive::new(std::fs::File::open(path)?)?;
+
+    // 查找 AndroidManifest.xml
+    let manifest_data = if let Ok(mut manifest_file) = archive.by_name("AndroidManifest.xml") {
+        let mut buffer = Vec::new();
+        manifest_file.read_to_end(&mut buffer)?;
+        buffer
+    } else {
+        return Err(ApkError::MissingManifest);
+    };
+    
+    // 解析二进制 XML
+    let cursor = Cursor::new(manifest_data);
+    let axml = rusty_axml::parse_from_reader(cursor)
+        .map_err(|e| ApkError::Manifest(format!("{:?}", e)))?;
+
+    // 使用 helper 函数获取组件列表
+    let permissions = rusty_axml::get_requested_permissions(&axml);
+    let activities = rusty_axml::get_activities_names(&axml);
+    let services = rusty_axml::get_services_names(&axml);
+    let receivers = rusty_axml::get_receivers_names(&axml);
+    let providers = rusty_axml::get_providers_names(&axml);
+
+    // 查找 manifest 节点获取包名和版本信息
+    let mut package_name = String::new();
+    let mut version_name = String::new();
+    let mut version_code = String::new();
+    let mut compile_sdk_version = String::new();
+    let mut min_sdk_version = String::new();
+    let mut target_sdk_version = String::new();
+
+    // 从 AXML 树中提取 manifest 属性
+    let manifest_nodes = rusty_axml::find_nodes_by_type(&axml, "manifest");
+    if let Some(manifest_node) = manifest_nodes.first() {
+        let elem = manifest_node.borrow();
+
+        // 尝试不同的属性名称（有/无命名空间前缀）
+        if let Some(pkg) = elem.get_attr("package") {
+            package_name = clean_attr_value(pkg);
+        }
+        if let Some(vn) = elem.get_attr("android:versionName") {
+            version_name = clean_attr_value(vn);
+        } else if let Some(vn) = elem.get_attr("versionName") {
+            version_name = clean_attr_value(vn);
+        }
+        if let Some(vc) = elem.get_attr("android:versionCode") {
+            version_code = clean_attr_value(vc);
+        } else if let Some(vc) = elem.get_attr("versionCode") {
+            version_code = clean_attr_value(vc);
+        }
+        // 尝试多种可能的属性名称获取 compileSdkVersion
+        if let Some(csv) = elem.get_attr("android:compileSdkVersion") {
+            compile_sdk_version = clean_attr_value(csv);
+        } else if let Some(csv) = elem.get_attr("compileSdkVersion") {
+            compile_sdk_version = clean_attr_value(csv);
+        }
+
+        // 如果没有获取到 compileSdkVersion，尝试使用 compileSdkVersionCodename
+        if compile_sdk_version.is_empty() {
+            if let Some(csc) = elem.get_attr("android:compileSdkVersionCodename") {
+                compile_sdk_version = clean_attr_value(csc);
+            } else if let Some(csc) = elem.get_attr("compileSdkVersionCodename") {
+                compile_sdk_version = clean_attr_value(csc);
+            }
+        }
+    }
+
+    // 查找 uses-sdk 节点获取 SDK 版本
+    let uses_sdk_nodes = rusty_axml::find_nodes_by_type(&axml, "uses-sdk");
+    if let Some(uses_sdk_node) = uses_sdk_nodes.first() {
+        let elem = uses_sdk_node.borrow();
+
+        // 尝试不同的属性名称（有/无命名空间前缀）
+        if min_sdk_version.is_empty() {
+            if let Some(min) = elem.get_attr("android:minSdkVersion") {
+                min_sdk_version = clean_attr_value(min);
+            } else if let Some(min) = elem.get_attr("minSdkVersion") {
+                min_sdk_version = clean_attr_value(min);
+            }
+        }
+
+        if target_sdk_version.is_empty() {
+            if let Some(target) = elem.get_attr("android:targetSdkVersion") {
+                target_sdk_version = clean_attr_value(target);
+            } else if let Some(target) = elem.get_attr("targetSdkVersion") {
+                target_sdk_version = clean_attr_value(target);
+            }
+        }
+    }
+
+    // 如果没有获取到 SDK 版本，设置默认值
+    if min_sdk_version.is_empty() {
+        min_sdk_version = "未指定".to_string();
+    }
+    if target_sdk_version.is_empty() {
+        target_sdk_version = "未指定".to_string();
+    }
+    if compile_sdk_version.is_empty() {
+        compile_sdk_version = "未指定".to_string();
+    }
+    
+    // 提取应用图标（传入文件路径以重新打开 ZIP）
+    let icon_base64 = extract_app_icon(path);
+
+    // 检测加固和混淆（失败时不影响基本信息）
+    let protection = match protection::detect_protection(path) {
+        Ok(info) => Some(info),
+        Err(e) => {
+            log::warn!("加固检测失败: {}", e);
+            None
+        }
+    };
+
+    // 收集构建工具链信息
+    let build_provenance = match provenance::collect_provenance(path) {
+        Ok(info) => Some(info),
+        Err(e) => {
+            log::warn!("构建信息收集失败: {}", e);
+            None
+        }
+    };
+
+    // 检查 ZIP 结构（ZipArchive 能打开不代表结构安全）
+    let zip_integrity = match zip_integrity::check_zip_integrity(path) {
+        Ok(report) => Some(report),
+        Err(e) => {
+            log::warn!("ZIP 结构检查失败: {}", e);
+            None
+        }
+    };
+
+    Ok(PackageInfo {
+        package_name,
+        version_name,
+        version_code,
+        min_sdk_version,
+        target_sdk_version,
+        compile_sdk_version,
+        permissions,
+        activities,
+        services,
+        receivers,
+        providers,
+        file_size,
+        file_size_readable,
+        icon_base64,
+        protection,
+        zip_integrity,
+        frameworks: detect_frameworks(path),
+        build_provenance,
+        platform: "android".to_string(),
+        ios: None,
+        harmony: None,
+    })
+}
+
+// 检测跨平台框架（失败时不影响基本信息）
+fn detect_frameworks(path: &Path) -> Vec<FrameworkMatch> {
+    framework::detect_frameworks(path).unwrap_or_else(|e| {
+        log::warn!("框架检测失败: {}", e);
+        Vec::new()
+    })
+}
+
+// 解析 IPA 文件，按 PackageInfo 的字段对应填充
+fn parse_ipa_file(ipa_path: &Path) -> Result<PackageInfo, ApkError> {
+    let file_size = std::fs::metadata(ipa_path)?.len();
+    let mut info = ipa::parse_ipa(ipa_path)?;
+
+    let zip_integrity = match zip_integrity::check_zip_integrity(ipa_path) {
+        Ok(report) => Some(report),
+        Err(e) => {
+            log::warn!("ZIP 结构检查失败: {}", e);
+            None
+        }
+    };
+
+    let or_unspecified = |value: &str| {
+        if value.is_empty() { "未指定".to_string() } else { value.to_string() }
+    };
+
+    Ok(PackageInfo {
+        package_name: info.bundle_id.clone(),
+        version_name: info.version.clone(),
+        version_code: info.build.clone(),
+        min_sdk_version: or_unspecified(&info.minimum_os_version),
+        target_sdk_version: or_unspecified(&info.platform_version),
+        compile_sdk_version: or_unspecified(&info.sdk_name),
+        // iOS 没有权限声明，用隐私权限说明代替
+        permissions: info.usage_descriptions.clone(),
+        activities: Vec::new(),
+        services: Vec::new(),
+        receivers: Vec::new(),
+        providers: Vec::new(),
+        file_size,
+        file_size_readable: format_file_size(file_size),
+        icon_base64: info.icon_base64.take(),
+        protection: None,
+        zip_integrity,
+        frameworks: detect_frameworks(ipa_path),
+        build_provenance: None,
+        platform: "ios".to_string(),
+        ios: Some(info),
+        harmony: None,
+    })
+}
+
+// 解析 HarmonyOS .hap / .app 文件，UIAbility 对应 Activity，扩展能力对应 Service
+fn parse_harmony_file(package_path: &Path) -> Result<PackageInfo, ApkError> {
+    let file_size = std::fs::metadata(package_path)?.len();
+    let mut info = harmony::parse_harmony_package(package_path)?;
+
+    let zip_integrity = match zip_integrity::check_zip_integrity(package_path) {
+        Ok(report) => Some(report),
+        Err(e) => {
+            log::warn!("ZIP 结构检查失败: {}", e);
+            None
+        }
+    };
+
+    let or_unspecified = |value: &str| {
+        if value.is_empty() { "未指定".to_string() } else { value.to_string() }
+    };
+
+    let mut activities = Vec::new();
+    let mut services = Vec::new();
+    for module in &info.modules {
+        for ability in &module.abilities {
+            match ability.ability_type.as_str() {
+                "ability" | "page" => activities.push(ability.name.clone()),
+                other => services.push(format!("{} ({})", ability.name, other)),
+            }
+        }
+    }
+
+    Ok(PackageInfo {
+        package_name: info.bundle_name.clone(),
+        version_name: info.version_name.clone(),
+        version_code: info.version_code.clone(),
+        min_sdk_version: or_unspecified(&info.compatible_api_version),
+        target_sdk_version: or_unspecified(&info.target_api_version),
+        compile_sdk_version: or_unspecified(&info.compile_sdk_version),
+        permissions: info.permissions.clone(),
+        activities,
+        services,
+        receivers: Vec::new(),
+        providers: Vec::new(),
+        file_size,
+        file_size_readable: format_file_size(file_size),
+        icon_base64: info.icon_base64.take(),
+        protection: None,
+        zip_integrity,
+        frameworks: detect_frameworks(package_path),
+        build_provenance: None,
+        platform: "harmonyos".to_string(),
+        ios: None,
+        harmony: Some(info),
+    })
+}
+
+// 解析 XAPK 文件
+fn parse_xapk_file(xapk_path: &Path) -> Result<PackageInfo, ApkError> {
+    use std::io::Write;
+    use std::env;
+
+    // 打开 XAPK 文件（ZIP 格式）
+    let mut xapk_archive = ZipArchive::new(std::fs::File::open(xapk_path)?)?;
+    let xapk_files: Vec<String> = xapk_archive.file_names().map(|s| s.to_string()).collect();
+
+    // 查找 APK 文件（按优先级）
+    let apk_priority = [
+        "base.apk",                     // 最常见
+        "split_config.base.apk",        // 某些 XAPK 的命名
+        "master.apk",                   // 备选名称
+    ];
+
+    // 首先尝试优先级列表中的名称，没找到时使用任意 .apk 文件
+    let target_apk_name = apk_priority
+        .iter()
+        .find(|name| xapk_files.iter().any(|f| f == *name))
+        .map(|name| name.to_string())
+        .or_else(|| xapk_files.iter().find(|f| f.to_lowercase().ends_with(".apk")).cloned());
+
+    let apk_name = target_apk_name.ok_or(ApkError::NoApkInXapk(xapk_files))?;
+    log::debug!("XAPK {} 中解析 {}", xapk_path.display(), apk_name);
+
+    let mut apk_file = xapk_archive.by_name(&apk_name)?;
+
+    // 创建临时目录
+    let temp_dir = env::temp_dir();
+    let temp_apk_path = temp_dir.join(format!("release_assistant_xapk_{}_{}.apk",
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs()));
+
+    // 提取 APK 到临时文件
+    let mut temp_file = std::fs::File::create(&temp_apk_path)?;
+    let mut buffer = Vec::new();
+    apk_file.read_to_end(&mut buffer)?;
+    temp_file.write_all(&buffer)?;
+
+    // 解析提取的 APK
+    let result = parse_package(&temp_apk_path);
+
+    // 清理临时文件
+    let _ = std::fs::remove_file(&temp_apk_path);
+
+    result
+}
+
+// 读取候选图标中第一个可读的文件，返回 data URL
+fn read_first_icon<R: Read + std::io::Seek>(archive: &mut ZipArchive<R>, candidates: &[String]) -> Option<String> {
+    use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+
+    for icon_path in candidates {
+        let mut icon_data = Vec::new();
+        let read = archive
+            .by_name(icon_path)
+            .map_err(|e| e.to_string())
+            .and_then(|mut icon_file| icon_file.read_to_end(&mut icon_data).map_err(|e| e.to_string()));
+        match read {
+            Ok(_) => return Some(format!("data:image/png;base64,{}", BASE64.encode(&icon_data))),
+            Err(e) => log::debug!("读取图标 {} 失败: {}", icon_path, e),
+        }
+    }
+    None
+}
+
+// 文件名（不含路径，处理 / 和 \ 两种分隔符）是否以 ic_launcher 开头（不区分大小写）
+fn is_launcher_icon_name(zip_path: &str) -> bool {
+    let file_name = zip_path.split('/').next_back().unwrap_or("");
+    let file_name = file_name.split('\\').next_back().unwrap_or(file_name);
+    file_name.to_lowercase().starts_with("ic_launcher")
+}
+
+// 提取应用图标并返回 Base64 编码
+// 需要传入文件路径以重新打开 ZIP，避免读取冲突
+// 按分辨率从高到低查找 ic_launcher 开头的 PNG 图片
+fn extract_app_icon(file_path: &Path) -> Option<String> {
+    // 重新打开 ZIP 文件以避免读取冲突
+    let file = std::fs::File::open(file_path).ok()?;
+    let mut archive = ZipArchive::new(file).ok()?;
+
+    let zip_names: Vec<String> = archive.file_names().map(|s| s.to_string()).collect();
+
+    // 首先尝试从所有 PNG 文件中查找 ic_launcher 开头的图标
+    let mut launcher_icons: Vec<String> = zip_names
+        .iter()
+        .filter(|zip_path| zip_path.to_lowercase().ends_with(".png") && is_launcher_icon_name(zip_path))
+        .cloned()
+        .collect();
+
+    if !launcher_icons.is_empty() {
+        // 按路径长度排序，优先选择路径较短的（通常是高分辨率）
+        launcher_icons.sort_by_key(|a| a.len());
+        if let Some(icon) = read_first_icon(&mut archive, &launcher_icons) {
+            return Some(icon);
+        }
+    }
+
+    // 如果没找到 ic_launcher，回退到原来的 DPI 目录查找方式
+
+    // DPI 目录列表（从高到低分辨率）
+    const DPI_DIRS: &[&str] = &[
+        "mipmap-xxxhdpi",   // 512dpi - 最高分辨率
+        "drawable-xxxhdpi",
+        "mipmap-xxhdpi",    // 480dpi
+        "drawable-xxhdpi",
+        "mipmap-xhdpi",     // 320dpi
+        "drawable-xhdpi",
+        "mipmap-hdpi",      // 240dpi
+        "drawable-hdpi",
+        "mipmap-mdpi",      // 160dpi
+        "drawable-mdpi",
+        "mipmap-ldpi",      // 120dpi
+        "drawable-ldpi",
+        "mipmap",           // 默认
+        "drawable",
+    ];
+
+    // 按分辨率从高到低依次查找
+    for dpi_dir in DPI_DIRS {
+        // 查找当前 DPI 目录下所有以 ic_launcher 开头的 PNG 文件
+        let patterns = [format!("/{}/", dpi_dir), format!("\\{}\\", dpi_dir)];
+        let prefixes = [format!("{}/", dpi_dir), format!("{}\\", dpi_dir)];
+        let mut icons_in_this_dpi: Vec<String> = zip_names
+            .iter()
+            .filter(|zip_path| {
+                patterns.iter().any(|p| zip_path.contains(p.as_str()))
+                    || prefixes.iter().any(|p| zip_path.starts_with(p.as_str()))
+            })
+            .filter(|zip_path| zip_path.to_lowercase().ends_with(".png") && is_launcher_icon_name(zip_path))
+            .cloned()
+            .collect();
+
+        // 如果当前 DPI 目录找到了图标，按文件名排序优先返回 ic_launcher.png
+        if !icons_in_this_dpi.is_empty() {
+            // 优先选择 ic_launcher.png，然后是 ic_launcher_round.png，最后是其他变体
+            icons_in_this_dpi.sort_by(|a, b| {
+                let a_lower = a.to_lowercase();
+                let b_lower = b.to_lowercase();
+
+                // ic_launcher.png 优先级最高
+                if a_lower.ends_with("ic_launcher.png") && !b_lower.ends_with("ic_launcher.png") {
+                    return std::cmp::Ordering::Less;
+                }
+                if !a_lower.ends_with("ic_launcher.png") && b_lower.ends_with("ic_launcher.png") {
+                    return std::cmp::Ordering::Greater;
+                }
+
+                // ic_launcher_round.png 次优先
+                if a_lower.ends_with("ic_launcher_round.png") && !b_lower.ends_with("ic_launcher_round.png") {
+                    return std::cmp::Ordering::Less;
+                }
+                if !a_lower.ends_with("ic_launcher_round.png") && b_lower.ends_with("ic_launcher_round.png") {
+                    return std::cmp::Ordering::Greater;
+                }
+
+                // 其他情况按字母顺序
+                a.cmp(b)
+            });
+
+            // 尝试读取优先级最高的图标
+            if let Some(icon) = read_first_icon(&mut archive, &icons_in_this_dpi) {
+                return Some(icon);
+            }
+        }
+    }
+
+    log::debug!("{} 中未找到 ic_launcher 开头的 PNG 图标", file_path.display());
+    None
+}
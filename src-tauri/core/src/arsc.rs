@@ -0,0 +1,343 @@
+// ==================== resources.arsc 资源表解析 ====================
+// 只解析资源 ID -> 名称/取值所需的部分，用于把 manifest 中的 @0x7f... 引用还原成文件路径或字符串
+
+use std::collections::HashMap;
+
+use crate::chunk::{
+    self, read_u16, read_u32, read_u8, ChunkHeader, ResValue, RES_STRING_POOL_TYPE,
+    RES_TABLE_PACKAGE_TYPE, RES_TABLE_TYPE, RES_TABLE_TYPE_TYPE, TYPE_REFERENCE, TYPE_STRING,
+};
+
+const NO_ENTRY: u32 = 0xffff_ffff;
+const FLAG_SPARSE: u8 = 0x01;
+const FLAG_OFFSET16: u8 = 0x02;
+const ENTRY_FLAG_COMPLEX: u16 = 0x0001;
+const ENTRY_FLAG_COMPACT: u16 = 0x0008;
+
+/// 资源配置（只保留常用维度）
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResConfig {
+    pub language: String,
+    pub country: String,
+    pub density: u16,
+    pub sdk_version: u16,
+}
+
+impl ResConfig {
+    /// 是否为默认配置
+    pub fn is_default(&self) -> bool {
+        self.language.is_empty() && self.country.is_empty() && self.density == 0 && self.sdk_version == 0
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ResEntry {
+    pub type_name: String,
+    pub key: String,
+    pub config: ResConfig,
+    /// 复杂资源（style、array 等）没有单一取值
+    pub value: Option<ResValue>,
+}
+
+#[derive(Debug, Default)]
+pub struct ResourceTable {
+    /// 全局字符串池
+    pub strings: Vec<String>,
+    pub package_names: Vec<String>,
+    entries: HashMap<u32, Vec<ResEntry>>,
+}
+
+impl ResourceTable {
+    /// 解析 resources.arsc
+    pub fn parse(data: &[u8]) -> Result<Self, String> {
+        let header = ChunkHeader::read(data, 0)?;
+        if header.chunk_type != RES_TABLE_TYPE {
+            return Err("不是有效的 resources.arsc 文件".to_string());
+        }
+
+        let mut table = ResourceTable::default();
+        let mut offset = header.header_size as usize;
+        let end = header.size as usize;
+
+        while offset + 8 <= end {
+            let chunk_header = ChunkHeader::read(data, offset)?;
+            match chunk_header.chunk_type {
+                RES_STRING_POOL_TYPE => table.strings = chunk::read_string_pool(data, offset)?,
+                RES_TABLE_PACKAGE_TYPE => table.parse_package(data, offset, chunk_header)?,
+                _ => {}
+            }
+            offset += chunk_header.size as usize;
+        }
+
+        Ok(table)
+    }
+
+    fn parse_package(&mut self, data: &[u8], offset: usize, header: ChunkHeader) -> Result<(), String> {
+        let package_id = read_u32(data, offset + 8)?;
+
+        let name_units: Vec<u16> = (0..128)
+            .map(|i| read_u16(data, offset + 12 + i * 2))
+            .collect::<Result<_, _>>()?;
+        let name_len = name_units.iter().position(|&c| c == 0).unwrap_or(name_units.len());
+        self.package_names.push(String::from_utf16_lossy(&name_units[..name_len]));
+
+        let type_strings_offset = read_u32(data, offset + 268)? as usize;
+        let key_strings_offset = read_u32(data, offset + 276)? as usize;
+        let type_names = chunk::read_string_pool(data, offset + type_strings_offset)?;
+        let key_names = chunk::read_string_pool(data, offset + key_strings_offset)?;
+
+        let end = offset + header.size as usize;
+        let mut chunk_offset = offset + header.header_size as usize;
+        while chunk_offset + 8 <= end {
+            let chunk_header = ChunkHeader::read(data, chunk_offset)?;
+            if chunk_header.chunk_type == RES_TABLE_TYPE_TYPE {
+                self.parse_type(data, chunk_offset, chunk_header, package_id, &type_names, &key_names)?;
+            }
+            chunk_offset += chunk_header.size as usize;
+        }
+
+        Ok(())
+    }
+
+    fn parse_type(
+        &mut self,
+        data: &[u8],
+        offset: usize,
+        header: ChunkHeader,
+        package_id: u32,
+        type_names: &[String],
+        key_names: &[String],
+    ) -> Result<(), String> {
+        let type_id = read_u8(data, offset + 8)? as u32;
+        let flags = read_u8(data, offset + 9)?;
+        let entry_count = read_u32(data, offset + 12)? as usize;
+        let entries_start = offset + read_u32(data, offset + 16)? as usize;
+        let config = read_config(data, offset + 20)?;
+        // 类型 ID 从 1 开始
+        let type_name = type_id
+            .checked_sub(1)
+            .and_then(|index| type_names.get(index as usize))
+            .cloned()
+            .ok_or_else(|| format!("资源类型 ID 无效: {}", type_id))?;
+
+        // 收集 (条目索引, 条目偏移)
+        let index_start = offset + header.header_size as usize;
+        let mut entry_offsets = Vec::new();
+        for i in 0..entry_count {
+            if flags & FLAG_SPARSE != 0 {
+                let index = read_u16(data, index_start + i * 4)? as u32;
+                let entry_offset = read_u16(data, index_start + i * 4 + 2)? as u32 * 4;
+                entry_offsets.push((index, entry_offset));
+            } else if flags & FLAG_OFFSET16 != 0 {
+                let entry_offset = read_u16(data, index_start + i * 2)?;
+                if entry_offset != 0xffff {
+                    entry_offsets.push((i as u32, entry_offset as u32 * 4));
+                }
+            } else {
+                let entry_offset = read_u32(data, index_start + i * 4)?;
+                if entry_offset != NO_ENTRY {
+                    entry_offsets.push((i as u32, entry_offset));
+                }
+            }
+        }
+
+        for (index, entry_offset) in entry_offsets {
+            let entry_pos = entries_start + entry_offset as usize;
+            let entry_flags = read_u16(data, entry_pos + 2)?;
+
+            let (key_index, value) = if entry_flags & ENTRY_FLAG_COMPACT != 0 {
+                // 紧凑格式：key(u16) flags(u16，高 8 位为数据类型) data(u32)
+                let value = ResValue {
+                    data_type: (entry_flags >> 8) as u8,
+                    data: read_u32(data, entry_pos + 4)?,
+                };
+                (read_u16(data, entry_pos)? as u32, Some(value))
+            } else if entry_flags & ENTRY_FLAG_COMPLEX != 0 {
+                (read_u32(data, entry_pos + 4)?, None)
+            } else {
+                let entry_size = read_u16(data, entry_pos)? as usize;
+                (read_u32(data, entry_pos + 4)?, Some(ResValue::read(data, entry_pos + entry_size)?))
+            };
+
+            let resource_id = (package_id << 24) | (type_id << 16) | index;
+            self.entries.entry(resource_id).or_default().push(ResEntry {
+                type_name: type_name.clone(),
+                key: key_names.get(key_index as usize).cloned().unwrap_or_default(),
+                config: config.clone(),
+                value,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// 某个资源 ID 在所有配置下的条目
+    pub fn entries(&self, resource_id: u32) -> &[ResEntry] {
+        self.entries.get(&resource_id).map(|v| v.as_slice()).unwrap_or(&[])
+    }
+
+    /// 资源名称，如 "xml/network_security_config"
+    pub fn name_of(&self, resource_id: u32) -> Option<String> {
+        self.entries(resource_id)
+            .first()
+            .map(|entry| format!("{}/{}", entry.type_name, entry.key))
+    }
+
+    /// 解析字符串类型的资源（会跟随引用），优先返回默认配置的值
+    pub fn resolve_string(&self, resource_id: u32) -> Option<String> {
+        self.resolve_all_strings(resource_id)
+            .into_iter()
+            .min_by_key(|(config, _)| !config.is_default())
+            .map(|(_, value)| value)
+    }
+
+    /// 解析字符串类型的资源在所有配置下的值（常用于文件路径，如不同密度的图标）
+    pub fn resolve_all_strings(&self, resource_id: u32) -> Vec<(ResConfig, String)> {
+        self.resolve_strings_with_depth(resource_id, 0)
+    }
+
+    fn resolve_strings_with_depth(&self, resource_id: u32, depth: u32) -> Vec<(ResConfig, String)> {
+        // 防止循环引用
+        if depth > 8 {
+            return Vec::new();
+        }
+
+        let mut results = Vec::new();
+        for entry in self.entries(resource_id) {
+            match entry.value {
+                Some(value) if value.data_type == TYPE_STRING => {
+                    if let Some(s) = self.strings.get(value.data as usize) {
+                        results.push((entry.config.clone(), s.clone()));
+                    }
+                }
+                Some(value) if value.data_type == TYPE_REFERENCE => {
+                    for (_, s) in self.resolve_strings_with_depth(value.data, depth + 1) {
+                        results.push((entry.config.clone(), s));
+                    }
+                }
+                _ => {}
+            }
+        }
+        results
+    }
+}
+
+// 解析 ResTable_config 中的语言、地区、密度和 SDK 版本
+fn read_config(data: &[u8], offset: usize) -> Result<ResConfig, String> {
+    let size = read_u32(data, offset)? as usize;
+    let read_chars = |pos: usize| -> Result<String, String> {
+        let bytes = [read_u8(data, pos)?, read_u8(data, pos + 1)?];
+        Ok(bytes.iter().filter(|b| **b != 0).map(|b| *b as char).collect())
+    };
+
+    Ok(ResConfig {
+        language: read_chars(offset + 8)?,
+        country: read_chars(offset + 10)?,
+        density: read_u16(data, offset + 14)?,
+        sdk_version: if size >= 28 { read_u16(data, offset + 24)? } else { 0 },
+    })
+}
+
+/// 把 manifest 属性中的资源引用（"0x7f120003" 或 "@0x7f120003"）解析为资源 ID
+pub fn parse_reference(value: &str) -> Option<u32> {
+    let hex = value.trim_start_matches('@').strip_prefix("0x")?;
+    u32::from_str_radix(hex, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunk::{write_string_pool, write_u16, write_u32};
+
+    /// 拼出只有一个包、一个类型 chunk、一个字符串条目的 resources.arsc
+    fn table_with_type(type_id: u8) -> Vec<u8> {
+        let global_strings = write_string_pool(&["Demo".to_string()]);
+        let type_strings = write_string_pool(&["string".to_string()]);
+        let key_strings = write_string_pool(&["app_name".to_string()]);
+
+        // 类型 chunk：头部 20 字节 + 64 字节配置，之后是 1 个条目偏移和条目本身
+        const TYPE_HEADER_SIZE: u16 = 20 + 64;
+        let mut type_chunk = Vec::new();
+        write_u16(&mut type_chunk, RES_TABLE_TYPE_TYPE);
+        write_u16(&mut type_chunk, TYPE_HEADER_SIZE);
+        write_u32(&mut type_chunk, TYPE_HEADER_SIZE as u32 + 4 + 16);
+        type_chunk.push(type_id);
+        type_chunk.push(0);
+        write_u16(&mut type_chunk, 0);
+        write_u32(&mut type_chunk, 1);
+        write_u32(&mut type_chunk, TYPE_HEADER_SIZE as u32 + 4);
+        write_u32(&mut type_chunk, 64);
+        type_chunk.resize(TYPE_HEADER_SIZE as usize, 0);
+        write_u32(&mut type_chunk, 0);
+        // 条目：size(u16) flags(u16) key(u32)，之后是 Res_value
+        write_u16(&mut type_chunk, 8);
+        write_u16(&mut type_chunk, 0);
+        write_u32(&mut type_chunk, 0);
+        ResValue { data_type: TYPE_STRING, data: 0 }.write(&mut type_chunk);
+
+        const PACKAGE_HEADER_SIZE: u32 = 288;
+        let mut package = Vec::new();
+        write_u16(&mut package, RES_TABLE_PACKAGE_TYPE);
+        write_u16(&mut package, PACKAGE_HEADER_SIZE as u16);
+        write_u32(
+            &mut package,
+            PACKAGE_HEADER_SIZE + (type_strings.len() + key_strings.len() + type_chunk.len()) as u32,
+        );
+        write_u32(&mut package, 0x7f);
+        let mut name: Vec<u16> = "com.example".encode_utf16().collect();
+        name.resize(128, 0);
+        for unit in name {
+            write_u16(&mut package, unit);
+        }
+        write_u32(&mut package, PACKAGE_HEADER_SIZE);
+        write_u32(&mut package, 1);
+        write_u32(&mut package, PACKAGE_HEADER_SIZE + type_strings.len() as u32);
+        write_u32(&mut package, 1);
+        write_u32(&mut package, 0);
+        package.extend_from_slice(&type_strings);
+        package.extend_from_slice(&key_strings);
+        package.extend_from_slice(&type_chunk);
+
+        let mut table = Vec::new();
+        write_u16(&mut table, RES_TABLE_TYPE);
+        write_u16(&mut table, 12);
+        write_u32(&mut table, 12 + (global_strings.len() + package.len()) as u32);
+        write_u32(&mut table, 1);
+        table.extend_from_slice(&global_strings);
+        table.extend_from_slice(&package);
+        table
+    }
+
+    #[test]
+    fn parses_string_resource() {
+        let table = ResourceTable::parse(&table_with_type(1)).unwrap();
+        assert_eq!(table.package_names, vec!["com.example".to_string()]);
+        assert_eq!(table.name_of(0x7f01_0000).as_deref(), Some("string/app_name"));
+        assert_eq!(table.resolve_string(0x7f01_0000).as_deref(), Some("Demo"));
+    }
+
+    #[test]
+    fn rejects_type_id_zero() {
+        assert!(ResourceTable::parse(&table_with_type(0)).is_err());
+    }
+
+    #[test]
+    fn rejects_type_id_without_name() {
+        assert!(ResourceTable::parse(&table_with_type(9)).is_err());
+    }
+
+    #[test]
+    fn rejects_truncated_table() {
+        let data = table_with_type(1);
+        for len in [0, 8, 12, data.len() / 2, data.len() - 1] {
+            assert!(ResourceTable::parse(&data[..len]).is_err());
+        }
+    }
+
+    #[test]
+    fn parses_references() {
+        assert_eq!(parse_reference("@0x7f120003"), Some(0x7f12_0003));
+        assert_eq!(parse_reference("0x01010000"), Some(0x0101_0000));
+        assert_eq!(parse_reference("@string/app_name"), None);
+    }
+}
@@ -0,0 +1,608 @@
+// ==================== 二进制 XML (AXML) 无损读写 ====================
+// rusty_axml 会丢弃文本节点、属性类型和资源 ID，这里保留完整信息，
+// 用于解析 res/xml 下的配置文件以及修改后重新编码 AndroidManifest.xml
+
+use std::collections::HashMap;
+
+use crate::chunk::{
+    self, read_u16, read_u32, write_u16, write_u32, ChunkHeader, ResValue, RES_STRING_POOL_TYPE,
+    RES_XML_CDATA_TYPE, RES_XML_END_ELEMENT_TYPE, RES_XML_END_NAMESPACE_TYPE, RES_XML_RESOURCE_MAP_TYPE,
+    RES_XML_START_ELEMENT_TYPE, RES_XML_START_NAMESPACE_TYPE, RES_XML_TYPE, TYPE_STRING,
+};
+
+const NO_INDEX: u32 = 0xffff_ffff;
+pub const ANDROID_NAMESPACE: &str = "http://schemas.android.com/apk/res/android";
+
+#[derive(Debug, Clone)]
+pub struct Attribute {
+    pub namespace: Option<String>,
+    pub name: String,
+    /// 资源 ID（如 android:versionCode 为 0x0101021b），没有时为 0
+    pub resource_id: u32,
+    pub raw_value: Option<String>,
+    pub value: ResValue,
+}
+
+#[derive(Debug, Clone)]
+pub enum Node {
+    Element(Element),
+    Text(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct Element {
+    pub namespace: Option<String>,
+    pub name: String,
+    pub attributes: Vec<Attribute>,
+    pub children: Vec<Node>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Namespace {
+    pub prefix: String,
+    pub uri: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Document {
+    pub namespaces: Vec<Namespace>,
+    pub root: Element,
+}
+
+impl Attribute {
+    /// 属性值的可读形式
+    pub fn display_value(&self) -> String {
+        match &self.raw_value {
+            Some(raw) => raw.clone(),
+            None => self.value.to_display(&[]),
+        }
+    }
+}
+
+impl Element {
+    /// 按名称查找属性（忽略命名空间）
+    pub fn attr(&self, name: &str) -> Option<&Attribute> {
+        self.attributes.iter().find(|a| a.name == name)
+    }
+
+    /// 按名称读取属性的可读值
+    pub fn attr_value(&self, name: &str) -> Option<String> {
+        self.attr(name).map(|a| a.display_value())
+    }
+
+    /// 按命名空间和名称查找可修改的属性
+    pub fn attr_mut(&mut self, namespace: Option<&str>, name: &str) -> Option<&mut Attribute> {
+        self.attributes
+            .iter_mut()
+            .find(|a| a.namespace.as_deref() == namespace && a.name == name)
+    }
+
+    /// 直接子元素（可修改）
+    pub fn child_elements_mut(&mut self) -> impl Iterator<Item = &mut Element> {
+        self.children.iter_mut().filter_map(|node| match node {
+            Node::Element(element) => Some(element),
+            Node::Text(_) => None,
+        })
+    }
+
+    /// 直接子元素
+    pub fn child_elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|node| match node {
+            Node::Element(element) => Some(element),
+            Node::Text(_) => None,
+        })
+    }
+
+    /// 指定名称的直接子元素
+    pub fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> + 'a {
+        self.child_elements().filter(move |e| e.name == name)
+    }
+
+    /// 拼接所有文本子节点
+    pub fn text(&self) -> String {
+        self.children
+            .iter()
+            .filter_map(|node| match node {
+                Node::Text(text) => Some(text.as_str()),
+                Node::Element(_) => None,
+            })
+            .collect::<String>()
+            .trim()
+            .to_string()
+    }
+}
+
+/// 解析二进制 XML
+pub fn parse(data: &[u8]) -> Result<Document, String> {
+    let file_header = ChunkHeader::read(data, 0)?;
+    if file_header.chunk_type != RES_XML_TYPE {
+        return Err("不是有效的二进制 XML 文件".to_string());
+    }
+
+    let end = file_header.size as usize;
+    let mut offset = file_header.header_size as usize;
+
+    let mut strings: Vec<String> = Vec::new();
+    let mut resource_ids: Vec<u32> = Vec::new();
+    let mut namespaces: Vec<Namespace> = Vec::new();
+    let mut stack: Vec<Element> = Vec::new();
+    let mut root: Option<Element> = None;
+
+    let string_at = |strings: &[String], index: u32| -> Option<String> {
+        if index == NO_INDEX {
+            None
+        } else {
+            strings.get(index as usize).cloned()
+        }
+    };
+
+    while offset + 8 <= end {
+        let header = ChunkHeader::read(data, offset)?;
+        // XML 节点的扩展数据紧跟在 16 字节的 ResXMLTree_node 之后
+        let body = offset + header.header_size as usize;
+
+        match header.chunk_type {
+            RES_STRING_POOL_TYPE => {
+                strings = chunk::read_string_pool(data, offset)?;
+            }
+            RES_XML_RESOURCE_MAP_TYPE => {
+                let map_size = header
+                    .size
+                    .checked_sub(header.header_size as u32)
+                    .ok_or("资源映射表 chunk 大小无效")?;
+                let count = map_size as usize / 4;
+                resource_ids = (0..count)
+                    .map(|i| read_u32(data, offset + header.header_size as usize + i * 4))
+                    .collect::<Result<_, _>>()?;
+            }
+            RES_XML_START_NAMESPACE_TYPE => {
+                let prefix = string_at(&strings, read_u32(data, body)?).unwrap_or_default();
+                let uri = string_at(&strings, read_u32(data, body + 4)?).unwrap_or_default();
+                if !namespaces.iter().any(|ns| ns.prefix == prefix && ns.uri == uri) {
+                    namespaces.push(Namespace { prefix, uri });
+                }
+            }
+            RES_XML_START_ELEMENT_TYPE => {
+                let namespace = string_at(&strings, read_u32(data, body)?);
+                let name = string_at(&strings, read_u32(data, body + 4)?).unwrap_or_default();
+                let attribute_start = read_u16(data, body + 8)? as usize;
+                let attribute_size = read_u16(data, body + 10)? as usize;
+                let attribute_count = read_u16(data, body + 12)? as usize;
+
+                let mut attributes = Vec::with_capacity(attribute_count);
+                for i in 0..attribute_count {
+                    let attr_offset = body + attribute_start + i * attribute_size;
+                    let namespace = string_at(&strings, read_u32(data, attr_offset)?);
+                    let name_index = read_u32(data, attr_offset + 4)?;
+                    let raw_index = read_u32(data, attr_offset + 8)?;
+                    let value = ResValue::read(data, attr_offset + 12)?;
+                    // 字符串类型的值统一通过 raw_value 读取
+                    let raw_value = match string_at(&strings, raw_index) {
+                        None if value.data_type == TYPE_STRING => string_at(&strings, value.data),
+                        raw => raw,
+                    };
+                    attributes.push(Attribute {
+                        namespace,
+                        name: string_at(&strings, name_index).unwrap_or_default(),
+                        resource_id: resource_ids.get(name_index as usize).copied().unwrap_or(0),
+                        raw_value,
+                        value,
+                    });
+                }
+
+                stack.push(Element {
+                    namespace,
+                    name,
+                    attributes,
+                    children: Vec::new(),
+                });
+            }
+            RES_XML_END_ELEMENT_TYPE => {
+                let element = stack.pop().ok_or("XML 结束标签不匹配")?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(Node::Element(element)),
+                    None => root = Some(element),
+                }
+            }
+            RES_XML_CDATA_TYPE => {
+                if let (Some(text), Some(parent)) =
+                    (string_at(&strings, read_u32(data, body)?), stack.last_mut())
+                {
+                    parent.children.push(Node::Text(text));
+                }
+            }
+            _ => {}
+        }
+
+        offset += header.size as usize;
+    }
+
+    let root = root.ok_or("未找到 XML 根节点")?;
+    Ok(Document { namespaces, root })
+}
+
+// ==================== 编码 ====================
+
+// 字符串池构建：带资源 ID 的属性名必须排在最前面，与资源映射表一一对应
+struct StringPoolBuilder {
+    strings: Vec<String>,
+    resource_ids: Vec<u32>,
+    resource_index: HashMap<(String, u32), u32>,
+    index: HashMap<String, u32>,
+}
+
+impl StringPoolBuilder {
+    fn new(root: &Element) -> Self {
+        let mut builder = StringPoolBuilder {
+            strings: Vec::new(),
+            resource_ids: Vec::new(),
+            resource_index: HashMap::new(),
+            index: HashMap::new(),
+        };
+        builder.collect_resource_names(root);
+        builder
+    }
+
+    fn collect_resource_names(&mut self, element: &Element) {
+        for attr in element.attributes.iter().filter(|a| a.resource_id != 0) {
+            let key = (attr.name.clone(), attr.resource_id);
+            if !self.resource_index.contains_key(&key) {
+                self.resource_index.insert(key, self.strings.len() as u32);
+                self.strings.push(attr.name.clone());
+                self.resource_ids.push(attr.resource_id);
+            }
+        }
+        for child in element.child_elements() {
+            self.collect_resource_names(child);
+        }
+    }
+
+    fn intern(&mut self, value: &str) -> u32 {
+        if let Some(index) = self.index.get(value) {
+            return *index;
+        }
+        let index = self.strings.len() as u32;
+        self.strings.push(value.to_string());
+        self.index.insert(value.to_string(), index);
+        index
+    }
+
+    fn intern_optional(&mut self, value: Option<&str>) -> u32 {
+        value.map(|v| self.intern(v)).unwrap_or(NO_INDEX)
+    }
+
+    fn attribute_name(&mut self, attr: &Attribute) -> u32 {
+        match self.resource_index.get(&(attr.name.clone(), attr.resource_id)) {
+            Some(index) if attr.resource_id != 0 => *index,
+            _ => self.intern(&attr.name),
+        }
+    }
+}
+
+// 写入 XML 节点头：type, headerSize(16), size, lineNumber, comment
+fn write_node_header(out: &mut Vec<u8>, chunk_type: u16, size: u32, line_number: u32) {
+    write_u16(out, chunk_type);
+    write_u16(out, 16);
+    write_u32(out, size);
+    write_u32(out, line_number);
+    write_u32(out, NO_INDEX);
+}
+
+fn encode_element(element: &Element, pool: &mut StringPoolBuilder, out: &mut Vec<u8>, line: &mut u32) {
+    const ATTRIBUTE_SIZE: u16 = 20;
+
+    *line += 1;
+    let namespace = pool.intern_optional(element.namespace.as_deref());
+    let name = pool.intern(&element.name);
+
+    // id / class / style 属性的位置（从 1 开始，0 表示没有）
+    let special_index = |attr_name: &str| -> u16 {
+        element
+            .attributes
+            .iter()
+            .position(|a| a.namespace.is_none() && a.name == attr_name)
+            .map(|i| i as u16 + 1)
+            .unwrap_or(0)
+    };
+
+    let size = 36 + element.attributes.len() as u32 * ATTRIBUTE_SIZE as u32;
+    write_node_header(out, RES_XML_START_ELEMENT_TYPE, size, *line);
+    write_u32(out, namespace);
+    write_u32(out, name);
+    write_u16(out, 20); // attributeStart
+    write_u16(out, ATTRIBUTE_SIZE);
+    write_u16(out, element.attributes.len() as u16);
+    write_u16(out, special_index("id"));
+    write_u16(out, special_index("class"));
+    write_u16(out, special_index("style"));
+
+    for attr in &element.attributes {
+        write_u32(out, pool.intern_optional(attr.namespace.as_deref()));
+        write_u32(out, pool.attribute_name(attr));
+        let mut value = attr.value;
+        if value.data_type == TYPE_STRING {
+            // 字符串值与 rawValue 指向同一个字符串
+            let index = pool.intern(attr.raw_value.as_deref().unwrap_or(""));
+            value.data = index;
+            write_u32(out, index);
+        } else {
+            write_u32(out, pool.intern_optional(attr.raw_value.as_deref()));
+        }
+        value.write(out);
+    }
+
+    for child in &element.children {
+        match child {
+            Node::Element(child) => encode_element(child, pool, out, line),
+            Node::Text(text) => {
+                *line += 1;
+                let index = pool.intern(text);
+                write_node_header(out, RES_XML_CDATA_TYPE, 28, *line);
+                write_u32(out, index);
+                ResValue {
+                    data_type: TYPE_STRING,
+                    data: index,
+                }
+                .write(out);
+            }
+        }
+    }
+
+    *line += 1;
+    write_node_header(out, RES_XML_END_ELEMENT_TYPE, 24, *line);
+    write_u32(out, namespace);
+    write_u32(out, name);
+}
+
+/// 编码为二进制 XML
+pub fn encode(document: &Document) -> Vec<u8> {
+    let mut pool = StringPoolBuilder::new(&document.root);
+    let mut body = Vec::new();
+    let mut line = 1;
+
+    let namespaces: Vec<(u32, u32)> = document
+        .namespaces
+        .iter()
+        .map(|ns| (pool.intern(&ns.prefix), pool.intern(&ns.uri)))
+        .collect();
+
+    for (prefix, uri) in &namespaces {
+        write_node_header(&mut body, RES_XML_START_NAMESPACE_TYPE, 24, line);
+        write_u32(&mut body, *prefix);
+        write_u32(&mut body, *uri);
+    }
+    encode_element(&document.root, &mut pool, &mut body, &mut line);
+    for (prefix, uri) in namespaces.iter().rev() {
+        write_node_header(&mut body, RES_XML_END_NAMESPACE_TYPE, 24, line);
+        write_u32(&mut body, *prefix);
+        write_u32(&mut body, *uri);
+    }
+
+    let string_pool = chunk::write_string_pool(&pool.strings);
+    let mut resource_map = Vec::with_capacity(8 + pool.resource_ids.len() * 4);
+    write_u16(&mut resource_map, RES_XML_RESOURCE_MAP_TYPE);
+    write_u16(&mut resource_map, 8);
+    write_u32(&mut resource_map, 8 + pool.resource_ids.len() as u32 * 4);
+    for id in &pool.resource_ids {
+        write_u32(&mut resource_map, *id);
+    }
+
+    let size = 8 + string_pool.len() + resource_map.len() + body.len();
+    let mut out = Vec::with_capacity(size);
+    write_u16(&mut out, RES_XML_TYPE);
+    write_u16(&mut out, 8);
+    write_u32(&mut out, size as u32);
+    out.extend_from_slice(&string_pool);
+    out.extend_from_slice(&resource_map);
+    out.extend_from_slice(&body);
+    out
+}
+
+/// 测试用：把文本 XML 转为 Document，android: / dist: 前缀放到对应命名空间，属性值均按字符串保存
+#[cfg(test)]
+pub(crate) fn document_from_xml(xml: &str) -> Document {
+    use quick_xml::events::{BytesStart, Event};
+    use quick_xml::Reader;
+
+    fn split_prefix(key: &[u8]) -> (Option<String>, String) {
+        let key = String::from_utf8_lossy(key).to_string();
+        if let Some(name) = key.strip_prefix("android:") {
+            (Some(ANDROID_NAMESPACE.to_string()), name.to_string())
+        } else if let Some(name) = key.strip_prefix("dist:") {
+            (Some(crate::bundle::DIST_NAMESPACE.to_string()), name.to_string())
+        } else {
+            (None, key)
+        }
+    }
+
+    fn element_from(start: &BytesStart) -> Element {
+        let attributes = start
+            .attributes()
+            .map(|attr| attr.expect("属性格式错误"))
+            .filter(|attr| !attr.key.as_ref().starts_with(b"xmlns"))
+            .map(|attr| {
+                let (namespace, name) = split_prefix(attr.key.as_ref());
+                Attribute {
+                    namespace,
+                    name,
+                    resource_id: 0,
+                    raw_value: Some(attr.unescape_value().expect("属性值格式错误").to_string()),
+                    value: ResValue { data_type: TYPE_STRING, data: 0 },
+                }
+            })
+            .collect();
+        let (namespace, name) = split_prefix(start.name().as_ref());
+        Element {
+            namespace,
+            name,
+            attributes,
+            children: Vec::new(),
+        }
+    }
+
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+    let mut stack: Vec<Element> = Vec::new();
+    let mut root = None;
+    loop {
+        let finished = match reader.read_event().expect("XML 格式错误") {
+            Event::Start(start) => {
+                stack.push(element_from(&start));
+                None
+            }
+            Event::Empty(start) => Some(element_from(&start)),
+            Event::End(_) => stack.pop(),
+            Event::Text(text) => {
+                let text = text.decode().expect("文本格式错误").to_string();
+                if let Some(parent) = stack.last_mut() {
+                    parent.children.push(Node::Text(text));
+                }
+                None
+            }
+            Event::Eof => break,
+            _ => None,
+        };
+        if let Some(element) = finished {
+            match stack.last_mut() {
+                Some(parent) => parent.children.push(Node::Element(element)),
+                None => root = Some(element),
+            }
+        }
+    }
+    Document {
+        namespaces: vec![Namespace {
+            prefix: "android".to_string(),
+            uri: ANDROID_NAMESPACE.to_string(),
+        }],
+        root: root.expect("缺少根元素"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunk::{TYPE_INT_BOOLEAN, TYPE_INT_DEC};
+    use crate::manifest;
+    use std::io::Cursor;
+
+    fn string_attr(namespace: Option<&str>, name: &str, resource_id: u32, value: &str) -> Attribute {
+        Attribute {
+            namespace: namespace.map(str::to_string),
+            name: name.to_string(),
+            resource_id,
+            raw_value: Some(value.to_string()),
+            value: ResValue { data_type: TYPE_STRING, data: 0 },
+        }
+    }
+
+    fn int_attr(name: &str, resource_id: u32, data_type: u8, data: u32) -> Attribute {
+        Attribute {
+            namespace: Some(ANDROID_NAMESPACE.to_string()),
+            name: name.to_string(),
+            resource_id,
+            raw_value: None,
+            value: ResValue { data_type, data },
+        }
+    }
+
+    fn sample_manifest() -> Document {
+        let application = Element {
+            namespace: None,
+            name: "application".to_string(),
+            attributes: vec![
+                int_attr("debuggable", 0x0101_000f, TYPE_INT_BOOLEAN, 0xffff_ffff),
+                string_attr(Some(ANDROID_NAMESPACE), "label", 0x0101_0001, "Demo"),
+            ],
+            children: vec![Node::Text("hello".to_string())],
+        };
+        Document {
+            namespaces: vec![Namespace {
+                prefix: "android".to_string(),
+                uri: ANDROID_NAMESPACE.to_string(),
+            }],
+            root: Element {
+                namespace: None,
+                name: "manifest".to_string(),
+                attributes: vec![
+                    int_attr("versionCode", 0x0101_021b, TYPE_INT_DEC, 7),
+                    string_attr(Some(ANDROID_NAMESPACE), "versionName", 0x0101_021c, "1.0.7"),
+                    string_attr(None, "package", 0, "com.example"),
+                ],
+                children: vec![Node::Element(application)],
+            },
+        }
+    }
+
+    #[test]
+    fn encode_then_parse_round_trip() {
+        let data = encode(&sample_manifest());
+        let document = parse(&data).unwrap();
+
+        assert_eq!(document.namespaces.len(), 1);
+        assert_eq!(document.namespaces[0].uri, ANDROID_NAMESPACE);
+        let root = &document.root;
+        assert_eq!(root.name, "manifest");
+        assert_eq!(root.attr_value("package").as_deref(), Some("com.example"));
+        assert_eq!(root.attr_value("versionName").as_deref(), Some("1.0.7"));
+        let version_code = root.attr("versionCode").unwrap();
+        assert_eq!(version_code.resource_id, 0x0101_021b);
+        assert_eq!(version_code.namespace.as_deref(), Some(ANDROID_NAMESPACE));
+        assert_eq!(version_code.value, ResValue { data_type: TYPE_INT_DEC, data: 7 });
+
+        let application = root.children_named("application").next().unwrap();
+        assert_eq!(application.attr("debuggable").unwrap().resource_id, 0x0101_000f);
+        assert_eq!(application.text(), "hello");
+
+        // 再次编码结果不变
+        assert_eq!(encode(&document), data);
+    }
+
+    #[test]
+    fn encoded_manifest_is_readable_by_rusty_axml() {
+        // rusty_axml 不支持 CDATA 节点，去掉文本后再比较
+        let mut document = sample_manifest();
+        for application in document.root.child_elements_mut() {
+            application.children.clear();
+        }
+        let data = encode(&document);
+        let axml = rusty_axml::parse_from_reader(Cursor::new(data)).unwrap();
+
+        let root = axml.root();
+        let root = root.borrow();
+        assert_eq!(root.element_type(), "manifest");
+        assert_eq!(manifest::get_attr(&root, "package").as_deref(), Some("com.example"));
+        assert_eq!(manifest::get_int_attr(&root, "versionCode"), Some(7));
+        assert_eq!(manifest::get_attr(&root, "versionName").as_deref(), Some("1.0.7"));
+
+        let application = manifest::application_node(&axml).unwrap();
+        assert_eq!(manifest::get_bool_attr(&application.borrow(), "debuggable"), Some(true));
+    }
+
+    #[test]
+    fn invalid_resource_map_header_is_rejected() {
+        let mut data = encode(&sample_manifest());
+        // 资源映射表紧跟在字符串池之后，把 headerSize 改为大于 chunk 大小
+        let pool_size = read_u32(&data, 8 + 4).unwrap() as usize;
+        let map_offset = 8 + pool_size;
+        assert_eq!(read_u16(&data, map_offset).unwrap(), RES_XML_RESOURCE_MAP_TYPE);
+        data[map_offset + 2..map_offset + 4].copy_from_slice(&0x100u16.to_le_bytes());
+        assert!(parse(&data).is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_documents() {
+        assert!(parse(&[]).is_err());
+        assert!(parse(&[0u8; 8]).is_err());
+
+        let data = encode(&sample_manifest());
+        for len in [8, data.len() / 2, data.len() - 1] {
+            assert!(parse(&data[..len]).is_err());
+        }
+        // 只有开始标签、没有根节点
+        let mut truncated = data.clone();
+        truncated.truncate(data.len() - 24 * 3);
+        let size = truncated.len() as u32;
+        truncated[4..8].copy_from_slice(&size.to_le_bytes());
+        assert!(parse(&truncated).is_err());
+    }
+}
@@ -0,0 +1,820 @@
+// ==================== 从 AAB 生成 APK ====================
+// 离线完成 bundletool build-apks 的常用场景：按设备描述生成该设备需要安装的拆分 APK，
+// 或把 base 和安装时模块合并成一个 universal APK。资源表和 XML 从 protobuf 格式转换为二进制格式
+
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::axml::{self, Document, Element, Namespace, Node, ANDROID_NAMESPACE};
+use crate::bundle::{self, BundleModule};
+use crate::device_compat::{self, DeviceSpec, MIN_SPLIT_SDK};
+use crate::error::AnalysisError;
+use crate::manifest::read_entry;
+use crate::manifest_editor::{resource_id_for, set_attribute};
+use crate::resource_table::{self, ConfigValue, Entry, ResourceTable};
+use crate::signing::{self, SignOptions};
+use crate::zipalign;
+
+const MANIFEST_ENTRY: &str = "AndroidManifest.xml";
+const RESOURCES_PROTO: &str = "resources.pb";
+const RESOURCES_ENTRY: &str = "resources.arsc";
+const UNIVERSAL_FILE_NAME: &str = "universal.apk";
+
+// 不压缩存储的文件：原生库可直接映射加载，resources.arsc 需要 mmap，媒体文件本身已压缩
+const NO_COMPRESS_EXTENSIONS: &[&str] = &[
+    ".so", ".arsc", ".png", ".jpg", ".jpeg", ".gif", ".webp", ".mp3", ".mp4", ".ogg", ".wav", ".m4a",
+];
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BuiltApk {
+    pub module: String,
+    /// 拆分名，base 模块的主 APK 为 "base"，universal APK 为 "universal"
+    pub split: String,
+    pub file_path: String,
+    pub file_size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BuildApksResult {
+    pub output_dir: String,
+    /// device / universal
+    pub mode: String,
+    pub apks: Vec<BuiltApk>,
+    pub signed: bool,
+    /// 安装命令（adb install 或 adb install-multiple）
+    pub install_command: String,
+    pub warnings: Vec<String>,
+}
+
+// 拆分 APK 包含的内容
+enum SplitTarget {
+    Master,
+    Abi(String),
+    Density(u32),
+    Language(String),
+}
+
+impl SplitTarget {
+    // 根据拆分名判断维度，如 config.arm64_v8a、feature.config.xxhdpi、config.zh
+    fn from_split(split: &str) -> Self {
+        let Some((_, suffix)) = split.split_once("config.") else {
+            return SplitTarget::Master;
+        };
+        if let Some(abi) = device_compat::abi_of_split(suffix) {
+            SplitTarget::Abi(abi.to_string())
+        } else if let Some(dpi) = device_compat::bucket_dpi(suffix) {
+            SplitTarget::Density(dpi)
+        } else {
+            SplitTarget::Language(suffix.to_string())
+        }
+    }
+}
+
+// 待写入 APK 的条目：(APK 内路径, 数据)
+type ApkEntries = Vec<(String, Vec<u8>)>;
+
+// ==================== 模块内容 ====================
+
+fn read_module_file(archive: &mut ZipArchive<File>, module: &str, path: &str) -> Result<Vec<u8>, String> {
+    read_entry(archive, &format!("{}/{}", module, path))
+}
+
+fn module_table(archive: &mut ZipArchive<File>, module: &BundleModule) -> Result<Option<ResourceTable>, String> {
+    if !module.entries.iter().any(|e| e == RESOURCES_PROTO) {
+        return Ok(None);
+    }
+    let data = read_module_file(archive, &module.name, RESOURCES_PROTO)?;
+    ResourceTable::from_proto(&data)
+        .map(Some)
+        .map_err(|e| format!("解析 {} 模块资源表失败: {}", module.name, e))
+}
+
+// dex/ 下的文件按 classes.dex、classes2.dex ... 的顺序排列
+fn dex_files(module: &BundleModule) -> Vec<&str> {
+    let mut files: Vec<&str> = module
+        .entries
+        .iter()
+        .filter_map(|e| e.strip_prefix("dex/"))
+        .filter(|e| e.ends_with(".dex") && !e.contains('/'))
+        .collect();
+    files.sort_by_key(|name| {
+        name.trim_start_matches("classes")
+            .trim_end_matches(".dex")
+            .parse::<u32>()
+            .unwrap_or(1)
+    });
+    files
+}
+
+fn dex_name(index: usize) -> String {
+    if index == 1 {
+        "classes.dex".to_string()
+    } else {
+        format!("classes{}.dex", index)
+    }
+}
+
+// 资源文件（res/ 下的 proto XML 转换为二进制 XML）
+fn read_res_file(archive: &mut ZipArchive<File>, module: &str, path: &str, proto_xml: bool) -> Result<Vec<u8>, String> {
+    let data = read_module_file(archive, module, path)?;
+    if !proto_xml {
+        return Ok(data);
+    }
+    let document = bundle::parse_proto_xml(&data).map_err(|e| format!("转换 {}/{} 失败: {}", module, path, e))?;
+    Ok(axml::encode(&document))
+}
+
+// 资源表引用的文件
+fn resource_files(archive: &mut ZipArchive<File>, module: &str, table: &ResourceTable, entries: &mut ApkEntries) -> Result<(), String> {
+    let mut seen = HashSet::new();
+    for (path, proto_xml) in table.files() {
+        if seen.insert(path) {
+            entries.push((path.to_string(), read_res_file(archive, module, path, proto_xml)?));
+        }
+    }
+    Ok(())
+}
+
+// 主 APK 中除 manifest 和资源表之外的文件：dex、root/、assets/、lib/ 和资源表未引用的 res/ 文件
+fn module_files(
+    archive: &mut ZipArchive<File>,
+    module: &BundleModule,
+    table: Option<&ResourceTable>,
+    include_lib: bool,
+    dex_offset: usize,
+    entries: &mut ApkEntries,
+) -> Result<usize, String> {
+    let dex = dex_files(module);
+    for (index, name) in dex.iter().enumerate() {
+        let data = read_module_file(archive, &module.name, &format!("dex/{}", name))?;
+        entries.push((dex_name(dex_offset + index + 1), data));
+    }
+
+    let referenced: HashSet<&str> = table.map(|t| t.files().into_iter().map(|(path, _)| path).collect()).unwrap_or_default();
+    for path in &module.entries {
+        let target = if let Some(rest) = path.strip_prefix("root/") {
+            rest.to_string()
+        } else if path.starts_with("assets/")
+            || (include_lib && path.starts_with("lib/"))
+            || (path.starts_with("res/") && !referenced.contains(path.as_str()))
+        {
+            path.clone()
+        } else {
+            continue;
+        };
+        if target.is_empty() || target.ends_with('/') {
+            continue;
+        }
+        entries.push((target, read_module_file(archive, &module.name, path)?));
+    }
+    Ok(dex.len())
+}
+
+// ==================== 资源拆分 ====================
+
+// 同一配置（除密度外）下，选择不低于目标密度的最小密度，没有时选最大密度
+fn is_best_density(entry: &Entry, value: &ConfigValue, target: u32) -> bool {
+    let base = value.config.without_density();
+    let densities: Vec<u32> = entry
+        .values
+        .iter()
+        .filter(|v| v.config.has_specific_density() && v.config.without_density() == base)
+        .map(|v| v.config.density as u32)
+        .collect();
+    let best = densities
+        .iter()
+        .filter(|d| **d >= target)
+        .min()
+        .or_else(|| densities.iter().max());
+    best == Some(&(value.config.density as u32))
+}
+
+fn split_table(table: &ResourceTable, target: &SplitTarget, density_split: bool, language_split: bool) -> ResourceTable {
+    table.filter(|entry, value| {
+        let localized = !value.config.language.is_empty();
+        let density_specific = value.config.has_specific_density();
+        match target {
+            SplitTarget::Master => !(language_split && localized || density_split && density_specific),
+            SplitTarget::Density(dpi) => {
+                density_specific && !(language_split && localized) && is_best_density(entry, value, *dpi)
+            }
+            SplitTarget::Language(language) => value.config.language == *language,
+            SplitTarget::Abi(_) => false,
+        }
+    })
+}
+
+// ==================== Manifest ====================
+
+fn application_mut(document: &mut Document) -> &mut Element {
+    if !document.root.child_elements().any(|e| e.name == "application") {
+        document.root.children.push(Node::Element(Element {
+            namespace: None,
+            name: "application".to_string(),
+            attributes: Vec::new(),
+            children: Vec::new(),
+        }));
+    }
+    document
+        .root
+        .child_elements_mut()
+        .find(|e| e.name == "application")
+        .expect("application 节点已存在")
+}
+
+fn set_android_bool(document: &mut Document, element_is_root: bool, name: &str, value: bool) -> Result<(), String> {
+    let resource_id = resource_id_for(document, Some(ANDROID_NAMESPACE), name)?;
+    let element = if element_is_root { &mut document.root } else { application_mut(document) };
+    set_attribute(element, Some(ANDROID_NAMESPACE), name, resource_id, &value.to_string(), Some("bool"))
+}
+
+// 主 APK 的 manifest：功能模块标记为 feature split，有配置拆分时 base 要求安装拆分
+fn master_manifest(module: &BundleModule, split_required: bool, has_code: bool) -> Result<Vec<u8>, String> {
+    let mut document = module.manifest.clone();
+    if module.name != "base" {
+        if document.root.attr("split").is_none() {
+            set_attribute(&mut document.root, None, "split", 0, &module.name, Some("string"))?;
+        }
+        set_android_bool(&mut document, true, "isFeatureSplit", true)?;
+    }
+    if split_required {
+        set_android_bool(&mut document, false, "isSplitRequired", true)?;
+    }
+    if !has_code {
+        set_android_bool(&mut document, false, "hasCode", false)?;
+    }
+    Ok(axml::encode(&document))
+}
+
+// 配置拆分的 manifest 只包含包名、版本号和拆分名
+fn config_manifest(base: &Document, module: &str, split: &str) -> Result<Vec<u8>, String> {
+    let mut root = Element {
+        namespace: None,
+        name: "manifest".to_string(),
+        attributes: Vec::new(),
+        children: Vec::new(),
+    };
+    // 带资源 ID 的属性排在前面
+    for name in ["versionCode", "package"] {
+        if let Some(attr) = base.root.attr(name) {
+            root.attributes.push(attr.clone());
+        }
+    }
+    set_attribute(&mut root, None, "split", 0, split, Some("string"))?;
+    if module != "base" {
+        set_attribute(&mut root, None, "configForSplit", 0, module, Some("string"))?;
+    }
+
+    let mut document = Document {
+        namespaces: vec![Namespace {
+            prefix: "android".to_string(),
+            uri: ANDROID_NAMESPACE.to_string(),
+        }],
+        root,
+    };
+    let resource_id = resource_id_for(base, Some(ANDROID_NAMESPACE), "hasCode")?;
+    set_attribute(application_mut(&mut document), Some(ANDROID_NAMESPACE), "hasCode", resource_id, "false", Some("bool"))?;
+    Ok(axml::encode(&document))
+}
+
+// universal APK 的 manifest：合并功能模块 application 下的组件，去掉拆分要求
+fn universal_manifest(base: &BundleModule, features: &[&BundleModule]) -> Result<Vec<u8>, String> {
+    let mut document = base.manifest.clone();
+    let components: Vec<Node> = features
+        .iter()
+        .flat_map(|module| module.manifest.root.children_named("application"))
+        .flat_map(|application| application.children.iter().cloned())
+        .collect();
+    let application = application_mut(&mut document);
+    application
+        .attributes
+        .retain(|a| !["isSplitRequired", "requiredSplitTypes", "splitTypes"].contains(&a.name.as_str()));
+    application.children.extend(components);
+    Ok(axml::encode(&document))
+}
+
+// ==================== 写入 APK ====================
+
+fn write_apk(path: &Path, manifest: Vec<u8>, entries: ApkEntries, warnings: &mut Vec<String>) -> Result<(), String> {
+    let output = File::create(path).map_err(|e| format!("无法创建输出文件: {}", e))?;
+    let mut writer = ZipWriter::new(output);
+    let mut seen = HashSet::new();
+
+    for (name, data) in std::iter::once((MANIFEST_ENTRY.to_string(), manifest)).chain(entries) {
+        if !seen.insert(name.clone()) {
+            warnings.push(format!("{} 中存在重复的文件 {}，已保留第一个", path.display(), name));
+            continue;
+        }
+        let lower = name.to_lowercase();
+        let method = if NO_COMPRESS_EXTENSIONS.iter().any(|ext| lower.ends_with(ext)) {
+            CompressionMethod::Stored
+        } else {
+            CompressionMethod::Deflated
+        };
+        writer
+            .start_file(name.as_str(), SimpleFileOptions::default().compression_method(method))
+            .map_err(|e| format!("写入 {} 失败: {}", name, e))?;
+        writer.write_all(&data).map_err(|e| format!("写入 {} 失败: {}", name, e))?;
+    }
+    writer.finish().map_err(|e| format!("写入 APK 失败: {}", e))?;
+
+    // apksigner 要求签名前完成对齐，原生库按页对齐
+    zipalign::align_apk(path, path, None)?;
+    Ok(())
+}
+
+fn finish_apk(path: &Path, module: &str, split: &str, sign_options: Option<&SignOptions>) -> Result<BuiltApk, String> {
+    if let Some(options) = sign_options {
+        signing::sign_apk(path, options).map_err(|e| e.to_string())?;
+    }
+    Ok(BuiltApk {
+        module: module.to_string(),
+        split: split.to_string(),
+        file_path: path.to_string_lossy().to_string(),
+        file_size: fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+    })
+}
+
+// ==================== 生成 ====================
+
+fn build_device_apks(
+    archive: &mut ZipArchive<File>,
+    config: &bundle::BundleConfig,
+    modules: &[BundleModule],
+    spec: &DeviceSpec,
+    output_dir: &Path,
+    sign_options: Option<&SignOptions>,
+    warnings: &mut Vec<String>,
+) -> Result<Vec<BuiltApk>, String> {
+    // 缺少系统版本时无法判断任何按版本拆分的 APK，不能当作匹配全部
+    if spec.sdk_version == 0 {
+        return Err("设备描述缺少 sdkVersion".to_string());
+    }
+    if spec.sdk_version < MIN_SPLIT_SDK {
+        return Err("设备系统版本低于 Android 5.0，不支持拆分 APK，请生成 universal APK".to_string());
+    }
+    if spec.supported_abis.is_empty() && modules.iter().any(|m| !m.abis().is_empty()) {
+        warnings.push("设备描述未提供 supportedAbis，生成的 APK 不含原生库".to_string());
+    }
+
+    let selected = device_compat::select_bundle_splits(config, modules, spec, warnings);
+    let base_manifest = &modules[0].manifest;
+    let mut apks = Vec::new();
+
+    for module in modules {
+        let splits: Vec<_> = selected.iter().filter(|s| s.module == module.name).collect();
+        if splits.is_empty() {
+            continue;
+        }
+        let table = module_table(archive, module)?;
+        let targets: Vec<SplitTarget> = splits.iter().map(|s| SplitTarget::from_split(&s.split)).collect();
+        let density_split = targets.iter().any(|t| matches!(t, SplitTarget::Density(_)));
+        let has_config_splits = targets.iter().any(|t| !matches!(t, SplitTarget::Master));
+
+        for (split, target) in splits.iter().zip(&targets) {
+            let file_name = split
+                .file
+                .as_deref()
+                .and_then(|f| f.rsplit('/').next())
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("{}.apk", split.split));
+            let path = output_dir.join(&file_name);
+
+            let mut entries = ApkEntries::new();
+            let split_table = table
+                .as_ref()
+                .map(|t| split_table(t, target, density_split, config.splits.language))
+                .filter(|t| !t.is_empty());
+            let manifest = match target {
+                SplitTarget::Master => {
+                    let dex_count = module_files(archive, module, table.as_ref(), !config.splits.abi, 0, &mut entries)?;
+                    master_manifest(module, module.name == "base" && has_config_splits, dex_count > 0)?
+                }
+                SplitTarget::Abi(abi) => {
+                    let prefix = format!("lib/{}/", abi);
+                    for path in module.entries.iter().filter(|e| e.starts_with(&prefix) && !e.ends_with('/')) {
+                        entries.push((path.clone(), read_module_file(archive, &module.name, path)?));
+                    }
+                    config_manifest(base_manifest, &module.name, &split.split)?
+                }
+                SplitTarget::Density(_) | SplitTarget::Language(_) => {
+                    config_manifest(base_manifest, &module.name, &split.split)?
+                }
+            };
+            if let Some(table) = &split_table {
+                resource_files(archive, &module.name, table, &mut entries)?;
+                entries.push((RESOURCES_ENTRY.to_string(), resource_table::encode(table)));
+            }
+
+            write_apk(&path, manifest, entries, warnings)?;
+            apks.push(finish_apk(&path, &module.name, &split.split, sign_options)?);
+        }
+    }
+    Ok(apks)
+}
+
+fn build_universal_apk(
+    archive: &mut ZipArchive<File>,
+    modules: &[BundleModule],
+    output_dir: &Path,
+    sign_options: Option<&SignOptions>,
+    warnings: &mut Vec<String>,
+) -> Result<Vec<BuiltApk>, String> {
+    let fused: Vec<&BundleModule> = modules.iter().filter(|m| m.name == "base" || m.fused()).collect();
+    let skipped: Vec<&str> = modules.iter().filter(|m| m.name != "base" && !m.fused()).map(|m| m.name.as_str()).collect();
+    if !skipped.is_empty() {
+        warnings.push(format!("以下功能模块不合并进 universal APK: {}", skipped.join(", ")));
+    }
+
+    let mut entries = ApkEntries::new();
+    let mut merged = ResourceTable::default();
+    let mut dex_count = 0;
+    for module in &fused {
+        let table = module_table(archive, module)?;
+        dex_count += module_files(archive, module, table.as_ref(), true, dex_count, &mut entries)?;
+        if let Some(table) = table {
+            resource_files(archive, &module.name, &table, &mut entries)?;
+            merged.merge(table);
+        }
+    }
+    if !merged.is_empty() {
+        entries.push((RESOURCES_ENTRY.to_string(), resource_table::encode(&merged)));
+    }
+
+    let manifest = universal_manifest(fused[0], &fused[1..])?;
+    let path = output_dir.join(UNIVERSAL_FILE_NAME);
+    write_apk(&path, manifest, entries, warnings)?;
+    Ok(vec![finish_apk(&path, "base", "universal", sign_options)?])
+}
+
+/// 从 AAB 生成 APK
+///
+/// mode 为 device 时按设备描述（device-spec.json）生成拆分 APK，为 universal 时生成单个 universal APK。
+pub fn build_apks(
+    bundle_path: &Path,
+    output_dir: &Path,
+    mode: &str,
+    device_spec: Option<&str>,
+    sign_options: Option<&SignOptions>,
+) -> Result<BuildApksResult, AnalysisError> {
+    if !bundle_path.exists() {
+        return Err(AnalysisError::NotFound);
+    }
+    let file = File::open(bundle_path)?;
+    let mut archive = ZipArchive::new(file)?;
+    let (config, modules) = bundle::read_bundle(&mut archive)?;
+    fs::create_dir_all(output_dir).map_err(|e| format!("无法创建输出目录: {}", e))?;
+
+    let mut warnings = Vec::new();
+    let apks = match mode {
+        "device" => {
+            let spec_text = device_spec.ok_or("device 模式需要提供设备描述")?;
+            let spec: DeviceSpec = serde_json::from_str(spec_text).map_err(|e| format!("设备描述格式错误: {}", e))?;
+            build_device_apks(&mut archive, &config, &modules, &spec, output_dir, sign_options, &mut warnings)?
+        }
+        "universal" => build_universal_apk(&mut archive, &modules, output_dir, sign_options, &mut warnings)?,
+        other => return Err(format!("不支持的生成模式: {}", other).into()),
+    };
+
+    let signed = sign_options.is_some();
+    if !signed {
+        warnings.push("输出的 APK 未签名，安装前需要签名".to_string());
+    }
+
+    let paths: Vec<String> = apks.iter().map(|apk| format!("\"{}\"", apk.file_path)).collect();
+    let install_command = if apks.len() == 1 {
+        format!("adb install {}", paths[0])
+    } else {
+        format!("adb install-multiple {}", paths.join(" "))
+    };
+
+    Ok(BuildApksResult {
+        output_dir: output_dir.to_string_lossy().to_string(),
+        mode: mode.to_string(),
+        apks,
+        signed,
+        install_command,
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arsc;
+    use std::io::{Cursor, Read};
+
+    // ==================== 手工拼装 aapt2 protobuf ====================
+
+    fn varint(out: &mut Vec<u8>, mut value: u64) {
+        while value >= 0x80 {
+            out.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+        out.push(value as u8);
+    }
+
+    fn bytes_field(number: u32, payload: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        varint(&mut out, ((number << 3) | 2) as u64);
+        varint(&mut out, payload.len() as u64);
+        out.extend_from_slice(payload);
+        out
+    }
+
+    fn varint_field(number: u32, value: u64) -> Vec<u8> {
+        let mut out = Vec::new();
+        varint(&mut out, (number << 3) as u64);
+        varint(&mut out, value);
+        out
+    }
+
+    // XmlAttribute：namespace_uri=1, name=2, value=3, resource_id=5, compiled_item=6
+    fn proto_attribute(android: bool, name: &str, value: &str, resource_id: u32, item: Option<Vec<u8>>) -> Vec<u8> {
+        let mut out = Vec::new();
+        if android {
+            out.extend(bytes_field(1, ANDROID_NAMESPACE.as_bytes()));
+        }
+        out.extend(bytes_field(2, name.as_bytes()));
+        out.extend(bytes_field(3, value.as_bytes()));
+        if resource_id != 0 {
+            out.extend(varint_field(5, resource_id as u64));
+        }
+        if let Some(item) = item {
+            out.extend(bytes_field(6, &item));
+        }
+        out
+    }
+
+    // XmlElement：namespace_declaration=1, name=3, attribute=4, child=5（XmlNode.element=1）
+    fn proto_element(name: &str, declare_android: bool, attributes: &[Vec<u8>], children: &[Vec<u8>]) -> Vec<u8> {
+        let mut out = Vec::new();
+        if declare_android {
+            let mut namespace = bytes_field(1, b"android");
+            namespace.extend(bytes_field(2, ANDROID_NAMESPACE.as_bytes()));
+            out.extend(bytes_field(1, &namespace));
+        }
+        out.extend(bytes_field(3, name.as_bytes()));
+        for attribute in attributes {
+            out.extend(bytes_field(4, attribute));
+        }
+        for child in children {
+            out.extend(bytes_field(5, &bytes_field(1, child)));
+        }
+        out
+    }
+
+    fn proto_manifest() -> Vec<u8> {
+        // Item.prim=7 -> Primitive.int_decimal_value=6
+        let version_code = bytes_field(7, &varint_field(6, 3));
+        let label = bytes_field(2, &bytes_field(1, b"Demo"));
+        let application = proto_element(
+            "application",
+            false,
+            &[proto_attribute(true, "label", "Demo", 0x0101_0001, Some(label))],
+            &[],
+        );
+        let manifest = proto_element(
+            "manifest",
+            true,
+            &[
+                proto_attribute(true, "versionCode", "3", 0x0101_021b, Some(version_code)),
+                proto_attribute(false, "package", "com.example", 0, None),
+            ],
+            &[application],
+        );
+        bytes_field(1, &manifest)
+    }
+
+    fn proto_layout() -> Vec<u8> {
+        let orientation = bytes_field(7, &varint_field(6, 1));
+        let layout = proto_element(
+            "LinearLayout",
+            true,
+            &[proto_attribute(true, "orientation", "vertical", 0x0101_00c4, Some(orientation))],
+            &[],
+        );
+        bytes_field(1, &layout)
+    }
+
+    // ConfigValue：config=1（Configuration.locale=3, density=18），value=2（Value.item=4）
+    fn config_value(locale: &str, density: u64, item: Vec<u8>) -> Vec<u8> {
+        let mut config = Vec::new();
+        if !locale.is_empty() {
+            config.extend(bytes_field(3, locale.as_bytes()));
+        }
+        if density != 0 {
+            config.extend(varint_field(18, density));
+        }
+        let mut out = bytes_field(1, &config);
+        out.extend(bytes_field(2, &bytes_field(4, &item)));
+        out
+    }
+
+    fn string_item(text: &str) -> Vec<u8> {
+        bytes_field(2, &bytes_field(1, text.as_bytes()))
+    }
+
+    // FileReference：path=1, type=2（1=PNG，3=PROTO_XML）
+    fn file_item(path: &str, file_type: u64) -> Vec<u8> {
+        let mut file = bytes_field(1, path.as_bytes());
+        file.extend(varint_field(2, file_type));
+        bytes_field(5, &file)
+    }
+
+    fn proto_type(id: u64, name: &str, entry_name: &str, values: &[Vec<u8>]) -> Vec<u8> {
+        let mut entry = bytes_field(1, &varint_field(1, 0));
+        entry.extend(bytes_field(2, entry_name.as_bytes()));
+        for value in values {
+            entry.extend(bytes_field(6, value));
+        }
+        let mut out = bytes_field(1, &varint_field(1, id));
+        out.extend(bytes_field(2, name.as_bytes()));
+        out.extend(bytes_field(3, &entry));
+        out
+    }
+
+    fn proto_resources() -> Vec<u8> {
+        let mut package = bytes_field(1, &varint_field(1, 0x7f));
+        package.extend(bytes_field(2, b"com.example"));
+        for resource_type in [
+            proto_type(
+                1,
+                "string",
+                "app_name",
+                &[
+                    config_value("", 0, string_item("Demo")),
+                    config_value("zh-CN", 0, string_item("演示")),
+                    config_value("fr", 0, string_item("Démo")),
+                ],
+            ),
+            proto_type(
+                2,
+                "drawable",
+                "icon",
+                &[
+                    config_value("", 160, file_item("res/drawable-mdpi-v4/icon.png", 1)),
+                    config_value("", 480, file_item("res/drawable-xxhdpi-v4/icon.png", 1)),
+                ],
+            ),
+            proto_type(3, "layout", "main", &[config_value("", 0, file_item("res/layout/main.xml", 3))]),
+        ] {
+            package.extend(bytes_field(3, &resource_type));
+        }
+        bytes_field(2, &package)
+    }
+
+    fn write_bundle(path: &Path) {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        let files: Vec<(&str, Vec<u8>)> = vec![
+            ("BundleConfig.pb", Vec::new()),
+            ("base/manifest/AndroidManifest.xml", proto_manifest()),
+            ("base/resources.pb", proto_resources()),
+            ("base/dex/classes.dex", b"dex\n035\0primary".to_vec()),
+            ("base/dex/classes2.dex", b"dex\n035\0secondary".to_vec()),
+            ("base/lib/arm64-v8a/libapp.so", b"\x7fELF arm64".to_vec()),
+            ("base/lib/x86_64/libapp.so", b"\x7fELF x86_64".to_vec()),
+            ("base/res/drawable-mdpi-v4/icon.png", b"mdpi".to_vec()),
+            ("base/res/drawable-xxhdpi-v4/icon.png", b"xxhdpi".to_vec()),
+            ("base/res/layout/main.xml", proto_layout()),
+            ("base/assets/config.json", b"{}".to_vec()),
+            ("base/root/kotlin/kotlin.kotlin_builtins", b"builtins".to_vec()),
+        ];
+        for (name, data) in files {
+            writer.start_file(name, options).unwrap();
+            writer.write_all(&data).unwrap();
+        }
+        fs::write(path, writer.finish().unwrap().into_inner()).unwrap();
+    }
+
+    // ==================== 检查输出 ====================
+
+    struct Apk {
+        names: Vec<String>,
+        archive: ZipArchive<Cursor<Vec<u8>>>,
+    }
+
+    impl Apk {
+        fn open(path: &Path) -> Self {
+            let archive = ZipArchive::new(Cursor::new(fs::read(path).unwrap())).unwrap();
+            let mut names: Vec<String> = archive.file_names().map(str::to_string).collect();
+            names.sort();
+            Apk { names, archive }
+        }
+
+        fn read(&mut self, name: &str) -> Vec<u8> {
+            let mut data = Vec::new();
+            self.archive.by_name(name).unwrap().read_to_end(&mut data).unwrap();
+            data
+        }
+
+        fn manifest(&mut self) -> Document {
+            axml::parse(&self.read(MANIFEST_ENTRY)).unwrap()
+        }
+
+        fn strings(&mut self) -> Vec<String> {
+            let table = arsc::ResourceTable::parse(&self.read(RESOURCES_ENTRY)).unwrap();
+            let mut strings: Vec<String> = table.resolve_all_strings(0x7f01_0000).into_iter().map(|(_, s)| s).collect();
+            strings.sort();
+            strings
+        }
+    }
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("build_apks_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn builds_device_splits_from_bundle() {
+        let dir = temp_dir("device");
+        let bundle_path = dir.join("app.aab");
+        write_bundle(&bundle_path);
+        let spec = r#"{"sdkVersion":30,"supportedAbis":["arm64-v8a"],"screenDensity":480,"supportedLocales":["zh-CN"]}"#;
+        let result = build_apks(&bundle_path, &dir.join("out"), "device", Some(spec), None).unwrap();
+
+        let splits: Vec<&str> = result.apks.iter().map(|a| a.split.as_str()).collect();
+        assert_eq!(splits, ["base", "config.arm64_v8a", "config.xxhdpi", "config.zh"]);
+        assert!(result.install_command.starts_with("adb install-multiple "));
+        assert!(!result.signed);
+        let mut apks: Vec<Apk> = result.apks.iter().map(|a| Apk::open(Path::new(&a.file_path))).collect();
+        let _ = fs::remove_dir_all(&dir);
+
+        // 主 APK：代码、assets、root/ 下的文件和不区分配置的资源，proto XML 转换为二进制 XML
+        let base = &mut apks[0];
+        assert_eq!(
+            base.names,
+            [
+                "AndroidManifest.xml",
+                "assets/config.json",
+                "classes.dex",
+                "classes2.dex",
+                "kotlin/kotlin.kotlin_builtins",
+                "res/layout/main.xml",
+                "resources.arsc",
+            ]
+        );
+        let manifest = base.manifest();
+        assert_eq!(manifest.root.attr_value("package").as_deref(), Some("com.example"));
+        assert_eq!(manifest.root.attr("versionCode").unwrap().value.data, 3);
+        let application = manifest.root.children_named("application").next().unwrap();
+        assert_eq!(application.attr_value("label").as_deref(), Some("Demo"));
+        assert_eq!(application.attr_value("isSplitRequired").as_deref(), Some("true"));
+        let layout = axml::parse(&base.read("res/layout/main.xml")).unwrap();
+        assert_eq!(layout.root.name, "LinearLayout");
+        assert_eq!(layout.root.attr("orientation").unwrap().value.data, 1);
+        assert_eq!(base.strings(), ["Demo"]);
+
+        // 配置拆分：manifest 只带包名、版本号和拆分名，不含代码
+        let abi = &mut apks[1];
+        assert_eq!(abi.names, ["AndroidManifest.xml", "lib/arm64-v8a/libapp.so"]);
+        let manifest = abi.manifest();
+        assert_eq!(manifest.root.attr_value("split").as_deref(), Some("config.arm64_v8a"));
+        assert_eq!(manifest.root.attr("versionCode").unwrap().value.data, 3);
+        let application = manifest.root.children_named("application").next().unwrap();
+        assert_eq!(application.attr_value("hasCode").as_deref(), Some("false"));
+
+        let density = &mut apks[2];
+        assert_eq!(density.names, ["AndroidManifest.xml", "res/drawable-xxhdpi-v4/icon.png", "resources.arsc"]);
+        assert_eq!(density.manifest().root.attr_value("split").as_deref(), Some("config.xxhdpi"));
+
+        let language = &mut apks[3];
+        assert_eq!(language.names, ["AndroidManifest.xml", "resources.arsc"]);
+        assert_eq!(language.strings(), ["演示"]);
+    }
+
+    #[test]
+    fn builds_universal_apk_from_bundle() {
+        let dir = temp_dir("universal");
+        let bundle_path = dir.join("app.aab");
+        write_bundle(&bundle_path);
+        let result = build_apks(&bundle_path, &dir.join("out"), "universal", None, None).unwrap();
+        assert_eq!(result.apks.len(), 1);
+        assert!(result.install_command.starts_with("adb install "));
+        let mut apk = Apk::open(Path::new(&result.apks[0].file_path));
+        let _ = fs::remove_dir_all(&dir);
+
+        for name in [
+            "lib/arm64-v8a/libapp.so",
+            "lib/x86_64/libapp.so",
+            "res/drawable-mdpi-v4/icon.png",
+            "res/drawable-xxhdpi-v4/icon.png",
+            "classes2.dex",
+        ] {
+            assert!(apk.names.iter().any(|n| n == name), "缺少 {}", name);
+        }
+        assert_eq!(apk.strings(), ["Demo", "Démo", "演示"]);
+        let manifest = apk.manifest();
+        let application = manifest.root.children_named("application").next().unwrap();
+        assert!(application.attr("isSplitRequired").is_none());
+    }
+}
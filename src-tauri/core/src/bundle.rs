@@ -0,0 +1,367 @@
+// ==================== Android App Bundle (AAB) 读取 ====================
+// AAB 中每个模块一个目录（base/、feature/ 等），manifest 和资源表是 aapt2 的 protobuf 格式，
+// 这里把 proto XML 转换成与二进制 XML 相同的 Document，并读取模块分发方式和拆分维度
+
+use std::collections::BTreeSet;
+use std::io::{Read, Seek};
+
+use zip::ZipArchive;
+
+use crate::axml::{Attribute, Document, Element, Namespace, Node};
+use crate::chunk::{
+    ResValue, TYPE_ATTRIBUTE, TYPE_DIMENSION, TYPE_FLOAT, TYPE_FRACTION, TYPE_INT_BOOLEAN,
+    TYPE_INT_COLOR_ARGB4, TYPE_INT_COLOR_ARGB8, TYPE_INT_COLOR_RGB4, TYPE_INT_COLOR_RGB8, TYPE_INT_DEC,
+    TYPE_INT_HEX, TYPE_NULL, TYPE_REFERENCE, TYPE_STRING,
+};
+use crate::manifest::read_entry;
+use crate::protobuf::Message;
+
+pub const DIST_NAMESPACE: &str = "http://schemas.android.com/apk/distribution";
+const BUNDLE_CONFIG_PATH: &str = "BundleConfig.pb";
+const MANIFEST_PATH: &str = "manifest/AndroidManifest.xml";
+const RESOURCES_PATH: &str = "resources.pb";
+
+// 资源配置中的特殊密度：任意密度、不缩放
+const DENSITY_ANY: u32 = 0xfffe;
+const DENSITY_NONE: u32 = 0xffff;
+
+/// BundleConfig.pb 中可关闭的拆分维度，未配置时全部开启
+#[derive(Debug, Clone, Copy)]
+pub struct SplitDimensions {
+    pub abi: bool,
+    pub density: bool,
+    pub language: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct BundleConfig {
+    pub splits: SplitDimensions,
+}
+
+/// 模块分发条件（dist:conditions）
+#[derive(Debug, Clone)]
+pub enum DeliveryCondition {
+    DeviceFeature(String),
+    MinSdk(u32),
+    MaxSdk(u32),
+    /// 用户国家/地区、设备组等无法离线判断的条件
+    Other(String),
+}
+
+#[derive(Debug, Clone)]
+pub enum ModuleDelivery {
+    InstallTime,
+    Conditional(Vec<DeliveryCondition>),
+    OnDemand,
+}
+
+#[derive(Debug, Clone)]
+pub struct BundleModule {
+    pub name: String,
+    pub manifest: Document,
+    /// 模块目录下的文件（去掉模块名前缀）
+    pub entries: Vec<String>,
+    /// 资源表中出现的语言和密度
+    pub languages: BTreeSet<String>,
+    pub densities: BTreeSet<u32>,
+}
+
+impl BundleModule {
+    /// lib/ 下包含原生库的 ABI
+    pub fn abis(&self) -> BTreeSet<String> {
+        self.entries
+            .iter()
+            .filter_map(|e| e.strip_prefix("lib/"))
+            .filter(|e| e.ends_with(".so"))
+            .filter_map(|e| e.split('/').next())
+            .map(|abi| abi.to_string())
+            .collect()
+    }
+
+    pub fn delivery(&self) -> ModuleDelivery {
+        module_delivery(&self.manifest)
+    }
+
+    /// 是否合并进 universal APK（dist:fusing），未声明时按是否安装时分发判断
+    pub fn fused(&self) -> bool {
+        let fusing = dist_module(&self.manifest).and_then(|m| m.child_elements().find(|e| e.name == "fusing"));
+        match fusing.and_then(|f| f.attr_value("include")) {
+            Some(include) => include == "true",
+            None => matches!(self.delivery(), ModuleDelivery::InstallTime),
+        }
+    }
+}
+
+// ==================== proto XML 转换 ====================
+
+// aapt2 Item 转换为 Res_value，字符串类型同时返回原始字符串
+pub fn convert_item(item: &Message) -> Result<(ResValue, Option<String>), String> {
+    let value = |data_type: u8, data: u32| ResValue { data_type, data };
+
+    if let Some(reference) = item.message(1)? {
+        let data_type = if reference.varint(1) == Some(1) { TYPE_ATTRIBUTE } else { TYPE_REFERENCE };
+        return Ok((value(data_type, reference.varint(2).unwrap_or(0) as u32), None));
+    }
+    // String / RawString / StyledString / FileReference 的第一个字段都是字符串
+    for field in [2, 3, 4, 5] {
+        if let Some(text) = item.message(field)? {
+            return Ok((value(TYPE_STRING, 0), Some(text.string(1))));
+        }
+    }
+    if item.has(6) {
+        return Ok((value(TYPE_INT_BOOLEAN, 0), None));
+    }
+
+    let Some(primitive) = item.message(7)? else {
+        return Err("不支持的资源值类型".to_string());
+    };
+    let data = |field: u32| primitive.varint(field).unwrap_or(0) as u32;
+    let converted = if primitive.has(1) {
+        value(TYPE_NULL, 0)
+    } else if primitive.has(2) {
+        // DATA_NULL_EMPTY
+        value(TYPE_NULL, 1)
+    } else if primitive.has(3) {
+        value(TYPE_FLOAT, data(3))
+    } else if primitive.has(13) || primitive.has(4) {
+        value(TYPE_DIMENSION, data(if primitive.has(13) { 13 } else { 4 }))
+    } else if primitive.has(14) || primitive.has(5) {
+        value(TYPE_FRACTION, data(if primitive.has(14) { 14 } else { 5 }))
+    } else if primitive.has(6) {
+        value(TYPE_INT_DEC, data(6))
+    } else if primitive.has(7) {
+        value(TYPE_INT_HEX, data(7))
+    } else if primitive.has(8) {
+        // 二进制格式中 true 为 0xffffffff
+        value(TYPE_INT_BOOLEAN, if data(8) != 0 { 0xffff_ffff } else { 0 })
+    } else if primitive.has(9) {
+        value(TYPE_INT_COLOR_ARGB8, data(9))
+    } else if primitive.has(10) {
+        value(TYPE_INT_COLOR_RGB8, data(10))
+    } else if primitive.has(11) {
+        value(TYPE_INT_COLOR_ARGB4, data(11))
+    } else if primitive.has(12) {
+        value(TYPE_INT_COLOR_RGB4, data(12))
+    } else {
+        value(TYPE_NULL, 0)
+    };
+    Ok((converted, None))
+}
+
+fn optional(text: String) -> Option<String> {
+    (!text.is_empty()).then_some(text)
+}
+
+fn convert_element(element: &Message, namespaces: &mut Vec<Namespace>) -> Result<Element, String> {
+    for namespace in element.messages(1)? {
+        let declared = Namespace {
+            prefix: namespace.string(1),
+            uri: namespace.string(2),
+        };
+        if !namespaces.iter().any(|ns| ns.prefix == declared.prefix && ns.uri == declared.uri) {
+            namespaces.push(declared);
+        }
+    }
+
+    let mut attributes = Vec::new();
+    for attribute in element.messages(4)? {
+        let raw = attribute.string(3);
+        let (value, raw_value) = match attribute.message(6)? {
+            Some(item) => convert_item(&item)?,
+            // 未编译的属性按字符串保存
+            None => (ResValue { data_type: TYPE_STRING, data: 0 }, Some(raw)),
+        };
+        attributes.push(Attribute {
+            namespace: optional(attribute.string(1)),
+            name: attribute.string(2),
+            resource_id: attribute.varint(5).unwrap_or(0) as u32,
+            raw_value,
+            value,
+        });
+    }
+
+    let mut children = Vec::new();
+    for child in element.messages(5)? {
+        if let Some(child_element) = child.message(1)? {
+            children.push(Node::Element(convert_element(&child_element, namespaces)?));
+        } else if child.has(2) {
+            children.push(Node::Text(child.string(2)));
+        }
+    }
+
+    Ok(Element {
+        namespace: optional(element.string(2)),
+        name: element.string(3),
+        attributes,
+        children,
+    })
+}
+
+/// 把 aapt2 proto 格式的 XmlNode 转换为 Document
+pub fn parse_proto_xml(data: &[u8]) -> Result<Document, String> {
+    let node = Message::parse(data)?;
+    let root = node.message(1)?.ok_or("proto XML 中没有根元素")?;
+    let mut namespaces = Vec::new();
+    let root = convert_element(&root, &mut namespaces)?;
+    Ok(Document { namespaces, root })
+}
+
+// ==================== 模块与配置 ====================
+
+fn dist_module(manifest: &Document) -> Option<&Element> {
+    manifest
+        .root
+        .child_elements()
+        .find(|e| e.name == "module" && e.namespace.as_deref() == Some(DIST_NAMESPACE))
+}
+
+fn parse_conditions(conditions: &Element) -> Vec<DeliveryCondition> {
+    conditions
+        .child_elements()
+        .map(|condition| {
+            let number = |name: &str| condition.attr_value(name).and_then(|v| v.parse().ok());
+            match condition.name.as_str() {
+                "device-feature" => condition.attr_value("name").map(DeliveryCondition::DeviceFeature),
+                "min-sdk" => number("value").map(DeliveryCondition::MinSdk),
+                "max-sdk" => number("value").map(DeliveryCondition::MaxSdk),
+                _ => None,
+            }
+            .unwrap_or_else(|| DeliveryCondition::Other(condition.name.clone()))
+        })
+        .collect()
+}
+
+/// 读取模块的分发方式（<dist:module> 中的 delivery 或旧版 onDemand 属性）
+pub fn module_delivery(manifest: &Document) -> ModuleDelivery {
+    let Some(module) = dist_module(manifest) else {
+        return ModuleDelivery::InstallTime;
+    };
+
+    if let Some(delivery) = module.children_named("delivery").next() {
+        if let Some(install_time) = delivery.children_named("install-time").next() {
+            return match install_time.children_named("conditions").next() {
+                Some(conditions) => ModuleDelivery::Conditional(parse_conditions(conditions)),
+                None => ModuleDelivery::InstallTime,
+            };
+        }
+        if delivery.children_named("on-demand").next().is_some() {
+            return ModuleDelivery::OnDemand;
+        }
+    }
+
+    // 旧版写法：dist:onDemand 属性 + 模块下的 dist:conditions
+    if let Some(conditions) = module.children_named("conditions").next() {
+        return ModuleDelivery::Conditional(parse_conditions(conditions));
+    }
+    match module.attr_value("onDemand").as_deref() {
+        Some("true") => ModuleDelivery::OnDemand,
+        _ => ModuleDelivery::InstallTime,
+    }
+}
+
+/// 解析 BundleConfig.pb 中的拆分维度
+pub fn parse_bundle_config(data: &[u8]) -> Result<BundleConfig, String> {
+    let config = Message::parse(data)?;
+    let mut splits = SplitDimensions {
+        abi: true,
+        density: true,
+        language: true,
+    };
+    let splits_config = config.message(2)?.map(|o| o.message(1)).transpose()?.flatten();
+    if let Some(splits_config) = splits_config {
+        for dimension in splits_config.messages(1)? {
+            // SplitDimension.Value：1=ABI，2=SCREEN_DENSITY，3=LANGUAGE
+            let enabled = dimension.varint(2).unwrap_or(0) == 0;
+            match dimension.varint(1) {
+                Some(1) => splits.abi = enabled,
+                Some(2) => splits.density = enabled,
+                Some(3) => splits.language = enabled,
+                _ => {}
+            }
+        }
+    }
+
+    Ok(BundleConfig { splits })
+}
+
+/// 从 BCP-47 标签中取语言部分（"zh-CN" -> "zh"，"b+sr+Latn" -> "sr"）
+pub fn locale_language(locale: &str) -> String {
+    let locale = locale.strip_prefix("b+").unwrap_or(locale);
+    locale
+        .split(['-', '_', '+'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase()
+}
+
+/// 扫描 resources.pb 中出现的语言和密度
+pub fn resource_configs(data: &[u8]) -> Result<(BTreeSet<String>, BTreeSet<u32>), String> {
+    let mut languages = BTreeSet::new();
+    let mut densities = BTreeSet::new();
+
+    // ResourceTable.package -> Package.type -> Type.entry -> Entry.config_value -> ConfigValue.config
+    let table = Message::parse(data)?;
+    for package in table.messages(2)? {
+        for resource_type in package.messages(3)? {
+            for entry in resource_type.messages(3)? {
+                for config_value in entry.messages(6)? {
+                    let Some(config) = config_value.message(1)? else { continue };
+                    let language = locale_language(&config.string(3));
+                    if !language.is_empty() {
+                        languages.insert(language);
+                    }
+                    let density = config.varint(18).unwrap_or(0) as u32;
+                    if density != 0 && density != DENSITY_ANY && density != DENSITY_NONE {
+                        densities.insert(density);
+                    }
+                }
+            }
+        }
+    }
+    Ok((languages, densities))
+}
+
+/// 读取 AAB 的配置和全部模块，base 模块排在最前面
+pub fn read_bundle<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Result<(BundleConfig, Vec<BundleModule>), String> {
+    let config = match read_entry(archive, BUNDLE_CONFIG_PATH) {
+        Ok(data) => parse_bundle_config(&data)?,
+        Err(_) => return Err("未找到 BundleConfig.pb，不是有效的 AAB 文件".to_string()),
+    };
+
+    let entry_names: Vec<String> = archive.file_names().map(|s| s.to_string()).collect();
+    let mut module_names: Vec<String> = entry_names
+        .iter()
+        .filter_map(|name| name.strip_suffix(&format!("/{}", MANIFEST_PATH)))
+        .filter(|module| !module.contains('/'))
+        .map(|module| module.to_string())
+        .collect();
+    module_names.sort_by_key(|name| (name != "base", name.clone()));
+    if module_names.first().map(String::as_str) != Some("base") {
+        return Err("AAB 中没有 base 模块".to_string());
+    }
+
+    let mut modules = Vec::new();
+    for name in module_names {
+        let prefix = format!("{}/", name);
+        let manifest_data = read_entry(archive, &format!("{}{}", prefix, MANIFEST_PATH))?;
+        let manifest = parse_proto_xml(&manifest_data).map_err(|e| format!("解析 {} 模块 manifest 失败: {}", name, e))?;
+        let (languages, densities) = match read_entry(archive, &format!("{}{}", prefix, RESOURCES_PATH)) {
+            Ok(data) => resource_configs(&data).map_err(|e| format!("解析 {} 模块资源表失败: {}", name, e))?,
+            Err(_) => Default::default(),
+        };
+        let entries = entry_names
+            .iter()
+            .filter_map(|e| e.strip_prefix(&prefix))
+            .map(|e| e.to_string())
+            .collect();
+        modules.push(BundleModule {
+            name,
+            manifest,
+            entries,
+            languages,
+            densities,
+        });
+    }
+
+    Ok((config, modules))
+}
@@ -0,0 +1,285 @@
+// ==================== 渠道号读写 ====================
+// 支持 Walle（签名块 ID 0x71777777，JSON）、VasDolly（签名块 ID 0x881155ff）
+// 以及 VasDolly 的 v1 方案（ZIP 注释：渠道 + 长度(u16) + "ltlovezh"）
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::AnalysisError;
+use crate::signing_block::{self, VASDOLLY_CHANNEL_ID, VERITY_PADDING_ID, WALLE_CHANNEL_ID};
+
+const COMMENT_CHANNEL_MAGIC: &[u8] = b"ltlovezh";
+/// Walle JSON 中渠道号使用的键，附加信息不能使用
+pub const WALLE_CHANNEL_KEY: &str = "channel";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChannelFormat {
+    Walle,
+    Vasdolly,
+    /// 写入 ZIP 注释，仅适用于只有 v1 签名的 APK
+    Comment,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChannelInfo {
+    pub file_name: String,
+    pub channel: Option<String>,
+    pub format: Option<ChannelFormat>,
+    /// Walle 格式中除 channel 外的附加信息
+    pub extras: BTreeMap<String, String>,
+    pub has_signing_block: bool,
+    /// 签名块中的全部条目
+    pub signing_block_ids: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+fn parse_walle(value: &[u8]) -> Result<(String, BTreeMap<String, String>), String> {
+    let json: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_slice(value).map_err(|e| format!("Walle 渠道信息不是有效的 JSON: {}", e))?;
+
+    let mut channel = String::new();
+    let mut extras = BTreeMap::new();
+    for (key, value) in json {
+        let text = match value {
+            serde_json::Value::String(s) => s,
+            other => other.to_string(),
+        };
+        if key == WALLE_CHANNEL_KEY {
+            channel = text;
+        } else {
+            extras.insert(key, text);
+        }
+    }
+    Ok((channel, extras))
+}
+
+fn parse_comment_channel(comment: &[u8]) -> Option<String> {
+    let body = comment.strip_suffix(COMMENT_CHANNEL_MAGIC)?;
+    let len_start = body.len().checked_sub(2)?;
+    let len = u16::from_le_bytes([body[len_start], body[len_start + 1]]) as usize;
+    let channel = body[..len_start].get(len_start.checked_sub(len)?..)?;
+    String::from_utf8(channel.to_vec()).ok()
+}
+
+fn build_comment_channel(channel: &str) -> Vec<u8> {
+    let mut comment = channel.as_bytes().to_vec();
+    comment.extend_from_slice(&(channel.len() as u16).to_le_bytes());
+    comment.extend_from_slice(COMMENT_CHANNEL_MAGIC);
+    comment
+}
+
+/// 读取 APK 的渠道号
+pub fn read_apk_channel(path: &Path) -> Result<ChannelInfo, AnalysisError> {
+    if !path.exists() {
+        return Err(AnalysisError::NotFound);
+    }
+
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("")
+        .to_string();
+
+    let mut file = File::open(path)?;
+    let layout = signing_block::read_layout(&mut file)?;
+
+    let mut warnings = Vec::new();
+    let mut channel = None;
+    let mut format = None;
+    let mut extras = BTreeMap::new();
+
+    if let Some(block) = &layout.signing_block {
+        if let Some(value) = block.get(WALLE_CHANNEL_ID) {
+            match parse_walle(value) {
+                Ok((walle_channel, walle_extras)) => {
+                    channel = Some(walle_channel);
+                    format = Some(ChannelFormat::Walle);
+                    extras = walle_extras;
+                }
+                Err(e) => warnings.push(e),
+            }
+        }
+        if let Some(value) = block.get(VASDOLLY_CHANNEL_ID) {
+            let vasdolly_channel = String::from_utf8_lossy(value).into_owned();
+            match &channel {
+                None => {
+                    channel = Some(vasdolly_channel);
+                    format = Some(ChannelFormat::Vasdolly);
+                }
+                Some(existing) if *existing != vasdolly_channel => warnings.push(format!(
+                    "VasDolly 渠道 \"{}\" 与 Walle 渠道 \"{}\" 不一致",
+                    vasdolly_channel, existing
+                )),
+                _ => {}
+            }
+        }
+    }
+
+    if let Some(comment_channel) = parse_comment_channel(&layout.comment) {
+        match &channel {
+            None => {
+                channel = Some(comment_channel);
+                format = Some(ChannelFormat::Comment);
+            }
+            Some(existing) if *existing != comment_channel => warnings.push(format!(
+                "ZIP 注释中的渠道 \"{}\" 与签名块中的渠道 \"{}\" 不一致",
+                comment_channel, existing
+            )),
+            _ => {}
+        }
+    }
+
+    let signing_block_ids = layout
+        .signing_block
+        .as_ref()
+        .map(|block| block.pairs.iter().map(|(id, _)| signing_block::block_id_name(*id)).collect())
+        .unwrap_or_default();
+
+    Ok(ChannelInfo {
+        file_name,
+        channel,
+        format,
+        extras,
+        has_signing_block: layout.signing_block.is_some(),
+        signing_block_ids,
+        warnings,
+    })
+}
+
+/// 写入渠道号并输出到 output_path（可与输入相同）
+///
+/// 未指定格式时，有签名块则写入 Walle 格式，否则写入 ZIP 注释。
+pub fn write_apk_channel(
+    input_path: &Path,
+    output_path: &Path,
+    channel: &str,
+    extras: &BTreeMap<String, String>,
+    format: Option<ChannelFormat>,
+) -> Result<ChannelInfo, AnalysisError> {
+    let channel = channel.trim();
+    if channel.is_empty() {
+        return Err("渠道号不能为空".into());
+    }
+    if extras.contains_key(WALLE_CHANNEL_KEY) {
+        return Err(format!("附加信息不能使用 \"{}\" 作为键", WALLE_CHANNEL_KEY).into());
+    }
+    if !input_path.exists() {
+        return Err(AnalysisError::NotFound);
+    }
+
+    let mut input = File::open(input_path)?;
+    let layout = signing_block::read_layout(&mut input)?;
+
+    let format = format.unwrap_or(if layout.signing_block.is_some() {
+        ChannelFormat::Walle
+    } else {
+        ChannelFormat::Comment
+    });
+
+    let (new_block, comment) = match format {
+        ChannelFormat::Walle | ChannelFormat::Vasdolly => {
+            let block = layout
+                .signing_block
+                .as_ref()
+                .ok_or("APK 没有 v2/v3 签名块，只能将渠道写入 ZIP 注释")?;
+
+            let value = if format == ChannelFormat::Walle {
+                let mut json = serde_json::Map::new();
+                for (key, value) in extras {
+                    json.insert(key.clone(), serde_json::Value::String(value.clone()));
+                }
+                // 渠道号最后写入，保证不会被附加信息覆盖
+                json.insert(WALLE_CHANNEL_KEY.to_string(), serde_json::Value::String(channel.to_string()));
+                serde_json::to_vec(&json).map_err(|e| format!("序列化渠道信息失败: {}", e))?
+            } else {
+                if !extras.is_empty() {
+                    return Err("VasDolly 格式不支持附加信息，请使用 Walle 格式".into());
+                }
+                channel.as_bytes().to_vec()
+            };
+
+            // 移除旧的渠道条目，保留签名等其他条目的顺序
+            let mut pairs: Vec<(u32, Vec<u8>)> = block
+                .pairs
+                .iter()
+                .filter(|(id, _)| *id != WALLE_CHANNEL_ID && *id != VASDOLLY_CHANNEL_ID)
+                .cloned()
+                .collect();
+            let id = if format == ChannelFormat::Walle { WALLE_CHANNEL_ID } else { VASDOLLY_CHANNEL_ID };
+            pairs.push((id, value));
+
+            let pad = block.contains(VERITY_PADDING_ID);
+            (Some(signing_block::build_signing_block(&pairs, pad)), layout.comment.clone())
+        }
+        ChannelFormat::Comment => {
+            // v2/v3 签名覆盖 EOCD，修改注释会使签名失效
+            if layout.signing_block.is_some() {
+                return Err("APK 含有 v2/v3 签名块，修改 ZIP 注释会破坏签名，请使用 Walle 或 VasDolly 格式".into());
+            }
+            if !extras.is_empty() {
+                return Err("ZIP 注释格式不支持附加信息".into());
+            }
+            let comment = build_comment_channel(channel);
+            if comment.len() > u16::MAX as usize {
+                return Err("渠道号过长".into());
+            }
+            (None, comment)
+        }
+    };
+
+    // 先写入临时文件再替换，支持覆盖原文件
+    let temp_path = output_path.with_extension("apk.tmp");
+    let result = File::create(&temp_path)
+        .map_err(|e| format!("无法创建输出文件: {}", e))
+        .and_then(|mut output| {
+            signing_block::rewrite_apk(&mut input, &layout, &mut output, new_block.as_deref(), &comment)
+        });
+    drop(input);
+    if let Err(e) = result {
+        let _ = fs::remove_file(&temp_path);
+        return Err(e.into());
+    }
+    fs::rename(&temp_path, output_path).map_err(|e| format!("保存输出文件失败: {}", e))?;
+
+    // 重新读取以确认写入成功
+    let info = read_apk_channel(output_path)?;
+    if info.channel.as_deref() != Some(channel) {
+        return Err("写入后读取的渠道号不一致".into());
+    }
+    Ok(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn walle_channel_is_not_an_extra() {
+        let (channel, extras) = parse_walle(br#"{"channel":"huawei","store":"appgallery","build":3}"#).unwrap();
+        assert_eq!(channel, "huawei");
+        assert_eq!(extras.get("store").map(String::as_str), Some("appgallery"));
+        assert_eq!(extras.get("build").map(String::as_str), Some("3"));
+        assert!(!extras.contains_key(WALLE_CHANNEL_KEY));
+        assert!(parse_walle(b"not json").is_err());
+    }
+
+    #[test]
+    fn comment_channel_round_trip() {
+        assert_eq!(parse_comment_channel(&build_comment_channel("xiaomi")).as_deref(), Some("xiaomi"));
+        assert_eq!(parse_comment_channel(b"plain comment"), None);
+        // 长度字段超过实际内容
+        assert_eq!(parse_comment_channel(b"ab\xff\x00ltlovezh"), None);
+    }
+
+    #[test]
+    fn channel_key_in_extras_is_rejected() {
+        let extras = BTreeMap::from([(WALLE_CHANNEL_KEY.to_string(), "other".to_string())]);
+        let err = write_apk_channel(Path::new("missing.apk"), Path::new("out.apk"), "huawei", &extras, None)
+            .unwrap_err();
+        assert!(err.to_string().contains(WALLE_CHANNEL_KEY));
+    }
+}
@@ -0,0 +1,356 @@
+// ==================== 多渠道批量打包 ====================
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use zip::ZipArchive;
+
+use crate::channel::{self, ChannelFormat, WALLE_CHANNEL_KEY};
+use crate::error::AnalysisError;
+use crate::manifest::{self, get_attr};
+use crate::signing_block::{self, VASDOLLY_CHANNEL_ID, VERITY_PADDING_ID, WALLE_CHANNEL_ID};
+
+pub const DEFAULT_NAME_TEMPLATE: &str = "{package}_{versionName}_{channel}.apk";
+const CHECKSUM_FILE_NAME: &str = "SHA256SUMS.txt";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelEntry {
+    pub channel: String,
+    pub extras: BTreeMap<String, String>,
+}
+
+/// 单个渠道包的处理进度
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchProgress {
+    /// 从 1 开始
+    pub index: usize,
+    pub total: usize,
+    pub channel: String,
+    pub file_name: String,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChannelPackage {
+    pub channel: String,
+    pub file_name: String,
+    pub file_path: String,
+    pub file_size: u64,
+    pub sha256: Option<String>,
+    /// 重新读取渠道并比对签名块通过
+    pub verified: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BatchResult {
+    pub package_name: String,
+    pub version_name: String,
+    pub version_code: String,
+    pub output_dir: String,
+    pub packages: Vec<ChannelPackage>,
+    pub succeeded: usize,
+    pub failed: usize,
+    /// 校验和清单（sha256sum 格式）
+    pub checksum_file: Option<String>,
+}
+
+/// 解析渠道列表
+///
+/// 每行一个渠道，# 开头为注释。支持两种写法：
+/// - `huawei,store=appgallery,region=cn`：渠道后跟 key=value
+/// - CSV 表头 `channel,store,region`：后续各列按表头作为附加信息
+pub(crate) fn parse_channel_list(text: &str) -> Result<Vec<ChannelEntry>, String> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim().trim_start_matches('\u{feff}')))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .peekable();
+
+    let split = |line: &str| -> Vec<String> {
+        line.split([',', '\t']).map(|field| field.trim().to_string()).collect()
+    };
+
+    // 首行为 channel 开头的表头时按 CSV 处理
+    let header = match lines.peek() {
+        Some((_, line)) if split(line)[0].eq_ignore_ascii_case("channel") => {
+            let fields = split(line);
+            lines.next();
+            Some(fields)
+        }
+        _ => None,
+    };
+
+    let mut entries = Vec::new();
+    let mut seen = HashSet::new();
+    for (line_no, line) in lines {
+        let fields = split(line);
+        let channel = fields[0].clone();
+        if channel.is_empty() {
+            return Err(format!("第 {} 行缺少渠道号", line_no));
+        }
+        if !seen.insert(channel.clone()) {
+            return Err(format!("第 {} 行渠道号 \"{}\" 重复", line_no, channel));
+        }
+
+        let mut extras = BTreeMap::new();
+        for (column, field) in fields.iter().enumerate().skip(1) {
+            if field.is_empty() {
+                continue;
+            }
+            let (key, value) = match &header {
+                Some(names) => {
+                    let key = names
+                        .get(column)
+                        .filter(|name| !name.is_empty())
+                        .ok_or_else(|| format!("第 {} 行的列数多于表头", line_no))?;
+                    (key.clone(), field.clone())
+                }
+                None => {
+                    let (key, value) = field
+                        .split_once('=')
+                        .ok_or_else(|| format!("第 {} 行附加信息 \"{}\" 应为 key=value 格式", line_no, field))?;
+                    (key.trim().to_string(), value.trim().to_string())
+                }
+            };
+            // 附加信息与渠道号写在同一个 Walle JSON 中，不能覆盖渠道号
+            if key == WALLE_CHANNEL_KEY {
+                return Err(format!("第 {} 行附加信息不能使用 \"{}\" 作为键", line_no, WALLE_CHANNEL_KEY));
+            }
+            extras.insert(key, value);
+        }
+
+        entries.push(ChannelEntry { channel, extras });
+    }
+
+    if entries.is_empty() {
+        return Err("渠道列表为空".to_string());
+    }
+    Ok(entries)
+}
+
+// 文件名中不允许的字符替换为下划线
+fn sanitize_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect()
+}
+
+fn render_file_name(template: &str, package: &str, version_name: &str, version_code: &str, channel: &str) -> String {
+    let name = template
+        .replace("{package}", package)
+        .replace("{versionName}", version_name)
+        .replace("{versionCode}", version_code)
+        .replace("{channel}", channel);
+    let name = sanitize_file_name(&name);
+    if name.to_lowercase().ends_with(".apk") {
+        name
+    } else {
+        format!("{}.apk", name)
+    }
+}
+
+/// 计算文件的 SHA-256（十六进制）
+pub(crate) fn sha256_file(path: &Path) -> Result<String, String> {
+    let mut file = File::open(path).map_err(|e| format!("无法打开文件: {}", e))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1024 * 1024];
+    loop {
+        let bytes_read = file.read(&mut buffer).map_err(|e| e.to_string())?;
+        if bytes_read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..bytes_read]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+// 签名块中除渠道和填充外的条目（即签名本身）
+fn signature_pairs(path: &Path) -> Result<Vec<(u32, Vec<u8>)>, String> {
+    let mut file = File::open(path).map_err(|e| format!("无法打开文件: {}", e))?;
+    let layout = signing_block::read_layout(&mut file)?;
+    Ok(layout
+        .signing_block
+        .map(|block| {
+            block
+                .pairs
+                .into_iter()
+                .filter(|(id, _)| ![WALLE_CHANNEL_ID, VASDOLLY_CHANNEL_ID, VERITY_PADDING_ID].contains(id))
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+// 校验输出包：ZIP 可读、渠道与附加信息一致、签名条目未被改动
+fn verify_output(path: &Path, entry: &ChannelEntry, base_signatures: &[(u32, Vec<u8>)]) -> Result<(), String> {
+    let file = File::open(path).map_err(|e| format!("无法打开文件: {}", e))?;
+    ZipArchive::new(file).map_err(|e| format!("输出文件不是有效的 ZIP: {}", e))?;
+
+    let info = channel::read_apk_channel(path)?;
+    if info.channel.as_deref() != Some(entry.channel.as_str()) {
+        return Err(format!("渠道号不一致: {:?}", info.channel));
+    }
+    if info.format == Some(ChannelFormat::Walle) && info.extras != entry.extras {
+        return Err("附加信息不一致".to_string());
+    }
+    if signature_pairs(path)? != base_signatures {
+        return Err("签名块中的签名数据与基础包不一致".to_string());
+    }
+    Ok(())
+}
+
+/// 基于已签名的基础包批量生成渠道包，每处理完一个渠道调用一次 on_progress
+pub fn package_channels(
+    base_apk: &Path,
+    channel_list: &Path,
+    output_dir: &Path,
+    name_template: Option<&str>,
+    format: Option<ChannelFormat>,
+    mut on_progress: impl FnMut(&BatchProgress),
+) -> Result<BatchResult, AnalysisError> {
+    if !base_apk.exists() {
+        return Err("基础包不存在".into());
+    }
+
+    let list_text = fs::read_to_string(channel_list).map_err(|e| format!("无法读取渠道列表: {}", e))?;
+    let entries = parse_channel_list(&list_text)?;
+
+    let axml = manifest::load_manifest(base_apk)?;
+    let root = axml.root();
+    let (package_name, version_name, version_code) = {
+        let root = root.borrow();
+        (
+            get_attr(&root, "package").unwrap_or_default(),
+            get_attr(&root, "versionName").unwrap_or_default(),
+            get_attr(&root, "versionCode").unwrap_or_default(),
+        )
+    };
+
+    let template = name_template
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .unwrap_or(DEFAULT_NAME_TEMPLATE);
+    if !template.contains("{channel}") {
+        return Err("文件名模板必须包含 {channel}".into());
+    }
+
+    // 先生成全部文件名，避免中途发现重名
+    let file_names: Vec<String> = entries
+        .iter()
+        .map(|entry| render_file_name(template, &package_name, &version_name, &version_code, &entry.channel))
+        .collect();
+    let mut seen = HashSet::new();
+    if let Some(duplicate) = file_names.iter().find(|name| !seen.insert(name.to_lowercase())) {
+        return Err(format!("多个渠道生成了相同的文件名: {}", duplicate).into());
+    }
+
+    fs::create_dir_all(output_dir).map_err(|e| format!("无法创建输出目录: {}", e))?;
+    let base_signatures = signature_pairs(base_apk)?;
+
+    let total = entries.len();
+    let mut packages = Vec::with_capacity(total);
+    for (index, (entry, file_name)) in entries.iter().zip(file_names).enumerate() {
+        let output_path = output_dir.join(&file_name);
+
+        let result = channel::write_apk_channel(base_apk, &output_path, &entry.channel, &entry.extras, format)
+            .map_err(String::from)
+            .and_then(|_| verify_output(&output_path, entry, &base_signatures))
+            .and_then(|_| sha256_file(&output_path));
+
+        let (sha256, error) = match result {
+            Ok(hash) => (Some(hash), None),
+            Err(e) => (None, Some(e)),
+        };
+
+        on_progress(&BatchProgress {
+            index: index + 1,
+            total,
+            channel: entry.channel.clone(),
+            file_name: file_name.clone(),
+            success: error.is_none(),
+            error: error.clone(),
+        });
+
+        packages.push(ChannelPackage {
+            channel: entry.channel.clone(),
+            file_name,
+            file_path: output_path.to_string_lossy().to_string(),
+            file_size: fs::metadata(&output_path).map(|m| m.len()).unwrap_or(0),
+            verified: error.is_none(),
+            sha256,
+            error,
+        });
+    }
+
+    let succeeded = packages.iter().filter(|p| p.verified).count();
+
+    // 校验和清单，可直接用 sha256sum -c 校验
+    let checksum_file = if succeeded > 0 {
+        let content: String = packages
+            .iter()
+            .filter_map(|p| p.sha256.as_ref().map(|hash| format!("{}  {}\n", hash, p.file_name)))
+            .collect();
+        let checksum_path = output_dir.join(CHECKSUM_FILE_NAME);
+        fs::write(&checksum_path, content).map_err(|e| format!("写入校验和清单失败: {}", e))?;
+        Some(checksum_path.to_string_lossy().to_string())
+    } else {
+        None
+    };
+
+    Ok(BatchResult {
+        package_name,
+        version_name,
+        version_code,
+        output_dir: output_dir.to_string_lossy().to_string(),
+        succeeded,
+        failed: total - succeeded,
+        packages,
+        checksum_file,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_key_value_list() {
+        let entries = parse_channel_list("# 渠道\nhuawei,store=appgallery\n\nxiaomi\n").unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].channel, "huawei");
+        assert_eq!(entries[0].extras.get("store").map(String::as_str), Some("appgallery"));
+        assert!(entries[1].extras.is_empty());
+    }
+
+    #[test]
+    fn parses_csv_with_header() {
+        let entries = parse_channel_list("channel,store,region\nhuawei,appgallery,cn\noppo,,cn\n").unwrap();
+        assert_eq!(entries[0].extras.len(), 2);
+        assert_eq!(entries[1].extras.get("region").map(String::as_str), Some("cn"));
+        assert!(!entries[1].extras.contains_key("store"));
+    }
+
+    #[test]
+    fn rejects_invalid_lists() {
+        assert!(parse_channel_list("").is_err());
+        assert!(parse_channel_list("huawei\nhuawei").is_err());
+        assert!(parse_channel_list("huawei,store").is_err());
+        assert!(parse_channel_list("channel,store\nhuawei,a,b").is_err());
+    }
+
+    #[test]
+    fn rejects_channel_as_extra_key() {
+        assert!(parse_channel_list("huawei,channel=xiaomi").is_err());
+        assert!(parse_channel_list("channel,store,channel\nhuawei,a,xiaomi").is_err());
+    }
+}
@@ -0,0 +1,319 @@
+// ==================== Android 二进制资源通用结构 ====================
+// AXML 与 resources.arsc 共用的 chunk 头、字符串池和 Res_value 读写
+
+pub const RES_STRING_POOL_TYPE: u16 = 0x0001;
+pub const RES_TABLE_TYPE: u16 = 0x0002;
+pub const RES_XML_TYPE: u16 = 0x0003;
+pub const RES_XML_START_NAMESPACE_TYPE: u16 = 0x0100;
+pub const RES_XML_END_NAMESPACE_TYPE: u16 = 0x0101;
+pub const RES_XML_START_ELEMENT_TYPE: u16 = 0x0102;
+pub const RES_XML_END_ELEMENT_TYPE: u16 = 0x0103;
+pub const RES_XML_CDATA_TYPE: u16 = 0x0104;
+pub const RES_XML_RESOURCE_MAP_TYPE: u16 = 0x0180;
+pub const RES_TABLE_PACKAGE_TYPE: u16 = 0x0200;
+pub const RES_TABLE_TYPE_TYPE: u16 = 0x0201;
+
+// Res_value.dataType
+pub const TYPE_NULL: u8 = 0x00;
+pub const TYPE_REFERENCE: u8 = 0x01;
+pub const TYPE_ATTRIBUTE: u8 = 0x02;
+pub const TYPE_STRING: u8 = 0x03;
+pub const TYPE_FLOAT: u8 = 0x04;
+pub const TYPE_DIMENSION: u8 = 0x05;
+pub const TYPE_FRACTION: u8 = 0x06;
+pub const TYPE_INT_DEC: u8 = 0x10;
+pub const TYPE_INT_HEX: u8 = 0x11;
+pub const TYPE_INT_BOOLEAN: u8 = 0x12;
+pub const TYPE_INT_COLOR_ARGB8: u8 = 0x1c;
+pub const TYPE_INT_COLOR_RGB8: u8 = 0x1d;
+pub const TYPE_INT_COLOR_ARGB4: u8 = 0x1e;
+pub const TYPE_INT_COLOR_RGB4: u8 = 0x1f;
+
+const UTF8_FLAG: u32 = 0x100;
+
+pub fn read_u8(data: &[u8], offset: usize) -> Result<u8, String> {
+    data.get(offset).copied().ok_or_else(|| format!("数据越界: offset {}", offset))
+}
+
+pub fn read_u16(data: &[u8], offset: usize) -> Result<u16, String> {
+    offset
+        .checked_add(2)
+        .and_then(|end| data.get(offset..end))
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        .ok_or_else(|| format!("数据越界: offset {}", offset))
+}
+
+pub fn read_u32(data: &[u8], offset: usize) -> Result<u32, String> {
+    offset
+        .checked_add(4)
+        .and_then(|end| data.get(offset..end))
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| format!("数据越界: offset {}", offset))
+}
+
+pub fn write_u16(out: &mut Vec<u8>, value: u16) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+pub fn write_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+/// 通用 chunk 头（ResChunk_header）
+#[derive(Debug, Clone, Copy)]
+pub struct ChunkHeader {
+    pub chunk_type: u16,
+    pub header_size: u16,
+    pub size: u32,
+}
+
+impl ChunkHeader {
+    pub fn read(data: &[u8], offset: usize) -> Result<Self, String> {
+        let header = ChunkHeader {
+            chunk_type: read_u16(data, offset)?,
+            header_size: read_u16(data, offset + 2)?,
+            size: read_u32(data, offset + 4)?,
+        };
+        if header.size < 8 || offset + header.size as usize > data.len() {
+            return Err(format!("chunk 大小无效: 0x{:04x} size {}", header.chunk_type, header.size));
+        }
+        Ok(header)
+    }
+}
+
+/// 解析字符串池 chunk，offset 指向 chunk 头
+pub fn read_string_pool(data: &[u8], offset: usize) -> Result<Vec<String>, String> {
+    let header = ChunkHeader::read(data, offset)?;
+    if header.chunk_type != RES_STRING_POOL_TYPE {
+        return Err("不是字符串池 chunk".to_string());
+    }
+
+    let string_count = read_u32(data, offset + 8)? as usize;
+    let flags = read_u32(data, offset + 16)?;
+    let strings_start = read_u32(data, offset + 20)? as usize;
+    let is_utf8 = flags & UTF8_FLAG != 0;
+
+    let chunk_end = offset + header.size as usize;
+    let offsets_start = offset + header.header_size as usize;
+    // 数量来自文件内容，每个字符串至少占用 4 字节的偏移
+    if string_count > chunk_end.saturating_sub(offsets_start) / 4 {
+        return Err(format!("字符串池数量无效: {}", string_count));
+    }
+    let mut strings = Vec::with_capacity(string_count);
+
+    for i in 0..string_count {
+        let string_offset = offset + strings_start + read_u32(data, offsets_start + i * 4)? as usize;
+        if string_offset >= chunk_end {
+            return Err("字符串偏移越界".to_string());
+        }
+        let value = if is_utf8 {
+            read_utf8_string(data, string_offset)?
+        } else {
+            read_utf16_string(data, string_offset)?
+        };
+        strings.push(value);
+    }
+
+    Ok(strings)
+}
+
+/// 生成 UTF-16 编码的字符串池 chunk（不含样式）
+pub fn write_string_pool(strings: &[String]) -> Vec<u8> {
+    write_string_pool_with_styles(strings, &[])
+}
+
+/// 字符串样式区间（ResStringPool_span），name 为标签名在同一字符串池中的索引
+#[derive(Debug, Clone, Copy)]
+pub struct StyleSpan {
+    pub name: u32,
+    pub first_char: u32,
+    pub last_char: u32,
+}
+
+/// 生成带样式的字符串池，styles[i] 对应 strings[i]，因此带样式的字符串必须排在最前面
+pub fn write_string_pool_with_styles(strings: &[String], styles: &[Vec<StyleSpan>]) -> Vec<u8> {
+    const HEADER_SIZE: u32 = 28;
+    const SPAN_END: u32 = 0xffff_ffff;
+
+    let mut offsets = Vec::with_capacity(strings.len());
+    let mut string_data = Vec::new();
+    for string in strings {
+        offsets.push(string_data.len() as u32);
+        let units: Vec<u16> = string.encode_utf16().collect();
+        // 长度超过 0x7fff 时使用两个 u16 表示
+        if units.len() > 0x7fff {
+            write_u16(&mut string_data, 0x8000 | (units.len() >> 16) as u16);
+        }
+        write_u16(&mut string_data, (units.len() & 0xffff) as u16);
+        for unit in units {
+            write_u16(&mut string_data, unit);
+        }
+        write_u16(&mut string_data, 0);
+    }
+    // chunk 需按 4 字节对齐
+    while string_data.len() % 4 != 0 {
+        string_data.push(0);
+    }
+
+    let mut style_offsets = Vec::with_capacity(styles.len());
+    let mut style_data = Vec::new();
+    for spans in styles {
+        style_offsets.push(style_data.len() as u32);
+        for span in spans {
+            write_u32(&mut style_data, span.name);
+            write_u32(&mut style_data, span.first_char);
+            write_u32(&mut style_data, span.last_char);
+        }
+        write_u32(&mut style_data, SPAN_END);
+    }
+    if !styles.is_empty() {
+        // 样式区末尾需要一个完整的 ResStringPool_span 大小的 0xffffffff
+        write_u32(&mut style_data, SPAN_END);
+        write_u32(&mut style_data, SPAN_END);
+    }
+
+    let strings_start = HEADER_SIZE + (strings.len() + styles.len()) as u32 * 4;
+    let styles_start = if styles.is_empty() { 0 } else { strings_start + string_data.len() as u32 };
+    let size = strings_start + string_data.len() as u32 + style_data.len() as u32;
+
+    let mut out = Vec::with_capacity(size as usize);
+    write_u16(&mut out, RES_STRING_POOL_TYPE);
+    write_u16(&mut out, HEADER_SIZE as u16);
+    write_u32(&mut out, size);
+    write_u32(&mut out, strings.len() as u32);
+    write_u32(&mut out, styles.len() as u32);
+    write_u32(&mut out, 0); // flags：UTF-16
+    write_u32(&mut out, strings_start);
+    write_u32(&mut out, styles_start);
+    for offset in offsets.into_iter().chain(style_offsets) {
+        write_u32(&mut out, offset);
+    }
+    out.extend_from_slice(&string_data);
+    out.extend_from_slice(&style_data);
+    out
+}
+
+// UTF-8 字符串：字符数（1-2 字节）+ 字节数（1-2 字节）+ 内容
+fn read_utf8_string(data: &[u8], offset: usize) -> Result<String, String> {
+    let mut pos = offset;
+    let (_, len_size) = read_utf8_length(data, pos)?;
+    pos += len_size;
+    let (byte_len, len_size) = read_utf8_length(data, pos)?;
+    pos += len_size;
+    let bytes = data.get(pos..pos + byte_len).ok_or("字符串内容越界")?;
+    Ok(String::from_utf8_lossy(bytes).into_owned())
+}
+
+fn read_utf8_length(data: &[u8], offset: usize) -> Result<(usize, usize), String> {
+    let first = read_u8(data, offset)? as usize;
+    if first & 0x80 != 0 {
+        let second = read_u8(data, offset + 1)? as usize;
+        Ok((((first & 0x7f) << 8) | second, 2))
+    } else {
+        Ok((first, 1))
+    }
+}
+
+// UTF-16 字符串：字符数（1-2 个 u16）+ 内容
+fn read_utf16_string(data: &[u8], offset: usize) -> Result<String, String> {
+    let first = read_u16(data, offset)? as usize;
+    let (char_len, mut pos) = if first & 0x8000 != 0 {
+        let second = read_u16(data, offset + 2)? as usize;
+        (((first & 0x7fff) << 16) | second, offset + 4)
+    } else {
+        (first, offset + 2)
+    };
+
+    let mut units = Vec::with_capacity(char_len.min(data.len().saturating_sub(pos) / 2));
+    for _ in 0..char_len {
+        units.push(read_u16(data, pos)?);
+        pos += 2;
+    }
+    Ok(String::from_utf16_lossy(&units))
+}
+
+/// 资源值（Res_value）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResValue {
+    pub data_type: u8,
+    pub data: u32,
+}
+
+impl ResValue {
+    /// 读取 8 字节的 Res_value：size(u16) res0(u8) dataType(u8) data(u32)
+    pub fn read(data: &[u8], offset: usize) -> Result<Self, String> {
+        Ok(ResValue {
+            data_type: read_u8(data, offset + 3)?,
+            data: read_u32(data, offset + 4)?,
+        })
+    }
+
+    /// 写入 8 字节的 Res_value
+    pub fn write(self, out: &mut Vec<u8>) {
+        write_u16(out, 8);
+        out.push(0);
+        out.push(self.data_type);
+        write_u32(out, self.data);
+    }
+
+    /// 转换为可读字符串，字符串类型需要传入对应的字符串池
+    pub fn to_display(self, strings: &[String]) -> String {
+        match self.data_type {
+            TYPE_NULL => "(null)".to_string(),
+            TYPE_REFERENCE => format!("@0x{:08x}", self.data),
+            TYPE_ATTRIBUTE => format!("?0x{:08x}", self.data),
+            TYPE_STRING => strings.get(self.data as usize).cloned().unwrap_or_default(),
+            TYPE_FLOAT => f32::from_bits(self.data).to_string(),
+            TYPE_INT_DEC => (self.data as i32).to_string(),
+            TYPE_INT_HEX => format!("0x{:x}", self.data),
+            TYPE_INT_BOOLEAN => (self.data != 0).to_string(),
+            TYPE_INT_COLOR_ARGB8..=TYPE_INT_COLOR_RGB4 => format!("#{:08x}", self.data),
+            _ => format!("(type 0x{:x}) 0x{:x}", self.data_type, self.data),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn string_pool_round_trip() {
+        let strings = vec![String::new(), "app_name".to_string(), "中文".to_string(), "x".repeat(0x8000)];
+        let pool = write_string_pool(&strings);
+        assert_eq!(pool.len() % 4, 0);
+        assert_eq!(read_string_pool(&pool, 0).unwrap(), strings);
+    }
+
+    #[test]
+    fn string_pool_with_styles_round_trip() {
+        let strings = vec!["<b>粗体</b>".to_string(), "b".to_string()];
+        let styles = vec![vec![StyleSpan { name: 1, first_char: 0, last_char: 1 }]];
+        let pool = write_string_pool_with_styles(&strings, &styles);
+        assert_eq!(read_string_pool(&pool, 0).unwrap(), strings);
+    }
+
+    #[test]
+    fn oversized_string_count_is_rejected() {
+        let mut pool = write_string_pool(&["a".to_string()]);
+        pool[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(read_string_pool(&pool, 0).unwrap_err().contains("字符串池数量无效"));
+    }
+
+    #[test]
+    fn oversized_string_length_is_rejected() {
+        let mut pool = write_string_pool(&["a".to_string()]);
+        // 把字符数改为 0x7fffffff
+        let strings_start = read_u32(&pool, 20).unwrap() as usize;
+        pool[strings_start..strings_start + 4].copy_from_slice(&[0xff, 0xff, 0xff, 0xff]);
+        assert!(read_string_pool(&pool, 0).is_err());
+    }
+
+    #[test]
+    fn invalid_chunk_size_is_rejected() {
+        let mut pool = write_string_pool(&["a".to_string()]);
+        pool[4..8].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(read_string_pool(&pool, 0).is_err());
+        assert!(ChunkHeader::read(&[0u8; 4], 0).is_err());
+    }
+}
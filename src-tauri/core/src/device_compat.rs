@@ -0,0 +1,887 @@
+// ==================== 设备兼容性检查 ====================
+// 根据设备描述（SDK、ABI、屏幕密度、语言、OpenGL ES 版本和特性）判断安装包能否安装，
+// 支持单个 APK、拆分 APK 集合（.apks / .xapk）和 AAB，并列出设备会收到的拆分 APK
+
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::{Cursor, Read, Seek};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use zip::ZipArchive;
+
+use crate::axml::{self, Document, Element};
+use crate::bundle::{self, DeliveryCondition, ModuleDelivery};
+use crate::error::AnalysisError;
+use crate::manifest::read_entry;
+
+// bundletool 拆分使用的密度档位
+const DENSITY_BUCKETS: &[(&str, u32)] = &[
+    ("ldpi", 120),
+    ("mdpi", 160),
+    ("tvdpi", 213),
+    ("hdpi", 240),
+    ("xhdpi", 320),
+    ("xxhdpi", 480),
+    ("xxxhdpi", 640),
+];
+
+// 拆分名中的 ABI 写法（下划线）与 lib/ 目录名的对应关系
+const ABI_SPLITS: &[(&str, &str)] = &[
+    ("armeabi", "armeabi"),
+    ("armeabi_v7a", "armeabi-v7a"),
+    ("arm64_v8a", "arm64-v8a"),
+    ("x86", "x86"),
+    ("x86_64", "x86_64"),
+    ("mips", "mips"),
+    ("mips64", "mips64"),
+    ("riscv64", "riscv64"),
+];
+
+// 所有设备都自带的共享库
+const PLATFORM_LIBRARIES: &[&str] = &[
+    "org.apache.http.legacy",
+    "android.test.base",
+    "android.test.mock",
+    "android.test.runner",
+];
+
+// 拆分 APK 从 Android 5.0 开始支持
+pub const MIN_SPLIT_SDK: u32 = 21;
+// 未声明 glEsVersion 时默认要求 OpenGL ES 1.0
+const DEFAULT_GL_ES_VERSION: u32 = 0x10000;
+
+/// 设备描述，兼容 bundletool 的 device-spec.json
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DeviceSpec {
+    pub sdk_version: u32,
+    /// 按设备偏好排序的 ABI
+    pub supported_abis: Vec<String>,
+    pub screen_density: u32,
+    pub supported_locales: Vec<String>,
+    /// `pm list features` 的结果，可包含 reqGlEsVersion=0x30002
+    pub device_features: Vec<String>,
+    pub gl_extensions: Vec<String>,
+    /// OpenGL ES 版本，如 "3.2"
+    pub gl_es_version: Option<String>,
+    /// small / normal / large / xlarge，不填时跳过 supports-screens 检查
+    pub screen_size: Option<String>,
+    /// 设备上的共享库，不填时只能确认平台自带的库
+    pub shared_libraries: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Fail,
+    /// 设备描述中缺少判断所需的信息
+    Unknown,
+}
+
+/// 检查不通过时的后果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Impact {
+    /// 无法安装（adb install / 应用市场均失败）
+    Install,
+    /// 可以侧载安装，但 Google Play 不会向该设备展示
+    PlayFilter,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CompatibilityCheck {
+    pub id: String,
+    pub title: String,
+    pub status: CheckStatus,
+    pub impact: Impact,
+    pub detail: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SelectedSplit {
+    pub module: String,
+    /// 拆分名，base 模块的主 APK 为 "base"
+    pub split: String,
+    /// 拆分集合中对应的文件，AAB 中为 bundletool 生成的文件名
+    pub file: Option<String>,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CompatibilityReport {
+    pub package_name: String,
+    /// apk / split_set / aab
+    pub package_type: String,
+    pub installable: bool,
+    pub play_available: bool,
+    pub checks: Vec<CompatibilityCheck>,
+    pub splits: Vec<SelectedSplit>,
+    pub warnings: Vec<String>,
+}
+
+fn check(id: &str, title: &str, status: CheckStatus, impact: Impact, detail: String) -> CompatibilityCheck {
+    CompatibilityCheck {
+        id: id.to_string(),
+        title: title.to_string(),
+        status,
+        impact,
+        detail,
+    }
+}
+
+// 数值属性可能是十进制或十六进制（glEsVersion）
+fn parse_number(value: &str) -> Option<u32> {
+    match value.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
+fn number_attr(element: &Element, name: &str) -> Option<u32> {
+    element.attr_value(name).as_deref().and_then(parse_number)
+}
+
+fn format_gl_version(version: u32) -> String {
+    format!("{}.{}", version >> 16, version & 0xffff)
+}
+
+// "3.2" -> 0x30002
+fn parse_gl_version(version: &str) -> Option<u32> {
+    let (major, minor) = version.split_once('.').unwrap_or((version, "0"));
+    Some((major.trim().parse::<u32>().ok()? << 16) | minor.trim().parse::<u32>().ok()?)
+}
+
+fn density_bucket(density: u32) -> Option<&'static str> {
+    if density == 0 {
+        return None;
+    }
+    // 取最接近的档位，距离相同时取更高的档位
+    DENSITY_BUCKETS
+        .iter()
+        .min_by_key(|(_, dpi)| (dpi.abs_diff(density), std::cmp::Reverse(*dpi)))
+        .map(|(name, _)| *name)
+}
+
+pub fn bucket_dpi(name: &str) -> Option<u32> {
+    DENSITY_BUCKETS.iter().find(|(n, _)| *n == name).map(|(_, dpi)| *dpi)
+}
+
+pub fn abi_of_split(suffix: &str) -> Option<&'static str> {
+    ABI_SPLITS.iter().find(|(split, _)| *split == suffix).map(|(_, abi)| *abi)
+}
+
+fn split_of_abi(abi: &str) -> String {
+    ABI_SPLITS
+        .iter()
+        .find(|(_, a)| *a == abi)
+        .map(|(split, _)| split.to_string())
+        .unwrap_or_else(|| abi.replace('-', "_"))
+}
+
+fn apk_abis(entries: impl Iterator<Item = impl AsRef<str>>) -> BTreeSet<String> {
+    entries
+        .filter_map(|e| {
+            let e = e.as_ref();
+            let abi = e.strip_prefix("lib/")?.split('/').next()?;
+            e.ends_with(".so").then(|| abi.to_string())
+        })
+        .collect()
+}
+
+impl DeviceSpec {
+    fn has_feature(&self, name: &str) -> bool {
+        self.device_features
+            .iter()
+            .any(|f| f.strip_prefix("feature:").unwrap_or(f) == name)
+    }
+
+    fn gl_version(&self) -> Option<u32> {
+        if let Some(version) = self.gl_es_version.as_deref().and_then(parse_gl_version) {
+            return Some(version);
+        }
+        self.device_features
+            .iter()
+            .find_map(|f| f.strip_prefix("reqGlEsVersion="))
+            .and_then(parse_number)
+    }
+
+    fn languages(&self) -> BTreeSet<String> {
+        self.supported_locales.iter().map(|l| bundle::locale_language(l)).collect()
+    }
+
+    /// 按设备偏好选择 ABI
+    fn pick_abi<'a>(&self, available: &'a BTreeSet<String>) -> Option<&'a String> {
+        self.supported_abis.iter().find_map(|abi| available.get(abi))
+    }
+}
+
+// ==================== 安装检查 ====================
+
+fn check_sdk(manifest: &Document, spec: &DeviceSpec, checks: &mut Vec<CompatibilityCheck>) {
+    let uses_sdk = manifest.root.children_named("uses-sdk").next();
+    let min_sdk = uses_sdk.and_then(|e| number_attr(e, "minSdkVersion")).unwrap_or(1);
+    let target_sdk = uses_sdk.and_then(|e| number_attr(e, "targetSdkVersion"));
+    let max_sdk = uses_sdk.and_then(|e| number_attr(e, "maxSdkVersion"));
+
+    let status = if spec.sdk_version >= min_sdk { CheckStatus::Pass } else { CheckStatus::Fail };
+    checks.push(check(
+        "min_sdk",
+        "最低系统版本",
+        status,
+        Impact::Install,
+        format!("minSdkVersion {}，设备 API {}", min_sdk, spec.sdk_version),
+    ));
+
+    if let Some(max_sdk) = max_sdk {
+        // 系统安装时忽略 maxSdkVersion，只有 Google Play 会据此过滤
+        let status = if spec.sdk_version <= max_sdk { CheckStatus::Pass } else { CheckStatus::Fail };
+        checks.push(check(
+            "max_sdk",
+            "最高系统版本",
+            status,
+            Impact::PlayFilter,
+            format!("maxSdkVersion {}，设备 API {}", max_sdk, spec.sdk_version),
+        ));
+    }
+
+    // Android 14 起拒绝安装 targetSdk < 23 的应用，Android 15 提高到 24
+    let floor = match spec.sdk_version {
+        35.. => Some(24),
+        34 => Some(23),
+        _ => None,
+    };
+    if let (Some(floor), Some(target_sdk)) = (floor, target_sdk) {
+        let status = if target_sdk >= floor { CheckStatus::Pass } else { CheckStatus::Fail };
+        checks.push(check(
+            "target_sdk_floor",
+            "targetSdk 安装下限",
+            status,
+            Impact::Install,
+            format!("设备 API {} 要求 targetSdkVersion ≥ {}，当前为 {}", spec.sdk_version, floor, target_sdk),
+        ));
+    }
+}
+
+fn check_abi(abis: &BTreeSet<String>, spec: &DeviceSpec, checks: &mut Vec<CompatibilityCheck>) {
+    if abis.is_empty() {
+        checks.push(check("native_abi", "原生库 ABI", CheckStatus::Pass, Impact::Install, "不包含原生库".to_string()));
+        return;
+    }
+    let package_abis = abis.iter().cloned().collect::<Vec<_>>().join(", ");
+    let (status, detail) = if spec.supported_abis.is_empty() {
+        (CheckStatus::Unknown, format!("设备描述中没有 supportedAbis，安装包包含 {}", package_abis))
+    } else {
+        match spec.pick_abi(abis) {
+            Some(abi) => (CheckStatus::Pass, format!("使用 {}（安装包包含 {}）", abi, package_abis)),
+            None => (
+                CheckStatus::Fail,
+                format!("安装包只包含 {}，设备支持 {}", package_abis, spec.supported_abis.join(", ")),
+            ),
+        }
+    };
+    checks.push(check("native_abi", "原生库 ABI", status, Impact::Install, detail));
+}
+
+fn check_features(manifest: &Document, spec: &DeviceSpec, checks: &mut Vec<CompatibilityCheck>) {
+    let required: Vec<&Element> = manifest
+        .root
+        .children_named("uses-feature")
+        .filter(|e| e.attr_value("required").as_deref() != Some("false"))
+        .collect();
+
+    let mut missing = Vec::new();
+    let mut unknown = Vec::new();
+    for feature in &required {
+        if let Some(name) = feature.attr_value("name") {
+            if spec.device_features.is_empty() {
+                unknown.push(name);
+            } else if !spec.has_feature(&name) {
+                missing.push(name);
+            }
+        }
+    }
+    if !required.is_empty() || !spec.device_features.is_empty() {
+        let named = required.iter().filter(|e| e.attr("name").is_some()).count();
+        let (status, detail) = if !missing.is_empty() {
+            (CheckStatus::Fail, format!("设备缺少 {}", missing.join(", ")))
+        } else if !unknown.is_empty() {
+            (CheckStatus::Unknown, format!("设备描述中没有 deviceFeatures，无法确认 {}", unknown.join(", ")))
+        } else {
+            (CheckStatus::Pass, format!("满足全部 {} 项必需特性", named))
+        };
+        checks.push(check("uses_feature", "必需硬件/软件特性", status, Impact::PlayFilter, detail));
+    }
+
+    // OpenGL ES 版本取所有 uses-feature 中的最大值
+    let gl_required = required
+        .iter()
+        .filter_map(|e| number_attr(e, "glEsVersion"))
+        .max()
+        .unwrap_or(DEFAULT_GL_ES_VERSION);
+    if gl_required > DEFAULT_GL_ES_VERSION {
+        let (status, detail) = match spec.gl_version() {
+            Some(version) if version >= gl_required => (
+                CheckStatus::Pass,
+                format!("要求 {}，设备支持 {}", format_gl_version(gl_required), format_gl_version(version)),
+            ),
+            Some(version) => (
+                CheckStatus::Fail,
+                format!("要求 {}，设备只支持 {}", format_gl_version(gl_required), format_gl_version(version)),
+            ),
+            None => (
+                CheckStatus::Unknown,
+                format!("要求 {}，设备描述中没有 OpenGL ES 版本", format_gl_version(gl_required)),
+            ),
+        };
+        checks.push(check("gl_es_version", "OpenGL ES 版本", status, Impact::PlayFilter, detail));
+    }
+}
+
+fn check_screens(manifest: &Document, spec: &DeviceSpec, checks: &mut Vec<CompatibilityCheck>) {
+    let Some(supports) = manifest.root.children_named("supports-screens").next() else { return };
+    let Some(size) = spec.screen_size.as_deref() else {
+        checks.push(check(
+            "supports_screens",
+            "屏幕尺寸",
+            CheckStatus::Unknown,
+            Impact::PlayFilter,
+            "声明了 supports-screens，设备描述中没有 screenSize".to_string(),
+        ));
+        return;
+    };
+
+    let attribute = match size {
+        "small" => "smallScreens",
+        "normal" => "normalScreens",
+        "large" => "largeScreens",
+        "xlarge" => "xlargeScreens",
+        _ => {
+            checks.push(check(
+                "supports_screens",
+                "屏幕尺寸",
+                CheckStatus::Unknown,
+                Impact::PlayFilter,
+                format!("无法识别的 screenSize: {}", size),
+            ));
+            return;
+        }
+    };
+    let supported = supports.attr_value(attribute).as_deref() != Some("false");
+    let status = if supported { CheckStatus::Pass } else { CheckStatus::Fail };
+    checks.push(check(
+        "supports_screens",
+        "屏幕尺寸",
+        status,
+        Impact::PlayFilter,
+        format!("{}=\"{}\"，设备屏幕为 {}", attribute, supported, size),
+    ));
+}
+
+fn check_libraries(manifest: &Document, spec: &DeviceSpec, checks: &mut Vec<CompatibilityCheck>) {
+    let Some(application) = manifest.root.children_named("application").next() else { return };
+    let required: Vec<String> = application
+        .children_named("uses-library")
+        .filter(|e| e.attr_value("required").as_deref() != Some("false"))
+        .filter_map(|e| e.attr_value("name"))
+        .collect();
+    if required.is_empty() {
+        return;
+    }
+
+    let (missing, unknown): (Vec<&String>, Vec<&String>) = match &spec.shared_libraries {
+        Some(libraries) => (required.iter().filter(|l| !libraries.contains(l)).collect(), Vec::new()),
+        None => (
+            Vec::new(),
+            required.iter().filter(|l| !PLATFORM_LIBRARIES.contains(&l.as_str())).collect(),
+        ),
+    };
+    let join = |libraries: &[&String]| libraries.iter().map(|l| l.as_str()).collect::<Vec<_>>().join(", ");
+    let (status, detail) = if !missing.is_empty() {
+        (CheckStatus::Fail, format!("设备缺少共享库 {}", join(&missing)))
+    } else if !unknown.is_empty() {
+        (CheckStatus::Unknown, format!("设备描述中没有 sharedLibraries，无法确认 {}", join(&unknown)))
+    } else {
+        (CheckStatus::Pass, format!("需要 {}", required.join(", ")))
+    };
+    checks.push(check("uses_library", "必需共享库", status, Impact::Install, detail));
+}
+
+// ==================== 拆分选择 ====================
+
+fn evaluate_delivery(delivery: &ModuleDelivery, spec: &DeviceSpec, warnings: &mut Vec<String>, module: &str) -> Option<String> {
+    match delivery {
+        ModuleDelivery::InstallTime => Some("安装时分发的功能模块".to_string()),
+        ModuleDelivery::OnDemand => None,
+        ModuleDelivery::Conditional(conditions) => {
+            for condition in conditions {
+                let satisfied = match condition {
+                    DeliveryCondition::DeviceFeature(name) => spec.has_feature(name),
+                    DeliveryCondition::MinSdk(sdk) => spec.sdk_version >= *sdk,
+                    DeliveryCondition::MaxSdk(sdk) => spec.sdk_version <= *sdk,
+                    DeliveryCondition::Other(name) => {
+                        warnings.push(format!("{} 模块的分发条件 {} 无法离线判断，按满足处理", module, name));
+                        true
+                    }
+                };
+                if !satisfied {
+                    return None;
+                }
+            }
+            Some("满足条件分发的功能模块".to_string())
+        }
+    }
+}
+
+fn config_split_name(module: &str, suffix: &str) -> String {
+    if module == "base" {
+        format!("config.{}", suffix)
+    } else {
+        format!("{}.config.{}", module, suffix)
+    }
+}
+
+pub fn select_bundle_splits(
+    config: &bundle::BundleConfig,
+    modules: &[bundle::BundleModule],
+    spec: &DeviceSpec,
+    warnings: &mut Vec<String>,
+) -> Vec<SelectedSplit> {
+    let languages = spec.languages();
+    let mut splits = Vec::new();
+    for module in modules {
+        let reason = if module.name == "base" {
+            Some("基础 APK".to_string())
+        } else {
+            evaluate_delivery(&module.delivery(), spec, warnings, &module.name)
+        };
+        let Some(reason) = reason else { continue };
+
+        let mut add = |split: String, file: String, reason: String| {
+            splits.push(SelectedSplit {
+                module: module.name.clone(),
+                split,
+                file: Some(format!("splits/{}.apk", file)),
+                reason,
+            });
+        };
+        let master = if module.name == "base" { "base".to_string() } else { module.name.clone() };
+        add(master, format!("{}-master", module.name), reason);
+
+        if config.splits.abi {
+            if let Some(abi) = spec.pick_abi(&module.abis()) {
+                let suffix = split_of_abi(abi);
+                add(config_split_name(&module.name, &suffix), format!("{}-{}", module.name, suffix), format!("ABI {}", abi));
+            }
+        }
+        if config.splits.density && !module.densities.is_empty() {
+            if let Some(bucket) = density_bucket(spec.screen_density) {
+                add(
+                    config_split_name(&module.name, bucket),
+                    format!("{}-{}", module.name, bucket),
+                    format!("屏幕密度 {}dpi", spec.screen_density),
+                );
+            }
+        }
+        if config.splits.language {
+            for language in languages.intersection(&module.languages) {
+                add(config_split_name(&module.name, language), format!("{}-{}", module.name, language), format!("语言 {}", language));
+            }
+        }
+    }
+    splits
+}
+
+struct ApkPart {
+    file: String,
+    manifest: Document,
+    abis: BTreeSet<String>,
+}
+
+impl ApkPart {
+    fn read(data: Vec<u8>, file: &str) -> Result<Self, String> {
+        let mut archive = ZipArchive::new(Cursor::new(data)).map_err(|e| format!("无法解析 {}: {}", file, e))?;
+        Self::from_archive(&mut archive, file)
+    }
+
+    fn from_archive<R: Read + Seek>(archive: &mut ZipArchive<R>, file: &str) -> Result<Self, String> {
+        let manifest_data = read_entry(archive, "AndroidManifest.xml")?;
+        let manifest = axml::parse(&manifest_data).map_err(|e| format!("解析 {} 的 manifest 失败: {}", file, e))?;
+        Ok(ApkPart {
+            file: file.to_string(),
+            manifest,
+            abis: apk_abis(archive.file_names()),
+        })
+    }
+
+    fn split(&self) -> Option<String> {
+        self.manifest.root.attr_value("split").filter(|s| !s.is_empty())
+    }
+}
+
+fn select_split_set(parts: &[ApkPart], spec: &DeviceSpec, warnings: &mut Vec<String>) -> (Vec<SelectedSplit>, BTreeSet<String>) {
+    let languages = spec.languages();
+    let mut splits = Vec::new();
+    let mut modules = vec!["base".to_string()];
+    let mut base_abis = BTreeSet::new();
+
+    // 先确定主 APK 和要安装的功能模块
+    for part in parts {
+        let reason = match part.split() {
+            None => {
+                base_abis.extend(part.abis.iter().cloned());
+                Some(("base".to_string(), "基础 APK".to_string()))
+            }
+            Some(split) if !split.contains("config.") => {
+                let delivery = bundle::module_delivery(&part.manifest);
+                evaluate_delivery(&delivery, spec, warnings, &split).map(|reason| (split, reason))
+            }
+            Some(_) => continue,
+        };
+        if let Some((module, reason)) = reason {
+            if !modules.contains(&module) {
+                modules.push(module.clone());
+            }
+            splits.push(SelectedSplit {
+                split: module.clone(),
+                module,
+                file: Some(part.file.clone()),
+                reason,
+            });
+        }
+    }
+
+    // 再按模块选择配置拆分
+    for module in &modules {
+        let configs: Vec<(&ApkPart, String)> = parts
+            .iter()
+            .filter_map(|part| {
+                let split = part.split()?;
+                let suffix = split.split_once("config.")?.1.to_string();
+                let owner = part.manifest.root.attr_value("configForSplit").filter(|s| !s.is_empty());
+                (owner.as_deref().unwrap_or("base") == module).then_some((part, suffix))
+            })
+            .collect();
+        let mut select = |part: &ApkPart, reason: String| {
+            splits.push(SelectedSplit {
+                module: module.clone(),
+                split: part.split().unwrap_or_default(),
+                file: Some(part.file.clone()),
+                reason,
+            });
+        };
+
+        let abi_splits: BTreeSet<String> = configs
+            .iter()
+            .filter_map(|(_, suffix)| abi_of_split(suffix))
+            .map(|abi| abi.to_string())
+            .collect();
+        if module == "base" {
+            base_abis.extend(abi_splits.iter().cloned());
+        }
+        if let Some(abi) = spec.pick_abi(&abi_splits) {
+            if let Some((part, _)) = configs.iter().find(|(_, s)| abi_of_split(s) == Some(abi.as_str())) {
+                select(part, format!("ABI {}", abi));
+            }
+        }
+
+        // 密度拆分只在集合中存在的档位里选最接近的
+        let density = configs
+            .iter()
+            .filter_map(|(part, suffix)| bucket_dpi(suffix).map(|dpi| (*part, dpi)))
+            .filter(|_| spec.screen_density > 0)
+            .min_by_key(|(_, dpi)| (dpi.abs_diff(spec.screen_density), std::cmp::Reverse(*dpi)));
+        if let Some((part, _)) = density {
+            select(part, format!("屏幕密度 {}dpi", spec.screen_density));
+        }
+
+        for (part, suffix) in &configs {
+            if abi_of_split(suffix).is_some() || bucket_dpi(suffix).is_some() {
+                continue;
+            }
+            let is_language = (2..=3).contains(&suffix.len()) && suffix.chars().all(|c| c.is_ascii_lowercase());
+            if !is_language {
+                warnings.push(format!("无法识别的配置拆分 {}，未计入", part.file));
+            } else if languages.contains(suffix) {
+                select(part, format!("语言 {}", suffix));
+            }
+        }
+    }
+
+    (splits, base_abis)
+}
+
+// ==================== 入口 ====================
+
+fn run_checks(manifest: &Document, abis: &BTreeSet<String>, spec: &DeviceSpec) -> Vec<CompatibilityCheck> {
+    let mut checks = Vec::new();
+    check_sdk(manifest, spec, &mut checks);
+    check_abi(abis, spec, &mut checks);
+    check_features(manifest, spec, &mut checks);
+    check_screens(manifest, spec, &mut checks);
+    check_libraries(manifest, spec, &mut checks);
+    checks
+}
+
+/// 检查安装包在指定设备上的兼容性，device_spec 为 JSON 文本
+pub fn check_compatibility(path: &Path, device_spec: &str) -> Result<CompatibilityReport, AnalysisError> {
+    let spec: DeviceSpec = serde_json::from_str(device_spec).map_err(|e| format!("设备描述格式错误: {}", e))?;
+    if spec.sdk_version == 0 {
+        return Err("设备描述缺少 sdkVersion".into());
+    }
+
+    let file = File::open(path)?;
+    let mut archive = ZipArchive::new(file)?;
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+
+    let mut warnings = Vec::new();
+    let (package_type, manifest, splits, checks) = if extension == "aab" {
+        let (config, modules) = bundle::read_bundle(&mut archive)?;
+        let splits = select_bundle_splits(&config, &modules, &spec, &mut warnings);
+        let base = modules.into_iter().next().ok_or("AAB 中没有 base 模块")?;
+        let checks = run_checks(&base.manifest, &base.abis(), &spec);
+        ("aab", base.manifest, splits, checks)
+    } else if archive.by_name("AndroidManifest.xml").is_ok() {
+        let part = ApkPart::from_archive(&mut archive, &path.file_name().unwrap_or_default().to_string_lossy())?;
+        let mut checks = run_checks(&part.manifest, &part.abis, &spec);
+        // 声明了 requiredSplitTypes 或 isSplitRequired 的主 APK 不能单独安装
+        let split_required = part.manifest.root.attr("requiredSplitTypes").is_some()
+            || part
+                .manifest
+                .root
+                .children_named("application")
+                .any(|a| a.attr_value("isSplitRequired").as_deref() == Some("true"));
+        if split_required {
+            checks.push(check(
+                "split_required",
+                "拆分 APK",
+                CheckStatus::Fail,
+                Impact::Install,
+                "该 APK 要求与配置拆分一起安装，单独安装会失败".to_string(),
+            ));
+        }
+        ("apk", part.manifest, Vec::new(), checks)
+    } else {
+        // .apks / .xapk：读取内部全部 APK，bundletool 的 standalones/ 只用于 Android 5.0 以下
+        let names: Vec<String> = archive
+            .file_names()
+            .filter(|n| n.ends_with(".apk") && !n.starts_with("standalones/"))
+            .map(|s| s.to_string())
+            .collect();
+        let mut parts = Vec::new();
+        for name in &names {
+            parts.push(ApkPart::read(read_entry(&mut archive, name)?, name)?);
+        }
+        let base_index = parts.iter().position(|p| p.split().is_none()).ok_or("拆分集合中没有主 APK")?;
+        let (splits, abis) = select_split_set(&parts, &spec, &mut warnings);
+        let base = parts.swap_remove(base_index);
+        let checks = run_checks(&base.manifest, &abis, &spec);
+        ("split_set", base.manifest, splits, checks)
+    };
+
+    if package_type != "apk" && spec.sdk_version < MIN_SPLIT_SDK {
+        warnings.push("Android 5.0 以下不支持拆分 APK，设备需要安装独立 APK（standalone）".to_string());
+    }
+
+    let installable = !checks.iter().any(|c| c.status == CheckStatus::Fail && c.impact == Impact::Install);
+    let play_available = installable && !checks.iter().any(|c| c.status == CheckStatus::Fail);
+
+    Ok(CompatibilityReport {
+        package_name: manifest.root.attr_value("package").unwrap_or_default(),
+        package_type: package_type.to_string(),
+        installable,
+        play_available,
+        checks,
+        splits,
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bundle::{BundleModule, SplitDimensions};
+
+    fn manifest(body: &str) -> Document {
+        axml::document_from_xml(&format!(
+            r#"<manifest xmlns:android="http://schemas.android.com/apk/res/android" xmlns:dist="http://schemas.android.com/apk/distribution" package="com.example">{}</manifest>"#,
+            body
+        ))
+    }
+
+    fn spec(json: &str) -> DeviceSpec {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn status_of(checks: &[CompatibilityCheck], id: &str) -> Option<CheckStatus> {
+        checks.iter().find(|c| c.id == id).map(|c| c.status)
+    }
+
+    #[test]
+    fn parses_bundletool_device_spec() {
+        let spec = spec(
+            r#"{"supportedAbis":["arm64-v8a","armeabi-v7a"],"supportedLocales":["zh-CN","en-US"],
+                "deviceFeatures":["reqGlEsVersion=0x30002","android.hardware.camera"],
+                "glExtensions":[],"screenDensity":420,"sdkVersion":31}"#,
+        );
+        assert_eq!(spec.sdk_version, 31);
+        assert_eq!(spec.screen_density, 420);
+        assert_eq!(spec.gl_version(), Some(0x30002));
+        assert!(spec.has_feature("android.hardware.camera"));
+        assert_eq!(spec.languages(), BTreeSet::from(["en".to_string(), "zh".to_string()]));
+        // 显式的 glEsVersion 优先于 reqGlEsVersion
+        assert_eq!(self::spec(r#"{"glEsVersion":"3.1"}"#).gl_version(), Some(0x30001));
+
+        let err = check_compatibility(Path::new("unused.apk"), r#"{"supportedAbis":["x86"]}"#).unwrap_err();
+        assert_eq!(err.to_string(), "设备描述缺少 sdkVersion");
+    }
+
+    #[test]
+    fn density_buckets() {
+        assert_eq!(bucket_dpi("xxhdpi"), Some(480));
+        assert_eq!(bucket_dpi("nodpi"), None);
+        assert_eq!(density_bucket(0), None);
+        assert_eq!(density_bucket(160), Some("mdpi"));
+        assert_eq!(density_bucket(213), Some("tvdpi"));
+        assert_eq!(density_bucket(420), Some("xxhdpi"));
+        // 与 xhdpi、xxhdpi 距离相同时取更高的档位
+        assert_eq!(density_bucket(400), Some("xxhdpi"));
+        assert_eq!(density_bucket(900), Some("xxxhdpi"));
+    }
+
+    #[test]
+    fn sdk_out_of_range() {
+        let manifest = manifest(r#"<uses-sdk android:minSdkVersion="24" android:maxSdkVersion="33" android:targetSdkVersion="23"/>"#);
+
+        let mut checks = Vec::new();
+        check_sdk(&manifest, &spec(r#"{"sdkVersion":21}"#), &mut checks);
+        assert_eq!(status_of(&checks, "min_sdk"), Some(CheckStatus::Fail));
+        assert_eq!(status_of(&checks, "max_sdk"), Some(CheckStatus::Pass));
+        assert_eq!(status_of(&checks, "target_sdk_floor"), None);
+
+        // maxSdkVersion 只影响 Google Play，targetSdk 23 在 Android 15 上无法安装
+        let mut checks = Vec::new();
+        check_sdk(&manifest, &spec(r#"{"sdkVersion":35}"#), &mut checks);
+        assert_eq!(status_of(&checks, "min_sdk"), Some(CheckStatus::Pass));
+        let max_sdk = checks.iter().find(|c| c.id == "max_sdk").unwrap();
+        assert_eq!((max_sdk.status, max_sdk.impact), (CheckStatus::Fail, Impact::PlayFilter));
+        assert_eq!(status_of(&checks, "target_sdk_floor"), Some(CheckStatus::Fail));
+
+        let mut checks = Vec::new();
+        check_sdk(&manifest, &spec(r#"{"sdkVersion":34}"#), &mut checks);
+        assert_eq!(status_of(&checks, "target_sdk_floor"), Some(CheckStatus::Pass));
+    }
+
+    #[test]
+    fn abi_mismatch() {
+        let abis = apk_abis(["lib/arm64-v8a/libapp.so", "lib/arm64-v8a/README", "assets/lib/x86/libfake.so"].iter());
+        assert_eq!(abis, BTreeSet::from(["arm64-v8a".to_string()]));
+
+        let mut checks = Vec::new();
+        check_abi(&abis, &spec(r#"{"sdkVersion":30,"supportedAbis":["x86_64","x86"]}"#), &mut checks);
+        assert_eq!(status_of(&checks, "native_abi"), Some(CheckStatus::Fail));
+
+        let mut checks = Vec::new();
+        check_abi(&abis, &spec(r#"{"sdkVersion":30,"supportedAbis":["x86_64","arm64-v8a"]}"#), &mut checks);
+        assert_eq!(status_of(&checks, "native_abi"), Some(CheckStatus::Pass));
+
+        let mut checks = Vec::new();
+        check_abi(&abis, &spec(r#"{"sdkVersion":30}"#), &mut checks);
+        assert_eq!(status_of(&checks, "native_abi"), Some(CheckStatus::Unknown));
+    }
+
+    #[test]
+    fn missing_features_and_low_gl_version() {
+        let manifest = manifest(
+            r#"<uses-feature android:name="android.hardware.camera"/>
+               <uses-feature android:name="android.hardware.nfc" android:required="false"/>
+               <uses-feature android:glEsVersion="0x00030002" android:required="true"/>"#,
+        );
+
+        let mut checks = Vec::new();
+        let device = spec(r#"{"sdkVersion":30,"deviceFeatures":["android.hardware.touchscreen","reqGlEsVersion=0x30000"]}"#);
+        check_features(&manifest, &device, &mut checks);
+        let features = checks.iter().find(|c| c.id == "uses_feature").unwrap();
+        assert_eq!(features.status, CheckStatus::Fail);
+        assert_eq!(features.detail, "设备缺少 android.hardware.camera");
+        let gl = checks.iter().find(|c| c.id == "gl_es_version").unwrap();
+        assert_eq!(gl.status, CheckStatus::Fail);
+        assert_eq!(gl.detail, "要求 3.2，设备只支持 3.0");
+
+        let mut checks = Vec::new();
+        let device = spec(r#"{"sdkVersion":30,"deviceFeatures":["feature:android.hardware.camera"],"glEsVersion":"3.2"}"#);
+        check_features(&manifest, &device, &mut checks);
+        assert_eq!(status_of(&checks, "uses_feature"), Some(CheckStatus::Pass));
+        assert_eq!(status_of(&checks, "gl_es_version"), Some(CheckStatus::Pass));
+
+        let mut checks = Vec::new();
+        check_features(&manifest, &spec(r#"{"sdkVersion":30}"#), &mut checks);
+        assert_eq!(status_of(&checks, "uses_feature"), Some(CheckStatus::Unknown));
+        assert_eq!(status_of(&checks, "gl_es_version"), Some(CheckStatus::Unknown));
+    }
+
+    fn module(name: &str, body: &str, entries: &[&str], languages: &[&str], densities: &[u32]) -> BundleModule {
+        BundleModule {
+            name: name.to_string(),
+            manifest: manifest(body),
+            entries: entries.iter().map(|e| e.to_string()).collect(),
+            languages: languages.iter().map(|l| l.to_string()).collect(),
+            densities: densities.iter().copied().collect(),
+        }
+    }
+
+    fn bundle_modules() -> Vec<BundleModule> {
+        vec![
+            module(
+                "base",
+                "",
+                &["lib/arm64-v8a/libapp.so", "lib/armeabi-v7a/libapp.so", "lib/x86_64/libapp.so"],
+                &["en", "fr", "zh"],
+                &[160, 320, 480],
+            ),
+            module(
+                "camera",
+                r#"<dist:module><dist:delivery><dist:install-time><dist:conditions>
+                       <dist:min-sdk dist:value="33"/>
+                   </dist:conditions></dist:install-time></dist:delivery></dist:module>"#,
+                &[],
+                &[],
+                &[],
+            ),
+            module("extras", r#"<dist:module dist:onDemand="true"/>"#, &[], &[], &[]),
+        ]
+    }
+
+    fn split_names(splits: &[SelectedSplit]) -> Vec<&str> {
+        splits.iter().map(|s| s.split.as_str()).collect()
+    }
+
+    #[test]
+    fn selects_bundle_splits_for_device() {
+        let config = bundle::BundleConfig {
+            splits: SplitDimensions { abi: true, density: true, language: true },
+        };
+        let device = spec(
+            r#"{"sdkVersion":30,"supportedAbis":["arm64-v8a","armeabi-v7a"],"screenDensity":420,
+                "supportedLocales":["zh-CN","de-DE"]}"#,
+        );
+        let mut warnings = Vec::new();
+        let splits = select_bundle_splits(&config, &bundle_modules(), &device, &mut warnings);
+        assert_eq!(split_names(&splits), ["base", "config.arm64_v8a", "config.xxhdpi", "config.zh"]);
+        assert_eq!(splits[1].file.as_deref(), Some("splits/base-arm64_v8a.apk"));
+        assert!(warnings.is_empty());
+
+        // 满足 min-sdk 后安装条件模块，按需模块始终不在安装集合中
+        let device = spec(r#"{"sdkVersion":33,"supportedAbis":["x86_64"],"screenDensity":160,"supportedLocales":["fr"]}"#);
+        let splits = select_bundle_splits(&config, &bundle_modules(), &device, &mut warnings);
+        assert_eq!(split_names(&splits), ["base", "config.x86_64", "config.mdpi", "config.fr", "camera"]);
+
+        // 关闭的拆分维度不再生成配置拆分
+        let config = bundle::BundleConfig {
+            splits: SplitDimensions { abi: true, density: false, language: false },
+        };
+        let splits = select_bundle_splits(&config, &bundle_modules(), &device, &mut warnings);
+        assert_eq!(split_names(&splits), ["base", "config.x86_64", "camera"]);
+    }
+}
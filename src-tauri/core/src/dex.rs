@@ -0,0 +1,238 @@
+// ==================== DEX 文件解析 ====================
+// 只读取字符串池等头部索引，不解析字节码
+
+use crate::chunk::{read_u16, read_u32, read_u8};
+
+const DEX_MAGIC: &[u8] = b"dex\n";
+
+pub struct DexFile {
+    pub strings: Vec<String>,
+    /// 头部声明的文件大小
+    pub declared_size: u32,
+    /// 类型描述符（指向 strings 的下标）
+    type_ids: Vec<u32>,
+    /// class_defs 中定义的类（指向 type_ids 的下标）
+    class_type_ids: Vec<u32>,
+    /// method_ids 中的 (所属类 type_ids 下标, 方法名 strings 下标)，包括调用的外部方法
+    method_ids: Vec<(u32, u32)>,
+}
+
+impl DexFile {
+    pub fn parse(data: &[u8]) -> Result<Self, String> {
+        if data.len() < 0x70 || &data[..4] != DEX_MAGIC {
+            return Err("不是有效的 DEX 文件".to_string());
+        }
+
+        let string_ids_size = read_u32(data, 0x38)? as usize;
+        let string_ids_off = read_u32(data, 0x3c)? as usize;
+
+        // 数量来自文件头，每个 string_id 占 4 字节，不可能超过文件大小
+        let mut strings = Vec::with_capacity(string_ids_size.min(data.len() / 4));
+        for i in 0..string_ids_size {
+            let string_data_off = read_u32(data, string_ids_off + i * 4)? as usize;
+            strings.push(read_string_data(data, string_data_off)?);
+        }
+
+        let type_ids_size = read_u32(data, 0x40)? as usize;
+        let type_ids_off = read_u32(data, 0x44)? as usize;
+        let type_ids = (0..type_ids_size)
+            .map(|i| read_u32(data, type_ids_off + i * 4))
+            .collect::<Result<_, _>>()?;
+
+        // method_id_item 每项 8 字节：class_idx(u16) proto_idx(u16) name_idx(u32)
+        let method_ids_size = read_u32(data, 0x58)? as usize;
+        let method_ids_off = read_u32(data, 0x5c)? as usize;
+        let method_ids = (0..method_ids_size)
+            .map(|i| {
+                let offset = method_ids_off + i * 8;
+                Ok((read_u16(data, offset)? as u32, read_u32(data, offset + 4)?))
+            })
+            .collect::<Result<_, String>>()?;
+
+        // class_def_item 每项 32 字节，第一个字段为 class_idx
+        let class_defs_size = read_u32(data, 0x60)? as usize;
+        let class_defs_off = read_u32(data, 0x64)? as usize;
+        let class_type_ids = (0..class_defs_size)
+            .map(|i| read_u32(data, class_defs_off + i * 32))
+            .collect::<Result<_, _>>()?;
+
+        Ok(DexFile {
+            strings,
+            declared_size: read_u32(data, 0x20)?,
+            type_ids,
+            class_type_ids,
+            method_ids,
+        })
+    }
+
+    /// DEX 中定义的类名（Java 形式，如 com.example.MainActivity）
+    pub fn class_names(&self) -> Vec<String> {
+        self.class_type_ids
+            .iter()
+            .filter_map(|type_idx| self.type_ids.get(*type_idx as usize))
+            .filter_map(|string_idx| self.strings.get(*string_idx as usize))
+            .map(|descriptor| descriptor_to_class_name(descriptor))
+            .collect()
+    }
+
+    /// 引用的方法（类名, 方法名），包括 framework 等外部类的方法
+    pub fn method_refs(&self) -> Vec<(String, String)> {
+        self.method_ids
+            .iter()
+            .filter_map(|(type_idx, name_idx)| {
+                let descriptor = self.strings.get(*self.type_ids.get(*type_idx as usize)? as usize)?;
+                let name = self.strings.get(*name_idx as usize)?;
+                Some((descriptor_to_class_name(descriptor), name.clone()))
+            })
+            .collect()
+    }
+
+    /// 测试用：由字符串常量和方法引用（Java 类名, 方法名）构造，不包含类定义
+    #[cfg(test)]
+    pub(crate) fn from_parts(strings: &[&str], methods: &[(&str, &str)]) -> DexFile {
+        let mut dex = DexFile {
+            strings: strings.iter().map(|s| s.to_string()).collect(),
+            declared_size: 0,
+            type_ids: Vec::new(),
+            class_type_ids: Vec::new(),
+            method_ids: Vec::new(),
+        };
+        fn string_index(dex: &mut DexFile, value: String) -> u32 {
+            let index = dex.strings.iter().position(|s| *s == value).unwrap_or_else(|| {
+                dex.strings.push(value);
+                dex.strings.len() - 1
+            });
+            index as u32
+        }
+        for (class, method) in methods {
+            let descriptor = string_index(&mut dex, format!("L{};", class.replace('.', "/")));
+            let type_idx = dex.type_ids.iter().position(|t| *t == descriptor).unwrap_or_else(|| {
+                dex.type_ids.push(descriptor);
+                dex.type_ids.len() - 1
+            });
+            let name = string_index(&mut dex, method.to_string());
+            dex.method_ids.push((type_idx as u32, name));
+        }
+        dex
+    }
+}
+
+/// 把类型描述符 Lcom/example/Foo; 转换为 com.example.Foo
+pub fn descriptor_to_class_name(descriptor: &str) -> String {
+    descriptor
+        .strip_prefix('L')
+        .and_then(|s| s.strip_suffix(';'))
+        .unwrap_or(descriptor)
+        .replace('/', ".")
+}
+
+// string_data_item: uleb128 编码的 UTF-16 长度 + 以 0 结尾的 MUTF-8 内容
+fn read_string_data(data: &[u8], offset: usize) -> Result<String, String> {
+    let (_, len_size) = read_uleb128(data, offset)?;
+    let start = offset + len_size;
+    let end = data
+        .get(start..)
+        .and_then(|rest| rest.iter().position(|b| *b == 0))
+        .map(|pos| start + pos)
+        .ok_or("DEX 字符串未结束")?;
+    Ok(String::from_utf8_lossy(&data[start..end]).into_owned())
+}
+
+/// 读取 uleb128，返回 (值, 占用字节数)
+pub fn read_uleb128(data: &[u8], offset: usize) -> Result<(u32, usize), String> {
+    let mut result: u32 = 0;
+    for i in 0..5 {
+        let byte = read_u8(data, offset + i)?;
+        result |= ((byte & 0x7f) as u32) << (i * 7);
+        if byte & 0x80 == 0 {
+            return Ok((result, i + 1));
+        }
+    }
+    Err("uleb128 编码无效".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 拼出只包含一个类、一个方法引用的 DEX
+    fn tiny_dex() -> Vec<u8> {
+        let strings = ["Lcom/example/Foo;", "run"];
+        let mut data = vec![0u8; 0x70];
+        data[..4].copy_from_slice(DEX_MAGIC);
+
+        let put = |data: &mut Vec<u8>, offset: usize, value: u32| {
+            data[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+        };
+        // string_ids @0x70, type_ids @0x78, method_ids @0x7c, class_defs @0x84, string_data @0xa4
+        put(&mut data, 0x38, strings.len() as u32);
+        put(&mut data, 0x3c, 0x70);
+        put(&mut data, 0x40, 1);
+        put(&mut data, 0x44, 0x78);
+        put(&mut data, 0x58, 1);
+        put(&mut data, 0x5c, 0x7c);
+        put(&mut data, 0x60, 1);
+        put(&mut data, 0x64, 0x84);
+        data.resize(0xa4, 0);
+
+        let mut string_data_off = 0xa4u32;
+        for (i, s) in strings.iter().enumerate() {
+            put(&mut data, 0x70 + i * 4, string_data_off);
+            data.push(s.len() as u8);
+            data.extend_from_slice(s.as_bytes());
+            data.push(0);
+            string_data_off = data.len() as u32;
+        }
+        put(&mut data, 0x78, 0); // type_ids[0] -> strings[0]
+        put(&mut data, 0x80, 1); // method_ids[0].name_idx -> strings[1]
+        put(&mut data, 0x84, 0); // class_defs[0].class_idx -> type_ids[0]
+        let size = data.len() as u32;
+        put(&mut data, 0x20, size);
+        data
+    }
+
+    #[test]
+    fn parses_classes_and_methods() {
+        let dex = DexFile::parse(&tiny_dex()).unwrap();
+        assert_eq!(dex.strings, vec!["Lcom/example/Foo;".to_string(), "run".to_string()]);
+        assert_eq!(dex.class_names(), vec!["com.example.Foo".to_string()]);
+        assert_eq!(dex.method_refs(), vec![("com.example.Foo".to_string(), "run".to_string())]);
+    }
+
+    #[test]
+    fn oversized_string_count_is_rejected() {
+        let mut data = tiny_dex();
+        data[0x38..0x3c].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(DexFile::parse(&data).is_err());
+    }
+
+    #[test]
+    fn out_of_bounds_offsets_are_rejected() {
+        for field in [0x3c, 0x44, 0x5c, 0x64] {
+            let mut data = tiny_dex();
+            data[field..field + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+            assert!(DexFile::parse(&data).is_err());
+        }
+    }
+
+    #[test]
+    fn unterminated_string_is_rejected() {
+        let mut data = tiny_dex();
+        data.pop();
+        assert!(DexFile::parse(&data).is_err());
+    }
+
+    #[test]
+    fn rejects_non_dex() {
+        assert!(DexFile::parse(b"dex\n035").is_err());
+        assert!(DexFile::parse(&[0u8; 0x70]).is_err());
+    }
+
+    #[test]
+    fn uleb128() {
+        assert_eq!(read_uleb128(&[0x7f], 0), Ok((0x7f, 1)));
+        assert_eq!(read_uleb128(&[0x80, 0x01], 0), Ok((0x80, 2)));
+        assert!(read_uleb128(&[0xff; 5], 0).is_err());
+        assert!(read_uleb128(&[0x80], 0).is_err());
+    }
+}
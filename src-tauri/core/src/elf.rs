@@ -0,0 +1,273 @@
+// ==================== ELF 文件解析 ====================
+// 读取 .so 的节区表和程序头，Android 原生库均为小端序
+
+use crate::chunk::{read_u16, read_u32, read_u8};
+
+const ELF_MAGIC: &[u8] = b"\x7fELF";
+const NT_GNU_BUILD_ID: u32 = 3;
+const PT_LOAD: u32 = 1;
+
+#[derive(Debug, Clone)]
+pub struct Section {
+    pub name: String,
+    pub offset: u64,
+    pub size: u64,
+}
+
+pub struct ElfFile<'a> {
+    data: &'a [u8],
+    pub sections: Vec<Section>,
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Result<u64, String> {
+    let high = offset.checked_add(4).ok_or("ELF 偏移量无效")?;
+    Ok(read_u32(data, offset)? as u64 | ((read_u32(data, high)? as u64) << 32))
+}
+
+impl<'a> ElfFile<'a> {
+    pub fn parse(data: &'a [u8]) -> Result<Self, String> {
+        if data.len() < 0x34 || &data[..4] != ELF_MAGIC {
+            return Err("不是有效的 ELF 文件".to_string());
+        }
+        let is_64 = read_u8(data, 4)? == 2;
+
+        // e_shoff / e_shentsize / e_shnum / e_shstrndx
+        let (sh_off, sh_entsize, sh_num, sh_strndx) = if is_64 {
+            (
+                read_u64(data, 0x28)? as usize,
+                read_u16(data, 0x3a)? as usize,
+                read_u16(data, 0x3c)? as usize,
+                read_u16(data, 0x3e)? as usize,
+            )
+        } else {
+            (
+                read_u32(data, 0x20)? as usize,
+                read_u16(data, 0x2e)? as usize,
+                read_u16(data, 0x30)? as usize,
+                read_u16(data, 0x32)? as usize,
+            )
+        };
+
+        // (sh_name, sh_offset, sh_size)，头部字段越界或溢出的节区记为 None，保持下标不变
+        let raw_sections: Vec<Option<(u32, u64, u64)>> = (0..sh_num)
+            .map(|i| {
+                let base = i.checked_mul(sh_entsize).and_then(|o| sh_off.checked_add(o))?;
+                let (name_index, offset, size) = if is_64 {
+                    (read_u32(data, base).ok()?, read_u64(data, base + 0x18).ok()?, read_u64(data, base + 0x20).ok()?)
+                } else {
+                    (
+                        read_u32(data, base).ok()?,
+                        read_u32(data, base + 0x10).ok()? as u64,
+                        read_u32(data, base + 0x14).ok()? as u64,
+                    )
+                };
+                offset.checked_add(size)?;
+                Some((name_index, offset, size))
+            })
+            .collect();
+
+        let names_offset = raw_sections.get(sh_strndx).copied().flatten().map(|s| s.1 as usize);
+        let sections = raw_sections
+            .iter()
+            .flatten()
+            .map(|(name_index, offset, size)| Section {
+                name: names_offset
+                    .and_then(|base| base.checked_add(*name_index as usize))
+                    .map(|name_offset| read_c_string(data, name_offset))
+                    .unwrap_or_default(),
+                offset: *offset,
+                size: *size,
+            })
+            .collect();
+
+        Ok(ElfFile { data, sections })
+    }
+
+    /// 按名称读取节区内容
+    pub fn section_data(&self, name: &str) -> Option<&'a [u8]> {
+        let section = self.sections.iter().find(|s| s.name == name)?;
+        let end = section.offset.checked_add(section.size)?;
+        self.data.get(section.offset as usize..end as usize)
+    }
+
+    /// 读取 .note.gnu.build-id：namesz(4) + descsz(4) + type(4) + name("GNU\0") + desc
+    pub fn build_id(&self) -> Option<String> {
+        let note = self.section_data(".note.gnu.build-id")?;
+        let name_size = read_u32(note, 0).ok()? as usize;
+        let desc_size = read_u32(note, 4).ok()? as usize;
+        if read_u32(note, 8).ok()? != NT_GNU_BUILD_ID {
+            return None;
+        }
+        let desc_start = 12 + name_size.div_ceil(4) * 4;
+        note.get(desc_start..desc_start.checked_add(desc_size)?).map(hex::encode)
+    }
+
+    /// PT_LOAD 段的最小对齐（p_align），16 KB 页设备要求不小于 16384
+    pub fn load_alignment(&self) -> Option<u64> {
+        let data = self.data;
+        let is_64 = read_u8(data, 4).ok()? == 2;
+        // e_phoff / e_phentsize / e_phnum
+        let (ph_off, ph_entsize, ph_num) = if is_64 {
+            (read_u64(data, 0x20).ok()? as usize, read_u16(data, 0x36).ok()? as usize, read_u16(data, 0x38).ok()? as usize)
+        } else {
+            (read_u32(data, 0x1c).ok()? as usize, read_u16(data, 0x2a).ok()? as usize, read_u16(data, 0x2c).ok()? as usize)
+        };
+
+        (0..ph_num)
+            .filter_map(|i| {
+                // 程序头表偏移来自文件内容，越界或溢出的条目直接跳过
+                let base = i.checked_mul(ph_entsize)?.checked_add(ph_off)?;
+                if read_u32(data, base).ok()? != PT_LOAD {
+                    return None;
+                }
+                if is_64 {
+                    read_u64(data, base.checked_add(0x30)?).ok()
+                } else {
+                    read_u32(data, base.checked_add(0x1c)?).ok().map(u64::from)
+                }
+            })
+            .min()
+    }
+}
+
+fn read_c_string(data: &[u8], offset: usize) -> String {
+    let bytes = data.get(offset..).unwrap_or(&[]);
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER_SIZE: usize = 0x40;
+    const SECTION_HEADER_SIZE: usize = 0x40;
+
+    /// 拼出 64 位小端 ELF：ELF 头 + 各节区内容 + .shstrtab + 节区头表
+    fn elf64(sections: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut data = vec![0u8; HEADER_SIZE];
+        data[..4].copy_from_slice(ELF_MAGIC);
+        data[4] = 2;
+        data[5] = 1;
+
+        let mut names = vec![0u8];
+        let mut headers = vec![(0u32, 0u64, 0u64)];
+        for (name, content) in sections.iter().chain([&(".shstrtab", &[][..])]) {
+            let name_index = names.len() as u32;
+            names.extend_from_slice(name.as_bytes());
+            names.push(0);
+            let content: &[u8] = if *name == ".shstrtab" { &names } else { content };
+            headers.push((name_index, data.len() as u64, content.len() as u64));
+            data.extend_from_slice(content);
+        }
+
+        let sh_off = data.len() as u64;
+        for (name_index, offset, size) in &headers {
+            let mut header = vec![0u8; SECTION_HEADER_SIZE];
+            header[..4].copy_from_slice(&name_index.to_le_bytes());
+            header[0x18..0x20].copy_from_slice(&offset.to_le_bytes());
+            header[0x20..0x28].copy_from_slice(&size.to_le_bytes());
+            data.extend_from_slice(&header);
+        }
+        data[0x28..0x30].copy_from_slice(&sh_off.to_le_bytes());
+        data[0x3a..0x3c].copy_from_slice(&(SECTION_HEADER_SIZE as u16).to_le_bytes());
+        data[0x3c..0x3e].copy_from_slice(&(headers.len() as u16).to_le_bytes());
+        data[0x3e..0x40].copy_from_slice(&(headers.len() as u16 - 1).to_le_bytes());
+        data
+    }
+
+    const PROGRAM_HEADER_SIZE: usize = 0x38;
+
+    /// 在文件末尾追加程序头表，每项为 (p_type, p_align)
+    fn with_segments(mut data: Vec<u8>, segments: &[(u32, u64)]) -> Vec<u8> {
+        let ph_off = data.len() as u64;
+        for (p_type, align) in segments {
+            let mut header = vec![0u8; PROGRAM_HEADER_SIZE];
+            header[..4].copy_from_slice(&p_type.to_le_bytes());
+            header[0x30..0x38].copy_from_slice(&align.to_le_bytes());
+            data.extend_from_slice(&header);
+        }
+        data[0x20..0x28].copy_from_slice(&ph_off.to_le_bytes());
+        data[0x36..0x38].copy_from_slice(&(PROGRAM_HEADER_SIZE as u16).to_le_bytes());
+        data[0x38..0x3a].copy_from_slice(&(segments.len() as u16).to_le_bytes());
+        data
+    }
+
+    fn build_id_note(id: &[u8]) -> Vec<u8> {
+        let mut note = Vec::new();
+        note.extend_from_slice(&4u32.to_le_bytes());
+        note.extend_from_slice(&(id.len() as u32).to_le_bytes());
+        note.extend_from_slice(&NT_GNU_BUILD_ID.to_le_bytes());
+        note.extend_from_slice(b"GNU\0");
+        note.extend_from_slice(id);
+        note
+    }
+
+    #[test]
+    fn reads_sections_and_build_id() {
+        let note = build_id_note(&[0xde, 0xad, 0xbe, 0xef]);
+        let data = elf64(&[(".note.gnu.build-id", &note), (".text", &[0x90; 8])]);
+        let elf = ElfFile::parse(&data).unwrap();
+        assert!(elf.sections.iter().any(|s| s.name == ".text" && s.size == 8));
+        assert_eq!(elf.section_data(".text"), Some(&[0x90u8; 8][..]));
+        assert_eq!(elf.build_id().as_deref(), Some("deadbeef"));
+    }
+
+    #[test]
+    fn rejects_non_elf() {
+        assert!(ElfFile::parse(b"not an elf").is_err());
+        assert!(ElfFile::parse(&[0u8; 0x40]).is_err());
+    }
+
+    #[test]
+    fn overflowing_section_table_offset_is_skipped() {
+        let mut data = elf64(&[(".text", &[0x90; 8])]);
+        data[0x28..0x30].copy_from_slice(&u64::MAX.to_le_bytes());
+        let elf = ElfFile::parse(&data).unwrap();
+        assert!(elf.sections.is_empty());
+        assert!(elf.build_id().is_none());
+    }
+
+    #[test]
+    fn overflowing_section_range_is_skipped() {
+        let mut data = elf64(&[(".text", &[0x90; 8]), (".data", &[1; 4])]);
+        // 第 1 个节区（.text）的 sh_size 改为 u64::MAX
+        let sh_off = read_u64(&data, 0x28).unwrap() as usize;
+        let size_pos = sh_off + SECTION_HEADER_SIZE + 0x20;
+        data[size_pos..size_pos + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+
+        let elf = ElfFile::parse(&data).unwrap();
+        assert!(elf.section_data(".text").is_none());
+        assert_eq!(elf.section_data(".data"), Some(&[1u8; 4][..]));
+    }
+
+    #[test]
+    fn out_of_bounds_section_data_is_none() {
+        let mut data = elf64(&[(".text", &[0x90; 8])]);
+        let sh_off = read_u64(&data, 0x28).unwrap() as usize;
+        let offset_pos = sh_off + SECTION_HEADER_SIZE + 0x18;
+        data[offset_pos..offset_pos + 8].copy_from_slice(&(u64::MAX - 8).to_le_bytes());
+
+        let elf = ElfFile::parse(&data).unwrap();
+        assert!(elf.section_data(".text").is_none());
+    }
+
+    #[test]
+    fn load_alignment_is_smallest_load_segment() {
+        let data = with_segments(elf64(&[(".text", &[0x90; 8])]), &[(PT_LOAD, 0x4000), (6, 8), (PT_LOAD, 0x1000)]);
+        assert_eq!(ElfFile::parse(&data).unwrap().load_alignment(), Some(0x1000));
+    }
+
+    #[test]
+    fn overflowing_program_header_offset_is_ignored() {
+        let mut data = with_segments(elf64(&[(".text", &[0x90; 8])]), &[(PT_LOAD, 0x4000), (PT_LOAD, 0x4000)]);
+        data[0x20..0x28].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert_eq!(ElfFile::parse(&data).unwrap().load_alignment(), None);
+
+        // 条目大小过大时，第二项的偏移会溢出
+        let mut data = with_segments(elf64(&[(".text", &[0x90; 8])]), &[(PT_LOAD, 0x4000), (PT_LOAD, 0x4000)]);
+        data[0x20..0x28].copy_from_slice(&(u64::MAX - 0x100).to_le_bytes());
+        data[0x36..0x38].copy_from_slice(&u16::MAX.to_le_bytes());
+        assert_eq!(ElfFile::parse(&data).unwrap().load_alignment(), None);
+    }
+}
@@ -0,0 +1,69 @@
+// ==================== 分析错误 ====================
+// 各模块内部用 String 传递带上下文的中文错误信息，对外的入口函数统一返回 AnalysisError
+
+use std::fmt;
+
+/// 检查、转换类功能的错误，Display 输出可以直接展示给用户
+#[derive(Debug)]
+pub enum AnalysisError {
+    /// 文件不存在
+    NotFound,
+    /// 读取文件失败
+    Io(std::io::Error),
+    /// 无法解析 ZIP 结构
+    Zip(zip::result::ZipError),
+    /// 解析或检查失败，附带中文说明
+    Parse(String),
+}
+
+impl fmt::Display for AnalysisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnalysisError::NotFound => write!(f, "文件不存在"),
+            AnalysisError::Io(e) => write!(f, "读取文件失败: {}", e),
+            AnalysisError::Zip(e) => write!(f, "无法解析 ZIP 文件: {}", e),
+            AnalysisError::Parse(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for AnalysisError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AnalysisError::Io(e) => Some(e),
+            AnalysisError::Zip(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for AnalysisError {
+    fn from(e: std::io::Error) -> Self {
+        AnalysisError::Io(e)
+    }
+}
+
+impl From<zip::result::ZipError> for AnalysisError {
+    fn from(e: zip::result::ZipError) -> Self {
+        AnalysisError::Zip(e)
+    }
+}
+
+impl From<String> for AnalysisError {
+    fn from(message: String) -> Self {
+        AnalysisError::Parse(message)
+    }
+}
+
+impl From<&str> for AnalysisError {
+    fn from(message: &str) -> Self {
+        AnalysisError::Parse(message.to_string())
+    }
+}
+
+// 模块之间互相调用时仍按 String 传递，`?` 可以直接转换
+impl From<AnalysisError> for String {
+    fn from(error: AnalysisError) -> Self {
+        error.to_string()
+    }
+}
@@ -0,0 +1,92 @@
+// ==================== 文件信息 ====================
+// 文件大小与 MD5 / SHA1 / SHA256，分块读取计算哈希，避免大文件占用过多内存
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use md5::Md5;
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+use crate::apk::format_file_size;
+use crate::error::AnalysisError;
+
+// 每次读取 8MB
+const CHUNK_SIZE: usize = 8 * 1024 * 1024;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FileInfo {
+    pub file_name: String,
+    pub file_extension: String,
+    pub file_size: u64,
+    pub file_size_readable: String,
+    pub file_path: String,
+    pub md5: String,
+    pub sha1: String,
+    pub sha256: String,
+}
+
+/// 读取文件基本信息并计算哈希
+pub fn file_info(path: &Path) -> Result<FileInfo, AnalysisError> {
+    if !path.exists() {
+        return Err(AnalysisError::NotFound);
+    }
+
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("").to_string();
+    let file_extension = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_string();
+    let file_size = path.metadata()?.len();
+
+    let mut file = File::open(path)?;
+    let mut hasher_md5 = Md5::new();
+    let mut hasher_sha1 = Sha1::new();
+    let mut hasher_sha256 = Sha256::new();
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    loop {
+        let bytes_read = file.read(&mut buffer)?;
+        if bytes_read == 0 {
+            break;
+        }
+        let chunk = &buffer[..bytes_read];
+        hasher_md5.update(chunk);
+        hasher_sha1.update(chunk);
+        hasher_sha256.update(chunk);
+    }
+
+    Ok(FileInfo {
+        file_name,
+        file_extension,
+        file_size,
+        file_size_readable: format_file_size(file_size),
+        file_path: path.to_string_lossy().to_string(),
+        md5: hex::encode(hasher_md5.finalize()),
+        sha1: hex::encode(hasher_sha1.finalize()),
+        sha256: hex::encode(hasher_sha256.finalize()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashes_file_contents() {
+        let path = std::env::temp_dir().join(format!("file_info_{}_abc.txt", std::process::id()));
+        std::fs::write(&path, b"abc").unwrap();
+        let info = file_info(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(info.file_extension, "txt");
+        assert_eq!(info.file_size, 3);
+        assert_eq!(info.md5, "900150983cd24fb0d6963f7d28e17f72");
+        assert_eq!(info.sha1, "a9993e364706816aba3e25717850c26c9cd0d89d");
+        assert_eq!(info.sha256, "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+    }
+
+    #[test]
+    fn missing_file_is_error() {
+        let err = file_info(Path::new("/nonexistent/file_info.bin")).unwrap_err();
+        assert!(matches!(err, AnalysisError::NotFound));
+    }
+}
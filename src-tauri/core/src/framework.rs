@@ -0,0 +1,390 @@
+// ==================== 跨平台框架检测 ====================
+// 根据特征文件识别 Flutter、React Native、Unity、Cocos、uni-app、Xamarin 等框架，适用于 APK 和 IPA
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use regex::bytes::Regex;
+use serde::{Deserialize, Serialize};
+use zip::ZipArchive;
+
+/// 框架特征
+struct FrameworkSignature {
+    name: &'static str,
+    /// 文件名（不含路径）匹配，以 * 结尾表示前缀匹配
+    files: &'static [&'static str],
+    /// 路径片段匹配，* 匹配一级目录名
+    paths: &'static [&'static str],
+}
+
+const FRAMEWORKS: &[FrameworkSignature] = &[
+    FrameworkSignature {
+        name: "Flutter",
+        files: &["libflutter.so"],
+        paths: &["flutter_assets/", "Flutter.framework/"],
+    },
+    FrameworkSignature {
+        name: "React Native",
+        files: &["index.android.bundle", "main.jsbundle", "libreactnativejni.so", "libreact_nativemodule_core.so", "libhermes.so"],
+        paths: &["React.framework/", "hermes.framework/"],
+    },
+    FrameworkSignature {
+        name: "Unity",
+        files: &["libunity.so", "libil2cpp.so", "data.unity3d", "globalgamemanagers"],
+        paths: &["assets/bin/Data/", "UnityFramework.framework/"],
+    },
+    FrameworkSignature {
+        name: "Cocos",
+        files: &["libcocos2d*", "libcocos.so"],
+        paths: &["jsb-adapter/", "assets/src/cocos2d-jsb"],
+    },
+    FrameworkSignature {
+        name: "uni-app",
+        files: &["dcloud_control.xml"],
+        paths: &["assets/apps/*/www/", "Pandora/apps/*/www/"],
+    },
+    FrameworkSignature {
+        name: "Xamarin / .NET MAUI",
+        files: &["libmonodroid.so", "libxamarin-app.so", "libmonosgen-2.0.so", "Mono.Android.dll", "Xamarin.iOS.dll", "Microsoft.iOS.dll"],
+        paths: &["assemblies/assemblies.blob"],
+    },
+    FrameworkSignature {
+        name: "Cordova",
+        files: &["cordova.js", "cordova_plugins.js"],
+        paths: &[],
+    },
+    FrameworkSignature {
+        name: "Capacitor",
+        files: &["native-bridge.js", "capacitor.config.json"],
+        paths: &[],
+    },
+];
+
+// 读取二进制文件查找版本号时的大小上限
+const MAX_SCAN_SIZE: u64 = 128 * 1024 * 1024;
+const MAX_EVIDENCE: usize = 5;
+// Hermes 字节码文件头魔数（小端序）
+const HERMES_MAGIC: u64 = 0x1F19_03C1_03BC_1FC6;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FrameworkMatch {
+    pub name: String,
+    pub version: Option<String>,
+    /// 附加信息：引擎哈希、字节码版本、脚本后端等
+    pub details: BTreeMap<String, String>,
+    pub evidence: Vec<String>,
+}
+
+fn file_name_of(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+fn matches_file(name: &str, pattern: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => name == pattern,
+    }
+}
+
+fn matches_path(name: &str, pattern: &str) -> bool {
+    let Some((prefix, suffix)) = pattern.split_once('*') else {
+        return name.contains(pattern);
+    };
+    let Some(start) = name.find(prefix) else { return false };
+    let rest = &name[start + prefix.len()..];
+    match rest.find('/') {
+        Some(end) if end > 0 => rest[end..].starts_with(suffix),
+        _ => false,
+    }
+}
+
+fn read_limited(archive: &mut ZipArchive<File>, name: &str, limit: u64) -> Option<Vec<u8>> {
+    let entry = archive.by_name(name).ok()?;
+    let mut data = Vec::new();
+    entry.take(limit).read_to_end(&mut data).ok()?;
+    Some(data)
+}
+
+// 在二进制中查找第一个匹配，返回第一个捕获组
+fn find_capture(data: &[u8], pattern: &str) -> Option<String> {
+    let regex = Regex::new(pattern).expect("内置规则无效");
+    regex
+        .captures(data)
+        .and_then(|c| c.get(1))
+        .map(|m| String::from_utf8_lossy(m.as_bytes()).to_string())
+}
+
+// 优先选择 arm64 的库，其余按名称排序
+fn pick_entry<'a>(evidence: &'a [String], names: &[&str]) -> Option<&'a String> {
+    let mut candidates: Vec<&String> = evidence
+        .iter()
+        .filter(|e| names.iter().any(|n| matches_file(file_name_of(e), n)))
+        .collect();
+    candidates.sort_by_key(|e| (!e.contains("arm64"), e.as_str()));
+    candidates.first().copied()
+}
+
+fn flutter_details(archive: &mut ZipArchive<File>, entries: &[String], found: &mut FrameworkMatch) {
+    // Android 为 libflutter.so，iOS 为 Flutter.framework/Flutter
+    let engine = entries
+        .iter()
+        .filter(|e| file_name_of(e) == "libflutter.so" || e.ends_with("Flutter.framework/Flutter"))
+        .min_by_key(|e| !e.contains("arm64"));
+    let Some(data) = engine.and_then(|name| read_limited(archive, name, MAX_SCAN_SIZE)) else {
+        return;
+    };
+
+    // 引擎提交哈希是库中第一个独立的 40 位十六进制字符串
+    if let Some(hash) = find_capture(&data, r"\x00([0-9a-f]{40})\x00") {
+        found.details.insert("engine_hash".to_string(), hash);
+    }
+    if let Some(dart) = find_capture(&data, r"(\d+\.\d+\.\d+(?:-\d+\.\d+\.[a-z]+)?) \((?:stable|beta|dev|main)\)") {
+        found.details.insert("dart_version".to_string(), dart.clone());
+        found.version = Some(format!("Dart {}", dart));
+    }
+}
+
+fn react_native_details(archive: &mut ZipArchive<File>, entries: &[String], found: &mut FrameworkMatch) {
+    let bundle = entries
+        .iter()
+        .find(|e| matches!(file_name_of(e), "index.android.bundle" | "main.jsbundle"));
+    let header = bundle.and_then(|name| read_limited(archive, name, 12));
+    match header {
+        Some(header) if header.len() == 12 && u64::from_le_bytes(header[..8].try_into().unwrap()) == HERMES_MAGIC => {
+            let version = u32::from_le_bytes(header[8..12].try_into().unwrap());
+            found.details.insert("js_engine".to_string(), "Hermes".to_string());
+            found.details.insert("hermes_bytecode_version".to_string(), version.to_string());
+            found.version = Some(format!("Hermes bytecode v{}", version));
+        }
+        Some(_) => {
+            found.details.insert("js_engine".to_string(), "JavaScriptCore（明文 JS Bundle）".to_string());
+        }
+        None => {}
+    }
+}
+
+fn unity_details(archive: &mut ZipArchive<File>, entries: &[String], found: &mut FrameworkMatch) {
+    if entries.iter().any(|e| file_name_of(e) == "libil2cpp.so") {
+        found.details.insert("scripting_backend".to_string(), "IL2CPP".to_string());
+    } else if entries.iter().any(|e| e.contains("/Managed/") && e.ends_with(".dll")) {
+        found.details.insert("scripting_backend".to_string(), "Mono".to_string());
+    }
+
+    // 版本号写在资源文件头部，如 2021.3.16f1；找不到时再扫描引擎库
+    const VERSION_PATTERN: &str = r"((?:20[1-9]\d|[56])\.\d+\.\d+[abfp]\d+)";
+    let sources: [(&str, u64); 4] = [
+        ("globalgamemanagers", 4096),
+        ("data.unity3d", 1024),
+        ("libunity.so", MAX_SCAN_SIZE),
+        ("UnityFramework", MAX_SCAN_SIZE),
+    ];
+    for (file, limit) in sources {
+        let Some(name) = pick_entry(entries, &[file]) else { continue };
+        if let Some(version) = read_limited(archive, name, limit).and_then(|d| find_capture(&d, VERSION_PATTERN)) {
+            found.version = Some(version);
+            return;
+        }
+    }
+}
+
+fn cocos_details(archive: &mut ZipArchive<File>, entries: &[String], found: &mut FrameworkMatch) {
+    let Some(library) = pick_entry(entries, &["libcocos2d*", "libcocos.so"]) else { return };
+    let variant = match file_name_of(library) {
+        "libcocos2djs.so" => "Cocos2d-x JS",
+        "libcocos2dlua.so" => "Cocos2d-x Lua",
+        "libcocos.so" => "Cocos Creator 3.x",
+        _ => "Cocos2d-x C++",
+    };
+    found.details.insert("variant".to_string(), variant.to_string());
+
+    if let Some(data) = read_limited(archive, library, MAX_SCAN_SIZE) {
+        found.version = find_capture(&data, r"(?i)cocos2d-x(?:-lite)?[ -]v?(\d+\.\d+(?:\.\d+)?)")
+            .or_else(|| find_capture(&data, r"Cocos Creator v?(\d+\.\d+\.\d+)"));
+    }
+}
+
+fn uni_app_details(archive: &mut ZipArchive<File>, entries: &[String], found: &mut FrameworkMatch) {
+    // assets/apps/<appid>/www/manifest.json（iOS 为 Pandora/apps/<appid>/www）
+    let Some(manifest) = entries.iter().find(|e| e.contains("apps/") && e.ends_with("/www/manifest.json")) else {
+        return;
+    };
+    let Some(json) = read_limited(archive, manifest, MAX_SCAN_SIZE)
+        .and_then(|data| serde_json::from_slice::<serde_json::Value>(&data).ok())
+    else {
+        return;
+    };
+    if let Some(app_id) = json.get("id").and_then(|v| v.as_str()) {
+        found.details.insert("app_id".to_string(), app_id.to_string());
+    }
+    if let Some(compiler) = json.pointer("/plus/uni-app/compilerVersion") {
+        let compiler = compiler.as_str().map(|s| s.to_string()).unwrap_or_else(|| compiler.to_string());
+        found.version = Some(compiler);
+    }
+    if let Some(renderer) = json.pointer("/plus/renderer").and_then(|v| v.as_str()) {
+        found.details.insert("renderer".to_string(), renderer.to_string());
+    }
+}
+
+fn xamarin_details(entries: &[String], found: &mut FrameworkMatch) {
+    let runtime = if entries.iter().any(|e| file_name_of(e).starts_with("Microsoft.Maui")) {
+        ".NET MAUI"
+    } else if entries.iter().any(|e| matches!(file_name_of(e), "Microsoft.iOS.dll" | "Microsoft.Android.Runtime.dll")) {
+        ".NET for Android/iOS"
+    } else {
+        "Xamarin"
+    };
+    found.details.insert("runtime".to_string(), runtime.to_string());
+}
+
+/// 检测 APK / IPA 使用的跨平台框架
+pub(crate) fn detect_frameworks(path: &Path) -> Result<Vec<FrameworkMatch>, String> {
+    let file = File::open(path).map_err(|e| format!("无法打开文件: {}", e))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("无法解析 ZIP 文件: {}", e))?;
+    let entry_names: Vec<String> = archive.file_names().map(|s| s.to_string()).collect();
+
+    let mut frameworks = Vec::new();
+    for signature in FRAMEWORKS {
+        let entries: Vec<String> = entry_names
+            .iter()
+            .filter(|name| {
+                let file_name = file_name_of(name);
+                signature.files.iter().any(|f| matches_file(file_name, f))
+                    || signature.paths.iter().any(|p| matches_path(name, p))
+            })
+            .cloned()
+            .collect();
+        if entries.is_empty() {
+            continue;
+        }
+
+        let mut found = FrameworkMatch {
+            name: signature.name.to_string(),
+            version: None,
+            details: BTreeMap::new(),
+            evidence: Vec::new(),
+        };
+        match signature.name {
+            "Flutter" => flutter_details(&mut archive, &entries, &mut found),
+            "React Native" => react_native_details(&mut archive, &entries, &mut found),
+            "Unity" => unity_details(&mut archive, &entries, &mut found),
+            "Cocos" => cocos_details(&mut archive, &entries, &mut found),
+            "uni-app" => uni_app_details(&mut archive, &entries, &mut found),
+            "Xamarin / .NET MAUI" => xamarin_details(&entry_names, &mut found),
+            _ => {}
+        }
+
+        // 证据优先展示特征文件，再展示目录匹配
+        let mut evidence = entries;
+        evidence.sort_by_key(|e| {
+            let by_file = signature.files.iter().any(|f| matches_file(file_name_of(e), f));
+            (!by_file, e.len())
+        });
+        evidence.truncate(MAX_EVIDENCE);
+        found.evidence = evidence;
+        frameworks.push(found);
+    }
+
+    Ok(frameworks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    fn detect(name: &str, files: &[(&str, Vec<u8>)]) -> Vec<FrameworkMatch> {
+        let path = std::env::temp_dir().join(format!("framework_{}_{}.apk", name, std::process::id()));
+        let mut writer = ZipWriter::new(File::create(&path).unwrap());
+        for (file, data) in files {
+            writer.start_file(*file, SimpleFileOptions::default()).unwrap();
+            writer.write_all(data).unwrap();
+        }
+        writer.finish().unwrap();
+        let result = detect_frameworks(&path);
+        let _ = std::fs::remove_file(&path);
+        result.unwrap()
+    }
+
+    fn hermes_bundle(version: u32) -> Vec<u8> {
+        let mut data = HERMES_MAGIC.to_le_bytes().to_vec();
+        data.extend_from_slice(&version.to_le_bytes());
+        data.extend_from_slice(&[0; 32]);
+        data
+    }
+
+    #[test]
+    fn uni_app_requires_www_directory() {
+        assert!(matches_path("assets/apps/__UNI__A1B2C3/www/manifest.json", "assets/apps/*/www/"));
+        assert!(matches_path("Payload/Demo.app/Pandora/apps/__UNI__A1B2C3/www/app.js", "Pandora/apps/*/www/"));
+        assert!(!matches_path("assets/apps/config.json", "assets/apps/*/www/"));
+        assert!(!matches_path("assets/apps//www/index.html", "assets/apps/*/www/"));
+        assert!(!matches_path("assets/apps/demo/static/www/index.html", "assets/apps/*/www/"));
+
+        // 其他应用的 assets/apps/ 目录不应被识别为 uni-app
+        let found = detect("apps", &[("assets/apps/featured.json", b"[]".to_vec())]);
+        assert!(found.is_empty());
+
+        let manifest = br#"{"id":"__UNI__A1B2C3","plus":{"renderer":"auto","uni-app":{"compilerVersion":"4.45"}}}"#;
+        let found = detect(
+            "uniapp",
+            &[
+                ("assets/apps/__UNI__A1B2C3/www/manifest.json", manifest.to_vec()),
+                ("assets/apps/__UNI__A1B2C3/www/app-service.js", b"".to_vec()),
+            ],
+        );
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].name, "uni-app");
+        assert_eq!(found[0].version.as_deref(), Some("4.45"));
+        assert_eq!(found[0].details["app_id"], "__UNI__A1B2C3");
+        assert_eq!(found[0].details["renderer"], "auto");
+    }
+
+    #[test]
+    fn reads_hermes_bytecode_header() {
+        let found = detect("hermes", &[("assets/index.android.bundle", hermes_bundle(96))]);
+        assert_eq!(found[0].name, "React Native");
+        assert_eq!(found[0].version.as_deref(), Some("Hermes bytecode v96"));
+        assert_eq!(found[0].details["hermes_bytecode_version"], "96");
+
+        // 明文 JS 和过短的文件不是 Hermes 字节码
+        let found = detect("jsc", &[("assets/index.android.bundle", b"var __BUNDLE_START_TIME__=this.nativePerformanceNow".to_vec())]);
+        assert_eq!(found[0].details["js_engine"], "JavaScriptCore（明文 JS Bundle）");
+        assert_eq!(found[0].version, None);
+        let found = detect("short", &[("assets/index.android.bundle", hermes_bundle(96)[..10].to_vec())]);
+        assert!(!found[0].details.contains_key("hermes_bytecode_version"));
+    }
+
+    #[test]
+    fn reads_engine_versions() {
+        let mut flutter = b"\x7fELF....\x00".to_vec();
+        flutter.extend_from_slice(b"1a65d409c7a1438a34d21b60bf30a6fd5db59314\x00");
+        flutter.extend_from_slice(b"other\x003.5.4 (stable) (Tue Oct 22 2024) on \"android_arm64\"");
+        let found = detect(
+            "engines",
+            &[
+                ("lib/armeabi-v7a/libflutter.so", b"3.0.0 (stable)".to_vec()),
+                ("lib/arm64-v8a/libflutter.so", flutter),
+                ("assets/bin/Data/globalgamemanagers", b"\x00\x00\x00\x162021.3.16f1\x00".to_vec()),
+                ("lib/arm64-v8a/libil2cpp.so", Vec::new()),
+                ("lib/arm64-v8a/libcocos2djs.so", b"Cocos2d-x-3.17.2".to_vec()),
+            ],
+        );
+        let by_name: BTreeMap<&str, &FrameworkMatch> = found.iter().map(|f| (f.name.as_str(), f)).collect();
+
+        let flutter = by_name["Flutter"];
+        assert_eq!(flutter.version.as_deref(), Some("Dart 3.5.4"));
+        assert_eq!(flutter.details["engine_hash"], "1a65d409c7a1438a34d21b60bf30a6fd5db59314");
+
+        let unity = by_name["Unity"];
+        assert_eq!(unity.version.as_deref(), Some("2021.3.16f1"));
+        assert_eq!(unity.details["scripting_backend"], "IL2CPP");
+
+        let cocos = by_name["Cocos"];
+        assert_eq!(cocos.version.as_deref(), Some("3.17.2"));
+        assert_eq!(cocos.details["variant"], "Cocos2d-x JS");
+    }
+}
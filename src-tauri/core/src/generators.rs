@@ -0,0 +1,162 @@
+// ==================== 工具生成器 ====================
+// UUID 与随机密码
+
+use std::fmt;
+use std::str::FromStr;
+
+use rand::Rng;
+use serde::Deserialize;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GeneratorError {
+    /// 不支持的 UUID 版本
+    UnsupportedUuidVersion(String),
+    /// 密码字符类型全部关闭
+    EmptyCharset,
+}
+
+impl fmt::Display for GeneratorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GeneratorError::UnsupportedUuidVersion(version) => write!(f, "不支持的 UUID 版本: {}", version),
+            GeneratorError::EmptyCharset => write!(f, "请至少选择一种字符类型"),
+        }
+    }
+}
+
+impl std::error::Error for GeneratorError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UuidVersion {
+    V4,
+    V7,
+}
+
+impl FromStr for UuidVersion {
+    type Err = GeneratorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "v4" => Ok(UuidVersion::V4),
+            "v7" => Ok(UuidVersion::V7),
+            other => Err(GeneratorError::UnsupportedUuidVersion(other.to_string())),
+        }
+    }
+}
+
+/// 生成 UUID
+pub fn generate_uuids(count: u32, version: UuidVersion, uppercase: bool, with_hyphens: bool) -> Vec<String> {
+    (0..count)
+        .map(|_| {
+            let uuid = match version {
+                UuidVersion::V4 => Uuid::new_v4(),
+                // v7 以毫秒时间戳开头，按生成顺序排序
+                UuidVersion::V7 => Uuid::now_v7(),
+            };
+
+            let mut uuid_string = uuid.to_string();
+            if !with_hyphens {
+                uuid_string = uuid_string.replace('-', "");
+            }
+            if uppercase {
+                uuid_string = uuid_string.to_uppercase();
+            }
+            uuid_string
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PasswordOptions {
+    pub length: u32,
+    pub uppercase: bool,
+    pub lowercase: bool,
+    pub numbers: bool,
+    pub symbols: bool,
+    #[serde(alias = "excludeAmbiguous")]
+    pub exclude_ambiguous: bool,
+}
+
+const UPPERCASE_CHARS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const LOWERCASE_CHARS: &str = "abcdefghijklmnopqrstuvwxyz";
+const NUMBER_CHARS: &str = "0123456789";
+const SYMBOL_CHARS: &str = "!@#$%^&*()_+-=[]{}|;:,.<>?";
+const AMBIGUOUS_CHARS: &str = "0OIl1";
+
+/// 生成密码，每种选中的字符类型至少出现一次
+pub fn generate_passwords(options: &PasswordOptions, count: u32) -> Result<Vec<String>, GeneratorError> {
+    let mut charset = String::new();
+    let mut required_chars = String::new();
+
+    let classes = [
+        (options.uppercase, UPPERCASE_CHARS),
+        (options.lowercase, LOWERCASE_CHARS),
+        (options.numbers, NUMBER_CHARS),
+        (options.symbols, SYMBOL_CHARS),
+    ];
+    for (enabled, chars) in classes {
+        if !enabled {
+            continue;
+        }
+        let chars = if options.exclude_ambiguous {
+            chars.replace(|c| AMBIGUOUS_CHARS.contains(c), "")
+        } else {
+            chars.to_string()
+        };
+        charset.push_str(&chars);
+        if let Some(first) = chars.chars().next() {
+            required_chars.push(first);
+        }
+    }
+
+    if charset.is_empty() {
+        return Err(GeneratorError::EmptyCharset);
+    }
+
+    let charset_vec: Vec<char> = charset.chars().collect();
+    let mut rng = rand::thread_rng();
+    let mut passwords = Vec::new();
+
+    for _ in 0..count {
+        // 先确保包含每种选中的字符类型
+        let mut password = required_chars.clone();
+
+        // 填充剩余长度
+        while password.len() < options.length as usize {
+            let random_index = rng.gen_range(0..charset_vec.len());
+            password.push(charset_vec[random_index]);
+        }
+
+        // 打乱密码顺序
+        let password_chars: Vec<char> = password.chars().collect();
+        let mut shuffled_password = String::new();
+        for _ in 0..password_chars.len() {
+            let random_index = rng.gen_range(0..password_chars.len());
+            shuffled_password.push(password_chars[random_index]);
+        }
+
+        passwords.push(shuffled_password);
+    }
+
+    Ok(passwords)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn v7_uuids_carry_version_seven() {
+        for uuid in generate_uuids(3, UuidVersion::V7, false, true) {
+            assert_eq!(Uuid::parse_str(&uuid).unwrap().get_version_num(), 7);
+        }
+    }
+
+    #[test]
+    fn v4_uuids_carry_version_four() {
+        let uuid = &generate_uuids(1, UuidVersion::V4, true, false)[0];
+        assert_eq!(uuid.len(), 32);
+        assert_eq!(Uuid::parse_str(uuid).unwrap().get_version_num(), 4);
+    }
+}
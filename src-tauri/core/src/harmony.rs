@@ -0,0 +1,477 @@
+// ==================== HarmonyOS HAP / APP 解析 ====================
+// .hap 为单个模块包（module.json），.app 为包含多个 .hap 和 pack.info 的上架包
+
+use std::fs::File;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use zip::ZipArchive;
+
+use crate::manifest::read_entry;
+use crate::signing_block::{self, ApkLayout};
+
+// HAP 签名块头部（32 字节）位于中央目录之前：
+// version(4) + blockCount(4) + blockSize(8) + magic(16)
+const HAP_SIGNING_BLOCK_HEAD_SIZE: u64 = 32;
+const HAP_SIGNING_BLOCK_MAGIC: &[u8; 16] = b"<hap sign block>";
+const HAP_SIGNING_BLOCK_MAGIC_OLD: &[u8; 16] = b"HAP Sig Block 42";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HarmonyAbility {
+    pub name: String,
+    /// UIAbility 为 "ability"，扩展能力为其 type（form、service 等）
+    pub ability_type: String,
+    pub exported: bool,
+    pub icon: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HarmonyModule {
+    pub name: String,
+    /// entry / feature / har / shared
+    pub module_type: String,
+    pub device_types: Vec<String>,
+    pub main_element: Option<String>,
+    pub abilities: Vec<HarmonyAbility>,
+    pub request_permissions: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HarmonyInfo {
+    pub bundle_name: String,
+    pub vendor: String,
+    pub version_name: String,
+    pub version_code: String,
+    /// 最低兼容 API 版本
+    pub compatible_api_version: String,
+    pub target_api_version: String,
+    pub api_release_type: String,
+    pub compile_sdk_version: String,
+    pub debug: bool,
+    pub modules: Vec<HarmonyModule>,
+    /// 所有模块申请的权限（去重）
+    pub permissions: Vec<String>,
+    /// 图标资源引用，如 $media:app_icon
+    pub icon_resource: Option<String>,
+    pub icon_base64: Option<String>,
+    pub has_signature: bool,
+    pub signature_version: Option<u32>,
+    pub warnings: Vec<String>,
+}
+
+fn str_of(value: &Value, key: &str) -> String {
+    match value.get(key) {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Number(n)) => n.to_string(),
+        _ => String::new(),
+    }
+}
+
+fn string_list(value: Option<&Value>) -> Vec<String> {
+    value
+        .and_then(|v| v.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 格式化 API 版本：新版本号编码为 发布版本 * 1000 + API 级别（50000012 → 12 (5.0.0)）
+pub fn format_api_version(value: &Value) -> String {
+    let Some(version) = value.as_u64() else {
+        return value.as_str().unwrap_or("").to_string();
+    };
+    if version < 1000 {
+        return version.to_string();
+    }
+    let api = version % 1000;
+    let release = version / 1000;
+    format!("{} ({}.{}.{})", api, release / 10000, release / 100 % 100, release % 100)
+}
+
+fn read_json<R: Read + Seek>(archive: &mut ZipArchive<R>, name: &str) -> Result<Option<Value>, String> {
+    match read_entry(archive, name) {
+        Ok(data) => serde_json::from_slice(&data)
+            .map(Some)
+            .map_err(|e| format!("解析 {} 失败: {}", name, e)),
+        Err(_) => Ok(None),
+    }
+}
+
+// 解析模块的 module.json（Stage 模型），旧版 FA 模型使用 config.json
+fn parse_module(config: &Value) -> HarmonyModule {
+    let module = config.get("module").cloned().unwrap_or(Value::Null);
+    let mut abilities = Vec::new();
+
+    for ability in module.get("abilities").and_then(|v| v.as_array()).into_iter().flatten() {
+        abilities.push(HarmonyAbility {
+            name: str_of(ability, "name"),
+            // FA 模型中 type 为 page / service / data
+            ability_type: match str_of(ability, "type") {
+                t if t.is_empty() => "ability".to_string(),
+                t => t,
+            },
+            exported: ability
+                .get("exported")
+                .or_else(|| ability.get("visible"))
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+            icon: ability.get("icon").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        });
+    }
+    for extension in module.get("extensionAbilities").and_then(|v| v.as_array()).into_iter().flatten() {
+        abilities.push(HarmonyAbility {
+            name: str_of(extension, "name"),
+            ability_type: str_of(extension, "type"),
+            exported: extension.get("exported").and_then(|v| v.as_bool()).unwrap_or(false),
+            icon: extension.get("icon").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        });
+    }
+
+    let request_permissions = module
+        .get("requestPermissions")
+        .or_else(|| module.get("reqPermissions"))
+        .and_then(|v| v.as_array())
+        .map(|items| items.iter().map(|p| str_of(p, "name")).filter(|n| !n.is_empty()).collect())
+        .unwrap_or_default();
+
+    let main_element = ["mainElement", "mainAbility"]
+        .iter()
+        .map(|key| str_of(&module, key))
+        .find(|s| !s.is_empty());
+
+    HarmonyModule {
+        name: match str_of(&module, "name") {
+            name if name.is_empty() => str_of(module.get("distro").unwrap_or(&Value::Null), "moduleName"),
+            name => name,
+        },
+        module_type: match str_of(&module, "type") {
+            t if t.is_empty() => str_of(module.get("distro").unwrap_or(&Value::Null), "moduleType"),
+            t => t,
+        },
+        device_types: string_list(module.get("deviceTypes").or_else(|| module.get("deviceType"))),
+        main_element,
+        abilities,
+        request_permissions,
+    }
+}
+
+fn read_module_config<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Result<Value, String> {
+    if let Some(config) = read_json(archive, "module.json")? {
+        return Ok(config);
+    }
+    read_json(archive, "config.json")?.ok_or_else(|| "未找到 module.json 或 config.json".to_string())
+}
+
+// 按资源引用（$media:name）查找图标，base 目录优先；分层图标取前景图
+fn extract_icon<R: Read + Seek>(archive: &mut ZipArchive<R>, resource: &str) -> Option<String> {
+    extract_icon_with_visited(archive, resource, &mut Vec::new())
+}
+
+// visited 记录已经展开过的资源，防止分层图标互相引用导致无限递归
+fn extract_icon_with_visited<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    resource: &str,
+    visited: &mut Vec<String>,
+) -> Option<String> {
+    use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+
+    if visited.iter().any(|r| r == resource) {
+        return None;
+    }
+    visited.push(resource.to_string());
+
+    let name = resource.strip_prefix("$media:")?;
+    let mut candidates: Vec<String> = archive
+        .file_names()
+        .filter(|f| {
+            f.starts_with("resources/")
+                && f.contains("/media/")
+                && Path::new(f).file_stem().and_then(|s| s.to_str()) == Some(name)
+        })
+        .map(|f| f.to_string())
+        .collect();
+    candidates.sort_by_key(|f| !f.starts_with("resources/base/"));
+
+    for candidate in candidates {
+        let extension = Path::new(&candidate)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+        let Ok(data) = read_entry(archive, &candidate) else {
+            continue;
+        };
+        match extension.as_str() {
+            "json" => {
+                let foreground = serde_json::from_slice::<Value>(&data).ok().and_then(|layered| {
+                    layered
+                        .get("layered-image")
+                        .and_then(|l| l.get("foreground"))
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.to_string())
+                });
+                if let Some(icon) = foreground.and_then(|f| extract_icon_with_visited(archive, &f, visited)) {
+                    return Some(icon);
+                }
+            }
+            "png" | "webp" => {
+                return Some(format!("data:image/{};base64,{}", extension, BASE64.encode(data)));
+            }
+            "jpg" | "jpeg" => return Some(format!("data:image/jpeg;base64,{}", BASE64.encode(data))),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// HAP 签名块（紧挨中央目录）
+pub struct HapSigningBlock {
+    pub offset: u64,
+    pub version: u32,
+}
+
+/// 读取中央目录前的 HAP 签名块头部
+pub(crate) fn read_hap_signing_block(file: &mut File, layout: &ApkLayout) -> Result<Option<HapSigningBlock>, String> {
+    if layout.central_directory_offset < HAP_SIGNING_BLOCK_HEAD_SIZE {
+        return Ok(None);
+    }
+    let mut head = [0u8; HAP_SIGNING_BLOCK_HEAD_SIZE as usize];
+    file.seek(SeekFrom::Start(layout.central_directory_offset - HAP_SIGNING_BLOCK_HEAD_SIZE))
+        .map_err(|e| e.to_string())?;
+    file.read_exact(&mut head).map_err(|e| e.to_string())?;
+    let magic = &head[16..32];
+    if magic != HAP_SIGNING_BLOCK_MAGIC && magic != HAP_SIGNING_BLOCK_MAGIC_OLD {
+        return Ok(None);
+    }
+
+    // blockSize 为包含头部在内的整个签名块大小
+    let block_size = u64::from_le_bytes(head[8..16].try_into().unwrap());
+    if block_size < HAP_SIGNING_BLOCK_HEAD_SIZE || block_size > layout.central_directory_offset {
+        return Err("HAP 签名块大小无效".to_string());
+    }
+    Ok(Some(HapSigningBlock {
+        offset: layout.central_directory_offset - block_size,
+        version: u32::from_le_bytes(head[0..4].try_into().unwrap()),
+    }))
+}
+
+fn read_signature_version(path: &Path) -> Result<Option<u32>, String> {
+    let mut file = File::open(path).map_err(|e| format!("无法打开文件: {}", e))?;
+    let layout = signing_block::read_layout(&mut file)?;
+    Ok(read_hap_signing_block(&mut file, &layout)?.map(|block| block.version))
+}
+
+/// 解析 .hap 或 .app 文件
+pub(crate) fn parse_harmony_package(path: &Path) -> Result<HarmonyInfo, String> {
+    let file = File::open(path).map_err(|e| format!("无法打开文件: {}", e))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("无法解析 ZIP 文件: {}", e))?;
+    let mut warnings = Vec::new();
+
+    let pack_info = read_json(&mut archive, "pack.info")?;
+
+    // .app 内的每个 .hap 都是独立的 ZIP，entry 模块放在最前面
+    let mut hap_names: Vec<String> = archive
+        .file_names()
+        .filter(|f| !f.contains('/') && f.ends_with(".hap"))
+        .map(|f| f.to_string())
+        .collect();
+
+    let mut configs = Vec::new();
+    let mut icon_base64 = None;
+    let mut icon_resource = None;
+    if hap_names.is_empty() {
+        let config = read_module_config(&mut archive)?;
+        icon_resource = app_icon_resource(&config);
+        if let Some(resource) = &icon_resource {
+            icon_base64 = extract_icon(&mut archive, resource);
+        }
+        configs.push(config);
+    } else {
+        hap_names.sort();
+        for hap_name in &hap_names {
+            let data = read_entry(&mut archive, hap_name)?;
+            let mut hap = match ZipArchive::new(Cursor::new(data)) {
+                Ok(hap) => hap,
+                Err(e) => {
+                    warnings.push(format!("{} 不是有效的 HAP: {}", hap_name, e));
+                    continue;
+                }
+            };
+            let config = match read_module_config(&mut hap) {
+                Ok(config) => config,
+                Err(e) => {
+                    warnings.push(format!("{}: {}", hap_name, e));
+                    continue;
+                }
+            };
+            let is_entry = parse_module(&config).module_type == "entry";
+            if icon_base64.is_none() || is_entry {
+                if let Some(resource) = app_icon_resource(&config) {
+                    if let Some(icon) = extract_icon(&mut hap, &resource) {
+                        icon_base64 = Some(icon);
+                        icon_resource = Some(resource);
+                    }
+                }
+            }
+            if is_entry {
+                configs.insert(0, config);
+            } else {
+                configs.push(config);
+            }
+        }
+        if configs.is_empty() {
+            return Err("APP 包中没有可解析的 HAP 模块".to_string());
+        }
+    }
+
+    let app = configs[0].get("app").cloned().unwrap_or(Value::Null);
+    let modules: Vec<HarmonyModule> = configs.iter().map(parse_module).collect();
+
+    let mut permissions: Vec<String> = modules
+        .iter()
+        .flat_map(|m| m.request_permissions.iter().cloned())
+        .collect();
+    permissions.sort();
+    permissions.dedup();
+
+    // API 版本：module.json 中的 app 字段优先，FA 模型在 apiVersion 中，上架包可从 pack.info 读取
+    let api_version = app.get("apiVersion").cloned().unwrap_or(Value::Null);
+    let pack_api = pack_info
+        .as_ref()
+        .and_then(|p| p.pointer("/summary/modules/0/apiVersion"))
+        .cloned()
+        .unwrap_or(Value::Null);
+    let pick_api = |keys: [(&Value, &str); 3]| {
+        keys.iter()
+            .filter_map(|(source, key)| source.get(key))
+            .map(format_api_version)
+            .find(|s| !s.is_empty())
+            .unwrap_or_default()
+    };
+    let compatible_api_version = pick_api([(&app, "minAPIVersion"), (&api_version, "compatible"), (&pack_api, "compatible")]);
+    let target_api_version = pick_api([(&app, "targetAPIVersion"), (&api_version, "target"), (&pack_api, "target")]);
+
+    // FA 模型的版本号在 app.version 中
+    let version = app.get("version").cloned().unwrap_or(Value::Null);
+    let pick = |key: &str, fallback: &str| match str_of(&app, key) {
+        s if s.is_empty() => str_of(&version, fallback),
+        s => s,
+    };
+
+    let signature_version = match read_signature_version(path) {
+        Ok(version) => version,
+        Err(e) => {
+            warnings.push(format!("签名块读取失败: {}", e));
+            None
+        }
+    };
+    if signature_version.is_none() && hap_names.is_empty() {
+        warnings.push("HAP 未签名，无法安装到设备".to_string());
+    }
+    if icon_base64.is_none() {
+        warnings.push("未找到图标资源文件".to_string());
+    }
+
+    Ok(HarmonyInfo {
+        bundle_name: str_of(&app, "bundleName"),
+        vendor: str_of(&app, "vendor"),
+        version_name: pick("versionName", "name"),
+        version_code: pick("versionCode", "code"),
+        compatible_api_version,
+        target_api_version,
+        api_release_type: match str_of(&app, "apiReleaseType") {
+            s if s.is_empty() => str_of(&api_version, "releaseType"),
+            s => s,
+        },
+        compile_sdk_version: str_of(&app, "compileSdkVersion"),
+        debug: app.get("debug").and_then(|v| v.as_bool()).unwrap_or(false),
+        modules,
+        permissions,
+        icon_resource,
+        icon_base64,
+        has_signature: signature_version.is_some(),
+        signature_version,
+        warnings,
+    })
+}
+
+// 应用图标：app.icon 优先，其次为入口 Ability 的图标
+fn app_icon_resource(config: &Value) -> Option<String> {
+    if let Some(icon) = config.pointer("/app/icon").and_then(|v| v.as_str()) {
+        return Some(icon.to_string());
+    }
+    let module = parse_module(config);
+    let main = module.main_element.as_deref();
+    module
+        .abilities
+        .iter()
+        .find(|a| Some(a.name.as_str()) == main)
+        .or_else(|| module.abilities.first())
+        .and_then(|a| a.icon.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    fn build_archive(entries: &[(&str, &[u8])]) -> ZipArchive<Cursor<Vec<u8>>> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in entries {
+            writer.start_file(*name, SimpleFileOptions::default()).unwrap();
+            writer.write_all(content).unwrap();
+        }
+        ZipArchive::new(Cursor::new(writer.finish().unwrap().into_inner())).unwrap()
+    }
+
+    fn layered(foreground: &str) -> Vec<u8> {
+        format!(r#"{{"layered-image":{{"background":"$media:bg","foreground":"{}"}}}}"#, foreground).into_bytes()
+    }
+
+    #[test]
+    fn layered_icon_uses_foreground() {
+        let mut archive = build_archive(&[
+            ("resources/base/media/app_icon.json", &layered("$media:fg")),
+            ("resources/base/media/fg.png", b"png"),
+        ]);
+        let icon = extract_icon(&mut archive, "$media:app_icon").unwrap();
+        assert_eq!(icon, "data:image/png;base64,cG5n");
+    }
+
+    #[test]
+    fn self_referencing_layered_icon_terminates() {
+        let mut archive = build_archive(&[("resources/base/media/app_icon.json", &layered("$media:app_icon"))]);
+        assert_eq!(extract_icon(&mut archive, "$media:app_icon"), None);
+
+        let mut archive = build_archive(&[
+            ("resources/base/media/a.json", &layered("$media:b")),
+            ("resources/base/media/b.json", &layered("$media:a")),
+        ]);
+        assert_eq!(extract_icon(&mut archive, "$media:a"), None);
+    }
+
+    #[test]
+    fn invalid_candidate_falls_back_to_next() {
+        let mut archive = build_archive(&[
+            ("resources/base/media/app_icon.json", b"not json"),
+            ("resources/zh_CN/media/app_icon.webp", b"webp"),
+        ]);
+        let icon = extract_icon(&mut archive, "$media:app_icon").unwrap();
+        assert!(icon.starts_with("data:image/webp;base64,"));
+    }
+
+    #[test]
+    fn formats_api_versions() {
+        assert_eq!(format_api_version(&Value::from(9)), "9");
+        assert_eq!(format_api_version(&Value::from(50000012)), "12 (5.0.0)");
+        assert_eq!(format_api_version(&Value::from("4.0.0")), "4.0.0");
+    }
+}
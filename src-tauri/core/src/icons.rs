@@ -0,0 +1,65 @@
+// ==================== APP 图标生成 ====================
+// 将一张源图生成多尺寸的正方形图标，可加边距和圆角
+
+use ::image::DynamicImage;
+
+use crate::image::{self, ImageError, OutputFormat, DEFAULT_JPEG_QUALITY};
+
+#[derive(Debug, Clone)]
+pub struct IconOptions {
+    /// 图标边长（像素）
+    pub sizes: Vec<u32>,
+    /// 圆角半径占边长的百分比
+    pub radius_percent: u32,
+    /// 四周边距占边长的百分比
+    pub padding_percent: u32,
+    pub format: OutputFormat,
+}
+
+#[derive(Debug, Clone)]
+pub struct Icon {
+    pub size: u32,
+    /// 按 format 编码后的图片字节
+    pub data: Vec<u8>,
+}
+
+/// 按 options 中的每个尺寸生成图标
+pub fn generate_icons(source: &[u8], options: &IconOptions) -> Result<Vec<Icon>, ImageError> {
+    if options.padding_percent >= 50 {
+        return Err(ImageError::InvalidPadding(options.padding_percent));
+    }
+    let source_img = image::load(source)?;
+
+    let mut icons = Vec::new();
+    for &size in &options.sizes {
+        let padding = (size as f64 * options.padding_percent as f64 / 100.0) as u32;
+        let content_size = size - padding * 2;
+
+        let resized = ::image::imageops::resize(
+            &source_img,
+            content_size,
+            content_size,
+            ::image::imageops::FilterType::Lanczos3,
+        );
+
+        // 透明的正方形画布，内容按边距居中
+        let mut canvas = ::image::RgbaImage::new(size, size);
+        canvas.fill(0u8);
+        ::image::imageops::overlay(&mut canvas, &resized, padding as i64, padding as i64);
+
+        let canvas = DynamicImage::ImageRgba8(canvas);
+        let radius = (size as f64 * options.radius_percent as f64 / 100.0) as u32;
+        let rounded = if radius > 0 {
+            image::round_corners(&canvas, radius)
+        } else {
+            canvas
+        };
+
+        icons.push(Icon {
+            size,
+            data: image::encode(&rounded, options.format, DEFAULT_JPEG_QUALITY)?,
+        });
+    }
+
+    Ok(icons)
+}
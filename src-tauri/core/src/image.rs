@@ -0,0 +1,256 @@
+// ==================== 图片处理 ====================
+// 调整尺寸、添加圆角和按输出格式编码，输入输出均为图片文件字节
+
+use std::fmt;
+use std::str::FromStr;
+
+use ::image::imageops::FilterType;
+use ::image::{DynamicImage, GenericImageView, ImageFormat};
+
+/// 未指定质量时的 JPEG 质量
+pub const DEFAULT_JPEG_QUALITY: u8 = 90;
+
+#[derive(Debug)]
+pub enum ImageError {
+    /// 无法识别或解码输入图片
+    Decode(::image::ImageError),
+    /// 按输出格式编码失败
+    Encode(::image::ImageError),
+    /// 未知的调整模式
+    UnknownMode(String),
+    /// 图标边距过大，内容区域为空
+    InvalidPadding(u32),
+}
+
+impl fmt::Display for ImageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImageError::Decode(e) => write!(f, "图片加载失败: {}", e),
+            ImageError::Encode(e) => write!(f, "图片编码失败: {}", e),
+            ImageError::UnknownMode(mode) => write!(f, "未知的调整模式: {}", mode),
+            ImageError::InvalidPadding(percent) => write!(f, "边距过大: {}%，需小于 50%", percent),
+        }
+    }
+}
+
+impl std::error::Error for ImageError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ImageError::Decode(e) | ImageError::Encode(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// 调整尺寸的方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResizeMode {
+    /// 保持宽高比，完整显示在目标区域内，空白处透明
+    Fit,
+    /// 保持宽高比，填满目标区域，居中裁剪多余部分
+    Fill,
+    /// 直接拉伸到目标尺寸
+    Stretch,
+}
+
+impl FromStr for ResizeMode {
+    type Err = ImageError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "fit" => Ok(ResizeMode::Fit),
+            "fill" => Ok(ResizeMode::Fill),
+            "stretch" => Ok(ResizeMode::Stretch),
+            other => Err(ImageError::UnknownMode(other.to_string())),
+        }
+    }
+}
+
+/// 输出图片格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Png,
+    Jpeg,
+    WebP,
+}
+
+impl OutputFormat {
+    /// 识别 image/png、png、jpg 等写法，无法识别时使用 PNG
+    pub fn from_name(name: &str) -> Self {
+        match name.to_ascii_lowercase().as_str() {
+            "image/jpeg" | "jpg" | "jpeg" => OutputFormat::Jpeg,
+            "image/webp" | "webp" => OutputFormat::WebP,
+            _ => OutputFormat::Png,
+        }
+    }
+
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            OutputFormat::Png => "image/png",
+            OutputFormat::Jpeg => "image/jpeg",
+            OutputFormat::WebP => "image/webp",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Png => "png",
+            OutputFormat::Jpeg => "jpg",
+            OutputFormat::WebP => "webp",
+        }
+    }
+}
+
+/// 从文件字节加载图片
+pub fn load(data: &[u8]) -> Result<DynamicImage, ImageError> {
+    ::image::load_from_memory(data).map_err(ImageError::Decode)
+}
+
+/// 按输出格式编码，PNG 使用快速压缩级别，JPEG 转换为 RGB 后按 quality 编码
+pub fn encode(img: &DynamicImage, format: OutputFormat, quality: u8) -> Result<Vec<u8>, ImageError> {
+    let mut buffer = Vec::new();
+    let mut cursor = std::io::Cursor::new(&mut buffer);
+    match format {
+        OutputFormat::Png => {
+            let encoder = ::image::codecs::png::PngEncoder::new_with_quality(
+                &mut cursor,
+                ::image::codecs::png::CompressionType::Fast,
+                ::image::codecs::png::FilterType::Adaptive,
+            );
+            img.write_with_encoder(encoder).map_err(ImageError::Encode)?;
+        }
+        OutputFormat::Jpeg => {
+            // JPEG 不支持透明通道
+            let rgb_img = img.to_rgb8();
+            let mut encoder = ::image::codecs::jpeg::JpegEncoder::new_with_quality(&mut cursor, quality);
+            encoder
+                .encode(&rgb_img, img.width(), img.height(), ::image::ExtendedColorType::Rgb8)
+                .map_err(ImageError::Encode)?;
+        }
+        OutputFormat::WebP => {
+            img.write_to(&mut cursor, ImageFormat::WebP).map_err(ImageError::Encode)?;
+        }
+    }
+    Ok(buffer)
+}
+
+/// 调整图片尺寸
+pub fn resize(
+    data: &[u8],
+    target_width: u32,
+    target_height: u32,
+    mode: ResizeMode,
+    format: OutputFormat,
+    quality: u8,
+) -> Result<Vec<u8>, ImageError> {
+    let img = load(data)?;
+    let (orig_width, orig_height) = img.dimensions();
+
+    let resized_img = match mode {
+        ResizeMode::Fit => {
+            let ratio = (target_width as f64 / orig_width as f64)
+                .min(target_height as f64 / orig_height as f64);
+            let new_width = (orig_width as f64 * ratio).round() as u32;
+            let new_height = (orig_height as f64 * ratio).round() as u32;
+
+            let resized = ::image::imageops::resize(&img, new_width, new_height, FilterType::Lanczos3);
+
+            // 创建目标尺寸的透明画布并居中放置
+            let mut canvas = ::image::RgbaImage::new(target_width, target_height);
+            canvas.fill(0u8);
+
+            let offset_x = ((target_width - new_width) / 2) as i64;
+            let offset_y = ((target_height - new_height) / 2) as i64;
+            ::image::imageops::overlay(&mut canvas, &resized, offset_x, offset_y);
+
+            DynamicImage::ImageRgba8(canvas)
+        }
+        ResizeMode::Fill => {
+            let ratio = (target_width as f64 / orig_width as f64)
+                .max(target_height as f64 / orig_height as f64);
+            let new_width = (orig_width as f64 * ratio).round() as u32;
+            let new_height = (orig_height as f64 * ratio).round() as u32;
+
+            let resized = ::image::imageops::resize(&img, new_width, new_height, FilterType::Lanczos3);
+
+            // 居中裁剪到目标尺寸
+            let offset_x = (new_width - target_width) / 2;
+            let offset_y = (new_height - target_height) / 2;
+            let cropped = resized.view(offset_x, offset_y, target_width, target_height).to_image();
+            DynamicImage::ImageRgba8(cropped)
+        }
+        ResizeMode::Stretch => DynamicImage::ImageRgba8(::image::imageops::resize(
+            &img,
+            target_width,
+            target_height,
+            FilterType::Lanczos3,
+        )),
+    };
+
+    encode(&resized_img, format, quality)
+}
+
+/// 为图片添加圆角，半径不超过短边的一半
+pub fn add_radius(data: &[u8], radius: u32, format: OutputFormat) -> Result<Vec<u8>, ImageError> {
+    let img = load(data)?;
+    let (width, height) = img.dimensions();
+    let max_radius = radius.min(width.min(height) / 2);
+
+    let rounded_img = if format == OutputFormat::Png {
+        // PNG 支持透明度，可以真正实现圆角
+        round_corners(&img, max_radius)
+    } else {
+        // JPG 等不支持透明度，只能绘制白色圆角背景
+        round_corners_with_background(&img, max_radius)
+    };
+
+    encode(&rounded_img, format, DEFAULT_JPEG_QUALITY)
+}
+
+/// 将四个角外的像素设为透明
+pub fn round_corners(img: &DynamicImage, radius: u32) -> DynamicImage {
+    let (width, height) = img.dimensions();
+
+    if radius == 0 {
+        return img.clone();
+    }
+
+    let mut rgba_img = img.to_rgba8();
+
+    // 只处理四个角：(x, y) 为相对左上角的距离，按镜像映射到四个角
+    let r = radius as f64;
+    let r_squared = r * r;
+    for y in 0..radius.min(height) {
+        for x in 0..radius.min(width) {
+            let dx = x as f64 - r;
+            let dy = y as f64 - r;
+            if dx * dx + dy * dy <= r_squared {
+                continue;
+            }
+            let corners = [
+                (x, y),
+                (width - 1 - x, y),
+                (x, height - 1 - y),
+                (width - 1 - x, height - 1 - y),
+            ];
+            for (px, py) in corners {
+                if let Some(pixel) = rgba_img.get_pixel_mut_checked(px, py) {
+                    pixel[3] = 0;
+                }
+            }
+        }
+    }
+
+    DynamicImage::ImageRgba8(rgba_img)
+}
+
+/// 圆角外填充白色背景，用于不支持透明的格式
+pub fn round_corners_with_background(img: &DynamicImage, radius: u32) -> DynamicImage {
+    let (width, height) = img.dimensions();
+    let rounded = round_corners(img, radius);
+
+    let mut bg_img = ::image::RgbaImage::from_pixel(width, height, ::image::Rgba([255, 255, 255, 255]));
+    ::image::imageops::overlay(&mut bg_img, &rounded.to_rgba8(), 0, 0);
+
+    DynamicImage::ImageRgba8(bg_img)
+}
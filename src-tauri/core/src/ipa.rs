@@ -0,0 +1,614 @@
+// ==================== iOS IPA 解析 ====================
+// 读取 Payload/*.app 下的 Info.plist、embedded.mobileprovision 和应用图标
+
+use std::fs::File;
+use std::io::{Cursor, Read};
+use std::path::Path;
+use std::time::SystemTime;
+
+use flate2::read::DeflateDecoder;
+use plist::{Dictionary, Value};
+use serde::{Deserialize, Serialize};
+use zip::ZipArchive;
+
+use crate::manifest::read_entry;
+
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+
+/// 描述文件信息（embedded.mobileprovision）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProvisioningProfile {
+    pub name: String,
+    pub uuid: String,
+    pub team_id: String,
+    pub team_name: String,
+    pub app_id_name: String,
+    /// development / ad-hoc / enterprise / app-store
+    pub profile_type: String,
+    pub creation_date: Option<String>,
+    pub expiration_date: Option<String>,
+    pub expired: bool,
+    pub provisions_all_devices: bool,
+    pub provisioned_devices: Vec<String>,
+    pub entitlements: serde_json::Value,
+}
+
+/// IPA 专有信息
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IpaInfo {
+    pub app_path: String,
+    pub bundle_id: String,
+    pub display_name: String,
+    pub executable: String,
+    /// CFBundleShortVersionString
+    pub version: String,
+    /// CFBundleVersion
+    pub build: String,
+    pub minimum_os_version: String,
+    pub platform_version: String,
+    pub sdk_name: String,
+    pub device_families: Vec<String>,
+    /// Info.plist 中声明的隐私权限说明（NS*UsageDescription）
+    pub usage_descriptions: Vec<String>,
+    pub provisioning: Option<ProvisioningProfile>,
+    pub icon_base64: Option<String>,
+    pub warnings: Vec<String>,
+}
+
+fn string_of(dict: &Dictionary, key: &str) -> String {
+    dict.get(key)
+        .and_then(|v| v.as_string())
+        .unwrap_or("")
+        .to_string()
+}
+
+fn date_of(dict: &Dictionary, key: &str) -> Option<plist::Date> {
+    dict.get(key).and_then(|v| v.as_date())
+}
+
+fn device_family_name(family: u64) -> String {
+    match family {
+        1 => "iPhone".to_string(),
+        2 => "iPad".to_string(),
+        3 => "Apple TV".to_string(),
+        4 => "Apple Watch".to_string(),
+        6 => "Mac Catalyst".to_string(),
+        7 => "Apple Vision".to_string(),
+        other => format!("未知设备 ({})", other),
+    }
+}
+
+// UIDeviceFamily 通常是整数数组，少数旧包直接写成单个整数
+fn device_families(dict: &Dictionary) -> Vec<String> {
+    let values: Vec<&Value> = match dict.get("UIDeviceFamily") {
+        Some(Value::Array(items)) => items.iter().collect(),
+        Some(value) => vec![value],
+        None => Vec::new(),
+    };
+    values
+        .into_iter()
+        .filter_map(|v| {
+            v.as_unsigned_integer()
+                .or_else(|| v.as_string().and_then(|s| s.parse().ok()))
+        })
+        .map(device_family_name)
+        .collect()
+}
+
+// 将 plist 值转换为 JSON，日期转为 ISO 8601 字符串，二进制数据转为 Base64
+fn plist_to_json(value: &Value) -> serde_json::Value {
+    use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+
+    match value {
+        Value::Array(items) => serde_json::Value::Array(items.iter().map(plist_to_json).collect()),
+        Value::Dictionary(dict) => serde_json::Value::Object(
+            dict.iter()
+                .map(|(k, v)| (k.clone(), plist_to_json(v)))
+                .collect(),
+        ),
+        Value::Boolean(b) => serde_json::Value::Bool(*b),
+        Value::Data(data) => serde_json::Value::String(BASE64.encode(data)),
+        Value::Date(date) => serde_json::Value::String(date.to_xml_format()),
+        Value::Real(real) => serde_json::json!(real),
+        Value::Integer(integer) => integer
+            .as_signed()
+            .map(|i| serde_json::json!(i))
+            .or_else(|| integer.as_unsigned().map(|u| serde_json::json!(u)))
+            .unwrap_or(serde_json::Value::Null),
+        Value::String(s) => serde_json::Value::String(s.clone()),
+        Value::Uid(uid) => serde_json::json!(uid.get()),
+        _ => serde_json::Value::Null,
+    }
+}
+
+// 查找 Payload/<name>.app/ 目录
+fn find_app_dir(names: &[String]) -> Option<String> {
+    names.iter().find_map(|name| {
+        let rest = name.strip_prefix("Payload/")?;
+        let end = rest.find(".app/")?;
+        let app = &rest[..end];
+        // 只接受 Payload 下第一层的 .app，跳过内嵌的 Watch/扩展应用
+        (!app.contains('/')).then(|| format!("Payload/{}.app/", app))
+    })
+}
+
+/// 解析描述文件：外层是 CMS 签名结构，内容为 XML plist
+pub(crate) fn parse_mobileprovision(data: &[u8]) -> Result<ProvisioningProfile, String> {
+    let start = find_bytes(data, b"<?xml").ok_or("描述文件中未找到 plist 内容")?;
+    let end_tag = b"</plist>";
+    let end = find_bytes(&data[start..], end_tag).ok_or("描述文件中的 plist 不完整")? + start + end_tag.len();
+    let dict = Value::from_reader_xml(&data[start..end])
+        .map_err(|e| format!("解析描述文件失败: {}", e))?
+        .into_dictionary()
+        .ok_or("描述文件格式无效")?;
+
+    let team_id = dict
+        .get("TeamIdentifier")
+        .and_then(|v| v.as_array())
+        .and_then(|ids| ids.first())
+        .and_then(|v| v.as_string())
+        .unwrap_or("")
+        .to_string();
+    let provisioned_devices: Vec<String> = dict
+        .get("ProvisionedDevices")
+        .and_then(|v| v.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|v| v.as_string().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+    let provisions_all_devices = dict
+        .get("ProvisionsAllDevices")
+        .and_then(|v| v.as_boolean())
+        .unwrap_or(false);
+    let entitlements = dict.get("Entitlements");
+    let get_task_allow = entitlements
+        .and_then(|v| v.as_dictionary())
+        .and_then(|e| e.get("get-task-allow"))
+        .and_then(|v| v.as_boolean())
+        .unwrap_or(false);
+
+    let profile_type = if provisions_all_devices {
+        "enterprise"
+    } else if !provisioned_devices.is_empty() {
+        if get_task_allow { "development" } else { "ad-hoc" }
+    } else {
+        "app-store"
+    };
+
+    let expiration = date_of(&dict, "ExpirationDate");
+    let expired = expiration
+        .map(|date| SystemTime::from(date) < SystemTime::now())
+        .unwrap_or(false);
+
+    Ok(ProvisioningProfile {
+        name: string_of(&dict, "Name"),
+        uuid: string_of(&dict, "UUID"),
+        team_id,
+        team_name: string_of(&dict, "TeamName"),
+        app_id_name: string_of(&dict, "AppIDName"),
+        profile_type: profile_type.to_string(),
+        creation_date: date_of(&dict, "CreationDate").map(|d| d.to_xml_format()),
+        expiration_date: expiration.map(|d| d.to_xml_format()),
+        expired,
+        provisions_all_devices,
+        provisioned_devices,
+        entitlements: entitlements.map(plist_to_json).unwrap_or(serde_json::Value::Null),
+    })
+}
+
+fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+// 从 Info.plist 收集图标文件名前缀
+fn icon_names(info: &Dictionary) -> Vec<String> {
+    let mut names = Vec::new();
+    for icons_key in ["CFBundleIcons", "CFBundleIcons~ipad"] {
+        if let Some(files) = info
+            .get(icons_key)
+            .and_then(|v| v.as_dictionary())
+            .and_then(|d| d.get("CFBundlePrimaryIcon"))
+            .and_then(|v| v.as_dictionary())
+            .and_then(|d| d.get("CFBundleIconFiles"))
+            .and_then(|v| v.as_array())
+        {
+            names.extend(files.iter().filter_map(|v| v.as_string().map(|s| s.to_string())));
+        }
+    }
+    if let Some(files) = info.get("CFBundleIconFiles").and_then(|v| v.as_array()) {
+        names.extend(files.iter().filter_map(|v| v.as_string().map(|s| s.to_string())));
+    }
+    if let Some(file) = info.get("CFBundleIconFile").and_then(|v| v.as_string()) {
+        names.push(file.to_string());
+    }
+    // 文件名可能带扩展名，统一去掉后按前缀匹配（AppIcon60x60 → AppIcon60x60@3x.png）
+    names
+        .into_iter()
+        .map(|n| n.trim_end_matches(".png").to_string())
+        .filter(|n| !n.is_empty())
+        .collect()
+}
+
+// 在 .app 根目录中选择最大的图标文件
+fn extract_icon(archive: &mut ZipArchive<File>, app_dir: &str, info: &Dictionary) -> Option<String> {
+    use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+
+    let mut names = icon_names(info);
+    if names.is_empty() {
+        names = vec!["AppIcon".to_string(), "Icon".to_string()];
+    }
+
+    let mut best: Option<(String, u64)> = None;
+    for i in 0..archive.len() {
+        let Ok(entry) = archive.by_index(i) else { continue };
+        let Some(file_name) = entry.name().strip_prefix(app_dir) else { continue };
+        if file_name.contains('/') || !file_name.to_lowercase().ends_with(".png") {
+            continue;
+        }
+        if !names.iter().any(|n| file_name.starts_with(n.as_str())) {
+            continue;
+        }
+        if best.as_ref().is_none_or(|(_, size)| entry.size() > *size) {
+            best = Some((entry.name().to_string(), entry.size()));
+        }
+    }
+
+    let (name, _) = best?;
+    let data = read_entry(archive, &name).ok()?;
+    let png = uncrush_png(&data)?;
+    Some(format!("data:image/png;base64,{}", BASE64.encode(png)))
+}
+
+/// 还原 Xcode 优化过的 CgBI PNG：原始 deflate 数据、BGRA 字节序、预乘 Alpha
+///
+/// 普通 PNG 原样返回；无法处理的格式返回 None。
+pub fn uncrush_png(data: &[u8]) -> Option<Vec<u8>> {
+    if data.len() < 8 || data[..8] != PNG_SIGNATURE {
+        return None;
+    }
+
+    let mut pos = 8;
+    let mut is_cgbi = false;
+    let mut header: Option<(u32, u32, u8, u8, u8)> = None;
+    let mut idat = Vec::new();
+    while pos + 8 <= data.len() {
+        let len = u32::from_be_bytes(data[pos..pos + 4].try_into().ok()?) as usize;
+        let kind = &data[pos + 4..pos + 8];
+        let body = data.get(pos + 8..pos + 8 + len)?;
+        match kind {
+            b"CgBI" => is_cgbi = true,
+            b"IHDR" if len >= 13 => {
+                header = Some((
+                    u32::from_be_bytes(body[0..4].try_into().ok()?),
+                    u32::from_be_bytes(body[4..8].try_into().ok()?),
+                    body[8],
+                    body[9],
+                    body[12],
+                ));
+            }
+            b"IDAT" => idat.extend_from_slice(body),
+            b"IEND" => break,
+            _ => {}
+        }
+        pos += 12 + len;
+    }
+
+    if !is_cgbi {
+        return Some(data.to_vec());
+    }
+
+    // 只处理 Xcode 实际生成的 8 位 RGB/RGBA、非隔行格式
+    let (width, height, bit_depth, color_type, interlace) = header?;
+    let channels = match color_type {
+        2 => 3,
+        6 => 4,
+        _ => return None,
+    };
+    if bit_depth != 8 || interlace != 0 {
+        return None;
+    }
+
+    // 每行前有 1 字节过滤类型，只解压需要的长度，避免异常数据解压出过大的内容
+    let stride = (width as usize).checked_mul(channels)?;
+    let expected = stride.checked_add(1)?.checked_mul(height as usize)?;
+    let mut raw = Vec::new();
+    DeflateDecoder::new(&idat[..])
+        .take(expected as u64)
+        .read_to_end(&mut raw)
+        .ok()?;
+    if raw.len() < expected {
+        return None;
+    }
+
+    let mut pixels = unfilter(&raw, stride, height as usize, channels)?;
+    for pixel in pixels.chunks_exact_mut(channels) {
+        pixel.swap(0, 2);
+        if channels == 4 {
+            let alpha = pixel[3] as u32;
+            if alpha > 0 && alpha < 255 {
+                for c in &mut pixel[..3] {
+                    *c = ((*c as u32 * 255 + alpha / 2) / alpha).min(255) as u8;
+                }
+            }
+        }
+    }
+
+    let image = if channels == 4 {
+        image::DynamicImage::ImageRgba8(image::RgbaImage::from_raw(width, height, pixels)?)
+    } else {
+        image::DynamicImage::ImageRgb8(image::RgbImage::from_raw(width, height, pixels)?)
+    };
+    let mut output = Cursor::new(Vec::new());
+    image.write_to(&mut output, image::ImageFormat::Png).ok()?;
+    Some(output.into_inner())
+}
+
+// 还原 PNG 扫描行过滤（None/Sub/Up/Average/Paeth）
+fn unfilter(raw: &[u8], stride: usize, height: usize, bpp: usize) -> Option<Vec<u8>> {
+    let mut pixels = vec![0u8; stride * height];
+    for row in 0..height {
+        let filter = raw[row * (stride + 1)];
+        let line = &raw[row * (stride + 1) + 1..(row + 1) * (stride + 1)];
+        let (previous, current) = pixels.split_at_mut(row * stride);
+        let above = if row > 0 { &previous[(row - 1) * stride..] } else { &[][..] };
+        let current = &mut current[..stride];
+        for i in 0..stride {
+            let a = if i >= bpp { current[i - bpp] as i16 } else { 0 };
+            let b = above.get(i).copied().unwrap_or(0) as i16;
+            let c = if i >= bpp { above.get(i - bpp).copied().unwrap_or(0) as i16 } else { 0 };
+            let predictor = match filter {
+                0 => 0,
+                1 => a,
+                2 => b,
+                3 => (a + b) / 2,
+                4 => {
+                    let p = a + b - c;
+                    let (pa, pb, pc) = ((p - a).abs(), (p - b).abs(), (p - c).abs());
+                    if pa <= pb && pa <= pc { a } else if pb <= pc { b } else { c }
+                }
+                _ => return None,
+            };
+            current[i] = line[i].wrapping_add(predictor as u8);
+        }
+    }
+    Some(pixels)
+}
+
+/// 解析 IPA 文件
+pub(crate) fn parse_ipa(path: &Path) -> Result<IpaInfo, String> {
+    let file = File::open(path).map_err(|e| format!("无法打开文件: {}", e))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("无法解析 IPA 文件: {}", e))?;
+
+    let names: Vec<String> = archive.file_names().map(|s| s.to_string()).collect();
+    let app_dir = find_app_dir(&names).ok_or("IPA 中未找到 Payload/*.app 目录")?;
+
+    // Info.plist 可能是二进制或 XML 格式，from_reader 会自动识别
+    let info_data = read_entry(&mut archive, &format!("{}Info.plist", app_dir))?;
+    let info = Value::from_reader(Cursor::new(info_data))
+        .map_err(|e| format!("解析 Info.plist 失败: {}", e))?
+        .into_dictionary()
+        .ok_or("Info.plist 格式无效")?;
+
+    let mut warnings = Vec::new();
+
+    let provisioning_name = format!("{}embedded.mobileprovision", app_dir);
+    let provisioning = if names.contains(&provisioning_name) {
+        match read_entry(&mut archive, &provisioning_name).and_then(|data| parse_mobileprovision(&data)) {
+            Ok(profile) => {
+                if profile.expired {
+                    warnings.push("描述文件已过期，应用将无法安装或启动".to_string());
+                }
+                Some(profile)
+            }
+            Err(e) => {
+                warnings.push(format!("描述文件解析失败: {}", e));
+                None
+            }
+        }
+    } else {
+        // App Store 下载的包不包含描述文件
+        None
+    };
+
+    let icon_base64 = extract_icon(&mut archive, &app_dir, &info);
+    if icon_base64.is_none() {
+        warnings.push("未找到可用的图标文件（图标可能只存在于 Assets.car 中）".to_string());
+    }
+
+    let mut usage_descriptions: Vec<String> = info
+        .keys()
+        .filter(|k| k.starts_with("NS") && k.ends_with("UsageDescription"))
+        .cloned()
+        .collect();
+    usage_descriptions.sort();
+
+    let display_name = match string_of(&info, "CFBundleDisplayName") {
+        name if name.is_empty() => string_of(&info, "CFBundleName"),
+        name => name,
+    };
+
+    Ok(IpaInfo {
+        app_path: app_dir.trim_end_matches('/').to_string(),
+        bundle_id: string_of(&info, "CFBundleIdentifier"),
+        display_name,
+        executable: string_of(&info, "CFBundleExecutable"),
+        version: string_of(&info, "CFBundleShortVersionString"),
+        build: string_of(&info, "CFBundleVersion"),
+        minimum_os_version: string_of(&info, "MinimumOSVersion"),
+        platform_version: string_of(&info, "DTPlatformVersion"),
+        sdk_name: string_of(&info, "DTSDKName"),
+        device_families: device_families(&info),
+        usage_descriptions,
+        provisioning,
+        icon_base64,
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::DeflateEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    // PNG 块，CRC 不参与解析，填 0
+    fn chunk(kind: &[u8], body: &[u8]) -> Vec<u8> {
+        let mut out = (body.len() as u32).to_be_bytes().to_vec();
+        out.extend_from_slice(kind);
+        out.extend_from_slice(body);
+        out.extend_from_slice(&[0; 4]);
+        out
+    }
+
+    // Xcode 风格的 CgBI PNG：BGRA、预乘 Alpha、原始 deflate
+    fn cgbi_png(width: u32, height: u32, filtered: &[u8]) -> Vec<u8> {
+        let mut header = width.to_be_bytes().to_vec();
+        header.extend_from_slice(&height.to_be_bytes());
+        header.extend_from_slice(&[8, 6, 0, 0, 0]);
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(filtered).unwrap();
+
+        let mut png = PNG_SIGNATURE.to_vec();
+        png.extend(chunk(b"CgBI", &[0x50, 0x00, 0x20, 0x06]));
+        png.extend(chunk(b"IHDR", &header));
+        png.extend(chunk(b"IDAT", &encoder.finish().unwrap()));
+        png.extend(chunk(b"IEND", &[]));
+        png
+    }
+
+    #[test]
+    fn uncrushes_cgbi_png() {
+        let row0: [u8; 8] = [0, 0, 255, 255, 128, 64, 0, 128];
+        let row1: [u8; 8] = [30, 20, 10, 255, 0, 0, 0, 0];
+        // 第一行不过滤，第二行使用 Up 过滤
+        let mut filtered = vec![0];
+        filtered.extend_from_slice(&row0);
+        filtered.push(2);
+        filtered.extend(row1.iter().zip(&row0).map(|(c, a)| c.wrapping_sub(*a)));
+
+        let png = uncrush_png(&cgbi_png(2, 2, &filtered)).unwrap();
+        let image = image::load_from_memory(&png).unwrap().to_rgba8();
+        assert_eq!(image.dimensions(), (2, 2));
+        assert_eq!(
+            image.into_raw(),
+            [255, 0, 0, 255, 0, 128, 255, 128, 10, 20, 30, 255, 0, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn uncrush_ignores_data_past_image_and_rejects_short_data() {
+        let mut filtered = vec![0u8; 2 * 9];
+        // 多出的数据不会被解压
+        let mut oversized = filtered.clone();
+        oversized.extend(std::iter::repeat_n(0xaa, 1 << 20));
+        assert!(uncrush_png(&cgbi_png(2, 2, &oversized)).is_some());
+
+        filtered.truncate(9);
+        assert!(uncrush_png(&cgbi_png(2, 2, &filtered)).is_none());
+        // 宽高溢出时直接放弃
+        assert!(uncrush_png(&cgbi_png(u32::MAX, u32::MAX, &filtered)).is_none());
+    }
+
+    #[test]
+    fn plain_png_is_returned_unchanged() {
+        let mut png = PNG_SIGNATURE.to_vec();
+        png.extend(chunk(b"IEND", &[]));
+        assert_eq!(uncrush_png(&png), Some(png));
+        assert_eq!(uncrush_png(b"GIF89a"), None);
+    }
+
+    #[test]
+    fn unfilter_all_filter_types() {
+        // 单通道、每行 3 字节：Sub、Average、Paeth、Up（含回绕）
+        let raw = [
+            1, 10, 5, 5, //
+            3, 5, 3, 2, //
+            4, 1, 1, 1, //
+            2, 0xff, 1, 0,
+        ];
+        let pixels = unfilter(&raw, 3, 4, 1).unwrap();
+        assert_eq!(pixels, [10, 15, 20, 10, 15, 19, 11, 16, 20, 10, 17, 20]);
+
+        assert_eq!(unfilter(&[5, 0, 0, 0], 3, 1, 1), None);
+    }
+
+    fn profile(extra: &str, expiration: &str) -> Vec<u8> {
+        let plist = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>AppIDName</key><string>Demo</string>
+    <key>CreationDate</key><date>2024-01-01T00:00:00Z</date>
+    <key>ExpirationDate</key><date>{}</date>
+    <key>Name</key><string>Demo Profile</string>
+    <key>TeamIdentifier</key><array><string>ABCDE12345</string></array>
+    <key>TeamName</key><string>Example Inc.</string>
+    <key>UUID</key><string>0f1e2d3c-0000-0000-0000-000000000000</string>
+    {}
+</dict>
+</plist>"#,
+            expiration, extra
+        );
+        // 外层 CMS 结构用任意二进制数据代替
+        let mut data = vec![0x30, 0x80, 0x06, 0x09];
+        data.extend_from_slice(plist.as_bytes());
+        data.extend_from_slice(&[0xa0, 0x82, 0x00, 0x00]);
+        data
+    }
+
+    const DEVICES: &str = "<key>ProvisionedDevices</key><array><string>00008030-000A</string></array>";
+
+    #[test]
+    fn mobileprovision_types() {
+        let development = parse_mobileprovision(&profile(
+            &format!("{}<key>Entitlements</key><dict><key>get-task-allow</key><true/></dict>", DEVICES),
+            "2099-01-01T00:00:00Z",
+        ))
+        .unwrap();
+        assert_eq!(development.profile_type, "development");
+        assert_eq!(development.team_id, "ABCDE12345");
+        assert_eq!(development.provisioned_devices, ["00008030-000A"]);
+        assert_eq!(development.entitlements["get-task-allow"], true);
+
+        let ad_hoc = parse_mobileprovision(&profile(
+            &format!("{}<key>Entitlements</key><dict><key>get-task-allow</key><false/></dict>", DEVICES),
+            "2099-01-01T00:00:00Z",
+        ))
+        .unwrap();
+        assert_eq!(ad_hoc.profile_type, "ad-hoc");
+
+        let enterprise =
+            parse_mobileprovision(&profile("<key>ProvisionsAllDevices</key><true/>", "2099-01-01T00:00:00Z")).unwrap();
+        assert_eq!(enterprise.profile_type, "enterprise");
+        assert!(enterprise.provisions_all_devices);
+
+        let app_store = parse_mobileprovision(&profile("", "2099-01-01T00:00:00Z")).unwrap();
+        assert_eq!(app_store.profile_type, "app-store");
+        assert_eq!(app_store.entitlements, serde_json::Value::Null);
+    }
+
+    #[test]
+    fn mobileprovision_expiry() {
+        let expired = parse_mobileprovision(&profile("", "2020-06-30T12:00:00Z")).unwrap();
+        assert!(expired.expired);
+        assert_eq!(expired.expiration_date.as_deref(), Some("2020-06-30T12:00:00Z"));
+        assert_eq!(expired.creation_date.as_deref(), Some("2024-01-01T00:00:00Z"));
+
+        let valid = parse_mobileprovision(&profile("", "2099-01-01T00:00:00Z")).unwrap();
+        assert!(!valid.expired);
+
+        assert_eq!(
+            parse_mobileprovision(b"\x30\x80 no plist").unwrap_err(),
+            "描述文件中未找到 plist 内容"
+        );
+        assert_eq!(
+            parse_mobileprovision(b"<?xml version=\"1.0\"?><plist><dict>").unwrap_err(),
+            "描述文件中的 plist 不完整"
+        );
+    }
+}
@@ -0,0 +1,47 @@
+// Release Assistant 核心库：安装包解析、签名检查、发布前检查、图片处理和生成器
+// 不依赖 Tauri，桌面端、命令行和其他 Rust 服务共用
+// 对外的检查入口返回带类型的错误；只在库内部使用的解析模块（返回 String 错误）不公开
+
+pub mod advisory;
+pub mod apk;
+pub(crate) mod arsc;
+pub(crate) mod axml;
+pub mod build_apks;
+pub(crate) mod bundle;
+pub mod channel;
+pub mod channel_batch;
+pub(crate) mod chunk;
+pub mod device_compat;
+pub(crate) mod dex;
+pub(crate) mod elf;
+pub mod error;
+pub mod file_info;
+pub mod framework;
+pub mod generators;
+pub mod harmony;
+pub mod icons;
+pub mod image;
+pub mod ipa;
+pub mod library;
+pub(crate) mod manifest;
+pub mod manifest_audit;
+pub mod manifest_editor;
+pub mod network_security;
+pub mod permissions;
+pub mod play_policy;
+pub mod protection;
+pub(crate) mod protobuf;
+pub mod provenance;
+pub(crate) mod resource_table;
+pub mod retrace;
+pub mod sbom;
+pub mod secret_scan;
+pub mod signing;
+pub(crate) mod signing_block;
+pub mod store_compliance;
+pub mod symbolicate;
+pub mod zip_integrity;
+pub(crate) mod zip_raw;
+pub mod zipalign;
+
+pub use error::AnalysisError;
@@ -0,0 +1,627 @@
+// ==================== AAR / JAR 库检查 ====================
+// SDK 对外发布前检查：合并到宿主应用的权限与组件、消费者混淆规则、原生库和字节码版本
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{Cursor, Read, Seek};
+use std::path::Path;
+
+use quick_xml::Reader;
+use quick_xml::events::Event;
+use serde::{Deserialize, Serialize};
+use zip::ZipArchive;
+
+use crate::error::AnalysisError;
+use crate::manifest::read_entry;
+use crate::permissions::{self, PermissionDetail};
+
+const CLASS_MAGIC: u32 = 0xCAFE_BABE;
+// Java 17 对应的 class 文件主版本号，更高版本需要较新的 AGP/D8
+const MAX_RECOMMENDED_CLASS_VERSION: u16 = 61;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PackageClasses {
+    pub package: String,
+    pub class_count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JarInfo {
+    pub class_count: usize,
+    pub packages: Vec<PackageClasses>,
+    /// class 文件主版本号范围
+    pub min_class_version: Option<u16>,
+    pub max_class_version: Option<u16>,
+    /// 对应的 Java 版本，如 "8"、"17"
+    pub java_version: Option<String>,
+    /// META-INF/MANIFEST.MF 主属性
+    pub manifest_attributes: BTreeMap<String, String>,
+    /// 多版本 JAR（META-INF/versions/N）
+    pub multi_release_versions: Vec<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NativeLibrary {
+    pub abi: String,
+    pub name: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AarManifest {
+    pub package_name: String,
+    pub min_sdk_version: Option<String>,
+    pub target_sdk_version: Option<String>,
+    /// 会合并进宿主应用的权限
+    pub permissions: Vec<PermissionDetail>,
+    pub activities: Vec<String>,
+    pub services: Vec<String>,
+    pub receivers: Vec<String>,
+    pub providers: Vec<String>,
+    pub features: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LibraryInfo {
+    pub file_name: String,
+    /// aar / jar
+    pub kind: String,
+    pub file_size: u64,
+    pub manifest: Option<AarManifest>,
+    /// AAR 的 classes.jar 或 JAR 本身
+    pub classes: Option<JarInfo>,
+    /// AAR libs/ 下内嵌的 JAR
+    pub embedded_jars: Vec<String>,
+    pub native_libs: Vec<NativeLibrary>,
+    pub abis: Vec<String>,
+    /// proguard.txt 消费者混淆规则（会应用到宿主应用）
+    pub consumer_rules: Option<String>,
+    pub consumer_rule_warnings: Vec<String>,
+    /// R.txt 中的资源数量（按类型）
+    pub resource_count: usize,
+    pub resource_types: BTreeMap<String, usize>,
+    pub warnings: Vec<String>,
+}
+
+/// class 文件主版本号转换为 Java 版本（52 → 8，48 → 1.4）
+pub fn java_version(major: u16) -> String {
+    if major <= 48 {
+        format!("1.{}", major.saturating_sub(44))
+    } else {
+        (major - 44).to_string()
+    }
+}
+
+// 解析 MANIFEST.MF 主段，续行以单个空格开头
+fn parse_manifest_mf(text: &str) -> BTreeMap<String, String> {
+    let mut attributes = BTreeMap::new();
+    let mut current: Option<(String, String)> = None;
+    for line in text.lines() {
+        if line.is_empty() {
+            break;
+        }
+        if let Some(rest) = line.strip_prefix(' ') {
+            if let Some((_, value)) = current.as_mut() {
+                value.push_str(rest);
+            }
+            continue;
+        }
+        if let Some((key, value)) = current.take() {
+            attributes.insert(key, value);
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            current = Some((key.trim().to_string(), value.trim_start().to_string()));
+        }
+    }
+    if let Some((key, value)) = current {
+        attributes.insert(key, value);
+    }
+    attributes
+}
+
+/// 检查 JAR 中的类、包和字节码版本
+pub fn inspect_jar<R: Read + Seek>(archive: &mut ZipArchive<R>) -> JarInfo {
+    let mut packages: BTreeMap<String, usize> = BTreeMap::new();
+    let mut min_version: Option<u16> = None;
+    let mut max_version: Option<u16> = None;
+    let mut multi_release_versions = Vec::new();
+    let mut class_count = 0;
+
+    for i in 0..archive.len() {
+        let Ok(mut entry) = archive.by_index(i) else { continue };
+        let name = entry.name().to_string();
+        if !name.ends_with(".class") || name.ends_with("module-info.class") {
+            continue;
+        }
+        // 多版本 JAR 的类只在对应 Java 版本下生效，不计入基础版本
+        if let Some(rest) = name.strip_prefix("META-INF/versions/") {
+            if let Some(version) = rest.split('/').next().and_then(|v| v.parse().ok()) {
+                if !multi_release_versions.contains(&version) {
+                    multi_release_versions.push(version);
+                }
+            }
+            continue;
+        }
+
+        class_count += 1;
+        let package = match name.rfind('/') {
+            Some(pos) => name[..pos].replace('/', "."),
+            None => "(default)".to_string(),
+        };
+        *packages.entry(package).or_insert(0) += 1;
+
+        let mut header = [0u8; 8];
+        if entry.read_exact(&mut header).is_ok()
+            && u32::from_be_bytes([header[0], header[1], header[2], header[3]]) == CLASS_MAGIC
+        {
+            let major = u16::from_be_bytes([header[6], header[7]]);
+            min_version = Some(min_version.map_or(major, |v| v.min(major)));
+            max_version = Some(max_version.map_or(major, |v| v.max(major)));
+        }
+    }
+    multi_release_versions.sort();
+
+    let manifest_attributes = read_entry(archive, "META-INF/MANIFEST.MF")
+        .map(|data| parse_manifest_mf(&String::from_utf8_lossy(&data)))
+        .unwrap_or_default();
+
+    let mut packages: Vec<PackageClasses> = packages
+        .into_iter()
+        .map(|(package, class_count)| PackageClasses { package, class_count })
+        .collect();
+    packages.sort_by(|a, b| b.class_count.cmp(&a.class_count).then_with(|| a.package.cmp(&b.package)));
+
+    JarInfo {
+        class_count,
+        packages,
+        min_class_version: min_version,
+        max_class_version: max_version,
+        java_version: max_version.map(java_version),
+        manifest_attributes,
+        multi_release_versions,
+    }
+}
+
+// 补全以 . 开头或不含包名的组件类名
+fn resolve_class_name(package: &str, name: &str) -> String {
+    if name.starts_with('.') {
+        format!("{}{}", package, name)
+    } else if !name.contains('.') && !package.is_empty() {
+        format!("{}.{}", package, name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// 解析 AAR 中的文本格式 AndroidManifest.xml
+pub(crate) fn parse_text_manifest(xml: &str) -> Result<AarManifest, String> {
+    let mut reader = Reader::from_str(xml);
+    let mut manifest = AarManifest {
+        package_name: String::new(),
+        min_sdk_version: None,
+        target_sdk_version: None,
+        permissions: Vec::new(),
+        activities: Vec::new(),
+        services: Vec::new(),
+        receivers: Vec::new(),
+        providers: Vec::new(),
+        features: Vec::new(),
+    };
+    let mut permission_names = Vec::new();
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("解析 AndroidManifest.xml 失败: {}", e))?;
+        let element = match &event {
+            Event::Start(e) | Event::Empty(e) => e,
+            Event::Eof => break,
+            _ => continue,
+        };
+
+        let mut attributes = BTreeMap::new();
+        for attribute in element.attributes().flatten() {
+            let key = String::from_utf8_lossy(attribute.key.as_ref()).to_string();
+            let value = attribute
+                .unescape_value()
+                .map(|v| v.to_string())
+                .unwrap_or_else(|_| String::from_utf8_lossy(&attribute.value).to_string());
+            attributes.insert(key, value);
+        }
+        let name = attributes.get("android:name").cloned().unwrap_or_default();
+
+        match element.name().as_ref() {
+            b"manifest" => manifest.package_name = attributes.get("package").cloned().unwrap_or_default(),
+            b"uses-sdk" => {
+                manifest.min_sdk_version = attributes.get("android:minSdkVersion").cloned();
+                manifest.target_sdk_version = attributes.get("android:targetSdkVersion").cloned();
+            }
+            b"uses-permission" | b"uses-permission-sdk-23" | b"uses-permission-sdk-m"
+                if !name.is_empty() && !permission_names.contains(&name) =>
+            {
+                permission_names.push(name)
+            }
+            b"uses-feature" if !name.is_empty() => manifest.features.push(name),
+            b"activity" | b"activity-alias" => manifest.activities.push(name),
+            b"service" => manifest.services.push(name),
+            b"receiver" => manifest.receivers.push(name),
+            b"provider" => manifest.providers.push(name),
+            _ => {}
+        }
+    }
+
+    // AGP 8 起包名改由 build.gradle 的 namespace 指定，manifest 中可能没有 package
+    let package = manifest.package_name.clone();
+    for list in [
+        &mut manifest.activities,
+        &mut manifest.services,
+        &mut manifest.receivers,
+        &mut manifest.providers,
+    ] {
+        list.retain(|n| !n.is_empty());
+        for name in list.iter_mut() {
+            *name = resolve_class_name(&package, name);
+        }
+    }
+    manifest.permissions = permission_names.iter().map(|n| permissions::describe(n)).collect();
+    Ok(manifest)
+}
+
+// 消费者规则会作用于整个宿主应用，检查影响全局的配置
+fn check_consumer_rules(rules: &str) -> Vec<String> {
+    let mut warnings = Vec::new();
+    for line in rules.lines() {
+        let line = line.trim();
+        if line.starts_with('#') || line.is_empty() {
+            continue;
+        }
+        let option = line.split_whitespace().next().unwrap_or("");
+        let message = match option {
+            "-dontobfuscate" => "-dontobfuscate 会关闭宿主应用的混淆",
+            "-dontshrink" => "-dontshrink 会关闭宿主应用的代码压缩",
+            "-dontoptimize" => "-dontoptimize 会关闭宿主应用的优化",
+            "-ignorewarnings" => "-ignorewarnings 会掩盖宿主应用的混淆警告",
+            "-repackageclasses" | "-flattenpackagehierarchy" | "-allowaccessmodification" => {
+                "包含影响宿主应用全局的混淆选项"
+            }
+            "-printmapping" | "-printconfiguration" | "-printusage" | "-printseeds" | "-dump" => {
+                "包含输出文件的选项，会在宿主应用构建时写文件"
+            }
+            "-keep" | "-keepclassmembers" | "-keepnames" if keeps_all_classes(line) => {
+                "存在匹配所有类的 keep 规则，宿主应用将无法压缩和混淆"
+            }
+            _ => continue,
+        };
+        warnings.push(format!("{}: {}", message, line));
+    }
+    warnings
+}
+
+// 类名模式为 * 或 ** 且没有 extends/implements、注解限定时，规则会保留所有类
+// -keep class **.R$* 这类带后缀的模式只匹配部分类，不算在内
+fn keeps_all_classes(rule: &str) -> bool {
+    let tokens: Vec<&str> = rule.split_whitespace().collect();
+    let Some(pos) = tokens.iter().position(|t| matches!(*t, "class" | "interface" | "enum")) else {
+        return false;
+    };
+    if tokens[..pos].iter().any(|t| t.starts_with('@')) {
+        return false;
+    }
+    matches!(tokens.get(pos + 1), Some(&"*" | &"**"))
+        && !matches!(tokens.get(pos + 2), Some(&"extends" | &"implements"))
+}
+
+// 统计 R.txt 中各类型的资源数量，格式：int drawable icon 0x7f010000
+// int styleable Foo_attr 0 是 styleable 数组下标，不是独立资源
+fn count_resources(text: &str) -> BTreeMap<String, usize> {
+    let mut types = BTreeMap::new();
+    for line in text.lines() {
+        let mut parts = line.split_whitespace();
+        match (parts.next(), parts.next()) {
+            (Some("int"), Some("styleable")) => {}
+            (Some("int" | "int[]"), Some(resource_type)) => {
+                *types.entry(resource_type.to_string()).or_insert(0) += 1;
+            }
+            _ => {}
+        }
+    }
+    types
+}
+
+fn check_class_version(jar: &JarInfo, name: &str, warnings: &mut Vec<String>) {
+    if let Some(major) = jar.max_class_version {
+        if major > MAX_RECOMMENDED_CLASS_VERSION {
+            warnings.push(format!(
+                "{} 包含 Java {} 字节码，高于 Java 17，旧版 AGP/D8 可能无法处理",
+                name,
+                java_version(major)
+            ));
+        }
+    }
+}
+
+fn inspect_aar(archive: &mut ZipArchive<File>, info: &mut LibraryInfo) -> Result<(), String> {
+    let manifest_data = read_entry(archive, "AndroidManifest.xml")
+        .map_err(|_| "AAR 中未找到 AndroidManifest.xml".to_string())?;
+    info.manifest = Some(parse_text_manifest(&String::from_utf8_lossy(&manifest_data))?);
+
+    match read_entry(archive, "classes.jar") {
+        Ok(data) => match ZipArchive::new(Cursor::new(data)) {
+            Ok(mut jar) => {
+                let classes = inspect_jar(&mut jar);
+                check_class_version(&classes, "classes.jar", &mut info.warnings);
+                info.classes = Some(classes);
+            }
+            Err(e) => info.warnings.push(format!("classes.jar 无法解析: {}", e)),
+        },
+        Err(_) => info.warnings.push("AAR 中没有 classes.jar".to_string()),
+    }
+
+    if let Ok(rules) = read_entry(archive, "proguard.txt") {
+        let rules = String::from_utf8_lossy(&rules).to_string();
+        info.consumer_rule_warnings = check_consumer_rules(&rules);
+        info.consumer_rules = Some(rules);
+    }
+
+    if let Ok(r_txt) = read_entry(archive, "R.txt") {
+        info.resource_types = count_resources(&String::from_utf8_lossy(&r_txt));
+        info.resource_count = info.resource_types.values().sum();
+    }
+
+    for i in 0..archive.len() {
+        let Ok(entry) = archive.by_index(i) else { continue };
+        let name = entry.name().to_string();
+        if let Some(rest) = name.strip_prefix("jni/") {
+            if let Some((abi, lib)) = rest.split_once('/') {
+                if lib.ends_with(".so") && !lib.contains('/') {
+                    info.native_libs.push(NativeLibrary {
+                        abi: abi.to_string(),
+                        name: lib.to_string(),
+                        size: entry.size(),
+                    });
+                    if !info.abis.iter().any(|a| a == abi) {
+                        info.abis.push(abi.to_string());
+                    }
+                }
+            }
+        } else if name.starts_with("libs/") && name.ends_with(".jar") {
+            info.embedded_jars.push(name);
+        }
+    }
+    info.abis.sort();
+
+    if !info.abis.is_empty() {
+        // 各 ABI 提供的 .so 不一致时，宿主应用在部分设备上会找不到库
+        let expected = info.native_libs.iter().filter(|l| l.abi == info.abis[0]).count();
+        if info.abis.iter().any(|abi| info.native_libs.iter().filter(|l| &l.abi == abi).count() != expected) {
+            info.warnings.push("各 ABI 目录中的原生库数量不一致".to_string());
+        }
+    }
+    Ok(())
+}
+
+/// 检查 .aar 或 .jar 文件
+pub fn inspect_library(path: &Path) -> Result<LibraryInfo, AnalysisError> {
+    if !path.exists() {
+        return Err(AnalysisError::NotFound);
+    }
+    let kind = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    if kind != "aar" && kind != "jar" {
+        return Err("仅支持 .aar 和 .jar 文件".into());
+    }
+
+    let file_size = std::fs::metadata(path)?.len();
+    let file = File::open(path)?;
+    let mut archive = ZipArchive::new(file)?;
+
+    let mut info = LibraryInfo {
+        file_name: path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("")
+            .to_string(),
+        kind: kind.clone(),
+        file_size,
+        manifest: None,
+        classes: None,
+        embedded_jars: Vec::new(),
+        native_libs: Vec::new(),
+        abis: Vec::new(),
+        consumer_rules: None,
+        consumer_rule_warnings: Vec::new(),
+        resource_count: 0,
+        resource_types: BTreeMap::new(),
+        warnings: Vec::new(),
+    };
+
+    if kind == "aar" {
+        inspect_aar(&mut archive, &mut info)?;
+    } else {
+        let classes = inspect_jar(&mut archive);
+        check_class_version(&classes, &info.file_name, &mut info.warnings);
+        info.classes = Some(classes);
+    }
+
+    Ok(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    fn zip_bytes(files: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (file, data) in files {
+            writer.start_file(*file, SimpleFileOptions::default()).unwrap();
+            writer.write_all(data).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn class_file(major: u16) -> Vec<u8> {
+        let mut data = CLASS_MAGIC.to_be_bytes().to_vec();
+        data.extend_from_slice(&0u16.to_be_bytes());
+        data.extend_from_slice(&major.to_be_bytes());
+        data
+    }
+
+    #[test]
+    fn java_versions() {
+        assert_eq!(java_version(45), "1.1");
+        assert_eq!(java_version(48), "1.4");
+        assert_eq!(java_version(49), "5");
+        assert_eq!(java_version(52), "8");
+        assert_eq!(java_version(61), "17");
+        assert_eq!(java_version(65), "21");
+    }
+
+    #[test]
+    fn manifest_mf_continuation_lines() {
+        let text = "Manifest-Version: 1.0\r\n\
+                    Implementation-Title: very-long-library-na\r\n me-continued\r\n\
+                    Bundle-Description: a\r\n b\r\n c\r\n\
+                    Created-By:17 (Oracle)\r\n\
+                    \r\n\
+                    Name: com/example/Foo.class\r\n\
+                    SHA-256-Digest: abc\r\n";
+        let attributes = parse_manifest_mf(text);
+        assert_eq!(attributes.len(), 4);
+        assert_eq!(attributes["Manifest-Version"], "1.0");
+        assert_eq!(attributes["Implementation-Title"], "very-long-library-name-continued");
+        assert_eq!(attributes["Bundle-Description"], "abc");
+        assert_eq!(attributes["Created-By"], "17 (Oracle)");
+        // 空行之后是逐条目属性段，不属于主属性
+        assert!(!attributes.contains_key("Name"));
+    }
+
+    #[test]
+    fn consumer_rule_warnings() {
+        let rules = "# -dontobfuscate\n\
+                     -dontobfuscate\n\
+                     -keep class * { *; }\n\
+                     -keepnames class ** { *; }\n\
+                     -keep class **.R$* { *; }\n\
+                     -keep class com.example.sdk.** { *; }\n\
+                     -keep public class * extends android.app.Activity\n\
+                     -keep @com.example.Keep class *\n\
+                     -printmapping mapping.txt\n\
+                     -keepattributes Signature\n";
+        let warnings = check_consumer_rules(rules);
+        assert_eq!(
+            warnings,
+            vec![
+                "-dontobfuscate 会关闭宿主应用的混淆: -dontobfuscate",
+                "存在匹配所有类的 keep 规则，宿主应用将无法压缩和混淆: -keep class * { *; }",
+                "存在匹配所有类的 keep 规则，宿主应用将无法压缩和混淆: -keepnames class ** { *; }",
+                "包含输出文件的选项，会在宿主应用构建时写文件: -printmapping mapping.txt",
+            ]
+        );
+    }
+
+    #[test]
+    fn resource_counts_by_type() {
+        let r_txt = "int drawable icon 0x7f010000\n\
+                     int drawable logo 0x7f010001\n\
+                     int string app_name 0x7f020000\n\
+                     int[] styleable SdkView { 0x7f030000, 0x7f030001 }\n\
+                     int styleable SdkView_color 0\n\
+                     int styleable SdkView_size 1\n\
+                     \n";
+        let types = count_resources(r_txt);
+        assert_eq!(types.len(), 3);
+        assert_eq!(types["drawable"], 2);
+        assert_eq!(types["string"], 1);
+        assert_eq!(types["styleable"], 1);
+    }
+
+    #[test]
+    fn inspects_jar_classes_and_multi_release() {
+        let data = zip_bytes(&[
+            ("META-INF/MANIFEST.MF", b"Manifest-Version: 1.0\r\nMulti-Release: true\r\n".to_vec()),
+            ("com/example/A.class", class_file(52)),
+            ("com/example/B.class", class_file(55)),
+            ("com/example/util/C.class", class_file(52)),
+            ("module-info.class", class_file(53)),
+            ("META-INF/versions/11/com/example/A.class", class_file(55)),
+        ]);
+        let jar = inspect_jar(&mut ZipArchive::new(Cursor::new(data)).unwrap());
+        assert_eq!(jar.class_count, 3);
+        assert_eq!(jar.packages[0].package, "com.example");
+        assert_eq!(jar.packages[0].class_count, 2);
+        assert_eq!(jar.min_class_version, Some(52));
+        assert_eq!(jar.max_class_version, Some(55));
+        assert_eq!(jar.java_version.as_deref(), Some("11"));
+        assert_eq!(jar.multi_release_versions, vec![11]);
+        assert_eq!(jar.manifest_attributes["Multi-Release"], "true");
+    }
+
+    #[test]
+    fn inspects_aar() {
+        let manifest = r#"<manifest xmlns:android="http://schemas.android.com/apk/res/android" package="com.example.sdk">
+            <uses-permission android:name="android.permission.CAMERA"/>
+            <application>
+                <service android:name=".PushService"/>
+            </application>
+        </manifest>"#;
+        let classes = zip_bytes(&[("com/example/sdk/Sdk.class", class_file(65))]);
+        let data = zip_bytes(&[
+            ("AndroidManifest.xml", manifest.as_bytes().to_vec()),
+            ("classes.jar", classes),
+            ("proguard.txt", b"-keep class com.example.sdk.** { *; }\n-dontshrink\n".to_vec()),
+            ("R.txt", b"int string sdk_name 0x7f020000\n".to_vec()),
+            ("jni/arm64-v8a/libsdk.so", vec![0; 16]),
+            ("jni/arm64-v8a/libextra.so", vec![0; 8]),
+            ("jni/armeabi-v7a/libsdk.so", vec![0; 12]),
+            ("libs/okio.jar", Vec::new()),
+        ]);
+        let path = std::env::temp_dir().join(format!("library_{}.aar", std::process::id()));
+        std::fs::write(&path, data).unwrap();
+        let info = inspect_library(&path);
+        let _ = std::fs::remove_file(&path);
+        let info = info.unwrap();
+
+        assert_eq!(info.kind, "aar");
+        let manifest = info.manifest.unwrap();
+        assert_eq!(manifest.package_name, "com.example.sdk");
+        assert_eq!(manifest.services, vec!["com.example.sdk.PushService"]);
+        assert_eq!(manifest.permissions.len(), 1);
+        assert!(manifest.permissions[0].sensitive);
+        assert_eq!(info.classes.unwrap().java_version.as_deref(), Some("21"));
+        assert_eq!(info.consumer_rule_warnings, vec!["-dontshrink 会关闭宿主应用的代码压缩: -dontshrink"]);
+        assert_eq!(info.resource_count, 1);
+        assert_eq!(info.abis, vec!["arm64-v8a", "armeabi-v7a"]);
+        assert_eq!(info.native_libs.len(), 3);
+        assert_eq!(info.embedded_jars, vec!["libs/okio.jar"]);
+        assert!(info.warnings.iter().any(|w| w.starts_with("classes.jar 包含 Java 21 字节码")));
+        assert!(info.warnings.iter().any(|w| w == "各 ABI 目录中的原生库数量不一致"));
+    }
+
+    #[test]
+    fn rejects_unsupported_extension() {
+        let path = std::env::temp_dir().join(format!("library_{}.zip", std::process::id()));
+        std::fs::write(&path, zip_bytes(&[])).unwrap();
+        let err = inspect_library(&path).unwrap_err();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(err.to_string(), "仅支持 .aar 和 .jar 文件");
+    }
+
+    #[test]
+    fn reports_typed_errors() {
+        let missing = std::env::temp_dir().join(format!("library_{}_missing.aar", std::process::id()));
+        assert!(matches!(inspect_library(&missing), Err(AnalysisError::NotFound)));
+
+        let path = std::env::temp_dir().join(format!("library_{}_broken.aar", std::process::id()));
+        std::fs::write(&path, b"not a zip").unwrap();
+        let err = inspect_library(&path).unwrap_err();
+        let _ = std::fs::remove_file(&path);
+        assert!(matches!(err, AnalysisError::Zip(_)));
+        assert!(err.to_string().starts_with("无法解析 ZIP 文件"));
+    }
+}
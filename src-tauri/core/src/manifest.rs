@@ -0,0 +1,103 @@
+// ==================== APK / AndroidManifest 通用读取 ====================
+
+use std::io::{Cursor, Read, Seek};
+use std::path::Path;
+
+use rusty_axml::parser::{Axml, XmlElement, XmlNode};
+use zip::ZipArchive;
+
+/// 从 APK 中读取并解析 AndroidManifest.xml
+pub fn load_manifest(path: &Path) -> Result<Axml, String> {
+    let file = std::fs::File::open(path).map_err(|e| format!("无法打开文件: {}", e))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("无法解析 ZIP 文件: {}", e))?;
+    load_manifest_from_archive(&mut archive)
+}
+
+/// 读取 ZIP 中指定文件的全部内容
+pub fn read_entry<R: Read + Seek>(archive: &mut ZipArchive<R>, name: &str) -> Result<Vec<u8>, String> {
+    let mut file = archive
+        .by_name(name)
+        .map_err(|_| format!("未找到 {} 文件", name))?;
+    let mut buffer = Vec::new();
+    file.read_to_end(&mut buffer)
+        .map_err(|e| format!("读取 {} 失败: {}", name, e))?;
+    Ok(buffer)
+}
+
+/// 从已打开的 ZIP 中读取并解析 AndroidManifest.xml
+pub fn load_manifest_from_archive<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+) -> Result<Axml, String> {
+    let buffer = read_entry(archive, "AndroidManifest.xml")?;
+
+    rusty_axml::parse_from_reader(Cursor::new(buffer))
+        .map_err(|e| format!("解析 AndroidManifest.xml 失败: {:?}", e))
+}
+
+// 处理原始格式的属性值
+// 某些属性值可能返回 "(type 0x10) 0x12927c70" 这种格式
+// 需要提取十六进制值并转换为十进制字符串
+pub fn clean_attr_value(value: &str) -> String {
+    // 检查是否是原始格式 "(type 0x...) 0x..."
+    if value.contains("(type 0x") && value.contains("0x") {
+        // 提取最后一个 0x 后的十六进制值
+        if let Some(hex_start) = value.rfind("0x") {
+            let hex_str = &value[hex_start + 2..];
+            // 转换为十进制
+            if let Ok(num) = u32::from_str_radix(hex_str.trim(), 16) {
+                return num.to_string();
+            }
+        }
+    }
+    value.to_string()
+}
+
+/// 读取属性（先尝试 android: 前缀，再尝试无前缀），返回清理后的值
+pub fn get_attr(elem: &XmlElement, name: &str) -> Option<String> {
+    elem.get_attr(&format!("android:{}", name))
+        .or_else(|| elem.get_attr(name))
+        .map(clean_attr_value)
+}
+
+/// 读取布尔属性，未设置时返回 None
+pub fn get_bool_attr(elem: &XmlElement, name: &str) -> Option<bool> {
+    match get_attr(elem, name)?.as_str() {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
+    }
+}
+
+/// 读取整数属性，未设置或无法解析时返回 None
+pub fn get_int_attr(elem: &XmlElement, name: &str) -> Option<u32> {
+    get_attr(elem, name)?.parse().ok()
+}
+
+/// 获取第一个 application 节点
+pub fn application_node(axml: &Axml) -> Option<XmlNode> {
+    rusty_axml::find_nodes_by_type(axml, "application").into_iter().next()
+}
+
+/// 读取 uses-sdk 中的 minSdkVersion / targetSdkVersion
+pub fn sdk_versions(axml: &Axml) -> (Option<u32>, Option<u32>) {
+    match rusty_axml::find_nodes_by_type(axml, "uses-sdk").first() {
+        Some(node) => {
+            let elem = node.borrow();
+            (get_int_attr(&elem, "minSdkVersion"), get_int_attr(&elem, "targetSdkVersion"))
+        }
+        None => (None, None),
+    }
+}
+
+/// 获取节点下指定类型的直接子节点
+pub fn children_of_type(node: &XmlNode, element_type: &str) -> Vec<XmlNode> {
+    node.borrow()
+        .children()
+        .iter()
+        .filter(|child| child.borrow().element_type() == element_type)
+        .cloned()
+        .collect()
+}
+
+/// 四大组件的节点类型
+pub const COMPONENT_TYPES: &[&str] = &["activity", "activity-alias", "service", "receiver", "provider"];
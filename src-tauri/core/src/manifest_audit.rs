@@ -0,0 +1,416 @@
+// ==================== Manifest 安全配置审计 ====================
+
+use std::path::Path;
+
+use rusty_axml::parser::{Axml, XmlNode};
+use serde::{Deserialize, Serialize};
+
+use crate::error::AnalysisError;
+use crate::manifest::{self, get_attr, get_bool_attr};
+use crate::play_policy::{self, ReleaseType};
+
+/// 发现项的严重程度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Low,
+    Medium,
+    High,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SecurityFinding {
+    pub id: String,
+    pub severity: Severity,
+    pub title: String,
+    pub description: String,
+    pub component: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ManifestAuditResult {
+    pub package_name: String,
+    pub target_sdk_version: Option<u32>,
+    pub findings: Vec<SecurityFinding>,
+    pub warnings: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+impl SecurityFinding {
+    pub fn new(id: &str, severity: Severity, title: &str, description: String, component: Option<String>) -> Self {
+        SecurityFinding {
+            id: id.to_string(),
+            severity,
+            title: title.to_string(),
+            description,
+            component,
+        }
+    }
+}
+
+/// 按严重程度从高到低排序发现项，并返回 (warnings, errors)：高危为错误，中低危为警告
+pub fn summarize_findings(findings: &mut [SecurityFinding]) -> (Vec<String>, Vec<String>) {
+    findings.sort_by_key(|finding| std::cmp::Reverse(finding.severity));
+
+    let mut warnings = Vec::new();
+    let mut errors = Vec::new();
+    for finding in findings.iter() {
+        let message = match &finding.component {
+            Some(component) => format!("{}: {}", finding.title, component),
+            None => finding.title.clone(),
+        };
+        match finding.severity {
+            Severity::High => errors.push(message),
+            Severity::Medium | Severity::Low => warnings.push(message),
+            Severity::Info => {}
+        }
+    }
+    (warnings, errors)
+}
+
+/// 审计 APK 的 AndroidManifest.xml
+pub fn audit_apk_manifest(path: &Path) -> Result<ManifestAuditResult, AnalysisError> {
+    if !path.exists() {
+        return Err(AnalysisError::NotFound);
+    }
+
+    let axml = manifest::load_manifest(path)?;
+    Ok(audit_manifest(&axml))
+}
+
+/// 对已解析的 manifest 执行全部审计规则
+pub fn audit_manifest(axml: &Axml) -> ManifestAuditResult {
+    let package_name = get_attr(&axml.root().borrow(), "package").unwrap_or_default();
+    let (_, target_sdk) = manifest::sdk_versions(axml);
+
+    let mut findings = Vec::new();
+
+    check_application_flags(axml, target_sdk, &mut findings);
+    check_components(axml, target_sdk, &mut findings);
+    // Google Play 的 targetSdk 下限取自内置政策规则，随规则文件更新
+    let play_min_target_sdk = play_policy::load_rules(None)
+        .ok()
+        .and_then(|rules| play_policy::min_target_sdk(&rules, ReleaseType::Update));
+    if let Some(min) = play_min_target_sdk {
+        check_target_sdk(target_sdk, min, &mut findings);
+    }
+
+    let (warnings, errors) = summarize_findings(&mut findings);
+
+    ManifestAuditResult {
+        package_name,
+        target_sdk_version: target_sdk,
+        findings,
+        warnings,
+        errors,
+    }
+}
+
+// 检查 application 节点上的全局开关
+fn check_application_flags(axml: &Axml, target_sdk: Option<u32>, findings: &mut Vec<SecurityFinding>) {
+    let Some(app_node) = manifest::application_node(axml) else {
+        return;
+    };
+    let app = app_node.borrow();
+
+    if get_bool_attr(&app, "debuggable") == Some(true) {
+        findings.push(SecurityFinding::new(
+            "debuggable",
+            Severity::High,
+            "应用开启了 debuggable",
+            "android:debuggable=\"true\" 允许附加调试器并读取应用私有数据，发布版本必须关闭".to_string(),
+            None,
+        ));
+    }
+
+    if get_bool_attr(&app, "testOnly") == Some(true) {
+        findings.push(SecurityFinding::new(
+            "test_only",
+            Severity::High,
+            "应用标记为 testOnly",
+            "android:testOnly=\"true\" 的 APK 只能通过 adb 安装，应用商店会拒绝上架".to_string(),
+            None,
+        ));
+    }
+
+    match get_bool_attr(&app, "allowBackup") {
+        Some(true) => findings.push(SecurityFinding::new(
+            "allow_backup",
+            Severity::Medium,
+            "应用允许备份 (allowBackup=true)",
+            "应用数据可通过 adb backup 或云备份导出，建议关闭或配置 fullBackupContent/dataExtractionRules".to_string(),
+            None,
+        )),
+        None => findings.push(SecurityFinding::new(
+            "allow_backup",
+            Severity::Low,
+            "未设置 allowBackup，默认允许备份",
+            "android:allowBackup 默认值为 true，建议显式设置为 false".to_string(),
+            None,
+        )),
+        Some(false) => {}
+    }
+
+    let network_security_config = get_attr(&app, "networkSecurityConfig");
+
+    match get_bool_attr(&app, "usesCleartextTraffic") {
+        Some(true) => findings.push(SecurityFinding::new(
+            "cleartext_traffic",
+            Severity::Medium,
+            "应用允许明文 HTTP 流量",
+            "android:usesCleartextTraffic=\"true\" 允许所有域名使用明文 HTTP，可能遭受中间人攻击".to_string(),
+            None,
+        )),
+        // targetSdk < 28 时默认允许明文流量
+        None if network_security_config.is_none() && target_sdk.is_none_or(|sdk| sdk < 28) => {
+            findings.push(SecurityFinding::new(
+                "cleartext_traffic",
+                Severity::Low,
+                "默认允许明文 HTTP 流量",
+                "targetSdkVersion 低于 28 且未配置 usesCleartextTraffic，系统默认允许明文 HTTP".to_string(),
+                None,
+            ))
+        }
+        _ => {}
+    }
+
+    if network_security_config.is_none() {
+        findings.push(SecurityFinding::new(
+            "missing_network_security_config",
+            Severity::Info,
+            "未配置 networkSecurityConfig",
+            "建议通过 network_security_config.xml 明确声明明文流量和证书信任策略".to_string(),
+            None,
+        ));
+    }
+}
+
+// 检查导出组件
+fn check_components(axml: &Axml, target_sdk: Option<u32>, findings: &mut Vec<SecurityFinding>) {
+    for component_type in manifest::COMPONENT_TYPES {
+        for node in rusty_axml::find_nodes_by_type(axml, component_type) {
+            check_component(&node, component_type, target_sdk, findings);
+        }
+    }
+}
+
+fn check_component(node: &XmlNode, component_type: &str, target_sdk: Option<u32>, findings: &mut Vec<SecurityFinding>) {
+    let elem = node.borrow();
+    let name = get_attr(&elem, "name").unwrap_or_else(|| "(unnamed)".to_string());
+    let label = format!("{} {}", component_type, name);
+
+    if get_bool_attr(&elem, "enabled") == Some(false) {
+        return;
+    }
+
+    let explicit_exported = get_bool_attr(&elem, "exported");
+    let has_intent_filter = !manifest::children_of_type(node, "intent-filter").is_empty();
+
+    // targetSdk < 31 时带 intent-filter 的组件默认导出；>= 31 时必须显式声明
+    if explicit_exported.is_none() && has_intent_filter {
+        if target_sdk.is_some_and(|sdk| sdk >= 31) {
+            findings.push(SecurityFinding::new(
+                "missing_exported",
+                Severity::High,
+                "组件包含 intent-filter 但未声明 android:exported",
+                "targetSdkVersion >= 31 时此类 APK 将无法安装".to_string(),
+                Some(label.clone()),
+            ));
+        } else {
+            findings.push(SecurityFinding::new(
+                "implicitly_exported",
+                Severity::Medium,
+                "组件因 intent-filter 被隐式导出",
+                "targetSdkVersion < 31 时带 intent-filter 的组件默认导出，建议显式声明 android:exported".to_string(),
+                Some(label.clone()),
+            ));
+        }
+    }
+
+    // provider 在 targetSdk < 17 时默认导出
+    let exported = match explicit_exported {
+        Some(value) => value,
+        None if component_type == "provider" => target_sdk.is_none_or(|sdk| sdk < 17),
+        None => has_intent_filter,
+    };
+    if !exported || is_launcher_activity(node) {
+        return;
+    }
+
+    let has_permission = get_attr(&elem, "permission").is_some()
+        || (component_type == "provider"
+            && get_attr(&elem, "readPermission").is_some()
+            && get_attr(&elem, "writePermission").is_some());
+    if !has_permission {
+        findings.push(SecurityFinding::new(
+            "exported_without_permission",
+            Severity::Medium,
+            "导出组件未设置访问权限",
+            "任何应用都可以启动或访问该组件，请确认是否需要导出或添加 android:permission".to_string(),
+            Some(label),
+        ));
+    }
+}
+
+// 判断是否为启动入口 Activity（MAIN + LAUNCHER），此类组件必须导出
+fn is_launcher_activity(node: &XmlNode) -> bool {
+    manifest::children_of_type(node, "intent-filter").iter().any(|filter| {
+        let has = |element_type: &str, value: &str| {
+            manifest::children_of_type(filter, element_type)
+                .iter()
+                .any(|child| get_attr(&child.borrow(), "name").as_deref() == Some(value))
+        };
+        has("action", "android.intent.action.MAIN") && has("category", "android.intent.category.LAUNCHER")
+    })
+}
+
+// 检查 targetSdkVersion 是否满足 Google Play 要求
+fn check_target_sdk(target_sdk: Option<u32>, min: u32, findings: &mut Vec<SecurityFinding>) {
+    match target_sdk {
+        Some(sdk) if sdk < min => findings.push(SecurityFinding::new(
+            "target_sdk_too_low",
+            Severity::Medium,
+            "targetSdkVersion 低于 Google Play 要求",
+            format!("当前 targetSdkVersion 为 {}，Google Play 要求不低于 {}", sdk, min),
+            None,
+        )),
+        None => findings.push(SecurityFinding::new(
+            "target_sdk_too_low",
+            Severity::Medium,
+            "未声明 targetSdkVersion",
+            format!("未声明 targetSdkVersion 时按 minSdkVersion 处理，Google Play 要求不低于 {}", min),
+            None,
+        )),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(id: &str, severity: Severity, component: Option<&str>) -> SecurityFinding {
+        SecurityFinding::new(id, severity, id, String::new(), component.map(str::to_string))
+    }
+
+    #[test]
+    fn summarize_sorts_and_splits_by_severity() {
+        let mut findings = vec![
+            finding("low", Severity::Low, None),
+            finding("info", Severity::Info, None),
+            finding("high", Severity::High, Some(".MainActivity")),
+            finding("medium", Severity::Medium, None),
+        ];
+        let (warnings, errors) = summarize_findings(&mut findings);
+
+        let order: Vec<&str> = findings.iter().map(|f| f.id.as_str()).collect();
+        assert_eq!(order, vec!["high", "medium", "low", "info"]);
+        assert_eq!(errors, vec!["high: .MainActivity".to_string()]);
+        assert_eq!(warnings, vec!["medium".to_string(), "low".to_string()]);
+    }
+
+    #[test]
+    fn target_sdk_below_play_minimum_is_reported() {
+        let mut findings = Vec::new();
+        check_target_sdk(Some(34), 35, &mut findings);
+        assert!(!findings.is_empty());
+
+        let mut findings = Vec::new();
+        check_target_sdk(Some(35), 35, &mut findings);
+        assert!(findings.is_empty());
+    }
+
+    // 文本 manifest 编码为 AXML 后用 rusty_axml 解析，与读取 APK 的路径一致
+    fn audit(target_sdk: u32, application: &str) -> ManifestAuditResult {
+        let xml = format!(
+            r#"<manifest xmlns:android="http://schemas.android.com/apk/res/android" package="com.example">
+                <uses-sdk android:minSdkVersion="16" android:targetSdkVersion="{}"/>
+                <application android:allowBackup="false">{}</application>
+            </manifest>"#,
+            target_sdk, application
+        );
+        let data = crate::axml::encode(&crate::axml::document_from_xml(&xml));
+        let axml = rusty_axml::parse_from_reader(std::io::Cursor::new(data)).unwrap();
+        audit_manifest(&axml)
+    }
+
+    fn ids_for<'a>(result: &'a ManifestAuditResult, component: &str) -> Vec<&'a str> {
+        result
+            .findings
+            .iter()
+            .filter(|f| f.component.as_deref() == Some(component))
+            .map(|f| f.id.as_str())
+            .collect()
+    }
+
+    const VIEW_ACTIVITY: &str = r#"
+        <activity android:name=".ShareActivity">
+            <intent-filter>
+                <action android:name="android.intent.action.SEND"/>
+                <category android:name="android.intent.category.DEFAULT"/>
+            </intent-filter>
+        </activity>"#;
+
+    #[test]
+    fn missing_exported_blocks_install_from_sdk_31() {
+        let result = audit(31, VIEW_ACTIVITY);
+        assert_eq!(result.package_name, "com.example");
+        assert_eq!(result.target_sdk_version, Some(31));
+        assert_eq!(ids_for(&result, "activity .ShareActivity"), vec!["missing_exported", "exported_without_permission"]);
+        assert!(result.errors.iter().any(|e| e.contains(".ShareActivity")), "{:?}", result.errors);
+    }
+
+    #[test]
+    fn intent_filter_exports_implicitly_below_sdk_31() {
+        let result = audit(30, VIEW_ACTIVITY);
+        assert_eq!(
+            ids_for(&result, "activity .ShareActivity"),
+            vec!["implicitly_exported", "exported_without_permission"]
+        );
+        assert!(result.findings.iter().all(|f| f.severity != Severity::High));
+    }
+
+    #[test]
+    fn launcher_activity_needs_no_permission() {
+        let result = audit(
+            35,
+            r#"<activity android:name=".MainActivity" android:exported="true">
+                <intent-filter>
+                    <action android:name="android.intent.action.MAIN"/>
+                    <category android:name="android.intent.category.LAUNCHER"/>
+                </intent-filter>
+            </activity>
+            <activity android:name=".DeepLinkActivity" android:exported="true" android:permission="com.example.OPEN"/>"#,
+        );
+        assert!(ids_for(&result, "activity .MainActivity").is_empty());
+        assert!(ids_for(&result, "activity .DeepLinkActivity").is_empty());
+    }
+
+    #[test]
+    fn provider_is_exported_by_default_below_sdk_17() {
+        let provider = r#"<provider android:name=".DataProvider" android:authorities="com.example.data"/>"#;
+        assert_eq!(ids_for(&audit(16, provider), "provider .DataProvider"), vec!["exported_without_permission"]);
+        assert!(ids_for(&audit(17, provider), "provider .DataProvider").is_empty());
+    }
+
+    #[test]
+    fn provider_needs_both_read_and_write_permission() {
+        let read_only = r#"<provider android:name=".DataProvider" android:exported="true"
+            android:readPermission="com.example.READ"/>"#;
+        assert_eq!(ids_for(&audit(35, read_only), "provider .DataProvider"), vec!["exported_without_permission"]);
+
+        let both = r#"<provider android:name=".DataProvider" android:exported="true"
+            android:readPermission="com.example.READ" android:writePermission="com.example.WRITE"/>"#;
+        assert!(ids_for(&audit(35, both), "provider .DataProvider").is_empty());
+    }
+
+    #[test]
+    fn disabled_components_are_skipped() {
+        let result = audit(
+            35,
+            r#"<service android:name=".SyncService" android:enabled="false" android:exported="true"/>"#,
+        );
+        assert!(ids_for(&result, "service .SyncService").is_empty());
+    }
+}
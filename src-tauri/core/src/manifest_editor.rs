@@ -0,0 +1,660 @@
+// ==================== Manifest 编辑与重新打包 ====================
+// 基于 axml 无损模型修改属性后重新编码（rusty_axml 的树不保留资源 ID 和值类型，无法写回）
+
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::axml::{self, Attribute, Document, Element, Node, ANDROID_NAMESPACE};
+use crate::chunk::{
+    ResValue, TYPE_INT_BOOLEAN, TYPE_INT_DEC, TYPE_INT_HEX, TYPE_REFERENCE, TYPE_STRING,
+};
+use crate::error::AnalysisError;
+use crate::manifest;
+use crate::signing::{self, SignOptions};
+use crate::zipalign;
+
+const MANIFEST_ENTRY: &str = "AndroidManifest.xml";
+
+// 常用 android: 属性的资源 ID（android.R.attr），新增属性时使用
+const ANDROID_ATTRIBUTE_IDS: &[(&str, u32)] = &[
+    ("theme", 0x0101_0000),
+    ("label", 0x0101_0001),
+    ("icon", 0x0101_0002),
+    ("name", 0x0101_0003),
+    ("permission", 0x0101_0006),
+    ("hasCode", 0x0101_000c),
+    ("enabled", 0x0101_000e),
+    ("debuggable", 0x0101_000f),
+    ("exported", 0x0101_0010),
+    ("value", 0x0101_0024),
+    ("resource", 0x0101_0025),
+    ("minSdkVersion", 0x0101_020c),
+    ("versionCode", 0x0101_021b),
+    ("versionName", 0x0101_021c),
+    ("targetSdkVersion", 0x0101_0270),
+    ("testOnly", 0x0101_0272),
+    ("allowBackup", 0x0101_0280),
+    ("extractNativeLibs", 0x0101_04ea),
+    ("usesCleartextTraffic", 0x0101_04ec),
+    ("networkSecurityConfig", 0x0101_0527),
+    ("roundIcon", 0x0101_052c),
+    ("isFeatureSplit", 0x0101_055b),
+    ("isSplitRequired", 0x0101_0591),
+];
+
+/// 单个属性修改
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttributeEdit {
+    /// 目标元素，如 manifest、application、activity
+    pub element: String,
+    /// 按 android:name 筛选元素（组件类名或 meta-data 名称），未指定时修改全部同名元素
+    pub component: Option<String>,
+    /// 属性名，android: 前缀表示 Android 命名空间，如 android:versionCode、package
+    pub attribute: String,
+    /// 新值，为空时删除该属性
+    pub value: Option<String>,
+    /// string / int / bool / reference，未指定时沿用原类型或自动推断
+    pub value_type: Option<String>,
+}
+
+/// 在 application 下新增或修改 meta-data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetaDataEdit {
+    pub name: String,
+    pub value: String,
+    pub value_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ManifestEdits {
+    #[serde(default)]
+    pub attributes: Vec<AttributeEdit>,
+    #[serde(default)]
+    pub meta_data: Vec<MetaDataEdit>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ManifestEditResult {
+    pub output_path: String,
+    /// 已应用的修改说明
+    pub applied: Vec<String>,
+    pub signed: bool,
+    pub warnings: Vec<String>,
+}
+
+// 拆分 android:xxx 形式的属性名
+fn split_attribute_name(document: &Document, attribute: &str) -> Result<(Option<String>, String), String> {
+    match attribute.split_once(':') {
+        Some((prefix, name)) => {
+            let uri = document
+                .namespaces
+                .iter()
+                .find(|ns| ns.prefix == prefix)
+                .map(|ns| ns.uri.clone())
+                .or_else(|| (prefix == "android").then(|| ANDROID_NAMESPACE.to_string()))
+                .ok_or_else(|| format!("未知的命名空间前缀: {}", prefix))?;
+            Ok((Some(uri), name.to_string()))
+        }
+        None => Ok((None, attribute.to_string())),
+    }
+}
+
+/// 查找属性的资源 ID：优先沿用文档中已有的，其次查内置表
+pub(crate) fn resource_id_for(document: &Document, namespace: Option<&str>, name: &str) -> Result<u32, String> {
+    if namespace != Some(ANDROID_NAMESPACE) {
+        return Ok(0);
+    }
+
+    fn find(element: &Element, name: &str) -> Option<u32> {
+        element
+            .attributes
+            .iter()
+            .find(|a| a.namespace.as_deref() == Some(ANDROID_NAMESPACE) && a.name == name && a.resource_id != 0)
+            .map(|a| a.resource_id)
+            .or_else(|| element.child_elements().find_map(|child| find(child, name)))
+    }
+
+    find(&document.root, name)
+        .or_else(|| ANDROID_ATTRIBUTE_IDS.iter().find(|(n, _)| *n == name).map(|(_, id)| *id))
+        .ok_or_else(|| format!("未知的 Android 属性 android:{}，无法确定资源 ID", name))
+}
+
+// 根据类型构造属性值，返回 (rawValue, Res_value)
+fn build_value(value: &str, value_type: &str) -> Result<(Option<String>, ResValue), String> {
+    let res_value = |data_type, data| ResValue { data_type, data };
+    match value_type {
+        "string" => Ok((Some(value.to_string()), res_value(TYPE_STRING, 0))),
+        "int" => {
+            let number = match value.strip_prefix("0x") {
+                Some(hex) => u32::from_str_radix(hex, 16).map_err(|_| format!("无效的整数: {}", value))?,
+                None => value
+                    .parse::<i64>()
+                    .ok()
+                    .filter(|n| *n >= i32::MIN as i64 && *n <= u32::MAX as i64)
+                    .ok_or_else(|| format!("无效的整数: {}", value))? as u32,
+            };
+            Ok((None, res_value(TYPE_INT_DEC, number)))
+        }
+        "bool" => match value {
+            "true" => Ok((None, res_value(TYPE_INT_BOOLEAN, 0xffff_ffff))),
+            "false" => Ok((None, res_value(TYPE_INT_BOOLEAN, 0))),
+            _ => Err(format!("无效的布尔值: {}", value)),
+        },
+        "reference" => {
+            let id = value.trim_start_matches('@').trim_start_matches("0x");
+            let id = u32::from_str_radix(id, 16).map_err(|_| format!("无效的资源引用: {}，应为 @0x7f...", value))?;
+            Ok((None, res_value(TYPE_REFERENCE, id)))
+        }
+        other => Err(format!("不支持的值类型: {}", other)),
+    }
+}
+
+// 未指定类型时：引用按引用处理，原属性为整数/布尔时沿用，新属性按内容推断
+fn infer_value_type(value: &str, existing: Option<&Attribute>) -> &'static str {
+    if value.starts_with("@0x") {
+        return "reference";
+    }
+    match existing.map(|a| a.value.data_type) {
+        Some(TYPE_INT_DEC | TYPE_INT_HEX) => "int",
+        Some(TYPE_INT_BOOLEAN) => "bool",
+        Some(_) => "string",
+        None if value == "true" || value == "false" => "bool",
+        None if value.parse::<i64>().is_ok() => "int",
+        None => "string",
+    }
+}
+
+/// 设置属性，不存在时按资源 ID 顺序插入
+pub(crate) fn set_attribute(
+    element: &mut Element,
+    namespace: Option<&str>,
+    name: &str,
+    resource_id: u32,
+    value: &str,
+    value_type: Option<&str>,
+) -> Result<(), String> {
+    let existing = element
+        .attributes
+        .iter()
+        .find(|a| a.namespace.as_deref() == namespace && a.name == name);
+    let value_type = value_type.unwrap_or_else(|| infer_value_type(value, existing));
+    let (raw_value, res_value) = build_value(value, value_type)?;
+
+    if let Some(attr) = element.attr_mut(namespace, name) {
+        attr.raw_value = raw_value;
+        attr.value = res_value;
+        return Ok(());
+    }
+
+    let attribute = Attribute {
+        namespace: namespace.map(str::to_string),
+        name: name.to_string(),
+        resource_id,
+        raw_value,
+        value: res_value,
+    };
+    let position = if resource_id == 0 {
+        element.attributes.len()
+    } else {
+        element
+            .attributes
+            .iter()
+            .position(|a| a.resource_id == 0 || a.resource_id > resource_id)
+            .unwrap_or(element.attributes.len())
+    };
+    element.attributes.insert(position, attribute);
+    Ok(())
+}
+
+// 组件名可能省略包名（.MainActivity）
+fn component_matches(element: &Element, component: &str, package: &str) -> bool {
+    let Some(name) = element
+        .attributes
+        .iter()
+        .find(|a| a.namespace.as_deref() == Some(ANDROID_NAMESPACE) && a.name == "name")
+        .and_then(|a| a.raw_value.as_deref())
+    else {
+        return false;
+    };
+    let expand = |n: &str| if n.starts_with('.') { format!("{}{}", package, n) } else { n.to_string() };
+    expand(name) == expand(component)
+}
+
+// 深度优先收集匹配的元素
+fn for_each_match(
+    element: &mut Element,
+    tag: &str,
+    component: Option<&str>,
+    package: &str,
+    visit: &mut dyn FnMut(&mut Element) -> Result<(), String>,
+) -> Result<usize, String> {
+    let mut count = 0;
+    if element.name == tag && component.is_none_or(|c| component_matches(element, c, package)) {
+        visit(element)?;
+        count += 1;
+    }
+    for child in element.child_elements_mut() {
+        count += for_each_match(child, tag, component, package, visit)?;
+    }
+    Ok(count)
+}
+
+fn apply_attribute_edit(document: &mut Document, edit: &AttributeEdit, package: &str) -> Result<String, String> {
+    let (namespace, name) = split_attribute_name(document, &edit.attribute)?;
+    let value = edit.value.as_deref().filter(|v| !v.is_empty());
+    let resource_id = match value {
+        Some(_) => resource_id_for(document, namespace.as_deref(), &name)?,
+        None => 0,
+    };
+
+    let matched = for_each_match(
+        &mut document.root,
+        &edit.element,
+        edit.component.as_deref(),
+        package,
+        &mut |element| match value {
+            Some(value) => set_attribute(element, namespace.as_deref(), &name, resource_id, value, edit.value_type.as_deref()),
+            None => {
+                element
+                    .attributes
+                    .retain(|a| !(a.namespace == namespace && a.name == name));
+                Ok(())
+            }
+        },
+    )?;
+
+    let target = match &edit.component {
+        Some(component) => format!("{}[{}]", edit.element, component),
+        None => edit.element.clone(),
+    };
+    if matched == 0 {
+        return Err(format!("未找到元素 {}", target));
+    }
+    Ok(match value {
+        Some(value) => format!("{} {}={}", target, edit.attribute, value),
+        None => format!("{} 删除 {}", target, edit.attribute),
+    })
+}
+
+fn apply_meta_data_edit(document: &mut Document, edit: &MetaDataEdit, package: &str) -> Result<String, String> {
+    let name_id = resource_id_for(document, Some(ANDROID_NAMESPACE), "name")?;
+    let value_id = resource_id_for(document, Some(ANDROID_NAMESPACE), "value")?;
+
+    let application = document
+        .root
+        .child_elements_mut()
+        .find(|e| e.name == "application")
+        .ok_or("未找到 application 节点")?;
+
+    let existing = application
+        .child_elements_mut()
+        .find(|e| e.name == "meta-data" && component_matches(e, &edit.name, package));
+    match existing {
+        Some(meta) => {
+            // 改为直接值，去掉 android:resource
+            meta.attributes
+                .retain(|a| !(a.namespace.as_deref() == Some(ANDROID_NAMESPACE) && a.name == "resource"));
+            set_attribute(meta, Some(ANDROID_NAMESPACE), "value", value_id, &edit.value, edit.value_type.as_deref())?;
+        }
+        None => {
+            let mut meta = Element {
+                namespace: None,
+                name: "meta-data".to_string(),
+                attributes: Vec::new(),
+                children: Vec::new(),
+            };
+            set_attribute(&mut meta, Some(ANDROID_NAMESPACE), "name", name_id, &edit.name, Some("string"))?;
+            set_attribute(&mut meta, Some(ANDROID_NAMESPACE), "value", value_id, &edit.value, edit.value_type.as_deref())?;
+            application.children.push(Node::Element(meta));
+        }
+    }
+    Ok(format!("meta-data {}={}", edit.name, edit.value))
+}
+
+/// 对 manifest 应用全部修改，返回修改说明
+pub(crate) fn apply_edits(document: &mut Document, edits: &ManifestEdits) -> Result<Vec<String>, String> {
+    let package = document.root.attr_value("package").unwrap_or_default();
+    let mut applied = Vec::new();
+    for edit in &edits.attributes {
+        applied.push(apply_attribute_edit(document, edit, &package)?);
+    }
+    for edit in &edits.meta_data {
+        applied.push(apply_meta_data_edit(document, edit, &package)?);
+    }
+    Ok(applied)
+}
+
+// v1 签名文件在内容修改后失效，重新打包时去掉
+fn is_signature_entry(name: &str) -> bool {
+    let Some(file_name) = name.strip_prefix("META-INF/") else {
+        return false;
+    };
+    if file_name.contains('/') {
+        return false;
+    }
+    let upper = file_name.to_uppercase();
+    upper == "MANIFEST.MF"
+        || upper.starts_with("SIG-")
+        || [".SF", ".RSA", ".DSA", ".EC"].iter().any(|ext| upper.ends_with(ext))
+}
+
+// 复制原 APK 的条目并替换 AndroidManifest.xml
+fn repack(input_path: &Path, output_path: &Path, manifest_data: &[u8]) -> Result<usize, String> {
+    let file = File::open(input_path).map_err(|e| format!("无法打开文件: {}", e))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("无法解析 ZIP 文件: {}", e))?;
+    let output = File::create(output_path).map_err(|e| format!("无法创建输出文件: {}", e))?;
+    let mut writer = ZipWriter::new(output);
+
+    let mut removed_signatures = 0;
+    for index in 0..archive.len() {
+        let entry = archive
+            .by_index_raw(index)
+            .map_err(|e| format!("读取 ZIP 条目失败: {}", e))?;
+        let name = entry.name().to_string();
+        if name == MANIFEST_ENTRY {
+            let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+            writer
+                .start_file(MANIFEST_ENTRY, options)
+                .map_err(|e| format!("写入 {} 失败: {}", MANIFEST_ENTRY, e))?;
+            writer
+                .write_all(manifest_data)
+                .map_err(|e| format!("写入 {} 失败: {}", MANIFEST_ENTRY, e))?;
+        } else if is_signature_entry(&name) {
+            removed_signatures += 1;
+        } else {
+            writer
+                .raw_copy_file(entry)
+                .map_err(|e| format!("复制 {} 失败: {}", name, e))?;
+        }
+    }
+
+    writer.finish().map_err(|e| format!("写入 APK 失败: {}", e))?;
+    Ok(removed_signatures)
+}
+
+/// 修改 APK 的 AndroidManifest.xml 并输出新 APK，可选重新签名
+pub fn edit_apk_manifest(
+    input_path: &Path,
+    output_path: &Path,
+    edits: &ManifestEdits,
+    sign_options: Option<&SignOptions>,
+) -> Result<ManifestEditResult, AnalysisError> {
+    if !input_path.exists() {
+        return Err(AnalysisError::NotFound);
+    }
+    if edits.attributes.is_empty() && edits.meta_data.is_empty() {
+        return Err("没有需要应用的修改".into());
+    }
+
+    let file = File::open(input_path)?;
+    let mut archive = ZipArchive::new(file)?;
+    let manifest_data = manifest::read_entry(&mut archive, MANIFEST_ENTRY)?;
+    drop(archive);
+
+    let mut document = axml::parse(&manifest_data)?;
+    let applied = apply_edits(&mut document, edits)?;
+    let encoded = axml::encode(&document);
+    // 确认编码结果可以被重新解析
+    axml::parse(&encoded).map_err(|e| format!("重新编码的 manifest 无效: {}", e))?;
+
+    let temp_path = output_path.with_extension("apk.tmp");
+    let removed_signatures = match repack(input_path, &temp_path, &encoded) {
+        Ok(count) => count,
+        Err(e) => {
+            let _ = fs::remove_file(&temp_path);
+            return Err(e.into());
+        }
+    };
+    fs::rename(&temp_path, output_path).map_err(|e| format!("保存输出文件失败: {}", e))?;
+
+    // apksigner 要求签名前完成对齐
+    zipalign::align_apk(output_path, output_path, None)?;
+
+    let mut warnings = Vec::new();
+    if removed_signatures > 0 {
+        warnings.push(format!("已移除 {} 个失效的 v1 签名文件", removed_signatures));
+    }
+
+    let signed = match sign_options {
+        Some(options) => {
+            signing::sign_apk(output_path, options).map_err(|e| e.to_string())?;
+            true
+        }
+        None => {
+            warnings.push("输出的 APK 未签名，安装前需要重新签名".to_string());
+            false
+        }
+    };
+
+    Ok(ManifestEditResult {
+        output_path: output_path.to_string_lossy().to_string(),
+        applied,
+        signed,
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Read};
+
+    const MANIFEST_XML: &str = r#"<manifest xmlns:android="http://schemas.android.com/apk/res/android" package="com.example" android:versionCode="7" android:versionName="1.0">
+        <application android:label="Demo" android:allowBackup="false">
+            <activity android:name=".MainActivity"/>
+            <meta-data android:name="CHANNEL" android:value="official"/>
+        </application>
+    </manifest>"#;
+
+    // 补上资源 ID，versionCode 改为整数类型，模拟 aapt2 的输出
+    fn sample_document() -> Document {
+        fn assign_ids(element: &mut Element) {
+            for attr in &mut element.attributes {
+                if attr.namespace.as_deref() == Some(ANDROID_NAMESPACE) {
+                    attr.resource_id = ANDROID_ATTRIBUTE_IDS
+                        .iter()
+                        .find(|(name, _)| *name == attr.name)
+                        .map(|(_, id)| *id)
+                        .unwrap();
+                }
+            }
+            for child in element.child_elements_mut() {
+                assign_ids(child);
+            }
+        }
+
+        let mut document = axml::document_from_xml(MANIFEST_XML);
+        assign_ids(&mut document.root);
+        let version_code = document.root.attr_mut(Some(ANDROID_NAMESPACE), "versionCode").unwrap();
+        version_code.raw_value = None;
+        version_code.value = ResValue { data_type: TYPE_INT_DEC, data: 7 };
+        document
+    }
+
+    fn sample_edits() -> ManifestEdits {
+        ManifestEdits {
+            attributes: vec![
+                AttributeEdit {
+                    element: "manifest".to_string(),
+                    component: None,
+                    attribute: "android:versionCode".to_string(),
+                    value: Some("42".to_string()),
+                    value_type: None,
+                },
+                AttributeEdit {
+                    element: "application".to_string(),
+                    component: None,
+                    attribute: "android:debuggable".to_string(),
+                    value: Some("true".to_string()),
+                    value_type: None,
+                },
+            ],
+            meta_data: vec![
+                MetaDataEdit {
+                    name: "CHANNEL".to_string(),
+                    value: "huawei".to_string(),
+                    value_type: None,
+                },
+                MetaDataEdit {
+                    name: "BUILD_NUMBER".to_string(),
+                    value: "128".to_string(),
+                    value_type: None,
+                },
+            ],
+        }
+    }
+
+    // rusty_axml 查找节点不保证文档顺序，按名称排序后返回
+    fn meta_data_values(axml: &rusty_axml::parser::Axml) -> Vec<(String, String)> {
+        rusty_axml::find_nodes_by_type(axml, "meta-data")
+            .iter()
+            .map(|node| {
+                let node = node.borrow();
+                (
+                    manifest::get_attr(&node, "name").unwrap_or_default(),
+                    manifest::get_attr(&node, "value").unwrap_or_default(),
+                )
+            })
+            .collect::<std::collections::BTreeMap<_, _>>()
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn edits_survive_reencoding() {
+        let mut document = sample_document();
+        let applied = apply_edits(&mut document, &sample_edits()).unwrap();
+        assert_eq!(applied.len(), 4);
+
+        // 新增属性按资源 ID 插入：label(0x01010001) < debuggable(0x0101000f) < allowBackup(0x01010280)
+        let application = document.root.child_elements().find(|e| e.name == "application").unwrap();
+        let order: Vec<_> = application.attributes.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(order, ["label", "debuggable", "allowBackup"]);
+        assert_eq!(
+            application.attributes[1].value,
+            ResValue { data_type: TYPE_INT_BOOLEAN, data: 0xffff_ffff }
+        );
+
+        // 已有的 meta-data 原地替换，新的追加到 application 末尾
+        let meta_names: Vec<_> = application
+            .children_named("meta-data")
+            .filter_map(|e| e.attr_value("name"))
+            .collect();
+        assert_eq!(meta_names, ["CHANNEL", "BUILD_NUMBER"]);
+
+        let encoded = axml::encode(&document);
+        let reparsed = axml::parse(&encoded).unwrap();
+        let version_code = reparsed.root.attr("versionCode").unwrap();
+        assert_eq!(version_code.resource_id, 0x0101_021b);
+        assert_eq!(version_code.value, ResValue { data_type: TYPE_INT_DEC, data: 42 });
+
+        let axml = rusty_axml::parse_from_reader(Cursor::new(encoded)).unwrap();
+        let root = rusty_axml::find_nodes_by_type(&axml, "manifest").into_iter().next().unwrap();
+        assert_eq!(manifest::get_attr(&root.borrow(), "versionCode").as_deref(), Some("42"));
+        assert_eq!(manifest::get_attr(&root.borrow(), "versionName").as_deref(), Some("1.0"));
+        let application = manifest::application_node(&axml).unwrap();
+        assert_eq!(manifest::get_bool_attr(&application.borrow(), "debuggable"), Some(true));
+
+        assert_eq!(
+            meta_data_values(&axml),
+            [
+                ("BUILD_NUMBER".to_string(), "128".to_string()),
+                ("CHANNEL".to_string(), "huawei".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn removing_and_missing_elements() {
+        let mut document = sample_document();
+        let edits = ManifestEdits {
+            attributes: vec![AttributeEdit {
+                element: "application".to_string(),
+                component: None,
+                attribute: "android:allowBackup".to_string(),
+                value: None,
+                value_type: None,
+            }],
+            meta_data: Vec::new(),
+        };
+        apply_edits(&mut document, &edits).unwrap();
+        let application = document.root.child_elements().find(|e| e.name == "application").unwrap();
+        assert!(application.attr("allowBackup").is_none());
+
+        let edits = ManifestEdits {
+            attributes: vec![AttributeEdit {
+                element: "activity".to_string(),
+                component: Some("com.example.Missing".to_string()),
+                attribute: "android:exported".to_string(),
+                value: Some("true".to_string()),
+                value_type: None,
+            }],
+            meta_data: Vec::new(),
+        };
+        let err = apply_edits(&mut document, &edits).unwrap_err();
+        assert_eq!(err, "未找到元素 activity[com.example.Missing]");
+
+        // 组件名省略包名时同样能匹配
+        let edits = ManifestEdits {
+            attributes: vec![AttributeEdit {
+                element: "activity".to_string(),
+                component: Some("com.example.MainActivity".to_string()),
+                attribute: "android:exported".to_string(),
+                value: Some("false".to_string()),
+                value_type: None,
+            }],
+            meta_data: Vec::new(),
+        };
+        apply_edits(&mut document, &edits).unwrap();
+    }
+
+    #[test]
+    fn repack_replaces_manifest_and_drops_v1_signature() {
+        let manifest_data = axml::encode(&sample_document());
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+        for (name, data) in [
+            (MANIFEST_ENTRY, manifest_data.as_slice()),
+            ("classes.dex", b"dex\n035\0".as_slice()),
+            ("META-INF/MANIFEST.MF", b"Manifest-Version: 1.0\r\n".as_slice()),
+            ("META-INF/CERT.SF", b"Signature-Version: 1.0\r\n".as_slice()),
+            ("META-INF/CERT.RSA", b"\x30\x82".as_slice()),
+            ("META-INF/services/com.example.Plugin", b"com.example.PluginImpl".as_slice()),
+        ] {
+            writer.start_file(name, stored).unwrap();
+            writer.write_all(data).unwrap();
+        }
+        let input_data = writer.finish().unwrap().into_inner();
+
+        let dir = std::env::temp_dir().join(format!("manifest_editor_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("input.apk");
+        let output = dir.join("output.apk");
+        fs::write(&input, input_data).unwrap();
+
+        let result = edit_apk_manifest(&input, &output, &sample_edits(), None);
+        let output_data = fs::read(&output);
+        let _ = fs::remove_dir_all(&dir);
+        let result = result.unwrap();
+
+        assert!(!result.signed);
+        assert_eq!(result.applied.len(), 4);
+        assert!(result.warnings.iter().any(|w| w.contains("3 个失效的 v1 签名文件")));
+
+        let mut archive = ZipArchive::new(Cursor::new(output_data.unwrap())).unwrap();
+        let names: Vec<_> = archive.file_names().map(str::to_string).collect();
+        assert_eq!(names.len(), 3);
+        assert!(names.iter().all(|n| !is_signature_entry(n)));
+        assert!(names.iter().any(|n| n == "META-INF/services/com.example.Plugin"));
+
+        let mut data = Vec::new();
+        archive.by_name(MANIFEST_ENTRY).unwrap().read_to_end(&mut data).unwrap();
+        let document = axml::parse(&data).unwrap();
+        assert_eq!(document.root.attr("versionCode").unwrap().value.data, 42);
+    }
+}
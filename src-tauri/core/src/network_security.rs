@@ -0,0 +1,540 @@
+// ==================== 网络安全配置与深度链接提取 ====================
+
+use std::fs::File;
+use std::path::Path;
+
+use rusty_axml::parser::{Axml, XmlNode};
+use serde::{Deserialize, Serialize};
+use zip::ZipArchive;
+
+use crate::arsc::{self, ResourceTable};
+use crate::axml::{self, Element};
+use crate::error::AnalysisError;
+use crate::manifest::{self, get_attr, get_bool_attr};
+
+// 未能通过资源表定位时尝试的默认路径
+const DEFAULT_CONFIG_PATH: &str = "res/xml/network_security_config.xml";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustAnchor {
+    /// "system"、"user" 或证书资源（如 "@raw/my_ca"）
+    pub src: String,
+    pub override_pins: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PinnedCertificate {
+    pub digest: String,
+    pub value: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DomainEntry {
+    pub name: String,
+    pub include_subdomains: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DomainConfig {
+    pub domains: Vec<DomainEntry>,
+    /// 继承父级配置后的实际值，None 表示沿用平台默认
+    pub cleartext_traffic_permitted: Option<bool>,
+    /// 未声明时继承父级 domain-config 或 base-config
+    pub trust_anchors: Vec<TrustAnchor>,
+    pub pins: Vec<PinnedCertificate>,
+    pub pin_expiration: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NetworkSecurityConfig {
+    /// 配置文件在 APK 中的路径
+    pub source: String,
+    pub base_cleartext_permitted: Option<bool>,
+    pub base_trust_anchors: Vec<TrustAnchor>,
+    pub domain_configs: Vec<DomainConfig>,
+    /// 允许明文流量的域名
+    pub cleartext_domains: Vec<String>,
+    /// 仅在 debuggable 构建中生效的信任锚
+    pub debug_overrides: Vec<TrustAnchor>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeepLink {
+    pub component: String,
+    pub component_type: String,
+    pub schemes: Vec<String>,
+    pub hosts: Vec<String>,
+    pub paths: Vec<String>,
+    /// scheme/host/path 组合后的 URL 模式
+    pub urls: Vec<String>,
+    pub auto_verify: bool,
+    pub browsable: bool,
+    /// autoVerify 且仅使用 http/https 的 App Links
+    pub is_app_link: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NetworkReport {
+    pub package_name: String,
+    pub uses_cleartext_traffic: Option<bool>,
+    pub network_security_config: Option<NetworkSecurityConfig>,
+    pub deep_links: Vec<DeepLink>,
+    pub warnings: Vec<String>,
+}
+
+/// 提取 APK 的网络安全配置和深度链接
+pub fn analyze_apk_network(path: &Path) -> Result<NetworkReport, AnalysisError> {
+    if !path.exists() {
+        return Err(AnalysisError::NotFound);
+    }
+
+    let file = File::open(path)?;
+    let mut archive = ZipArchive::new(file)?;
+    let axml = manifest::load_manifest_from_archive(&mut archive)?;
+
+    // 资源表不存在或解析失败时仍可输出深度链接
+    let table = manifest::read_entry(&mut archive, "resources.arsc")
+        .and_then(|data| ResourceTable::parse(&data))
+        .ok();
+
+    let package_name = get_attr(&axml.root().borrow(), "package").unwrap_or_default();
+    let mut warnings = Vec::new();
+
+    let app_node = manifest::application_node(&axml);
+    let uses_cleartext_traffic = app_node
+        .as_ref()
+        .and_then(|node| get_bool_attr(&node.borrow(), "usesCleartextTraffic"));
+    let config_ref = app_node
+        .as_ref()
+        .and_then(|node| get_attr(&node.borrow(), "networkSecurityConfig"));
+
+    let network_security_config = match config_ref {
+        Some(reference) => {
+            let source = arsc::parse_reference(&reference)
+                .and_then(|id| table.as_ref()?.resolve_string(id))
+                .unwrap_or_else(|| DEFAULT_CONFIG_PATH.to_string());
+            match manifest::read_entry(&mut archive, &source).and_then(|data| axml::parse(&data)) {
+                Ok(document) => Some(parse_network_security_config(&document.root, source, table.as_ref())),
+                Err(e) => {
+                    warnings.push(format!("无法解析网络安全配置 {}: {}", source, e));
+                    None
+                }
+            }
+        }
+        None => None,
+    };
+
+    if let Some(config) = &network_security_config {
+        collect_config_warnings(config, &mut warnings);
+    }
+
+    let deep_links = collect_deep_links(&axml);
+    for link in &deep_links {
+        if link.auto_verify && !link.is_app_link {
+            warnings.push(format!("{} 声明了 autoVerify，但包含非 http/https scheme，无法通过 App Links 验证", link.component));
+        }
+        if link.schemes.iter().any(|s| s == "http") {
+            warnings.push(format!("{} 的深度链接接受 http scheme", link.component));
+        }
+    }
+
+    Ok(NetworkReport {
+        package_name,
+        uses_cleartext_traffic,
+        network_security_config,
+        deep_links,
+        warnings,
+    })
+}
+
+// ========== network_security_config.xml ==========
+
+fn parse_bool(element: &Element, name: &str) -> Option<bool> {
+    element.attr_value(name).map(|v| v == "true")
+}
+
+fn parse_trust_anchors(element: &Element, table: Option<&ResourceTable>) -> Vec<TrustAnchor> {
+    element
+        .children_named("trust-anchors")
+        .flat_map(|anchors| anchors.children_named("certificates"))
+        .map(|cert| {
+            let src = cert.attr_value("src").unwrap_or_default();
+            // 证书资源引用还原为 @raw/xxx 形式
+            let src = match arsc::parse_reference(&src).and_then(|id| table?.name_of(id)) {
+                Some(name) => format!("@{}", name),
+                None => src,
+            };
+            TrustAnchor {
+                src,
+                override_pins: parse_bool(cert, "overridePins").unwrap_or(false),
+            }
+        })
+        .collect()
+}
+
+// 父级配置中可被继承的部分
+struct InheritedConfig {
+    cleartext: Option<bool>,
+    trust_anchors: Vec<TrustAnchor>,
+    pins: Vec<PinnedCertificate>,
+    pin_expiration: Option<String>,
+}
+
+// domain-config 可以嵌套，未声明的配置项从父级 domain-config 继承，顶层从 base-config 继承（base-config 没有 pin-set）
+fn parse_domain_configs(
+    element: &Element,
+    inherited: &InheritedConfig,
+    table: Option<&ResourceTable>,
+    out: &mut Vec<DomainConfig>,
+) {
+    for domain_config in element.children_named("domain-config") {
+        let cleartext = parse_bool(domain_config, "cleartextTrafficPermitted").or(inherited.cleartext);
+
+        let domains = domain_config
+            .children_named("domain")
+            .map(|domain| DomainEntry {
+                name: domain.text(),
+                include_subdomains: parse_bool(domain, "includeSubdomains").unwrap_or(false),
+            })
+            .collect();
+
+        let (pins, pin_expiration) = match domain_config.children_named("pin-set").next() {
+            Some(set) => (
+                set.children_named("pin")
+                    .map(|pin| PinnedCertificate {
+                        digest: pin.attr_value("digest").unwrap_or_else(|| "SHA-256".to_string()),
+                        value: pin.text(),
+                    })
+                    .collect(),
+                set.attr_value("expiration"),
+            ),
+            None => (inherited.pins.clone(), inherited.pin_expiration.clone()),
+        };
+
+        let mut trust_anchors = parse_trust_anchors(domain_config, table);
+        if domain_config.children_named("trust-anchors").next().is_none() {
+            trust_anchors = inherited.trust_anchors.clone();
+        }
+
+        let config = DomainConfig {
+            domains,
+            cleartext_traffic_permitted: cleartext,
+            trust_anchors,
+            pins,
+            pin_expiration,
+        };
+        let nested = InheritedConfig {
+            cleartext,
+            trust_anchors: config.trust_anchors.clone(),
+            pins: config.pins.clone(),
+            pin_expiration: config.pin_expiration.clone(),
+        };
+        out.push(config);
+
+        parse_domain_configs(domain_config, &nested, table, out);
+    }
+}
+
+fn parse_network_security_config(root: &Element, source: String, table: Option<&ResourceTable>) -> NetworkSecurityConfig {
+    let base_config = root.children_named("base-config").next();
+    let base_cleartext_permitted = base_config.and_then(|base| parse_bool(base, "cleartextTrafficPermitted"));
+    let base_trust_anchors = base_config
+        .map(|base| parse_trust_anchors(base, table))
+        .unwrap_or_default();
+
+    let base = InheritedConfig {
+        cleartext: base_cleartext_permitted,
+        trust_anchors: base_trust_anchors.clone(),
+        pins: Vec::new(),
+        pin_expiration: None,
+    };
+    let mut domain_configs = Vec::new();
+    parse_domain_configs(root, &base, table, &mut domain_configs);
+
+    let cleartext_domains = domain_configs
+        .iter()
+        .filter(|config| config.cleartext_traffic_permitted == Some(true))
+        .flat_map(|config| config.domains.iter().map(|d| d.name.clone()))
+        .collect();
+
+    let debug_overrides = root
+        .children_named("debug-overrides")
+        .flat_map(|overrides| parse_trust_anchors(overrides, table))
+        .collect();
+
+    NetworkSecurityConfig {
+        source,
+        base_cleartext_permitted,
+        base_trust_anchors,
+        domain_configs,
+        cleartext_domains,
+        debug_overrides,
+    }
+}
+
+fn collect_config_warnings(config: &NetworkSecurityConfig, warnings: &mut Vec<String>) {
+    if config.base_cleartext_permitted == Some(true) {
+        warnings.push("base-config 允许所有域名使用明文 HTTP".to_string());
+    }
+    if !config.cleartext_domains.is_empty() {
+        warnings.push(format!("以下域名允许明文 HTTP: {}", config.cleartext_domains.join(", ")));
+    }
+
+    let trusts_user_ca = config.base_trust_anchors.iter().any(|a| a.src == "user")
+        || config
+            .domain_configs
+            .iter()
+            .any(|c| c.trust_anchors.iter().any(|a| a.src == "user"));
+    if trusts_user_ca {
+        warnings.push("发布配置信任用户安装的 CA 证书，可被抓包工具中间人攻击".to_string());
+    }
+
+    for domain_config in &config.domain_configs {
+        if domain_config.trust_anchors.iter().any(|a| a.override_pins) && !domain_config.pins.is_empty() {
+            let names: Vec<&str> = domain_config.domains.iter().map(|d| d.name.as_str()).collect();
+            warnings.push(format!("{} 的证书锁定可被 overridePins 绕过", names.join(", ")));
+        }
+    }
+}
+
+// ========== 深度链接 ==========
+
+fn collect_deep_links(axml: &Axml) -> Vec<DeepLink> {
+    let mut links = Vec::new();
+    for component_type in ["activity", "activity-alias"] {
+        for node in rusty_axml::find_nodes_by_type(axml, component_type) {
+            let component = get_attr(&node.borrow(), "name").unwrap_or_default();
+            for filter in manifest::children_of_type(&node, "intent-filter") {
+                if let Some(link) = deep_link_from_filter(&filter, &component, component_type) {
+                    links.push(link);
+                }
+            }
+        }
+    }
+    links
+}
+
+fn deep_link_from_filter(filter: &XmlNode, component: &str, component_type: &str) -> Option<DeepLink> {
+    let names_of = |element_type: &str| -> Vec<String> {
+        manifest::children_of_type(filter, element_type)
+            .iter()
+            .filter_map(|node| get_attr(&node.borrow(), "name"))
+            .collect()
+    };
+
+    let actions = names_of("action");
+    if !actions.iter().any(|a| a == "android.intent.action.VIEW") {
+        return None;
+    }
+    let browsable = names_of("category")
+        .iter()
+        .any(|c| c == "android.intent.category.BROWSABLE");
+
+    // 同一个 intent-filter 中的多个 <data> 会被合并，scheme/host/path 两两组合
+    let mut schemes = Vec::new();
+    let mut hosts = Vec::new();
+    let mut paths = Vec::new();
+    for data in manifest::children_of_type(filter, "data") {
+        let data = data.borrow();
+        if let Some(scheme) = get_attr(&data, "scheme") {
+            push_unique(&mut schemes, scheme);
+        }
+        if let Some(host) = get_attr(&data, "host") {
+            let host = match get_attr(&data, "port") {
+                Some(port) => format!("{}:{}", host, port),
+                None => host,
+            };
+            push_unique(&mut hosts, host);
+        }
+        for attr in ["path", "pathPrefix", "pathPattern", "pathAdvancedPattern", "pathSuffix"] {
+            if let Some(path) = get_attr(&data, attr) {
+                let path = match attr {
+                    "pathPrefix" => format!("{}*", path),
+                    "pathSuffix" => format!("*{}", path),
+                    _ => path,
+                };
+                push_unique(&mut paths, path);
+            }
+        }
+    }
+
+    if schemes.is_empty() {
+        return None;
+    }
+
+    let mut urls = Vec::new();
+    for scheme in &schemes {
+        if hosts.is_empty() {
+            urls.push(format!("{}:", scheme));
+            continue;
+        }
+        for host in &hosts {
+            if paths.is_empty() {
+                urls.push(format!("{}://{}", scheme, host));
+            }
+            for path in &paths {
+                urls.push(format!("{}://{}{}", scheme, host, path));
+            }
+        }
+    }
+
+    let auto_verify = get_bool_attr(&filter.borrow(), "autoVerify") == Some(true);
+    let is_app_link = auto_verify && schemes.iter().all(|s| s == "http" || s == "https");
+
+    Some(DeepLink {
+        component: component.to_string(),
+        component_type: component_type.to_string(),
+        schemes,
+        hosts,
+        paths,
+        urls,
+        auto_verify,
+        browsable,
+        is_app_link,
+    })
+}
+
+fn push_unique(values: &mut Vec<String>, value: String) {
+    if !values.contains(&value) {
+        values.push(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_config(xml: &str) -> NetworkSecurityConfig {
+        let document = axml::document_from_xml(xml);
+        parse_network_security_config(&document.root, DEFAULT_CONFIG_PATH.to_string(), None)
+    }
+
+    fn warnings_of(config: &NetworkSecurityConfig) -> Vec<String> {
+        let mut warnings = Vec::new();
+        collect_config_warnings(config, &mut warnings);
+        warnings
+    }
+
+    fn anchor_sources(anchors: &[TrustAnchor]) -> Vec<&str> {
+        anchors.iter().map(|a| a.src.as_str()).collect()
+    }
+
+    #[test]
+    fn domain_configs_inherit_base_config() {
+        let config = parse_config(
+            r#"<network-security-config>
+                <base-config cleartextTrafficPermitted="true">
+                    <trust-anchors><certificates src="system"/></trust-anchors>
+                </base-config>
+                <domain-config>
+                    <domain includeSubdomains="true">legacy.example.com</domain>
+                </domain-config>
+                <domain-config cleartextTrafficPermitted="false">
+                    <domain>api.example.com</domain>
+                    <trust-anchors><certificates src="@raw/company_ca"/></trust-anchors>
+                </domain-config>
+            </network-security-config>"#,
+        );
+
+        assert_eq!(config.base_cleartext_permitted, Some(true));
+        let legacy = &config.domain_configs[0];
+        assert_eq!(legacy.domains[0].name, "legacy.example.com");
+        assert!(legacy.domains[0].include_subdomains);
+        assert_eq!(legacy.cleartext_traffic_permitted, Some(true));
+        assert_eq!(anchor_sources(&legacy.trust_anchors), vec!["system"]);
+
+        let api = &config.domain_configs[1];
+        assert_eq!(api.cleartext_traffic_permitted, Some(false));
+        assert_eq!(anchor_sources(&api.trust_anchors), vec!["@raw/company_ca"]);
+        assert_eq!(config.cleartext_domains, vec!["legacy.example.com"]);
+    }
+
+    #[test]
+    fn nested_domain_configs_inherit_parent() {
+        let config = parse_config(
+            r#"<network-security-config>
+                <domain-config cleartextTrafficPermitted="true">
+                    <domain>example.com</domain>
+                    <trust-anchors><certificates src="user"/></trust-anchors>
+                    <pin-set expiration="2027-01-01">
+                        <pin digest="SHA-256">7HIpactkIAq2Y49orFOOQKurWxmmSFZhBCoQYcRhJ3Y=</pin>
+                    </pin-set>
+                    <domain-config>
+                        <domain>cdn.example.com</domain>
+                    </domain-config>
+                    <domain-config cleartextTrafficPermitted="false">
+                        <domain>secure.example.com</domain>
+                        <pin-set><pin digest="SHA-256">fwza0LRMXouZHRC8Ei+4PyuldPDcf3UKgO/04cDM1oE=</pin></pin-set>
+                    </domain-config>
+                </domain-config>
+            </network-security-config>"#,
+        );
+
+        assert_eq!(config.domain_configs.len(), 3);
+        let cdn = &config.domain_configs[1];
+        assert_eq!(cdn.domains[0].name, "cdn.example.com");
+        assert_eq!(cdn.cleartext_traffic_permitted, Some(true));
+        assert_eq!(anchor_sources(&cdn.trust_anchors), vec!["user"]);
+        assert_eq!(cdn.pins.len(), 1);
+        assert_eq!(cdn.pin_expiration.as_deref(), Some("2027-01-01"));
+
+        // 子配置声明了自己的 pin-set，不继承父级的过期时间
+        let secure = &config.domain_configs[2];
+        assert_eq!(secure.cleartext_traffic_permitted, Some(false));
+        assert_eq!(secure.pins[0].value, "fwza0LRMXouZHRC8Ei+4PyuldPDcf3UKgO/04cDM1oE=");
+        assert_eq!(secure.pin_expiration, None);
+
+        assert_eq!(config.cleartext_domains, vec!["example.com", "cdn.example.com"]);
+    }
+
+    #[test]
+    fn override_pins_weakens_pinning() {
+        let config = parse_config(
+            r#"<network-security-config>
+                <domain-config>
+                    <domain>pay.example.com</domain>
+                    <trust-anchors><certificates src="user" overridePins="true"/></trust-anchors>
+                    <pin-set expiration="2026-06-30"><pin digest="SHA-256">abc=</pin></pin-set>
+                </domain-config>
+            </network-security-config>"#,
+        );
+        let domain = &config.domain_configs[0];
+        assert!(domain.trust_anchors[0].override_pins);
+        assert_eq!(domain.pin_expiration.as_deref(), Some("2026-06-30"));
+        assert_eq!(domain.pins[0].digest, "SHA-256");
+
+        let warnings = warnings_of(&config);
+        assert!(warnings.iter().any(|w| w.contains("overridePins")), "{:?}", warnings);
+        assert!(warnings.iter().any(|w| w.contains("用户安装的 CA")), "{:?}", warnings);
+    }
+
+    #[test]
+    fn user_ca_only_in_debug_overrides_is_not_reported() {
+        let config = parse_config(
+            r#"<network-security-config>
+                <base-config>
+                    <trust-anchors><certificates src="system"/></trust-anchors>
+                </base-config>
+                <debug-overrides>
+                    <trust-anchors><certificates src="user"/></trust-anchors>
+                </debug-overrides>
+            </network-security-config>"#,
+        );
+        assert_eq!(anchor_sources(&config.debug_overrides), vec!["user"]);
+        assert!(warnings_of(&config).is_empty());
+    }
+
+    #[test]
+    fn user_ca_in_base_config_is_reported() {
+        let config = parse_config(
+            r#"<network-security-config>
+                <base-config>
+                    <trust-anchors><certificates src="system"/><certificates src="user"/></trust-anchors>
+                </base-config>
+            </network-security-config>"#,
+        );
+        let warnings = warnings_of(&config);
+        assert_eq!(warnings.len(), 1, "{:?}", warnings);
+        assert!(warnings[0].contains("用户安装的 CA"));
+    }
+}
@@ -0,0 +1,458 @@
+// ==================== 权限分类数据库 ====================
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use rusty_axml::parser::Axml;
+use serde::{Deserialize, Serialize};
+
+use crate::error::AnalysisError;
+use crate::manifest::{self, get_attr};
+
+/// 权限保护级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProtectionLevel {
+    Normal,
+    Dangerous,
+    Signature,
+    Appop,
+    Unknown,
+}
+
+/// 内置权限定义
+pub struct PermissionDef {
+    pub name: &'static str,
+    pub level: ProtectionLevel,
+    pub group: Option<&'static str>,
+    pub added_in: u32,
+    pub deprecated_in: Option<u32>,
+    pub description_zh: &'static str,
+    pub description_en: &'static str,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PermissionDetail {
+    pub name: String,
+    pub protection_level: ProtectionLevel,
+    pub group: Option<String>,
+    pub added_in: Option<u32>,
+    pub deprecated_in: Option<u32>,
+    pub description_zh: String,
+    pub description_en: String,
+    /// 应用自身通过 <permission> 声明的自定义权限
+    pub is_custom: bool,
+    /// 是否属于需要隐私审核关注的敏感权限
+    pub sensitive: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PermissionReport {
+    pub package_name: String,
+    pub requested: Vec<PermissionDetail>,
+    pub declared: Vec<PermissionDetail>,
+    pub sensitive: Vec<PermissionDetail>,
+    pub count_by_level: HashMap<ProtectionLevel, usize>,
+}
+
+use ProtectionLevel::{Appop, Dangerous, Normal, Signature};
+
+const fn def(
+    name: &'static str,
+    level: ProtectionLevel,
+    group: Option<&'static str>,
+    added_in: u32,
+    deprecated_in: Option<u32>,
+    description_zh: &'static str,
+    description_en: &'static str,
+) -> PermissionDef {
+    PermissionDef { name, level, group, added_in, deprecated_in, description_zh, description_en }
+}
+
+const CALENDAR: Option<&str> = Some("android.permission-group.CALENDAR");
+const CAMERA: Option<&str> = Some("android.permission-group.CAMERA");
+const CONTACTS: Option<&str> = Some("android.permission-group.CONTACTS");
+const LOCATION: Option<&str> = Some("android.permission-group.LOCATION");
+const MICROPHONE: Option<&str> = Some("android.permission-group.MICROPHONE");
+const PHONE: Option<&str> = Some("android.permission-group.PHONE");
+const CALL_LOG: Option<&str> = Some("android.permission-group.CALL_LOG");
+const SENSORS: Option<&str> = Some("android.permission-group.SENSORS");
+const ACTIVITY_RECOGNITION: Option<&str> = Some("android.permission-group.ACTIVITY_RECOGNITION");
+const SMS: Option<&str> = Some("android.permission-group.SMS");
+const STORAGE: Option<&str> = Some("android.permission-group.STORAGE");
+const READ_MEDIA_VISUAL: Option<&str> = Some("android.permission-group.READ_MEDIA_VISUAL");
+const READ_MEDIA_AURAL: Option<&str> = Some("android.permission-group.READ_MEDIA_AURAL");
+const NEARBY_DEVICES: Option<&str> = Some("android.permission-group.NEARBY_DEVICES");
+const NOTIFICATIONS: Option<&str> = Some("android.permission-group.NOTIFICATIONS");
+
+/// Android 框架权限表（按权限组大致排序）
+pub const PERMISSIONS: &[PermissionDef] = &[
+    // 日历
+    def("android.permission.READ_CALENDAR", Dangerous, CALENDAR, 1, None, "读取日历事件", "Read calendar events"),
+    def("android.permission.WRITE_CALENDAR", Dangerous, CALENDAR, 1, None, "添加或修改日历事件", "Add or modify calendar events"),
+    // 相机
+    def("android.permission.CAMERA", Dangerous, CAMERA, 1, None, "使用相机拍摄照片和视频", "Take pictures and videos"),
+    // 通讯录
+    def("android.permission.READ_CONTACTS", Dangerous, CONTACTS, 1, None, "读取通讯录", "Read contacts"),
+    def("android.permission.WRITE_CONTACTS", Dangerous, CONTACTS, 1, None, "修改通讯录", "Modify contacts"),
+    def("android.permission.GET_ACCOUNTS", Dangerous, CONTACTS, 1, None, "获取设备上的账号列表", "Find accounts on the device"),
+    // 位置
+    def("android.permission.ACCESS_FINE_LOCATION", Dangerous, LOCATION, 1, None, "获取精确位置（GPS）", "Access precise location"),
+    def("android.permission.ACCESS_COARSE_LOCATION", Dangerous, LOCATION, 1, None, "获取大致位置（网络）", "Access approximate location"),
+    def("android.permission.ACCESS_BACKGROUND_LOCATION", Dangerous, LOCATION, 29, None, "在后台获取位置", "Access location in the background"),
+    def("android.permission.ACCESS_MEDIA_LOCATION", Dangerous, STORAGE, 29, None, "读取媒体文件中的位置信息", "Access locations in media files"),
+    def("android.permission.ACCESS_LOCATION_EXTRA_COMMANDS", Normal, None, 1, None, "访问额外的位置提供程序命令", "Access extra location provider commands"),
+    // 麦克风
+    def("android.permission.RECORD_AUDIO", Dangerous, MICROPHONE, 1, None, "录制音频", "Record audio"),
+    // 电话
+    def("android.permission.READ_PHONE_STATE", Dangerous, PHONE, 1, None, "读取手机状态和身份（含设备标识）", "Read phone status and identity"),
+    def("android.permission.READ_PHONE_NUMBERS", Dangerous, PHONE, 26, None, "读取本机电话号码", "Read phone numbers"),
+    def("android.permission.CALL_PHONE", Dangerous, PHONE, 1, None, "直接拨打电话", "Directly call phone numbers"),
+    def("android.permission.ANSWER_PHONE_CALLS", Dangerous, PHONE, 26, None, "接听来电", "Answer phone calls"),
+    def("android.permission.USE_SIP", Dangerous, PHONE, 9, None, "拨打/接听 SIP 网络电话", "Make and receive SIP calls"),
+    def("android.permission.ACCEPT_HANDOVER", Dangerous, PHONE, 28, None, "继续其他应用发起的通话", "Continue a call started in another app"),
+    def("com.android.voicemail.permission.ADD_VOICEMAIL", Dangerous, PHONE, 14, None, "添加语音邮件", "Add voicemail"),
+    def("android.permission.PROCESS_OUTGOING_CALLS", Dangerous, CALL_LOG, 1, Some(29), "监视、修改或重定向拨出电话", "Reroute outgoing calls"),
+    def("android.permission.READ_CALL_LOG", Dangerous, CALL_LOG, 16, None, "读取通话记录", "Read call log"),
+    def("android.permission.WRITE_CALL_LOG", Dangerous, CALL_LOG, 16, None, "修改通话记录", "Write call log"),
+    // 传感器与活动
+    def("android.permission.BODY_SENSORS", Dangerous, SENSORS, 20, None, "访问身体传感器（如心率）", "Access body sensors"),
+    def("android.permission.BODY_SENSORS_BACKGROUND", Dangerous, SENSORS, 33, None, "在后台访问身体传感器", "Access body sensors in the background"),
+    def("android.permission.ACTIVITY_RECOGNITION", Dangerous, ACTIVITY_RECOGNITION, 29, None, "识别身体活动（步数等）", "Recognize physical activity"),
+    def("android.permission.HIGH_SAMPLING_RATE_SENSORS", Normal, None, 31, None, "以高采样率访问传感器", "Access sensor data at a high sampling rate"),
+    // 短信
+    def("android.permission.SEND_SMS", Dangerous, SMS, 1, None, "发送短信", "Send SMS messages"),
+    def("android.permission.RECEIVE_SMS", Dangerous, SMS, 1, None, "接收短信", "Receive SMS messages"),
+    def("android.permission.READ_SMS", Dangerous, SMS, 1, None, "读取短信", "Read SMS messages"),
+    def("android.permission.RECEIVE_WAP_PUSH", Dangerous, SMS, 1, None, "接收 WAP 推送", "Receive WAP push messages"),
+    def("android.permission.RECEIVE_MMS", Dangerous, SMS, 1, None, "接收彩信", "Receive MMS messages"),
+    // 存储与媒体
+    def("android.permission.READ_EXTERNAL_STORAGE", Dangerous, STORAGE, 16, Some(33), "读取共享存储", "Read shared storage"),
+    def("android.permission.WRITE_EXTERNAL_STORAGE", Dangerous, STORAGE, 4, Some(30), "写入共享存储", "Modify shared storage"),
+    def("android.permission.READ_MEDIA_IMAGES", Dangerous, READ_MEDIA_VISUAL, 33, None, "读取图片", "Read images from shared storage"),
+    def("android.permission.READ_MEDIA_VIDEO", Dangerous, READ_MEDIA_VISUAL, 33, None, "读取视频", "Read videos from shared storage"),
+    def("android.permission.READ_MEDIA_VISUAL_USER_SELECTED", Dangerous, READ_MEDIA_VISUAL, 34, None, "读取用户选择的图片和视频", "Read user-selected images and videos"),
+    def("android.permission.READ_MEDIA_AUDIO", Dangerous, READ_MEDIA_AURAL, 33, None, "读取音频文件", "Read audio files from shared storage"),
+    def("android.permission.MANAGE_EXTERNAL_STORAGE", Appop, None, 30, None, "管理所有文件", "Manage all files on shared storage"),
+    // 附近设备
+    def("android.permission.BLUETOOTH_SCAN", Dangerous, NEARBY_DEVICES, 31, None, "扫描附近的蓝牙设备", "Discover nearby Bluetooth devices"),
+    def("android.permission.BLUETOOTH_CONNECT", Dangerous, NEARBY_DEVICES, 31, None, "连接已配对的蓝牙设备", "Connect to paired Bluetooth devices"),
+    def("android.permission.BLUETOOTH_ADVERTISE", Dangerous, NEARBY_DEVICES, 31, None, "向附近的蓝牙设备广播", "Advertise to nearby Bluetooth devices"),
+    def("android.permission.NEARBY_WIFI_DEVICES", Dangerous, NEARBY_DEVICES, 33, None, "发现并连接附近的 Wi-Fi 设备", "Find and connect to nearby Wi-Fi devices"),
+    def("android.permission.UWB_RANGING", Dangerous, NEARBY_DEVICES, 31, None, "测量与附近超宽带设备的距离", "Determine distance to nearby UWB devices"),
+    def("android.permission.BLUETOOTH", Normal, None, 1, Some(31), "连接蓝牙设备", "Pair with Bluetooth devices"),
+    def("android.permission.BLUETOOTH_ADMIN", Normal, None, 1, Some(31), "发现和配对蓝牙设备", "Discover and pair Bluetooth devices"),
+    // 通知
+    def("android.permission.POST_NOTIFICATIONS", Dangerous, NOTIFICATIONS, 33, None, "发送通知", "Post notifications"),
+    // 网络
+    def("android.permission.INTERNET", Normal, None, 1, None, "访问网络", "Full network access"),
+    def("android.permission.ACCESS_NETWORK_STATE", Normal, None, 1, None, "查看网络连接状态", "View network connections"),
+    def("android.permission.CHANGE_NETWORK_STATE", Normal, None, 1, None, "更改网络连接", "Change network connectivity"),
+    def("android.permission.ACCESS_WIFI_STATE", Normal, None, 1, None, "查看 Wi-Fi 连接", "View Wi-Fi connections"),
+    def("android.permission.CHANGE_WIFI_STATE", Normal, None, 1, None, "连接和断开 Wi-Fi", "Connect and disconnect from Wi-Fi"),
+    def("android.permission.CHANGE_WIFI_MULTICAST_STATE", Normal, None, 4, None, "接收 Wi-Fi 多播", "Allow Wi-Fi multicast reception"),
+    def("android.permission.NFC", Normal, None, 9, None, "使用 NFC", "Control Near Field Communication"),
+    // 系统与后台
+    def("android.permission.VIBRATE", Normal, None, 1, None, "控制振动", "Control vibration"),
+    def("android.permission.WAKE_LOCK", Normal, None, 1, None, "防止设备休眠", "Prevent device from sleeping"),
+    def("android.permission.RECEIVE_BOOT_COMPLETED", Normal, None, 1, None, "开机自启动", "Run at startup"),
+    def("android.permission.FOREGROUND_SERVICE", Normal, None, 28, None, "运行前台服务", "Run foreground service"),
+    def("android.permission.FOREGROUND_SERVICE_CAMERA", Normal, None, 34, None, "运行相机类前台服务", "Run camera foreground service"),
+    def("android.permission.FOREGROUND_SERVICE_CONNECTED_DEVICE", Normal, None, 34, None, "运行设备连接类前台服务", "Run connected device foreground service"),
+    def("android.permission.FOREGROUND_SERVICE_DATA_SYNC", Normal, None, 34, None, "运行数据同步类前台服务", "Run data sync foreground service"),
+    def("android.permission.FOREGROUND_SERVICE_HEALTH", Normal, None, 34, None, "运行健康类前台服务", "Run health foreground service"),
+    def("android.permission.FOREGROUND_SERVICE_LOCATION", Normal, None, 34, None, "运行位置类前台服务", "Run location foreground service"),
+    def("android.permission.FOREGROUND_SERVICE_MEDIA_PLAYBACK", Normal, None, 34, None, "运行媒体播放类前台服务", "Run media playback foreground service"),
+    def("android.permission.FOREGROUND_SERVICE_MEDIA_PROJECTION", Normal, None, 34, None, "运行屏幕投射类前台服务", "Run media projection foreground service"),
+    def("android.permission.FOREGROUND_SERVICE_MICROPHONE", Normal, None, 34, None, "运行麦克风类前台服务", "Run microphone foreground service"),
+    def("android.permission.FOREGROUND_SERVICE_PHONE_CALL", Normal, None, 34, None, "运行通话类前台服务", "Run phone call foreground service"),
+    def("android.permission.FOREGROUND_SERVICE_REMOTE_MESSAGING", Normal, None, 34, None, "运行远程消息类前台服务", "Run remote messaging foreground service"),
+    def("android.permission.FOREGROUND_SERVICE_SPECIAL_USE", Normal, None, 34, None, "运行特殊用途前台服务", "Run special use foreground service"),
+    def("android.permission.FOREGROUND_SERVICE_SYSTEM_EXEMPTED", Normal, None, 34, None, "运行系统豁免前台服务", "Run system exempted foreground service"),
+    def("android.permission.REQUEST_IGNORE_BATTERY_OPTIMIZATIONS", Normal, None, 23, None, "请求忽略电池优化", "Ask to ignore battery optimizations"),
+    def("android.permission.SCHEDULE_EXACT_ALARM", Appop, None, 31, None, "设置精确闹钟", "Schedule exact alarms"),
+    def("android.permission.USE_EXACT_ALARM", Normal, None, 33, None, "使用精确闹钟（闹钟/日历类应用）", "Use exact alarms"),
+    def("android.permission.USE_FULL_SCREEN_INTENT", Appop, None, 29, None, "显示全屏通知", "Show full screen notifications"),
+    def("android.permission.SYSTEM_ALERT_WINDOW", Appop, None, 1, None, "显示在其他应用上层（悬浮窗）", "Display over other apps"),
+    def("android.permission.WRITE_SETTINGS", Appop, None, 1, None, "修改系统设置", "Modify system settings"),
+    def("android.permission.PACKAGE_USAGE_STATS", Appop, None, 21, None, "读取应用使用情况", "Access app usage data"),
+    def("android.permission.REQUEST_INSTALL_PACKAGES", Appop, None, 26, None, "请求安装应用", "Request install packages"),
+    def("android.permission.REQUEST_DELETE_PACKAGES", Normal, None, 26, None, "请求卸载应用", "Request delete packages"),
+    def("android.permission.QUERY_ALL_PACKAGES", Normal, None, 30, None, "查询设备上安装的所有应用", "Query all installed packages"),
+    def("android.permission.GET_TASKS", Normal, None, 1, Some(21), "获取正在运行的应用", "Retrieve running apps"),
+    def("android.permission.KILL_BACKGROUND_PROCESSES", Normal, None, 8, None, "结束其他应用的后台进程", "Close other apps"),
+    def("android.permission.REORDER_TASKS", Normal, None, 1, None, "调整正在运行的应用顺序", "Reorder running apps"),
+    def("android.permission.EXPAND_STATUS_BAR", Normal, None, 1, None, "展开/收起状态栏", "Expand or collapse status bar"),
+    def("android.permission.DISABLE_KEYGUARD", Normal, None, 1, None, "停用屏幕锁定", "Disable your screen lock"),
+    def("android.permission.MODIFY_AUDIO_SETTINGS", Normal, None, 1, None, "修改音频设置", "Change audio settings"),
+    def("android.permission.SET_WALLPAPER", Normal, None, 1, None, "设置壁纸", "Set wallpaper"),
+    def("android.permission.ACCESS_NOTIFICATION_POLICY", Normal, None, 23, None, "访问勿扰模式", "Access Do Not Disturb"),
+    def("android.permission.READ_SYNC_SETTINGS", Normal, None, 1, None, "读取同步设置", "Read sync settings"),
+    def("android.permission.WRITE_SYNC_SETTINGS", Normal, None, 1, None, "修改同步设置", "Toggle sync on and off"),
+    def("android.permission.USE_BIOMETRIC", Normal, None, 28, None, "使用生物识别硬件", "Use biometric hardware"),
+    def("android.permission.USE_FINGERPRINT", Normal, None, 23, Some(28), "使用指纹硬件", "Use fingerprint hardware"),
+    def("android.permission.DETECT_SCREEN_CAPTURE", Normal, None, 34, None, "检测屏幕截图", "Detect screen captures"),
+    def("com.android.alarm.permission.SET_ALARM", Normal, None, 9, None, "设置闹钟", "Set an alarm"),
+    def("com.android.launcher.permission.INSTALL_SHORTCUT", Normal, None, 1, None, "创建桌面快捷方式", "Install shortcuts"),
+    def("com.google.android.gms.permission.AD_ID", Normal, None, 1, None, "读取广告 ID", "Access advertising ID"),
+    // 仅系统或同签名应用可获得
+    def("android.permission.BIND_ACCESSIBILITY_SERVICE", Signature, None, 16, None, "绑定无障碍服务", "Bind to an accessibility service"),
+    def("android.permission.BIND_NOTIFICATION_LISTENER_SERVICE", Signature, None, 18, None, "绑定通知监听服务", "Bind to a notification listener service"),
+    def("android.permission.BIND_DEVICE_ADMIN", Signature, None, 8, None, "绑定设备管理器", "Bind to a device administrator"),
+    def("android.permission.BIND_VPN_SERVICE", Signature, None, 14, None, "绑定 VPN 服务", "Bind to a VPN service"),
+    def("android.permission.BIND_INPUT_METHOD", Signature, None, 3, None, "绑定输入法", "Bind to an input method"),
+    def("android.permission.READ_PRIVILEGED_PHONE_STATE", Signature, None, 29, None, "读取特权电话状态（IMEI 等）", "Read privileged phone state"),
+    def("android.permission.INSTALL_PACKAGES", Signature, None, 1, None, "直接安装应用", "Directly install applications"),
+    def("android.permission.WRITE_SECURE_SETTINGS", Signature, None, 3, None, "修改安全系统设置", "Modify secure system settings"),
+    def("android.permission.READ_LOGS", Signature, None, 1, None, "读取系统日志", "Read sensitive log data"),
+    def("android.permission.CHANGE_CONFIGURATION", Signature, None, 1, None, "修改系统显示设置", "Change system display settings"),
+    def("android.permission.MOUNT_UNMOUNT_FILESYSTEMS", Signature, None, 1, None, "挂载/卸载文件系统", "Mount and unmount filesystems"),
+];
+
+// 非 dangerous 但受商店政策限制、需要在隐私审核中说明的权限
+const POLICY_RESTRICTED: &[&str] = &[
+    "android.permission.QUERY_ALL_PACKAGES",
+    "android.permission.REQUEST_INSTALL_PACKAGES",
+    "android.permission.USE_EXACT_ALARM",
+    "com.google.android.gms.permission.AD_ID",
+];
+
+/// 按名称查找内置权限定义
+pub fn lookup(name: &str) -> Option<&'static PermissionDef> {
+    PERMISSIONS.iter().find(|p| p.name == name)
+}
+
+impl PermissionDetail {
+    fn from_def(def: &PermissionDef) -> Self {
+        PermissionDetail {
+            name: def.name.to_string(),
+            protection_level: def.level,
+            group: def.group.map(|g| g.to_string()),
+            added_in: Some(def.added_in),
+            deprecated_in: def.deprecated_in,
+            description_zh: def.description_zh.to_string(),
+            description_en: def.description_en.to_string(),
+            is_custom: false,
+            sensitive: is_sensitive(def.name, def.level),
+        }
+    }
+
+    fn unknown(name: &str) -> Self {
+        PermissionDetail {
+            name: name.to_string(),
+            protection_level: ProtectionLevel::Unknown,
+            group: None,
+            added_in: None,
+            deprecated_in: None,
+            description_zh: "未收录的权限（可能来自第三方 SDK 或厂商系统）".to_string(),
+            description_en: "Unrecognized permission (third-party SDK or vendor)".to_string(),
+            is_custom: false,
+            sensitive: false,
+        }
+    }
+}
+
+fn is_sensitive(name: &str, level: ProtectionLevel) -> bool {
+    matches!(level, ProtectionLevel::Dangerous | ProtectionLevel::Appop) || POLICY_RESTRICTED.contains(&name)
+}
+
+// 将 <permission android:protectionLevel> 的数值转换为保护级别
+// 低 4 位为基础级别：0 normal, 1 dangerous, 2 signature, 3 signatureOrSystem；appop 标志位为 0x40
+fn protection_level_from_flags(value: &str) -> ProtectionLevel {
+    let flags = match value {
+        "normal" => return ProtectionLevel::Normal,
+        "dangerous" => return ProtectionLevel::Dangerous,
+        "signature" | "signatureOrSystem" => return ProtectionLevel::Signature,
+        _ => match value.parse::<u32>() {
+            Ok(flags) => flags,
+            Err(_) => return ProtectionLevel::Unknown,
+        },
+    };
+    if flags & 0x40 != 0 {
+        return ProtectionLevel::Appop;
+    }
+    match flags & 0xf {
+        0 => ProtectionLevel::Normal,
+        1 => ProtectionLevel::Dangerous,
+        2 | 3 => ProtectionLevel::Signature,
+        _ => ProtectionLevel::Unknown,
+    }
+}
+
+/// 收集应用通过 <permission> 声明的自定义权限
+pub fn declared_permissions(axml: &Axml) -> Vec<PermissionDetail> {
+    rusty_axml::find_nodes_by_type(axml, "permission")
+        .iter()
+        .filter_map(|node| {
+            let elem = node.borrow();
+            let name = get_attr(&elem, "name")?;
+            // 未声明 protectionLevel 时默认为 normal
+            let level = get_attr(&elem, "protectionLevel")
+                .map(|v| protection_level_from_flags(&v))
+                .unwrap_or(ProtectionLevel::Normal);
+            Some(PermissionDetail {
+                group: get_attr(&elem, "permissionGroup"),
+                added_in: None,
+                deprecated_in: None,
+                description_zh: "应用自定义权限".to_string(),
+                description_en: "Custom permission declared by the app".to_string(),
+                is_custom: true,
+                sensitive: level == ProtectionLevel::Dangerous,
+                protection_level: level,
+                name,
+            })
+        })
+        .collect()
+}
+
+/// 收集 uses-permission 与 uses-permission-sdk-23 中请求的权限
+pub fn requested_permissions(axml: &Axml) -> Vec<String> {
+    let mut names = Vec::new();
+    for element_type in ["uses-permission", "uses-permission-sdk-23"] {
+        for node in rusty_axml::find_nodes_by_type(axml, element_type) {
+            if let Some(name) = get_attr(&node.borrow(), "name") {
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+    }
+    names.sort();
+    names
+}
+
+/// 按名称获取权限详情，未收录的权限标记为未知
+pub fn describe(name: &str) -> PermissionDetail {
+    match lookup(name) {
+        Some(def) => PermissionDetail::from_def(def),
+        None => PermissionDetail::unknown(name),
+    }
+}
+
+/// 对 manifest 中的权限进行分类
+pub fn classify(axml: &Axml) -> PermissionReport {
+    let package_name = get_attr(&axml.root().borrow(), "package").unwrap_or_default();
+    let declared = declared_permissions(axml);
+
+    let requested: Vec<PermissionDetail> = requested_permissions(axml)
+        .iter()
+        .map(|name| match lookup(name) {
+            Some(def) => PermissionDetail::from_def(def),
+            None => match declared.iter().find(|d| &d.name == name) {
+                Some(custom) => custom.clone(),
+                None => PermissionDetail::unknown(name),
+            },
+        })
+        .collect();
+
+    let sensitive = requested.iter().filter(|p| p.sensitive).cloned().collect();
+
+    let mut count_by_level = HashMap::new();
+    for permission in &requested {
+        *count_by_level.entry(permission.protection_level).or_insert(0) += 1;
+    }
+
+    PermissionReport {
+        package_name,
+        requested,
+        declared,
+        sensitive,
+        count_by_level,
+    }
+}
+
+/// 读取 APK 并对权限进行分类
+pub fn classify_apk_permissions(path: &Path) -> Result<PermissionReport, AnalysisError> {
+    if !path.exists() {
+        return Err(AnalysisError::NotFound);
+    }
+
+    let axml = manifest::load_manifest(path)?;
+    Ok(classify(&axml))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn protection_level_from_named_and_numeric_flags() {
+        assert_eq!(protection_level_from_flags("normal"), ProtectionLevel::Normal);
+        assert_eq!(protection_level_from_flags("dangerous"), ProtectionLevel::Dangerous);
+        assert_eq!(protection_level_from_flags("signatureOrSystem"), ProtectionLevel::Signature);
+        assert_eq!(protection_level_from_flags("0"), ProtectionLevel::Normal);
+        assert_eq!(protection_level_from_flags("1"), ProtectionLevel::Dangerous);
+        assert_eq!(protection_level_from_flags("2"), ProtectionLevel::Signature);
+        assert_eq!(protection_level_from_flags("3"), ProtectionLevel::Signature);
+        // signature|privileged (0x12) 仍按低 4 位归为 signature
+        assert_eq!(protection_level_from_flags("18"), ProtectionLevel::Signature);
+        assert_eq!(protection_level_from_flags("4"), ProtectionLevel::Unknown);
+        assert_eq!(protection_level_from_flags("abc"), ProtectionLevel::Unknown);
+    }
+
+    #[test]
+    fn appop_bit_overrides_base_level() {
+        assert_eq!(protection_level_from_flags("64"), ProtectionLevel::Appop);
+        // signature|appop (0x42)
+        assert_eq!(protection_level_from_flags("66"), ProtectionLevel::Appop);
+    }
+
+    #[test]
+    fn describe_known_permissions() {
+        let internet = describe("android.permission.INTERNET");
+        assert_eq!(internet.protection_level, ProtectionLevel::Normal);
+        assert!(!internet.sensitive);
+
+        let location = describe("android.permission.ACCESS_FINE_LOCATION");
+        assert_eq!(location.protection_level, ProtectionLevel::Dangerous);
+        assert!(location.sensitive);
+
+        let overlay = describe("android.permission.SYSTEM_ALERT_WINDOW");
+        assert_eq!(overlay.protection_level, ProtectionLevel::Appop);
+        assert!(overlay.sensitive);
+
+        // normal 级别但受商店政策限制
+        let query = describe("android.permission.QUERY_ALL_PACKAGES");
+        assert_eq!(query.protection_level, ProtectionLevel::Normal);
+        assert!(query.sensitive);
+    }
+
+    #[test]
+    fn describe_unknown_permission() {
+        let detail = describe("com.vendor.permission.PUSH");
+        assert_eq!(detail.name, "com.vendor.permission.PUSH");
+        assert_eq!(detail.protection_level, ProtectionLevel::Unknown);
+        assert_eq!(detail.group, None);
+        assert_eq!(detail.added_in, None);
+        assert_eq!(detail.description_zh, "未收录的权限（可能来自第三方 SDK 或厂商系统）");
+        assert!(!detail.is_custom);
+        assert!(!detail.sensitive);
+    }
+
+    #[test]
+    fn classify_prefers_declared_custom_permissions() {
+        let xml = r#"<manifest xmlns:android="http://schemas.android.com/apk/res/android" package="com.example">
+            <permission android:name="com.example.permission.C2D" android:protectionLevel="signature"/>
+            <uses-permission android:name="com.example.permission.C2D"/>
+            <uses-permission android:name="com.vendor.permission.PUSH"/>
+            <uses-permission-sdk-23 android:name="android.permission.CAMERA"/>
+        </manifest>"#;
+        let data = crate::axml::encode(&crate::axml::document_from_xml(xml));
+        let axml = rusty_axml::parse_from_reader(std::io::Cursor::new(data)).unwrap();
+        let report = classify(&axml);
+
+        assert_eq!(report.package_name, "com.example");
+        assert_eq!(report.declared.len(), 1);
+        let names: Vec<&str> = report.requested.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["android.permission.CAMERA", "com.example.permission.C2D", "com.vendor.permission.PUSH"]);
+
+        let custom = &report.requested[1];
+        assert!(custom.is_custom);
+        assert_eq!(custom.protection_level, ProtectionLevel::Signature);
+        assert_eq!(report.requested[2].protection_level, ProtectionLevel::Unknown);
+
+        let sensitive: Vec<&str> = report.sensitive.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(sensitive, vec!["android.permission.CAMERA"]);
+        assert_eq!(report.count_by_level.get(&ProtectionLevel::Dangerous), Some(&1));
+    }
+}
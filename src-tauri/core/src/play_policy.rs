@@ -0,0 +1,569 @@
+// ==================== Google Play 上架政策检查 ====================
+// 规则放在 rules/play-policy.json 中，随政策更新修改数据文件即可，
+// 也可以传入新的规则文件覆盖内置版本。检查基于 PackageInfo 和 APK 中的 manifest、原生库
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use zip::ZipArchive;
+
+use crate::apk::PackageInfo;
+use crate::axml::{self, Document, Element};
+use crate::elf::ElfFile;
+use crate::error::AnalysisError;
+use crate::manifest::read_entry;
+use crate::zipalign;
+
+const BUILTIN_RULES: &str = include_str!("../rules/play-policy.json");
+
+// 前台服务类型：(名称, foregroundServiceType 标志位, 需要的权限)
+const FOREGROUND_SERVICE_TYPES: &[(&str, u32, &str)] = &[
+    ("dataSync", 0x1, "android.permission.FOREGROUND_SERVICE_DATA_SYNC"),
+    ("mediaPlayback", 0x2, "android.permission.FOREGROUND_SERVICE_MEDIA_PLAYBACK"),
+    ("phoneCall", 0x4, "android.permission.FOREGROUND_SERVICE_PHONE_CALL"),
+    ("location", 0x8, "android.permission.FOREGROUND_SERVICE_LOCATION"),
+    ("connectedDevice", 0x10, "android.permission.FOREGROUND_SERVICE_CONNECTED_DEVICE"),
+    ("mediaProjection", 0x20, "android.permission.FOREGROUND_SERVICE_MEDIA_PROJECTION"),
+    ("camera", 0x40, "android.permission.FOREGROUND_SERVICE_CAMERA"),
+    ("microphone", 0x80, "android.permission.FOREGROUND_SERVICE_MICROPHONE"),
+    ("health", 0x100, "android.permission.FOREGROUND_SERVICE_HEALTH"),
+    ("remoteMessaging", 0x200, "android.permission.FOREGROUND_SERVICE_REMOTE_MESSAGING"),
+    ("systemExempted", 0x400, "android.permission.FOREGROUND_SERVICE_SYSTEM_EXEMPTED"),
+    ("shortService", 0x800, ""),
+    ("mediaProcessing", 0x2000, "android.permission.FOREGROUND_SERVICE_MEDIA_PROCESSING"),
+    ("specialUse", 0x4000_0000, "android.permission.FOREGROUND_SERVICE_SPECIAL_USE"),
+];
+const FOREGROUND_SERVICE_PERMISSION: &str = "android.permission.FOREGROUND_SERVICE";
+
+/// 规则的检查方式和参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "check", rename_all = "snake_case")]
+pub enum RuleCheck {
+    TargetSdk {
+        min_target_sdk: u32,
+    },
+    /// 存在 32 位原生库时必须提供对应的 64 位版本
+    #[serde(rename = "native_64bit")]
+    Native64bit {
+        abi_pairs: Vec<(String, String)>,
+    },
+    /// 原生库 LOAD 段按页对齐，未压缩存储的 .so 在 ZIP 中也要按页对齐
+    PageAlignment {
+        page_size: u64,
+        min_target_sdk: u32,
+        abis: Vec<String>,
+    },
+    ForegroundServiceTypes {
+        min_target_sdk: u32,
+        declaration: String,
+    },
+    /// 需要在 Play 管理中心提交声明的敏感权限
+    RestrictedPermissions {
+        permissions: Vec<String>,
+        declaration: String,
+    },
+    ExportedFlag {
+        min_target_sdk: u32,
+    },
+}
+
+/// 提交类型：新应用首次上架或已上架应用的更新
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReleaseType {
+    New,
+    #[default]
+    Update,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyRule {
+    pub id: String,
+    pub title: String,
+    #[serde(flatten)]
+    pub check: RuleCheck,
+    /// 只适用于新应用或应用更新，未设置时两者都适用
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub applies_to: Option<ReleaseType>,
+    /// 政策生效日期
+    pub effective_date: Option<String>,
+    pub reference: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyRules {
+    pub version: String,
+    pub updated: String,
+    pub rules: Vec<PolicyRule>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleStatus {
+    Pass,
+    Fail,
+    /// 不违反规则，但需要在 Play 管理中心提交声明
+    Review,
+    NotApplicable,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RuleResult {
+    pub id: String,
+    pub title: String,
+    pub status: RuleStatus,
+    pub detail: String,
+    /// 涉及的权限、组件或原生库
+    pub items: Vec<String>,
+    pub effective_date: Option<String>,
+    pub reference: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PolicyReport {
+    pub package_name: String,
+    pub target_sdk_version: Option<u32>,
+    pub rules_version: String,
+    pub rules_updated: String,
+    /// 没有不通过的规则
+    pub passed: bool,
+    pub failed: usize,
+    pub review: usize,
+    pub results: Vec<RuleResult>,
+    pub warnings: Vec<String>,
+}
+
+/// 读取规则文件，未指定时使用内置规则
+pub fn load_rules(path: Option<&Path>) -> Result<PolicyRules, AnalysisError> {
+    let text = match path {
+        Some(path) => fs::read_to_string(path).map_err(|e| format!("无法读取规则文件: {}", e))?,
+        None => BUILTIN_RULES.to_string(),
+    };
+    serde_json::from_str(&text).map_err(|e| format!("规则文件格式错误: {}", e).into())
+}
+
+// APK 中检查需要的内容
+struct ApkFacts {
+    manifest: Document,
+    /// ABI -> 该目录下的 .so 文件名
+    native_libs: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl ApkFacts {
+    fn read(archive: &mut ZipArchive<File>) -> Result<Self, String> {
+        let manifest = axml::parse(&read_entry(archive, "AndroidManifest.xml")?)?;
+        let mut native_libs: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        for name in archive.file_names() {
+            let Some(rest) = name.strip_prefix("lib/") else { continue };
+            if let Some((abi, file)) = rest.split_once('/') {
+                if file.ends_with(".so") && !file.contains('/') {
+                    native_libs.entry(abi.to_string()).or_default().insert(file.to_string());
+                }
+            }
+        }
+        Ok(ApkFacts { manifest, native_libs })
+    }
+
+    fn application(&self) -> Option<&Element> {
+        self.manifest.root.children_named("application").next()
+    }
+}
+
+fn result(rule: &PolicyRule, status: RuleStatus, detail: String, items: Vec<String>) -> RuleResult {
+    RuleResult {
+        id: rule.id.clone(),
+        title: rule.title.clone(),
+        status,
+        detail,
+        items,
+        effective_date: rule.effective_date.clone(),
+        reference: rule.reference.clone(),
+    }
+}
+
+// ==================== 规则检查 ====================
+
+fn check_target_sdk(target_sdk: Option<u32>, min: u32) -> (RuleStatus, String) {
+    match target_sdk {
+        Some(sdk) if sdk >= min => (RuleStatus::Pass, format!("targetSdkVersion {} 满足要求（不低于 {}）", sdk, min)),
+        Some(sdk) => (RuleStatus::Fail, format!("targetSdkVersion 为 {}，要求不低于 {}", sdk, min)),
+        None => (RuleStatus::Fail, format!("未声明 targetSdkVersion，要求不低于 {}", min)),
+    }
+}
+
+fn check_64bit(facts: &ApkFacts, abi_pairs: &[(String, String)]) -> (RuleStatus, String, Vec<String>) {
+    let mut missing = Vec::new();
+    let mut checked = false;
+    for (abi32, abi64) in abi_pairs {
+        let Some(libs32) = facts.native_libs.get(abi32) else { continue };
+        checked = true;
+        let libs64 = facts.native_libs.get(abi64);
+        for lib in libs32 {
+            if !libs64.is_some_and(|libs| libs.contains(lib)) {
+                missing.push(format!("lib/{}/{}", abi64, lib));
+            }
+        }
+    }
+
+    if !checked {
+        (RuleStatus::NotApplicable, "未包含 32 位原生库".to_string(), Vec::new())
+    } else if missing.is_empty() {
+        (RuleStatus::Pass, "32 位原生库均有对应的 64 位版本".to_string(), Vec::new())
+    } else {
+        (RuleStatus::Fail, format!("缺少 {} 个 64 位原生库", missing.len()), missing)
+    }
+}
+
+fn check_page_alignment(
+    archive: &mut ZipArchive<File>,
+    path: &Path,
+    facts: &ApkFacts,
+    page_size: u64,
+    abis: &[String],
+) -> Result<(RuleStatus, String, Vec<String>), String> {
+    let libs: Vec<String> = abis
+        .iter()
+        .flat_map(|abi| {
+            facts.native_libs.get(abi).into_iter().flatten().map(move |lib| format!("lib/{}/{}", abi, lib))
+        })
+        .collect();
+    if libs.is_empty() {
+        return Ok((RuleStatus::NotApplicable, "未包含 64 位原生库".to_string(), Vec::new()));
+    }
+
+    let mut problems = Vec::new();
+    for name in &libs {
+        let mut data = Vec::new();
+        archive
+            .by_name(name)
+            .map_err(|e| format!("读取 {} 失败: {}", name, e))?
+            .read_to_end(&mut data)
+            .map_err(|e| format!("读取 {} 失败: {}", name, e))?;
+        match ElfFile::parse(&data).ok().and_then(|elf| elf.load_alignment()) {
+            Some(alignment) if alignment >= page_size => {}
+            Some(alignment) => problems.push(format!("{}: LOAD 段按 {} 字节对齐", name, alignment)),
+            None => problems.push(format!("{}: 无法读取 ELF 程序头", name)),
+        }
+    }
+
+    // 未压缩存储的 .so 直接从 APK 映射，ZIP 内偏移也要按页对齐
+    let report = zipalign::check_alignment(path, Some(page_size))?;
+    for entry in report.misaligned.iter().filter(|e| libs.contains(&e.name)) {
+        problems.push(format!("{}: 未按 {} 字节对齐存储（偏移 {}）", entry.name, page_size, entry.data_offset));
+    }
+
+    if problems.is_empty() {
+        Ok((RuleStatus::Pass, format!("{} 个 64 位原生库均支持 {} KB 页", libs.len(), page_size / 1024), Vec::new()))
+    } else {
+        Ok((RuleStatus::Fail, format!("{} 个原生库不支持 {} KB 页", problems.len(), page_size / 1024), problems))
+    }
+}
+
+fn check_foreground_services(
+    facts: &ApkFacts,
+    permissions: &BTreeSet<&str>,
+    declaration: &str,
+) -> (RuleStatus, String, Vec<String>) {
+    let services: Vec<&Element> = facts
+        .application()
+        .map(|app| app.children_named("service").collect())
+        .unwrap_or_default();
+
+    let mut declared = Vec::new();
+    let mut missing = Vec::new();
+    let mut untyped = Vec::new();
+    for service in &services {
+        let name = service.attr_value("name").unwrap_or_default();
+        let Some(attr) = service.attr("foregroundServiceType") else {
+            untyped.push(name);
+            continue;
+        };
+        let flags = attr.value.data;
+        for (type_name, flag, permission) in FOREGROUND_SERVICE_TYPES {
+            if flags & flag == 0 {
+                continue;
+            }
+            declared.push(format!("{}: {}", name, type_name));
+            if !permission.is_empty() && !permissions.contains(permission) {
+                missing.push(format!("{}: {} 类型缺少 {}", name, type_name, permission));
+            }
+        }
+    }
+
+    // 无法确定哪些服务会以前台方式启动，未声明类型的服务在持有 FOREGROUND_SERVICE 时都要列出，
+    // 以前台方式启动时会抛出 MissingForegroundServiceTypeException
+    let uses_foreground = permissions.contains(FOREGROUND_SERVICE_PERMISSION);
+    let untyped: Vec<String> = if uses_foreground {
+        untyped.into_iter().map(|name| format!("{}: 未声明 foregroundServiceType", name)).collect()
+    } else {
+        Vec::new()
+    };
+
+    if !missing.is_empty() {
+        missing.extend(untyped);
+        return (RuleStatus::Fail, "前台服务类型缺少对应的权限，启动服务时会抛出 SecurityException".to_string(), missing);
+    }
+    if declared.is_empty() && uses_foreground {
+        return (
+            RuleStatus::Fail,
+            "申请了 FOREGROUND_SERVICE 权限，但没有服务声明 foregroundServiceType".to_string(),
+            untyped,
+        );
+    }
+    if !declared.is_empty() {
+        let detail = if untyped.is_empty() {
+            declaration.to_string()
+        } else {
+            format!("{}；另有 {} 个服务未声明类型，如以前台方式启动会崩溃", declaration, untyped.len())
+        };
+        declared.extend(untyped);
+        return (RuleStatus::Review, detail, declared);
+    }
+    (RuleStatus::NotApplicable, "未使用前台服务".to_string(), Vec::new())
+}
+
+fn check_exported(facts: &ApkFacts) -> (RuleStatus, String, Vec<String>) {
+    let missing: Vec<String> = facts
+        .application()
+        .map(|app| {
+            app.child_elements()
+                .filter(|e| ["activity", "activity-alias", "service", "receiver"].contains(&e.name.as_str()))
+                .filter(|e| e.children_named("intent-filter").next().is_some() && e.attr("exported").is_none())
+                .map(|e| format!("{} {}", e.name, e.attr_value("name").unwrap_or_default()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if missing.is_empty() {
+        (RuleStatus::Pass, "带 intent-filter 的组件均声明了 android:exported".to_string(), Vec::new())
+    } else {
+        (RuleStatus::Fail, "带 intent-filter 的组件未声明 android:exported，无法安装".to_string(), missing)
+    }
+}
+
+/// 规则中适用于该提交类型的最低 targetSdkVersion，没有 target_sdk 规则时返回 None
+pub fn min_target_sdk(rules: &PolicyRules, release: ReleaseType) -> Option<u32> {
+    applicable_rules(rules, release)
+        .filter_map(|rule| match rule.check {
+            RuleCheck::TargetSdk { min_target_sdk } => Some(min_target_sdk),
+            _ => None,
+        })
+        .max()
+}
+
+// 适用于本次提交类型的规则
+fn applicable_rules(rules: &PolicyRules, release: ReleaseType) -> impl Iterator<Item = &PolicyRule> {
+    rules.rules.iter().filter(move |rule| rule.applies_to.is_none_or(|t| t == release))
+}
+
+/// 按规则检查 APK 是否满足 Google Play 上架要求，只检查适用于本次提交类型的规则
+pub fn check_policy(
+    info: &PackageInfo,
+    path: &Path,
+    rules: &PolicyRules,
+    release: ReleaseType,
+) -> Result<PolicyReport, AnalysisError> {
+    let file = File::open(path)?;
+    let mut archive = ZipArchive::new(file)?;
+    let facts = ApkFacts::read(&mut archive).map_err(|e| format!("仅支持 APK 文件: {}", e))?;
+
+    let target_sdk = info.target_sdk_version.parse::<u32>().ok();
+    let permissions: BTreeSet<&str> = info.permissions.iter().map(String::as_str).collect();
+    let below = |min: u32| target_sdk.is_some_and(|sdk| sdk < min);
+
+    let mut results = Vec::new();
+    let mut warnings = Vec::new();
+    for rule in applicable_rules(rules, release) {
+        let (status, detail, items) = match &rule.check {
+            RuleCheck::TargetSdk { min_target_sdk } => {
+                let (status, detail) = check_target_sdk(target_sdk, *min_target_sdk);
+                (status, detail, Vec::new())
+            }
+            RuleCheck::Native64bit { abi_pairs } => check_64bit(&facts, abi_pairs),
+            RuleCheck::PageAlignment { min_target_sdk, .. } if below(*min_target_sdk) => (
+                RuleStatus::NotApplicable,
+                format!("targetSdkVersion 低于 {} 时不要求", min_target_sdk),
+                Vec::new(),
+            ),
+            RuleCheck::PageAlignment { page_size, abis, .. } => {
+                match check_page_alignment(&mut archive, path, &facts, *page_size, abis) {
+                    Ok(outcome) => outcome,
+                    Err(e) => {
+                        warnings.push(format!("{}: {}", rule.title, e));
+                        continue;
+                    }
+                }
+            }
+            RuleCheck::ForegroundServiceTypes { min_target_sdk, .. } if below(*min_target_sdk) => (
+                RuleStatus::NotApplicable,
+                format!("targetSdkVersion 低于 {} 时不要求", min_target_sdk),
+                Vec::new(),
+            ),
+            RuleCheck::ForegroundServiceTypes { declaration, .. } => {
+                check_foreground_services(&facts, &permissions, declaration)
+            }
+            RuleCheck::RestrictedPermissions { permissions: restricted, declaration } => {
+                let found: Vec<String> = restricted
+                    .iter()
+                    .filter(|p| permissions.contains(p.as_str()))
+                    .cloned()
+                    .collect();
+                if found.is_empty() {
+                    (RuleStatus::Pass, "未申请相关权限".to_string(), found)
+                } else {
+                    (RuleStatus::Review, declaration.clone(), found)
+                }
+            }
+            RuleCheck::ExportedFlag { min_target_sdk } if below(*min_target_sdk) => (
+                RuleStatus::NotApplicable,
+                format!("targetSdkVersion 低于 {} 时不要求", min_target_sdk),
+                Vec::new(),
+            ),
+            RuleCheck::ExportedFlag { .. } => check_exported(&facts),
+        };
+        results.push(result(rule, status, detail, items));
+    }
+
+    let failed = results.iter().filter(|r| r.status == RuleStatus::Fail).count();
+    let review = results.iter().filter(|r| r.status == RuleStatus::Review).count();
+    Ok(PolicyReport {
+        package_name: info.package_name.clone(),
+        target_sdk_version: target_sdk,
+        rules_version: rules.version.clone(),
+        rules_updated: rules.updated.clone(),
+        passed: failed == 0,
+        failed,
+        review,
+        results,
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule_ids(rules: &PolicyRules, release: ReleaseType) -> Vec<&str> {
+        applicable_rules(rules, release).map(|r| r.id.as_str()).collect()
+    }
+
+    fn facts(xml: &str) -> ApkFacts {
+        ApkFacts {
+            manifest: axml::document_from_xml(xml),
+            native_libs: BTreeMap::new(),
+        }
+    }
+
+    // 文本 XML 中的 foregroundServiceType 按字符串保存，这里换成编译后的标志位
+    fn set_service_type(facts: &mut ApkFacts, service: &str, flags: u32) {
+        let application = facts.manifest.root.child_elements_mut().find(|e| e.name == "application").unwrap();
+        let service = application
+            .child_elements_mut()
+            .find(|e| e.attr_value("name").as_deref() == Some(service))
+            .unwrap();
+        let attr = service.attr_mut(Some(axml::ANDROID_NAMESPACE), "foregroundServiceType").unwrap();
+        attr.raw_value = None;
+        attr.value = crate::chunk::ResValue { data_type: crate::chunk::TYPE_INT_HEX, data: flags };
+    }
+
+    const SERVICES: &str = r#"<manifest package="com.example">
+        <application>
+            <service android:name=".PlayerService" android:foregroundServiceType="mediaPlayback"/>
+            <service android:name=".UploadService"/>
+        </application>
+    </manifest>"#;
+
+    #[test]
+    fn untyped_services_are_listed_next_to_typed_ones() {
+        let mut facts = facts(SERVICES);
+        set_service_type(&mut facts, ".PlayerService", 0x2);
+        let permissions = BTreeSet::from([
+            FOREGROUND_SERVICE_PERMISSION,
+            "android.permission.FOREGROUND_SERVICE_MEDIA_PLAYBACK",
+        ]);
+
+        let (status, detail, items) = check_foreground_services(&facts, &permissions, "声明");
+        assert_eq!(status, RuleStatus::Review);
+        assert!(detail.contains("1 个服务未声明类型"), "{}", detail);
+        assert_eq!(items, vec![".PlayerService: mediaPlayback", ".UploadService: 未声明 foregroundServiceType"]);
+    }
+
+    #[test]
+    fn missing_type_permission_fails() {
+        let mut facts = facts(SERVICES);
+        set_service_type(&mut facts, ".PlayerService", 0x2);
+        let permissions = BTreeSet::from([FOREGROUND_SERVICE_PERMISSION]);
+
+        let (status, _, items) = check_foreground_services(&facts, &permissions, "声明");
+        assert_eq!(status, RuleStatus::Fail);
+        assert!(items[0].contains("FOREGROUND_SERVICE_MEDIA_PLAYBACK"), "{:?}", items);
+        assert!(items.iter().any(|i| i.starts_with(".UploadService")), "{:?}", items);
+    }
+
+    #[test]
+    fn foreground_permission_without_any_type_fails() {
+        let facts = facts(
+            r#"<manifest><application><service android:name=".SyncService"/></application></manifest>"#,
+        );
+        let permissions = BTreeSet::from([FOREGROUND_SERVICE_PERMISSION]);
+        let (status, _, items) = check_foreground_services(&facts, &permissions, "声明");
+        assert_eq!(status, RuleStatus::Fail);
+        assert_eq!(items, vec![".SyncService: 未声明 foregroundServiceType"]);
+
+        // 不使用前台服务时普通服务无需声明类型
+        let (status, _, _) = check_foreground_services(&facts, &BTreeSet::new(), "声明");
+        assert_eq!(status, RuleStatus::NotApplicable);
+    }
+
+    #[test]
+    fn builtin_rules_parse() {
+        let rules = load_rules(None).unwrap();
+        assert!(!rules.rules.is_empty());
+        let ids: BTreeSet<&str> = rules.rules.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids.len(), rules.rules.len(), "规则 ID 重复");
+    }
+
+    #[test]
+    fn target_sdk_rule_depends_on_release_type() {
+        let rules = load_rules(None).unwrap();
+        let new = rule_ids(&rules, ReleaseType::New);
+        let update = rule_ids(&rules, ReleaseType::Update);
+
+        assert!(new.contains(&"target_sdk_new_apps") && !new.contains(&"target_sdk_updates"));
+        assert!(update.contains(&"target_sdk_updates") && !update.contains(&"target_sdk_new_apps"));
+        // 未限定提交类型的规则两者都检查
+        assert!(new.contains(&"native_64bit") && update.contains(&"native_64bit"));
+    }
+
+    #[test]
+    fn min_target_sdk_comes_from_rules() {
+        let text = r#"{"version":"1","updated":"2025-01-01","rules":[
+            {"id":"a","title":"a","check":"target_sdk","applies_to":"new","min_target_sdk":36},
+            {"id":"b","title":"b","check":"target_sdk","applies_to":"update","min_target_sdk":35}
+        ]}"#;
+        let rules: PolicyRules = serde_json::from_str(text).unwrap();
+        assert_eq!(min_target_sdk(&rules, ReleaseType::New), Some(36));
+        assert_eq!(min_target_sdk(&rules, ReleaseType::Update), Some(35));
+
+        let empty = PolicyRules { version: "1".into(), updated: String::new(), rules: Vec::new() };
+        assert_eq!(min_target_sdk(&empty, ReleaseType::Update), None);
+    }
+
+    #[test]
+    fn applies_to_is_optional_and_validated() {
+        let rule = |extra: &str| {
+            format!(
+                r#"{{"version":"1","updated":"2025-01-01","rules":[{{"id":"t","title":"t","check":"target_sdk","min_target_sdk":34{}}}]}}"#,
+                extra
+            )
+        };
+        let parse = |text: String| serde_json::from_str::<PolicyRules>(&text);
+
+        assert!(parse(rule("")).unwrap().rules[0].applies_to.is_none());
+        assert_eq!(parse(rule(r#","applies_to":"new""#)).unwrap().rules[0].applies_to, Some(ReleaseType::New));
+        assert!(parse(rule(r#","applies_to":"beta""#)).is_err());
+    }
+}
@@ -0,0 +1,366 @@
+// ==================== 加固 / 加壳 / 混淆检测 ====================
+
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use zip::ZipArchive;
+
+use crate::dex::DexFile;
+use crate::manifest::{self, get_attr};
+
+/// 加固厂商特征
+struct PackerSignature {
+    name: &'static str,
+    /// 文件名（不含路径）匹配，lib/ 与 assets/ 下均会检查
+    files: &'static [&'static str],
+    /// 其他应用也可能使用的通用文件名，需要同时命中壳类或至少两个不同的特征文件才计入
+    generic_files: &'static [&'static str],
+    /// 代理 Application / 壳入口类名
+    classes: &'static [&'static str],
+}
+
+const PACKERS: &[PackerSignature] = &[
+    PackerSignature {
+        name: "360 加固 (Jiagu)",
+        files: &["libjiagu.so", "libjiagu_art.so", "libjiagu_x86.so", "libjiagu_a64.so", "libjiagu_x64.so", "libprotectClass.so"],
+        generic_files: &[],
+        classes: &["com.stub.StubApp", "com.qihoo.util.StubApp", "com.qihoo.util.StubApplication"],
+    },
+    PackerSignature {
+        name: "腾讯乐固 (Legu)",
+        files: &["libshell-super.2019.so", "libshella-2.10.3.0.so", "libshellx-2.10.3.0.so", "libtup.so", "0OO00l111l1l", "o0oooOO0ooOo.dat", "tosversion"],
+        generic_files: &[],
+        classes: &["com.tencent.StubShell.TxAppEntry", "com.wrapper.proxyapplication.WrapperProxyApplication"],
+    },
+    PackerSignature {
+        name: "梆梆加固 (Bangcle)",
+        files: &["libsecexe.so", "libsecmain.so", "libSecShell.so", "libSecShell-x86.so", "libDexHelper.so", "libDexHelper-x86.so", "bangcleplugin", "secData0.jar"],
+        generic_files: &[],
+        classes: &["com.secneo.apkwrapper.ApplicationWrapper", "com.SecShell.SecShell.ApplicationWrapper", "com.secshell.secData.ApplicationWrapper"],
+    },
+    PackerSignature {
+        name: "爱加密 (ijiami)",
+        files: &["libexecmain.so", "ijiami.dat", "ijiami.ajm"],
+        generic_files: &["libexec.so", "af.bin", "signed.bin"],
+        classes: &["s.h.e.l.l.S", "com.shell.SuperApplication", "com.ijiami.residconfusion.ConfusionApplication"],
+    },
+    PackerSignature {
+        name: "百度加固",
+        files: &["libbaiduprotect.so", "libbaiduprotect_x86.so", "baiduprotect1.jar", "baiduprotect.jar"],
+        generic_files: &[],
+        classes: &["com.baidu.protect.StubApplication", "com.baidu.protect.A"],
+    },
+    PackerSignature {
+        name: "阿里聚安全",
+        files: &["libmobisec.so", "libmobisecy.so", "aliprotector.dex", "libaliutils.so"],
+        generic_files: &[],
+        classes: &["com.ali.mobisecenhance.StubApplication", "com.ali.fixHelper"],
+    },
+    PackerSignature {
+        name: "网易易盾",
+        files: &["libnesec.so", "libnesec-x86.so"],
+        generic_files: &[],
+        classes: &["com.netease.nis.wrapper.MyApplication", "com.netease.nis.wrapper.MyJni"],
+    },
+    PackerSignature {
+        name: "娜迦 (Nagain)",
+        files: &["libchaosvmp.so", "libddog.so", "libfdog.so", "libedog.so"],
+        generic_files: &[],
+        classes: &["com.nagapt.NagaptApplication", "com.edog.AppWrapper"],
+    },
+    PackerSignature {
+        name: "顶像加固",
+        files: &["libx3g.so"],
+        generic_files: &[],
+        classes: &["com.security.shell.AppStub1"],
+    },
+    PackerSignature {
+        name: "DexGuard",
+        files: &["libdexguard.so"],
+        generic_files: &[],
+        classes: &[],
+    },
+];
+
+// 短类名（如 a、b、aa）占比超过该值时认为已混淆
+const OBFUSCATION_RATIO_THRESHOLD: f64 = 0.3;
+// 壳 APK 的主 DEX 通常只包含少量类
+const SHELL_DEX_CLASS_LIMIT: usize = 30;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PackerMatch {
+    pub name: String,
+    pub evidence: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ObfuscationInfo {
+    pub obfuscated: bool,
+    pub total_classes: usize,
+    pub short_named_classes: usize,
+    pub short_name_ratio: f64,
+    /// DEX 字符串池中的 R8 标记（~~R8{...}）
+    pub r8_marker: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProtectionInfo {
+    pub packers: Vec<PackerMatch>,
+    pub obfuscation: ObfuscationInfo,
+    pub dex_anomalies: Vec<String>,
+}
+
+/// 检测 APK 使用的加固方案和混淆情况
+pub(crate) fn detect_protection(path: &Path) -> Result<ProtectionInfo, String> {
+    let file = File::open(path).map_err(|e| format!("无法打开文件: {}", e))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("无法解析 ZIP 文件: {}", e))?;
+
+    let entry_names: Vec<String> = archive.file_names().map(|s| s.to_string()).collect();
+    let application_class = manifest::load_manifest_from_archive(&mut archive)
+        .ok()
+        .and_then(|axml| manifest::application_node(&axml))
+        .and_then(|node| get_attr(&node.borrow(), "name"));
+
+    // 读取所有 DEX
+    let mut dex_files = Vec::new();
+    let mut dex_anomalies = Vec::new();
+    let mut dex_names: Vec<&String> = entry_names
+        .iter()
+        .filter(|name| !name.contains('/') && name.ends_with(".dex"))
+        .collect();
+    dex_names.sort();
+    for name in dex_names {
+        let mut data = Vec::new();
+        if let Ok(mut entry) = archive.by_name(name) {
+            entry
+                .read_to_end(&mut data)
+                .map_err(|e| format!("读取 {} 失败: {}", name, e))?;
+        }
+        match DexFile::parse(&data) {
+            Ok(dex) => {
+                if dex.declared_size as usize != data.len() {
+                    dex_anomalies.push(format!(
+                        "{} 头部声明大小 {} 与实际大小 {} 不一致（可能附加了加密数据）",
+                        name,
+                        dex.declared_size,
+                        data.len()
+                    ));
+                }
+                dex_files.push((name.clone(), dex));
+            }
+            Err(e) => dex_anomalies.push(format!("{} 无法解析: {}", name, e)),
+        }
+    }
+
+    let all_classes: Vec<String> = dex_files.iter().flat_map(|(_, dex)| dex.class_names()).collect();
+
+    if let Some((name, dex)) = dex_files.first() {
+        let class_count = dex.class_names().len();
+        if class_count < SHELL_DEX_CLASS_LIMIT && entry_names.iter().any(|n| n.ends_with(".so")) {
+            dex_anomalies.push(format!("{} 仅包含 {} 个类，真实代码可能被加密存放", name, class_count));
+        }
+    }
+
+    let packers = match_packers(&entry_names, application_class.as_deref(), &all_classes, &dex_files);
+    let obfuscation = estimate_obfuscation(&all_classes, &dex_files);
+
+    Ok(ProtectionInfo {
+        packers,
+        obfuscation,
+        dex_anomalies,
+    })
+}
+
+fn match_packers(
+    entry_names: &[String],
+    application_class: Option<&str>,
+    classes: &[String],
+    dex_files: &[(String, DexFile)],
+) -> Vec<PackerMatch> {
+    let mut matches = Vec::new();
+
+    for packer in PACKERS {
+        let mut evidence = Vec::new();
+        let mut generic_evidence = Vec::new();
+        let mut matched_files = BTreeSet::new();
+
+        for entry in entry_names {
+            let file_name = entry.rsplit('/').next().unwrap_or(entry);
+            if packer.files.contains(&file_name) {
+                evidence.push(format!("文件: {}", entry));
+                matched_files.insert(file_name);
+            } else if packer.generic_files.contains(&file_name) {
+                generic_evidence.push(format!("文件: {}", entry));
+                matched_files.insert(file_name);
+            }
+        }
+
+        let mut class_hit = false;
+        for class in packer.classes {
+            if application_class == Some(*class) {
+                evidence.push(format!("Application: {}", class));
+                class_hit = true;
+            } else if classes.iter().any(|c| c == class) {
+                evidence.push(format!("类: {}", class));
+                class_hit = true;
+            }
+        }
+
+        // 同一文件在多个 ABI 目录下只算一个特征
+        if class_hit || matched_files.len() >= 2 {
+            evidence.extend(generic_evidence);
+        }
+
+        if !evidence.is_empty() {
+            matches.push(PackerMatch {
+                name: packer.name.to_string(),
+                evidence,
+            });
+        }
+    }
+
+    // DexGuard 没有固定的壳文件，通过字符串池中的标识判断
+    let dexguard_marker = dex_files
+        .iter()
+        .find(|(_, dex)| dex.strings.iter().any(|s| s.contains("DexGuard")));
+    if let Some((name, _)) = dexguard_marker {
+        let evidence = format!("{} 中包含 DexGuard 标识", name);
+        match matches.iter_mut().find(|m| m.name == "DexGuard") {
+            Some(existing) => existing.evidence.push(evidence),
+            None => matches.push(PackerMatch {
+                name: "DexGuard".to_string(),
+                evidence: vec![evidence],
+            }),
+        }
+    }
+
+    matches
+}
+
+fn estimate_obfuscation(classes: &[String], dex_files: &[(String, DexFile)]) -> ObfuscationInfo {
+    // 统计应用类，排除 R 和 BuildConfig 等生成类以及内部类
+    let candidates: Vec<&str> = classes
+        .iter()
+        .filter_map(|class| class.rsplit('.').next())
+        .filter(|simple| !simple.contains('$') && *simple != "R" && *simple != "BuildConfig")
+        .collect();
+
+    let short_named_classes = candidates.iter().filter(|simple| simple.len() <= 2).count();
+    let total_classes = candidates.len();
+    let short_name_ratio = if total_classes == 0 {
+        0.0
+    } else {
+        short_named_classes as f64 / total_classes as f64
+    };
+
+    let r8_marker = dex_files
+        .iter()
+        .flat_map(|(_, dex)| dex.strings.iter())
+        .find(|s| s.starts_with("~~R8{"))
+        .cloned();
+
+    ObfuscationInfo {
+        obfuscated: short_name_ratio >= OBFUSCATION_RATIO_THRESHOLD,
+        total_classes,
+        short_named_classes,
+        short_name_ratio,
+        r8_marker,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(entries: &[&str]) -> Vec<String> {
+        entries.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn packer_names(matches: &[PackerMatch]) -> Vec<&str> {
+        matches.iter().map(|m| m.name.as_str()).collect()
+    }
+
+    #[test]
+    fn generic_packer_files_need_corroboration() {
+        // 单独的通用文件名不算，即使出现在多个 ABI 目录下
+        let entries = names(&["lib/arm64-v8a/libexec.so", "lib/armeabi-v7a/libexec.so", "classes.dex"]);
+        assert!(match_packers(&entries, None, &[], &[]).is_empty());
+        assert!(match_packers(&names(&["assets/signed.bin"]), None, &[], &[]).is_empty());
+
+        // 两个不同的特征文件
+        let matches = match_packers(&names(&["assets/af.bin", "assets/signed.bin"]), None, &[], &[]);
+        assert_eq!(packer_names(&matches), ["爱加密 (ijiami)"]);
+        assert_eq!(matches[0].evidence, ["文件: assets/af.bin", "文件: assets/signed.bin"]);
+
+        // 通用文件 + 壳类
+        let matches = match_packers(&entries, Some("s.h.e.l.l.S"), &[], &[]);
+        assert_eq!(
+            matches[0].evidence,
+            [
+                "Application: s.h.e.l.l.S",
+                "文件: lib/arm64-v8a/libexec.so",
+                "文件: lib/armeabi-v7a/libexec.so",
+            ]
+        );
+
+        // 专有文件名单独即可命中
+        let matches = match_packers(&names(&["assets/ijiami.dat"]), None, &[], &[]);
+        assert_eq!(packer_names(&matches), ["爱加密 (ijiami)"]);
+    }
+
+    #[test]
+    fn matches_packer_files_classes_and_dexguard_marker() {
+        let entries = names(&["lib/arm64-v8a/libjiagu_a64.so", "assets/libjiagu.so"]);
+        let classes = names(&["com.secneo.apkwrapper.ApplicationWrapper"]);
+        let dex_files = vec![("classes.dex".to_string(), DexFile::from_parts(&["Protected by DexGuard"], &[]))];
+
+        let matches = match_packers(&entries, Some("com.stub.StubApp"), &classes, &dex_files);
+        assert_eq!(packer_names(&matches), ["360 加固 (Jiagu)", "梆梆加固 (Bangcle)", "DexGuard"]);
+        assert_eq!(
+            matches[0].evidence,
+            [
+                "文件: lib/arm64-v8a/libjiagu_a64.so",
+                "文件: assets/libjiagu.so",
+                "Application: com.stub.StubApp",
+            ]
+        );
+        assert_eq!(matches[1].evidence, ["类: com.secneo.apkwrapper.ApplicationWrapper"]);
+        assert_eq!(matches[2].evidence, ["classes.dex 中包含 DexGuard 标识"]);
+
+        assert!(match_packers(&names(&["lib/arm64-v8a/libapp.so"]), Some("com.example.App"), &[], &[]).is_empty());
+    }
+
+    #[test]
+    fn estimates_obfuscation() {
+        let classes = names(&[
+            "a.a",
+            "a.b",
+            "b.c$d",
+            "com.example.MainActivity",
+            "com.example.R",
+            "com.example.R$id",
+            "com.example.BuildConfig",
+        ]);
+        let dex_files = vec![(
+            "classes.dex".to_string(),
+            DexFile::from_parts(&[r#"~~R8{"backend":"dex","min-api":21}"#], &[]),
+        )];
+
+        let info = estimate_obfuscation(&classes, &dex_files);
+        assert_eq!((info.short_named_classes, info.total_classes), (2, 3));
+        assert!(info.obfuscated);
+        assert_eq!(info.r8_marker.as_deref(), Some(r#"~~R8{"backend":"dex","min-api":21}"#));
+
+        let info = estimate_obfuscation(&names(&["com.example.MainActivity", "com.example.ui.ab", "com.example.Util", "com.example.Repo"]), &[]);
+        assert_eq!(info.short_name_ratio, 0.25);
+        assert!(!info.obfuscated);
+        assert!(info.r8_marker.is_none());
+
+        let info = estimate_obfuscation(&[], &[]);
+        assert_eq!(info.short_name_ratio, 0.0);
+        assert!(!info.obfuscated);
+    }
+}
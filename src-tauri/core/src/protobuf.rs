@@ -0,0 +1,118 @@
+// ==================== Protocol Buffers 线格式读取 ====================
+// AAB 中的 manifest、resources.pb 和 BundleConfig.pb 都是 protobuf，
+// 这里只实现按字段号读取所需的线格式解码，不依赖 .proto 生成代码
+
+use crate::chunk::read_u32;
+
+#[derive(Debug, Clone, Copy)]
+pub enum WireValue<'a> {
+    Varint(u64),
+    Fixed64(u64),
+    Bytes(&'a [u8]),
+    Fixed32(u32),
+}
+
+/// 已解码的消息：保留字段顺序，重复字段按出现顺序返回
+#[derive(Debug, Clone, Default)]
+pub struct Message<'a> {
+    pub fields: Vec<(u32, WireValue<'a>)>,
+}
+
+fn read_varint(data: &[u8], offset: &mut usize) -> Result<u64, String> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *data.get(*offset).ok_or("protobuf 数据截断")?;
+        *offset += 1;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err("protobuf varint 过长".to_string())
+}
+
+impl<'a> Message<'a> {
+    /// 解码一条消息的全部字段
+    pub fn parse(data: &'a [u8]) -> Result<Self, String> {
+        let mut fields = Vec::new();
+        let mut offset = 0;
+        while offset < data.len() {
+            let key = read_varint(data, &mut offset)?;
+            let field = (key >> 3) as u32;
+            let value = match key & 0x7 {
+                0 => WireValue::Varint(read_varint(data, &mut offset)?),
+                1 => {
+                    let bytes = data.get(offset..offset + 8).ok_or("protobuf 数据截断")?;
+                    offset += 8;
+                    WireValue::Fixed64(u64::from_le_bytes(bytes.try_into().unwrap()))
+                }
+                2 => {
+                    let len = read_varint(data, &mut offset)? as usize;
+                    let bytes = offset
+                        .checked_add(len)
+                        .and_then(|end| data.get(offset..end))
+                        .ok_or("protobuf 数据截断")?;
+                    offset += len;
+                    WireValue::Bytes(bytes)
+                }
+                5 => {
+                    let value = read_u32(data, offset)?;
+                    offset += 4;
+                    WireValue::Fixed32(value)
+                }
+                wire_type => return Err(format!("不支持的 protobuf 字段类型: {}", wire_type)),
+            };
+            fields.push((field, value));
+        }
+        Ok(Message { fields })
+    }
+
+    /// 字段的全部取值（重复字段）
+    pub fn all(&self, field: u32) -> impl Iterator<Item = WireValue<'a>> + '_ {
+        self.fields.iter().filter(move |(f, _)| *f == field).map(|(_, v)| *v)
+    }
+
+    /// 字段的最后一个取值（与 protobuf 的合并语义一致）
+    pub fn get(&self, field: u32) -> Option<WireValue<'a>> {
+        self.all(field).last()
+    }
+
+    pub fn has(&self, field: u32) -> bool {
+        self.get(field).is_some()
+    }
+
+    pub fn varint(&self, field: u32) -> Option<u64> {
+        match self.get(field)? {
+            WireValue::Varint(value) | WireValue::Fixed64(value) => Some(value),
+            WireValue::Fixed32(value) => Some(value as u64),
+            WireValue::Bytes(_) => None,
+        }
+    }
+
+    pub fn bytes(&self, field: u32) -> Option<&'a [u8]> {
+        match self.get(field)? {
+            WireValue::Bytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    /// 字符串字段，未设置时为空字符串
+    pub fn string(&self, field: u32) -> String {
+        self.bytes(field)
+            .map(|b| String::from_utf8_lossy(b).to_string())
+            .unwrap_or_default()
+    }
+
+    pub fn message(&self, field: u32) -> Result<Option<Message<'a>>, String> {
+        self.bytes(field).map(Message::parse).transpose()
+    }
+
+    pub fn messages(&self, field: u32) -> Result<Vec<Message<'a>>, String> {
+        self.all(field)
+            .filter_map(|value| match value {
+                WireValue::Bytes(bytes) => Some(Message::parse(bytes)),
+                _ => None,
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,315 @@
+// ==================== 构建溯源信息 ====================
+// 收集 AGP、R8/D8、Kotlin、AndroidX 版本、基线配置文件和原生库 Build ID，用于定位产物的工具链
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use zip::ZipArchive;
+
+use crate::dex::DexFile;
+use crate::elf::ElfFile;
+use crate::manifest::read_entry;
+
+const APP_METADATA_PATH: &str = "META-INF/com/android/build/gradle/app-metadata.properties";
+const PROFILE_MAGIC: &[u8] = b"pro\0";
+// DEX 字符串池中的编译器标记：~~R8{...}、~~D8{...}、~~L8{...}
+const MARKER_PREFIXES: [&str; 3] = ["~~R8", "~~D8", "~~L8"];
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CompilerMarker {
+    /// R8 / D8 / L8
+    pub tool: String,
+    pub version: Option<String>,
+    pub compilation_mode: Option<String>,
+    pub min_api: Option<u64>,
+    /// 与 mapping.txt 对应的哈希
+    pub pg_map_id: Option<String>,
+    pub r8_mode: Option<String>,
+    pub raw: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BaselineProfile {
+    pub path: String,
+    pub size: u64,
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NativeBuildId {
+    pub library: String,
+    pub build_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BuildProvenance {
+    pub agp_version: Option<String>,
+    /// app-metadata.properties 全部内容
+    pub app_metadata: BTreeMap<String, String>,
+    pub compiler_markers: Vec<CompilerMarker>,
+    pub pg_map_id: Option<String>,
+    /// kotlin_builtins 的元数据版本，随 Kotlin 编译器升级变化
+    pub kotlin_builtins_version: Option<String>,
+    pub kotlin_modules: Vec<String>,
+    /// META-INF/*.version 中记录的 AndroidX / Kotlin 库版本
+    pub library_versions: BTreeMap<String, String>,
+    pub baseline_profiles: Vec<BaselineProfile>,
+    pub native_build_ids: Vec<NativeBuildId>,
+}
+
+/// 解析 key=value 格式的 .properties 文本
+pub fn parse_properties(text: &str) -> BTreeMap<String, String> {
+    text.lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect()
+}
+
+fn parse_marker(raw: &str) -> Option<CompilerMarker> {
+    let prefix = MARKER_PREFIXES.iter().find(|p| raw.starts_with(*p))?;
+    let json: serde_json::Value = serde_json::from_str(&raw[prefix.len()..]).ok()?;
+    let text = |key: &str| json.get(key).and_then(|v| v.as_str()).map(|s| s.to_string());
+    Some(CompilerMarker {
+        tool: prefix.trim_start_matches('~').to_string(),
+        version: text("version"),
+        compilation_mode: text("compilation-mode"),
+        min_api: json.get("min-api").and_then(|v| v.as_u64()),
+        pg_map_id: text("pg-map-id"),
+        r8_mode: text("r8-mode"),
+        raw: raw.to_string(),
+    })
+}
+
+// kotlin_builtins 文件头：版本号个数(u32) + 各段版本号(u32)，均为大端序
+fn builtins_version(data: &[u8]) -> Option<String> {
+    let count = u32::from_be_bytes(data.get(0..4)?.try_into().ok()?) as usize;
+    if count == 0 || count > 8 {
+        return None;
+    }
+    let parts: Option<Vec<String>> = (0..count)
+        .map(|i| {
+            let start = 4 + i * 4;
+            data.get(start..start + 4)
+                .map(|b| u32::from_be_bytes(b.try_into().unwrap()).to_string())
+        })
+        .collect();
+    parts.map(|p| p.join("."))
+}
+
+// 从 DEX 字符串池中收集编译器标记，多个 DEX 中相同的标记只保留一份
+fn collect_markers(dex_data: &[u8], markers: &mut Vec<CompilerMarker>) {
+    let Ok(dex) = DexFile::parse(dex_data) else { return };
+    for raw in dex.strings.iter().filter(|s| s.starts_with("~~")) {
+        if markers.iter().any(|m| &m.raw == raw) {
+            continue;
+        }
+        if let Some(marker) = parse_marker(raw) {
+            markers.push(marker);
+        }
+    }
+}
+
+/// 只读取 DEX 中的 pg-map-id，用于匹配 mapping.txt
+pub(crate) fn read_pg_map_id(path: &Path) -> Result<Option<String>, String> {
+    let file = File::open(path).map_err(|e| format!("无法打开文件: {}", e))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("无法解析 ZIP 文件: {}", e))?;
+    let dex_names: Vec<String> = archive
+        .file_names()
+        .filter(|name| !name.contains('/') && name.ends_with(".dex"))
+        .map(|s| s.to_string())
+        .collect();
+
+    let mut markers = Vec::new();
+    for name in dex_names {
+        if let Ok(data) = read_entry(&mut archive, &name) {
+            collect_markers(&data, &mut markers);
+        }
+        if let Some(id) = markers.iter().find_map(|m| m.pg_map_id.clone()) {
+            return Ok(Some(id));
+        }
+    }
+    Ok(None)
+}
+
+/// 收集 APK 的构建溯源信息
+pub(crate) fn collect_provenance(path: &Path) -> Result<BuildProvenance, String> {
+    let file = File::open(path).map_err(|e| format!("无法打开文件: {}", e))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("无法解析 ZIP 文件: {}", e))?;
+    let mut entry_names: Vec<String> = archive.file_names().map(|s| s.to_string()).collect();
+    entry_names.sort();
+
+    let app_metadata = read_entry(&mut archive, APP_METADATA_PATH)
+        .map(|data| parse_properties(&String::from_utf8_lossy(&data)))
+        .unwrap_or_default();
+    let agp_version = app_metadata.get("androidGradlePluginVersion").cloned();
+
+    let mut compiler_markers: Vec<CompilerMarker> = Vec::new();
+    let mut library_versions = BTreeMap::new();
+    let mut kotlin_builtins_version = None;
+    let mut kotlin_modules = Vec::new();
+    let mut baseline_profiles = Vec::new();
+    let mut native_build_ids = Vec::new();
+
+    for name in &entry_names {
+        if !name.contains('/') && name.ends_with(".dex") {
+            if let Ok(data) = read_entry(&mut archive, name) {
+                collect_markers(&data, &mut compiler_markers);
+            }
+        } else if let Some(library) = name.strip_prefix("META-INF/").and_then(|n| n.strip_suffix(".version")) {
+            if library.contains('/') {
+                continue;
+            }
+            if let Ok(data) = read_entry(&mut archive, name) {
+                library_versions.insert(library.to_string(), String::from_utf8_lossy(&data).trim().to_string());
+            }
+        } else if name.ends_with(".kotlin_module") {
+            kotlin_modules.push(name.trim_start_matches("META-INF/").to_string());
+        } else if name.starts_with("kotlin/") && name.ends_with(".kotlin_builtins") {
+            if kotlin_builtins_version.is_none() {
+                kotlin_builtins_version = read_entry(&mut archive, name).ok().and_then(|d| builtins_version(&d));
+            }
+        } else if name.starts_with("assets/dexopt/") {
+            let Ok(data) = read_entry(&mut archive, name) else { continue };
+            // 文件头：魔数 "pro\0" + 版本号（如 "010\0"）
+            let version = (data.starts_with(PROFILE_MAGIC) && data.len() >= 8)
+                .then(|| String::from_utf8_lossy(&data[4..8]).trim_end_matches('\0').to_string());
+            baseline_profiles.push(BaselineProfile {
+                path: name.clone(),
+                size: data.len() as u64,
+                version,
+            });
+        } else if name.starts_with("lib/") && name.ends_with(".so") {
+            let Ok(data) = read_entry(&mut archive, name) else { continue };
+            if let Some(build_id) = ElfFile::parse(&data).ok().and_then(|elf| elf.build_id()) {
+                native_build_ids.push(NativeBuildId {
+                    library: name.clone(),
+                    build_id,
+                });
+            }
+        }
+    }
+
+    let pg_map_id = compiler_markers.iter().find_map(|m| m.pg_map_id.clone());
+
+    Ok(BuildProvenance {
+        agp_version,
+        app_metadata,
+        compiler_markers,
+        pg_map_id,
+        kotlin_builtins_version,
+        kotlin_modules,
+        library_versions,
+        baseline_profiles,
+        native_build_ids,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    fn builtins(parts: &[u32]) -> Vec<u8> {
+        let mut data = (parts.len() as u32).to_be_bytes().to_vec();
+        for part in parts {
+            data.extend_from_slice(&part.to_be_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn parses_r8_marker() {
+        let raw = r#"~~R8{"backend":"dex","compilation-mode":"release","has-checksums":false,"min-api":21,"pg-map-id":"4b2f0c1","r8-mode":"full","version":"8.2.42"}"#;
+        let marker = parse_marker(raw).unwrap();
+        assert_eq!(marker.tool, "R8");
+        assert_eq!(marker.version.as_deref(), Some("8.2.42"));
+        assert_eq!(marker.compilation_mode.as_deref(), Some("release"));
+        assert_eq!(marker.min_api, Some(21));
+        assert_eq!(marker.pg_map_id.as_deref(), Some("4b2f0c1"));
+        assert_eq!(marker.r8_mode.as_deref(), Some("full"));
+        assert_eq!(marker.raw, raw);
+    }
+
+    #[test]
+    fn parses_d8_marker_without_optional_fields() {
+        let marker = parse_marker(r#"~~D8{"compilation-mode":"debug","version":"8.1.56"}"#).unwrap();
+        assert_eq!(marker.tool, "D8");
+        assert_eq!(marker.compilation_mode.as_deref(), Some("debug"));
+        assert_eq!(marker.min_api, None);
+        assert_eq!(marker.pg_map_id, None);
+        assert_eq!(marker.r8_mode, None);
+    }
+
+    #[test]
+    fn rejects_invalid_markers() {
+        assert!(parse_marker("~~R8").is_none());
+        assert!(parse_marker("~~R8{not json").is_none());
+        assert!(parse_marker(r#"~~X8{"version":"1.0"}"#).is_none());
+        assert!(parse_marker(r#"R8{"version":"1.0"}"#).is_none());
+    }
+
+    #[test]
+    fn builtins_versions() {
+        assert_eq!(builtins_version(&builtins(&[1, 9, 0])).as_deref(), Some("1.9.0"));
+        assert_eq!(builtins_version(&builtins(&[2, 0, 0, 1])).as_deref(), Some("2.0.0.1"));
+        assert_eq!(builtins_version(&builtins(&[])), None);
+        assert_eq!(builtins_version(&builtins(&[0; 9])), None);
+        // 声明 3 段但只有 2 段数据
+        let mut truncated = builtins(&[1, 9, 0]);
+        truncated.truncate(12);
+        assert_eq!(builtins_version(&truncated), None);
+        assert_eq!(builtins_version(&[0, 0]), None);
+    }
+
+    #[test]
+    fn parses_properties() {
+        let properties = parse_properties("# generated\r\nappMetadataVersion=1.1\r\n\r\n androidGradlePluginVersion = 8.5.0 \r\nbroken line\r\n");
+        assert_eq!(properties.len(), 2);
+        assert_eq!(properties["appMetadataVersion"], "1.1");
+        assert_eq!(properties["androidGradlePluginVersion"], "8.5.0");
+    }
+
+    #[test]
+    fn collects_provenance_from_apk() {
+        let mut profile = PROFILE_MAGIC.to_vec();
+        profile.extend_from_slice(b"010\0");
+        profile.extend_from_slice(&[0; 8]);
+        let files: Vec<(&str, Vec<u8>)> = vec![
+            (APP_METADATA_PATH, b"appMetadataVersion=1.1\nandroidGradlePluginVersion=8.5.0\n".to_vec()),
+            ("META-INF/androidx.core_core.version", b"1.13.1\n".to_vec()),
+            ("META-INF/nested/ignored.version", b"0.0.1".to_vec()),
+            ("META-INF/app_release.kotlin_module", Vec::new()),
+            ("kotlin/kotlin.kotlin_builtins", builtins(&[1, 9, 0])),
+            ("assets/dexopt/baseline.prof", profile),
+            ("lib/arm64-v8a/libnot-elf.so", vec![0; 16]),
+        ];
+        let path = std::env::temp_dir().join(format!("provenance_{}.apk", std::process::id()));
+        let mut writer = ZipWriter::new(File::create(&path).unwrap());
+        for (file, data) in &files {
+            writer.start_file(*file, SimpleFileOptions::default()).unwrap();
+            writer.write_all(data).unwrap();
+        }
+        writer.finish().unwrap();
+        let result = collect_provenance(&path);
+        let _ = std::fs::remove_file(&path);
+        let provenance = result.unwrap();
+
+        assert_eq!(provenance.agp_version.as_deref(), Some("8.5.0"));
+        assert_eq!(provenance.app_metadata.len(), 2);
+        assert_eq!(provenance.library_versions.len(), 1);
+        assert_eq!(provenance.library_versions["androidx.core_core"], "1.13.1");
+        assert_eq!(provenance.kotlin_modules, vec!["app_release.kotlin_module"]);
+        assert_eq!(provenance.kotlin_builtins_version.as_deref(), Some("1.9.0"));
+        assert_eq!(provenance.baseline_profiles.len(), 1);
+        assert_eq!(provenance.baseline_profiles[0].version.as_deref(), Some("010"));
+        assert!(provenance.native_build_ids.is_empty());
+        assert!(provenance.compiler_markers.is_empty());
+        assert_eq!(provenance.pg_map_id, None);
+    }
+}
@@ -0,0 +1,882 @@
+// ==================== 资源表转换（resources.pb -> resources.arsc） ====================
+// AAB 中的资源表是 aapt2 的 protobuf 格式，安装到设备前需要转换为二进制 resources.arsc。
+// 这里保留完整的条目、配置和复杂资源（style、array、plurals 等），并支持按配置拆分
+
+use std::collections::HashMap;
+
+use crate::bundle;
+use crate::chunk::{
+    self, write_u16, write_u32, ResValue, StyleSpan, RES_TABLE_PACKAGE_TYPE, RES_TABLE_TYPE, RES_TABLE_TYPE_TYPE,
+    TYPE_INT_DEC, TYPE_INT_HEX, TYPE_NULL, TYPE_STRING,
+};
+use crate::protobuf::Message;
+
+const RES_TABLE_TYPE_SPEC_TYPE: u16 = 0x0202;
+const NO_ENTRY: u32 = 0xffff_ffff;
+const CONFIG_SIZE: u32 = 64;
+const PACKAGE_HEADER_SIZE: u32 = 288;
+
+// ResTable_entry.flags
+const FLAG_COMPLEX: u16 = 0x0001;
+const FLAG_PUBLIC: u16 = 0x0002;
+const FLAG_WEAK: u16 = 0x0004;
+const SPEC_PUBLIC: u32 = 0x4000_0000;
+
+// ResTable_map.name 中的特殊键
+const ATTR_TYPE: u32 = 0x0100_0000;
+const ATTR_MIN: u32 = 0x0100_0001;
+const ATTR_MAX: u32 = 0x0100_0002;
+const ATTR_OTHER: u32 = 0x0100_0004;
+const ATTR_ARRAY_BASE: u32 = 0x0200_0000;
+// Attribute.format_flags 中的 flags 类型
+const ATTR_FORMAT_FLAGS: u32 = 0x0002_0000;
+
+// 资源配置中的特殊密度：任意密度、不缩放
+const DENSITY_ANY: u16 = 0xfffe;
+const DENSITY_NONE: u16 = 0xffff;
+
+/// 资源配置（ResTable_config 中实际使用的维度）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Config {
+    pub mcc: u16,
+    pub mnc: u16,
+    pub language: String,
+    pub region: String,
+    pub script: String,
+    pub variant: String,
+    pub orientation: u8,
+    pub touchscreen: u8,
+    pub density: u16,
+    pub keyboard: u8,
+    pub navigation: u8,
+    pub input_flags: u8,
+    pub grammatical_gender: u8,
+    pub screen_width: u16,
+    pub screen_height: u16,
+    pub sdk_version: u16,
+    pub screen_layout: u8,
+    pub ui_mode: u8,
+    pub smallest_width_dp: u16,
+    pub width_dp: u16,
+    pub height_dp: u16,
+    pub screen_layout2: u8,
+    pub color_mode: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub tag: String,
+    pub first_char: u32,
+    pub last_char: u32,
+}
+
+#[derive(Debug, Clone)]
+pub enum Item {
+    Value(ResValue),
+    String(String),
+    Styled(String, Vec<Span>),
+    /// 资源文件，proto_xml 表示需要转换为二进制 XML
+    File { path: String, proto_xml: bool },
+}
+
+#[derive(Debug, Clone)]
+pub enum Value {
+    Item(Item),
+    /// style、array、plurals、attr 等复杂资源：父资源 + (键, 值) 列表
+    Bag { parent: u32, items: Vec<(u32, Item)> },
+}
+
+#[derive(Debug, Clone)]
+pub struct ConfigValue {
+    pub config: Config,
+    pub weak: bool,
+    pub value: Value,
+}
+
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub id: u16,
+    pub name: String,
+    pub public: bool,
+    pub values: Vec<ConfigValue>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ResourceType {
+    pub id: u8,
+    pub name: String,
+    pub entries: Vec<Entry>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Package {
+    pub id: u8,
+    pub name: String,
+    pub types: Vec<ResourceType>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ResourceTable {
+    pub packages: Vec<Package>,
+}
+
+// ==================== 读取 resources.pb ====================
+
+// BCP-47 标签拆成语言、文字、地区和变体（"sr-Latn-RS"、"es-419"、"b+sr+Latn"）
+fn parse_locale(tag: &str, config: &mut Config) {
+    let tag = tag.strip_prefix("b+").unwrap_or(tag);
+    let mut parts = tag.split(['-', '_', '+']).filter(|p| !p.is_empty());
+    let Some(language) = parts.next() else { return };
+    config.language = language.to_lowercase();
+    for part in parts {
+        let letters = part.chars().all(|c| c.is_ascii_alphabetic());
+        let digits = part.chars().all(|c| c.is_ascii_digit());
+        match part.len() {
+            4 if letters => {
+                let mut chars = part.chars();
+                config.script = chars.next().map(|c| c.to_ascii_uppercase()).into_iter().chain(chars.map(|c| c.to_ascii_lowercase())).collect();
+            }
+            2 if letters => config.region = part.to_uppercase(),
+            3 if digits => config.region = part.to_string(),
+            5..=8 => config.variant = part.to_string(),
+            _ => {}
+        }
+    }
+}
+
+fn parse_config(message: &Message) -> Config {
+    let number = |field: u32| message.varint(field).unwrap_or(0) as u32;
+    let mut config = Config {
+        mcc: number(1) as u16,
+        mnc: number(2) as u16,
+        screen_width: number(5) as u16,
+        screen_height: number(6) as u16,
+        width_dp: number(7) as u16,
+        height_dp: number(8) as u16,
+        smallest_width_dp: number(9) as u16,
+        orientation: number(15) as u8,
+        touchscreen: number(19) as u8,
+        density: number(18) as u16,
+        keyboard: number(21) as u8,
+        navigation: number(23) as u8,
+        sdk_version: number(24) as u16,
+        grammatical_gender: number(26) as u8,
+        ..Default::default()
+    };
+    parse_locale(&message.string(3), &mut config);
+
+    // proto 枚举转换为 ResTable_config 中的位域
+    let layout_dir = number(4) << 6;
+    let screen_size = number(10);
+    let screen_long = match number(11) {
+        1 => 0x20,
+        2 => 0x10,
+        _ => 0,
+    };
+    config.screen_layout = (layout_dir | screen_size | screen_long) as u8;
+    config.screen_layout2 = match number(12) {
+        1 => 0x2,
+        2 => 0x1,
+        _ => 0,
+    };
+    let wide_color = match number(13) {
+        1 => 0x2,
+        2 => 0x1,
+        _ => 0,
+    };
+    let hdr = match number(14) {
+        1 => 0x8,
+        2 => 0x4,
+        _ => 0,
+    };
+    config.color_mode = wide_color | hdr;
+    let night = match number(17) {
+        1 => 0x20,
+        2 => 0x10,
+        _ => 0,
+    };
+    config.ui_mode = (number(16) | night) as u8;
+    config.input_flags = (number(20) | (number(22) << 2)) as u8;
+    config
+}
+
+fn parse_item(item: &Message) -> Result<Item, String> {
+    if let Some(styled) = item.message(4)? {
+        let spans = styled
+            .messages(2)?
+            .iter()
+            .map(|span| Span {
+                tag: span.string(1),
+                first_char: span.varint(2).unwrap_or(0) as u32,
+                last_char: span.varint(3).unwrap_or(0) as u32,
+            })
+            .collect::<Vec<_>>();
+        return Ok(Item::Styled(styled.string(1), spans));
+    }
+    if let Some(file) = item.message(5)? {
+        // FileReference.Type：1=PNG，2=BINARY_XML，3=PROTO_XML
+        return Ok(Item::File {
+            path: file.string(1),
+            proto_xml: file.varint(2) == Some(3),
+        });
+    }
+    match bundle::convert_item(item)? {
+        (value, Some(text)) if value.data_type == TYPE_STRING => Ok(Item::String(text)),
+        (value, _) => Ok(Item::Value(value)),
+    }
+}
+
+fn reference_id(message: Option<Message>) -> u32 {
+    message.and_then(|r| r.varint(2)).unwrap_or(0) as u32
+}
+
+fn parse_compound(compound: &Message) -> Result<Value, String> {
+    let mut items = Vec::new();
+    let mut parent = 0;
+
+    if let Some(attr) = compound.message(1)? {
+        let format = attr.varint(1).unwrap_or(0) as u32;
+        items.push((ATTR_TYPE, Item::Value(ResValue { data_type: TYPE_INT_DEC, data: format })));
+        // 未设置 min/max 时 aapt2 写入 i32::MIN / i32::MAX
+        let min = attr.varint(2).unwrap_or(0) as i32;
+        let max = attr.varint(3).unwrap_or(0) as i32;
+        if min != i32::MIN && attr.has(2) {
+            items.push((ATTR_MIN, Item::Value(ResValue { data_type: TYPE_INT_DEC, data: min as u32 })));
+        }
+        if max != i32::MAX && attr.has(3) {
+            items.push((ATTR_MAX, Item::Value(ResValue { data_type: TYPE_INT_DEC, data: max as u32 })));
+        }
+        let default_type = if format & ATTR_FORMAT_FLAGS != 0 { TYPE_INT_HEX } else { TYPE_INT_DEC };
+        for symbol in attr.messages(4)? {
+            let data_type = match symbol.varint(5).unwrap_or(0) as u8 {
+                0 => default_type,
+                other => other,
+            };
+            items.push((
+                reference_id(symbol.message(3)?),
+                Item::Value(ResValue { data_type, data: symbol.varint(4).unwrap_or(0) as u32 }),
+            ));
+        }
+    } else if let Some(style) = compound.message(2)? {
+        parent = reference_id(style.message(1)?);
+        for entry in style.messages(3)? {
+            let Some(item) = entry.message(4)? else { continue };
+            items.push((reference_id(entry.message(3)?), parse_item(&item)?));
+        }
+    } else if let Some(styleable) = compound.message(3)? {
+        for entry in styleable.messages(1)? {
+            items.push((reference_id(entry.message(3)?), Item::Value(ResValue { data_type: TYPE_NULL, data: 0 })));
+        }
+    } else if let Some(array) = compound.message(4)? {
+        for (index, element) in array.messages(1)?.iter().enumerate() {
+            let Some(item) = element.message(3)? else { continue };
+            items.push((ATTR_ARRAY_BASE | index as u32, parse_item(&item)?));
+        }
+    } else if let Some(plural) = compound.message(5)? {
+        for entry in plural.messages(1)? {
+            let Some(item) = entry.message(4)? else { continue };
+            // Arity：ZERO..MANY = 0..4 对应 0x01000005..0x01000009，OTHER = 5 对应 0x01000004
+            let key = match entry.varint(3).unwrap_or(0) as u32 {
+                5 => ATTR_OTHER,
+                arity => ATTR_OTHER + 1 + arity,
+            };
+            items.push((key, parse_item(&item)?));
+        }
+    } else {
+        return Err("不支持的复杂资源类型".to_string());
+    }
+    Ok(Value::Bag { parent, items })
+}
+
+impl ResourceTable {
+    /// 解析 AAB 模块中的 resources.pb
+    pub fn from_proto(data: &[u8]) -> Result<Self, String> {
+        let table = Message::parse(data)?;
+        let mut packages = Vec::new();
+        for package in table.messages(2)? {
+            let mut types = Vec::new();
+            for resource_type in package.messages(3)? {
+                let mut entries = Vec::new();
+                for entry in resource_type.messages(3)? {
+                    let mut values = Vec::new();
+                    for config_value in entry.messages(6)? {
+                        let Some(value) = config_value.message(2)? else { continue };
+                        let config = config_value.message(1)?.map(|c| parse_config(&c)).unwrap_or_default();
+                        // Value：item = 4，compound_value = 5
+                        let parsed = match (value.message(4)?, value.message(5)?) {
+                            (Some(item), _) => Value::Item(parse_item(&item)?),
+                            (None, Some(compound)) => {
+                                // 宏（macro）只在编译期使用，不写入 resources.arsc
+                                if compound.has(6) {
+                                    continue;
+                                }
+                                parse_compound(&compound)?
+                            }
+                            (None, None) => continue,
+                        };
+                        values.push(ConfigValue {
+                            config,
+                            weak: value.varint(3).unwrap_or(0) != 0,
+                            value: parsed,
+                        });
+                    }
+                    // Visibility.Level：2 = PUBLIC
+                    let public = entry.message(3)?.and_then(|v| v.varint(1)) == Some(2);
+                    entries.push(Entry {
+                        id: entry.message(1)?.and_then(|id| id.varint(1)).unwrap_or(0) as u16,
+                        name: entry.string(2),
+                        public,
+                        values,
+                    });
+                }
+                // 类型 ID 从 1 开始，缺失或超出范围时无法写入 resources.arsc
+                let name = resource_type.string(2);
+                let id = resource_type.message(1)?.and_then(|id| id.varint(1)).unwrap_or(0);
+                if id == 0 || id > u8::MAX as u64 {
+                    return Err(format!("资源类型 {} 的 ID 无效: {}", name, id));
+                }
+                types.push(ResourceType {
+                    id: id as u8,
+                    name,
+                    entries,
+                });
+            }
+            packages.push(Package {
+                id: package.message(1)?.and_then(|id| id.varint(1)).unwrap_or(0) as u8,
+                name: package.string(2),
+                types,
+            });
+        }
+        Ok(ResourceTable { packages })
+    }
+
+    /// 只保留满足条件的配置值，去掉因此变空的条目和类型
+    pub fn filter(&self, keep: impl Fn(&Entry, &ConfigValue) -> bool) -> ResourceTable {
+        let packages = self
+            .packages
+            .iter()
+            .map(|package| Package {
+                id: package.id,
+                name: package.name.clone(),
+                types: package
+                    .types
+                    .iter()
+                    .map(|resource_type| ResourceType {
+                        id: resource_type.id,
+                        name: resource_type.name.clone(),
+                        entries: resource_type
+                            .entries
+                            .iter()
+                            .map(|entry| Entry {
+                                values: entry.values.iter().filter(|v| keep(entry, v)).cloned().collect(),
+                                ..entry.clone()
+                            })
+                            .filter(|entry| !entry.values.is_empty())
+                            .collect(),
+                    })
+                    .filter(|resource_type| !resource_type.entries.is_empty())
+                    .collect(),
+            })
+            .collect();
+        ResourceTable { packages }
+    }
+
+    /// 合并另一个模块的资源表，包 ID 和类型 ID 相同时合并条目
+    pub fn merge(&mut self, other: ResourceTable) {
+        for package in other.packages {
+            let Some(existing) = self.packages.iter_mut().find(|p| p.id == package.id) else {
+                self.packages.push(package);
+                continue;
+            };
+            for resource_type in package.types {
+                match existing.types.iter_mut().find(|t| t.id == resource_type.id) {
+                    Some(target) => target.entries.extend(resource_type.entries),
+                    None => existing.types.push(resource_type),
+                }
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.packages.iter().all(|p| p.types.is_empty())
+    }
+
+    /// 资源表引用的全部文件
+    pub fn files(&self) -> Vec<(&str, bool)> {
+        let mut files = Vec::new();
+        for value in self.values() {
+            let items: Vec<&Item> = match &value.value {
+                Value::Item(item) => vec![item],
+                Value::Bag { items, .. } => items.iter().map(|(_, item)| item).collect(),
+            };
+            for item in items {
+                if let Item::File { path, proto_xml } = item {
+                    files.push((path.as_str(), *proto_xml));
+                }
+            }
+        }
+        files
+    }
+
+    fn values(&self) -> impl Iterator<Item = &ConfigValue> {
+        self.packages
+            .iter()
+            .flat_map(|p| &p.types)
+            .flat_map(|t| &t.entries)
+            .flat_map(|e| &e.values)
+    }
+}
+
+// ==================== 编码 resources.arsc ====================
+
+// 全局字符串池：带样式的字符串必须排在最前面
+#[derive(Default)]
+struct GlobalPool {
+    strings: Vec<String>,
+    styles: Vec<Vec<StyleSpan>>,
+    index: HashMap<String, u32>,
+    styled_index: HashMap<(String, Vec<Span>), u32>,
+}
+
+impl GlobalPool {
+    fn new(table: &ResourceTable) -> Self {
+        let mut pool = GlobalPool::default();
+        let mut styled = Vec::new();
+        for value in table.values() {
+            let items: Vec<&Item> = match &value.value {
+                Value::Item(item) => vec![item],
+                Value::Bag { items, .. } => items.iter().map(|(_, item)| item).collect(),
+            };
+            for item in items {
+                if let Item::Styled(text, spans) = item {
+                    let key = (text.clone(), spans.clone());
+                    if !pool.styled_index.contains_key(&key) {
+                        pool.styled_index.insert(key, pool.strings.len() as u32);
+                        pool.strings.push(text.clone());
+                        styled.push(spans.clone());
+                    }
+                }
+            }
+        }
+        // 标签名排在带样式的字符串之后
+        for spans in styled {
+            let converted = spans
+                .iter()
+                .map(|span| StyleSpan {
+                    name: pool.intern(&span.tag),
+                    first_char: span.first_char,
+                    last_char: span.last_char,
+                })
+                .collect();
+            pool.styles.push(converted);
+        }
+        pool
+    }
+
+    fn intern(&mut self, value: &str) -> u32 {
+        if let Some(index) = self.index.get(value) {
+            return *index;
+        }
+        let index = self.strings.len() as u32;
+        self.strings.push(value.to_string());
+        self.index.insert(value.to_string(), index);
+        index
+    }
+
+    fn res_value(&mut self, item: &Item) -> ResValue {
+        let string = |data| ResValue { data_type: TYPE_STRING, data };
+        match item {
+            Item::Value(value) => *value,
+            Item::String(text) => string(self.intern(text)),
+            Item::File { path, .. } => string(self.intern(path)),
+            Item::Styled(text, spans) => string(self.styled_index[&(text.clone(), spans.clone())]),
+        }
+    }
+}
+
+// 打包两到三个字符的语言或地区代码（三个字符时压缩进两个字节）
+fn pack_code(code: &str, base: u8, out: &mut Vec<u8>) {
+    let bytes = code.as_bytes();
+    match bytes.len() {
+        2 => out.extend_from_slice(bytes),
+        3 => {
+            let first = bytes[0].wrapping_sub(base) & 0x7f;
+            let second = bytes[1].wrapping_sub(base) & 0x7f;
+            let third = bytes[2].wrapping_sub(base) & 0x7f;
+            out.push(0x80 | (third << 2) | (second >> 3));
+            out.push((second << 5) | first);
+        }
+        _ => out.extend_from_slice(&[0, 0]),
+    }
+}
+
+fn write_fixed(out: &mut Vec<u8>, text: &str, len: usize) {
+    let mut bytes = text.as_bytes().to_vec();
+    bytes.resize(len, 0);
+    out.extend_from_slice(&bytes);
+}
+
+impl Config {
+    pub fn is_default(&self) -> bool {
+        *self == Config::default()
+    }
+
+    /// 是否限定了具体密度（不含 anydpi / nodpi）
+    pub fn has_specific_density(&self) -> bool {
+        self.density != 0 && self.density != DENSITY_ANY && self.density != DENSITY_NONE
+    }
+
+    /// 去掉密度后的配置，用于比较同一资源的不同密度版本
+    pub fn without_density(&self) -> Config {
+        Config { density: 0, ..self.clone() }
+    }
+
+    // 与默认配置相比变化的维度（ResTable_config::CONFIG_*），写入 typeSpec
+    fn change_flags(&self) -> u32 {
+        let checks = [
+            (self.mcc != 0, 0x0001),
+            (self.mnc != 0, 0x0002),
+            (!(self.language.is_empty() && self.region.is_empty() && self.script.is_empty() && self.variant.is_empty()), 0x0004),
+            (self.touchscreen != 0, 0x0008),
+            (self.keyboard != 0, 0x0010),
+            (self.input_flags != 0, 0x0020),
+            (self.navigation != 0, 0x0040),
+            (self.orientation != 0, 0x0080),
+            (self.density != 0, 0x0100),
+            (self.screen_width != 0 || self.screen_height != 0 || self.width_dp != 0 || self.height_dp != 0, 0x0200),
+            (self.sdk_version != 0, 0x0400),
+            (self.screen_layout & 0x3f != 0, 0x0800),
+            (self.ui_mode != 0, 0x1000),
+            (self.smallest_width_dp != 0, 0x2000),
+            (self.screen_layout & 0xc0 != 0, 0x4000),
+            (self.screen_layout2 != 0, 0x8000),
+            (self.color_mode != 0, 0x10000),
+            (self.grammatical_gender != 0, 0x20000),
+        ];
+        checks.iter().filter(|(changed, _)| *changed).fold(0, |flags, (_, bit)| flags | bit)
+    }
+
+    // 64 字节的 ResTable_config
+    fn encode(&self, out: &mut Vec<u8>) {
+        let start = out.len();
+        write_u32(out, CONFIG_SIZE);
+        write_u16(out, self.mcc);
+        write_u16(out, self.mnc);
+        pack_code(&self.language, b'a', out);
+        pack_code(&self.region, b'0', out);
+        out.push(self.orientation);
+        out.push(self.touchscreen);
+        write_u16(out, self.density);
+        out.push(self.keyboard);
+        out.push(self.navigation);
+        out.push(self.input_flags);
+        out.push(self.grammatical_gender);
+        write_u16(out, self.screen_width);
+        write_u16(out, self.screen_height);
+        write_u16(out, self.sdk_version);
+        write_u16(out, 0); // minorVersion
+        out.push(self.screen_layout);
+        out.push(self.ui_mode);
+        write_u16(out, self.smallest_width_dp);
+        write_u16(out, self.width_dp);
+        write_u16(out, self.height_dp);
+        write_fixed(out, &self.script, 4);
+        write_fixed(out, &self.variant, 8);
+        out.push(self.screen_layout2);
+        out.push(self.color_mode);
+        write_u16(out, 0); // screenConfigPad2
+        out.resize(start + CONFIG_SIZE as usize, 0);
+    }
+}
+
+fn encode_entry(entry: &Entry, value: &ConfigValue, key: u32, pool: &mut GlobalPool, out: &mut Vec<u8>) {
+    let mut flags = 0;
+    if entry.public {
+        flags |= FLAG_PUBLIC;
+    }
+    if value.weak {
+        flags |= FLAG_WEAK;
+    }
+    match &value.value {
+        Value::Item(item) => {
+            write_u16(out, 8);
+            write_u16(out, flags);
+            write_u32(out, key);
+            pool.res_value(item).write(out);
+        }
+        Value::Bag { parent, items } => {
+            write_u16(out, 16);
+            write_u16(out, flags | FLAG_COMPLEX);
+            write_u32(out, key);
+            write_u32(out, *parent);
+            write_u32(out, items.len() as u32);
+            for (name, item) in items {
+                write_u32(out, *name);
+                pool.res_value(item).write(out);
+            }
+        }
+    }
+}
+
+fn encode_type(resource_type: &ResourceType, keys: &HashMap<&str, u32>, pool: &mut GlobalPool, out: &mut Vec<u8>) {
+    let entry_count = resource_type.entries.iter().map(|e| e.id as usize + 1).max().unwrap_or(0);
+
+    // 按配置分组，默认配置排在最前面
+    let mut configs: Vec<&Config> = Vec::new();
+    for value in resource_type.entries.iter().flat_map(|e| &e.values) {
+        if !configs.contains(&&value.config) {
+            configs.push(&value.config);
+        }
+    }
+    configs.sort_by_key(|config| !config.is_default());
+
+    let mut spec_flags = vec![0u32; entry_count];
+    for entry in &resource_type.entries {
+        let flags = &mut spec_flags[entry.id as usize];
+        for value in &entry.values {
+            *flags |= value.config.change_flags();
+        }
+        if entry.public {
+            *flags |= SPEC_PUBLIC;
+        }
+    }
+    write_u16(out, RES_TABLE_TYPE_SPEC_TYPE);
+    write_u16(out, 16);
+    write_u32(out, 16 + entry_count as u32 * 4);
+    out.push(resource_type.id);
+    out.push(0);
+    write_u16(out, configs.len() as u16);
+    write_u32(out, entry_count as u32);
+    for flags in spec_flags {
+        write_u32(out, flags);
+    }
+
+    for config in configs {
+        let mut offsets = vec![NO_ENTRY; entry_count];
+        let mut entries = Vec::new();
+        for entry in &resource_type.entries {
+            for value in entry.values.iter().filter(|v| &v.config == config) {
+                offsets[entry.id as usize] = entries.len() as u32;
+                encode_entry(entry, value, keys[entry.name.as_str()], pool, &mut entries);
+            }
+        }
+
+        let header_size = 20 + CONFIG_SIZE;
+        let entries_start = header_size + entry_count as u32 * 4;
+        write_u16(out, RES_TABLE_TYPE_TYPE);
+        write_u16(out, header_size as u16);
+        write_u32(out, entries_start + entries.len() as u32);
+        out.push(resource_type.id);
+        out.push(0); // flags
+        write_u16(out, 0);
+        write_u32(out, entry_count as u32);
+        write_u32(out, entries_start);
+        config.encode(out);
+        for offset in offsets {
+            write_u32(out, offset);
+        }
+        out.extend_from_slice(&entries);
+    }
+}
+
+fn encode_package(package: &Package, pool: &mut GlobalPool) -> Vec<u8> {
+    // 类型名按类型 ID 排列（ID 从 1 开始），中间缺失的 ID 用空字符串占位；ID 为 0 的类型无法表示，直接跳过
+    let type_count = package.types.iter().map(|t| t.id as usize).max().unwrap_or(0);
+    let mut type_names = vec![String::new(); type_count];
+    for resource_type in &package.types {
+        if let Some(name) = (resource_type.id as usize).checked_sub(1).and_then(|i| type_names.get_mut(i)) {
+            *name = resource_type.name.clone();
+        }
+    }
+
+    let mut key_names: Vec<String> = Vec::new();
+    let mut keys: HashMap<&str, u32> = HashMap::new();
+    for entry in package.types.iter().flat_map(|t| &t.entries) {
+        if !keys.contains_key(entry.name.as_str()) {
+            keys.insert(&entry.name, key_names.len() as u32);
+            key_names.push(entry.name.clone());
+        }
+    }
+
+    let type_pool = chunk::write_string_pool(&type_names);
+    let key_pool = chunk::write_string_pool(&key_names);
+    let mut body = Vec::new();
+    let mut types: Vec<&ResourceType> = package.types.iter().filter(|t| t.id != 0).collect();
+    types.sort_by_key(|t| t.id);
+    for resource_type in types {
+        encode_type(resource_type, &keys, pool, &mut body);
+    }
+
+    let size = PACKAGE_HEADER_SIZE as usize + type_pool.len() + key_pool.len() + body.len();
+    let mut out = Vec::with_capacity(size);
+    write_u16(&mut out, RES_TABLE_PACKAGE_TYPE);
+    write_u16(&mut out, PACKAGE_HEADER_SIZE as u16);
+    write_u32(&mut out, size as u32);
+    write_u32(&mut out, package.id as u32);
+    let mut name: Vec<u16> = package.name.encode_utf16().take(127).collect();
+    name.resize(128, 0);
+    for unit in name {
+        write_u16(&mut out, unit);
+    }
+    write_u32(&mut out, PACKAGE_HEADER_SIZE); // typeStrings
+    write_u32(&mut out, type_count as u32); // lastPublicType
+    write_u32(&mut out, PACKAGE_HEADER_SIZE + type_pool.len() as u32); // keyStrings
+    write_u32(&mut out, key_names.len() as u32); // lastPublicKey
+    write_u32(&mut out, 0); // typeIdOffset
+    out.extend_from_slice(&type_pool);
+    out.extend_from_slice(&key_pool);
+    out.extend_from_slice(&body);
+    out
+}
+
+/// 编码为二进制 resources.arsc
+pub fn encode(table: &ResourceTable) -> Vec<u8> {
+    let mut pool = GlobalPool::new(table);
+    // 先编码包，字符串池在编码条目时才完整
+    let packages: Vec<Vec<u8>> = table.packages.iter().map(|p| encode_package(p, &mut pool)).collect();
+    let string_pool = chunk::write_string_pool_with_styles(&pool.strings, &pool.styles);
+
+    let size = 12 + string_pool.len() + packages.iter().map(|p| p.len()).sum::<usize>();
+    let mut out = Vec::with_capacity(size);
+    write_u16(&mut out, RES_TABLE_TYPE);
+    write_u16(&mut out, 12);
+    write_u32(&mut out, size as u32);
+    write_u32(&mut out, table.packages.len() as u32);
+    out.extend_from_slice(&string_pool);
+    for package in packages {
+        out.extend_from_slice(&package);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arsc;
+
+    fn string_value(config: Config, text: &str) -> ConfigValue {
+        ConfigValue {
+            config,
+            weak: false,
+            value: Value::Item(Item::String(text.to_string())),
+        }
+    }
+
+    fn entry(id: u16, name: &str, values: Vec<ConfigValue>) -> Entry {
+        Entry {
+            id,
+            name: name.to_string(),
+            public: false,
+            values,
+        }
+    }
+
+    // 类型 ID 故意留空 2，检查占位后的类型名仍能按 ID 对上
+    fn sample_table() -> ResourceTable {
+        let zh = Config {
+            language: "zh".to_string(),
+            region: "CN".to_string(),
+            ..Config::default()
+        };
+        let style = ConfigValue {
+            config: Config::default(),
+            weak: false,
+            value: Value::Bag {
+                parent: 0,
+                items: vec![(0x0101_0000, Item::Value(ResValue { data_type: TYPE_INT_DEC, data: 3 }))],
+            },
+        };
+        ResourceTable {
+            packages: vec![Package {
+                id: 0x7f,
+                name: "com.example.app".to_string(),
+                types: vec![
+                    ResourceType {
+                        id: 3,
+                        name: "style".to_string(),
+                        entries: vec![entry(0, "AppTheme", vec![style])],
+                    },
+                    ResourceType {
+                        id: 1,
+                        name: "string".to_string(),
+                        entries: vec![
+                            entry(0, "app_name", vec![string_value(Config::default(), "Demo"), string_value(zh, "演示")]),
+                            entry(2, "title", vec![string_value(Config::default(), "Title")]),
+                        ],
+                    },
+                ],
+            }],
+        }
+    }
+
+    #[test]
+    fn encoded_table_parses_back() {
+        let data = encode(&sample_table());
+        let table = arsc::ResourceTable::parse(&data).unwrap();
+
+        assert_eq!(table.package_names, vec!["com.example.app".to_string()]);
+        assert_eq!(table.name_of(0x7f01_0000).as_deref(), Some("string/app_name"));
+        assert_eq!(table.resolve_string(0x7f01_0000).as_deref(), Some("Demo"));
+        let all = table.resolve_all_strings(0x7f01_0000);
+        assert_eq!(all.len(), 2);
+        assert!(all
+            .iter()
+            .any(|(config, value)| config.language == "zh" && config.country == "CN" && value == "演示"));
+
+        // 条目 ID 1 缺失，不应解析出内容
+        assert!(table.entries(0x7f01_0001).is_empty());
+        assert_eq!(table.resolve_string(0x7f01_0002).as_deref(), Some("Title"));
+
+        // 复杂资源只有名称，没有单一取值
+        assert_eq!(table.name_of(0x7f03_0000).as_deref(), Some("style/AppTheme"));
+        assert!(table.entries(0x7f03_0000)[0].value.is_none());
+    }
+
+    #[test]
+    fn encode_skips_type_without_id() {
+        let mut table = sample_table();
+        table.packages[0].types.push(ResourceType {
+            id: 0,
+            name: "broken".to_string(),
+            entries: vec![entry(0, "oops", vec![string_value(Config::default(), "x")])],
+        });
+
+        let parsed = arsc::ResourceTable::parse(&encode(&table)).unwrap();
+        assert_eq!(parsed.resolve_string(0x7f01_0000).as_deref(), Some("Demo"));
+        assert!(parsed.entries(0x7f00_0000).is_empty());
+    }
+
+    // 手工拼装 protobuf：长度前缀字段（这里的内容都不超过 127 字节）
+    fn field(number: u8, payload: &[u8]) -> Vec<u8> {
+        let mut out = vec![(number << 3) | 2, payload.len() as u8];
+        out.extend_from_slice(payload);
+        out
+    }
+
+    fn proto_table(type_id: Option<u8>) -> Vec<u8> {
+        let mut resource_type = Vec::new();
+        if let Some(id) = type_id {
+            resource_type.extend(field(1, &[0x08, id]));
+        }
+        resource_type.extend(field(2, b"string"));
+
+        let mut package = field(1, &[0x08, 0x7f]);
+        package.extend(field(2, b"com.example.app"));
+        package.extend(field(3, &resource_type));
+        field(2, &package)
+    }
+
+    #[test]
+    fn from_proto_reads_type_id() {
+        let table = ResourceTable::from_proto(&proto_table(Some(1))).unwrap();
+        assert_eq!(table.packages[0].id, 0x7f);
+        assert_eq!(table.packages[0].types[0].id, 1);
+        assert_eq!(table.packages[0].types[0].name, "string");
+    }
+
+    #[test]
+    fn from_proto_rejects_missing_type_id() {
+        let err = ResourceTable::from_proto(&proto_table(None)).unwrap_err();
+        assert!(err.contains("string"), "{}", err);
+    }
+}
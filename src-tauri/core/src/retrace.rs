@@ -0,0 +1,588 @@
+// ==================== R8 / ProGuard 堆栈还原 ====================
+// 解析 mapping.txt（类、字段、方法映射，行号区间与内联帧），按 retrace 的规则还原混淆后的堆栈
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::error::AnalysisError;
+
+/// 方法映射中的一行
+#[derive(Debug, Clone)]
+struct MethodMapping {
+    /// 在文件中的序号，相邻且混淆行号区间相同的记录构成一条内联链
+    seq: usize,
+    obfuscated_range: Option<(u32, u32)>,
+    original_range: Option<(u32, Option<u32>)>,
+    /// 内联自其他类时的原始类名
+    original_class: Option<String>,
+    original_name: String,
+    return_type: String,
+    arguments: String,
+    synthesized: bool,
+}
+
+#[derive(Debug, Default)]
+struct ClassMapping {
+    original: String,
+    source_file: Option<String>,
+    methods: HashMap<String, Vec<MethodMapping>>,
+    /// 混淆字段名 → (类型, 原始字段名)
+    fields: HashMap<String, Vec<(String, String)>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MappingSummary {
+    pub path: String,
+    pub compiler: Option<String>,
+    pub compiler_version: Option<String>,
+    pub min_api: Option<String>,
+    pub pg_map_id: Option<String>,
+    pub pg_map_hash: Option<String>,
+    pub class_count: usize,
+    pub method_count: usize,
+    pub field_count: usize,
+}
+
+/// 已建立索引的 mapping 文件
+pub struct Mapping {
+    classes: HashMap<String, ClassMapping>,
+    /// 原始类名 → 源文件名，用于内联自其他类的帧
+    source_files: HashMap<String, String>,
+    pub summary: MappingSummary,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StackFrame {
+    pub class_name: String,
+    pub method_name: String,
+    pub file_name: String,
+    pub line: Option<u32>,
+    /// 原始方法签名，如 void onClick(android.view.View)
+    pub signature: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RetracedFrame {
+    pub obfuscated: String,
+    /// 候选结果，每个候选是一条内联链（最内层在前）
+    pub candidates: Vec<Vec<StackFrame>>,
+    pub ambiguous: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MapIdCheck {
+    pub apk_pg_map_id: Option<String>,
+    pub mapping_pg_map_id: Option<String>,
+    pub matched: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RetraceResult {
+    pub output: String,
+    pub frames: Vec<RetracedFrame>,
+    pub mapping: MappingSummary,
+    pub map_id_check: Option<MapIdCheck>,
+    pub warnings: Vec<String>,
+}
+
+enum Member {
+    Method(MethodMapping),
+    /// (类型, 原始字段名)
+    Field(String, String),
+}
+
+// 解析 "1:5:" 这样的行号前缀
+fn split_range_prefix(text: &str) -> (Option<(u32, u32)>, &str) {
+    let mut parts = text.splitn(3, ':');
+    if let (Some(start), Some(end), Some(rest)) = (parts.next(), parts.next(), parts.next()) {
+        if let (Ok(start), Ok(end)) = (start.parse(), end.parse()) {
+            return (Some((start, end)), rest);
+        }
+    }
+    (None, text)
+}
+
+// 解析成员行，如 "1:5:void com.example.Foo.bar(int):10:14 -> a"
+fn parse_member(line: &str) -> Option<(String, Member)> {
+    let (left, obfuscated) = line.rsplit_once(" -> ")?;
+    let (obfuscated_range, signature) = split_range_prefix(left.trim());
+
+    let Some(open) = signature.find('(') else {
+        // 字段：类型 + 名称
+        let (field_type, name) = signature.split_once(' ')?;
+        return Some((obfuscated.trim().to_string(), Member::Field(field_type.to_string(), name.to_string())));
+    };
+    let close = signature[open..].find(')')? + open;
+    let (return_type, qualified_name) = signature[..open].split_once(' ')?;
+    let arguments = signature[open + 1..close].to_string();
+
+    let mut original = signature[close + 1..].split(':').filter(|s| !s.is_empty());
+    let original_range = original.next().and_then(|s| s.parse().ok()).map(|start| {
+        (start, original.next().and_then(|s| s.parse().ok()))
+    });
+
+    let (original_class, original_name) = match qualified_name.rsplit_once('.') {
+        Some((class, name)) => (Some(class.to_string()), name.to_string()),
+        None => (None, qualified_name.to_string()),
+    };
+
+    Some((
+        obfuscated.trim().to_string(),
+        Member::Method(MethodMapping {
+            seq: 0,
+            obfuscated_range,
+            original_range,
+            original_class,
+            original_name,
+            return_type: return_type.to_string(),
+            arguments,
+            synthesized: false,
+        }),
+    ))
+}
+
+impl Mapping {
+    /// 读取并索引 mapping.txt
+    pub fn load(path: &Path) -> Result<Mapping, AnalysisError> {
+        let file = File::open(path).map_err(|e| format!("无法打开 mapping 文件: {}", e))?;
+        let reader = BufReader::new(file);
+
+        let mut classes: HashMap<String, ClassMapping> = HashMap::new();
+        let mut summary = MappingSummary {
+            path: path.to_string_lossy().to_string(),
+            ..Default::default()
+        };
+        let mut current: Option<String> = None;
+        // 最近一条方法映射（混淆名, 下标），用于附加 # {...} 元数据
+        let mut last_method: Option<(String, usize)> = None;
+        let mut seq = 0;
+
+        for line in reader.lines() {
+            let line = line.map_err(|e| format!("读取 mapping 文件失败: {}", e))?;
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+
+            if let Some(comment) = trimmed.strip_prefix('#') {
+                let comment = comment.trim();
+                if current.is_none() {
+                    // 文件头，如 "# pg_map_id: 4a1b2c3"
+                    if let Some((key, value)) = comment.split_once(':') {
+                        let value = Some(value.trim().to_string());
+                        match key.trim() {
+                            "compiler" => summary.compiler = value,
+                            "compiler_version" => summary.compiler_version = value,
+                            "min_api" => summary.min_api = value,
+                            "pg_map_id" => summary.pg_map_id = value,
+                            "pg_map_hash" => summary.pg_map_hash = value,
+                            _ => {}
+                        }
+                    }
+                    continue;
+                }
+                let Ok(json) = serde_json::from_str::<serde_json::Value>(comment) else { continue };
+                let Some(class) = current.as_ref().and_then(|c| classes.get_mut(c)) else { continue };
+                match json.get("id").and_then(|v| v.as_str()) {
+                    Some("sourceFile") => {
+                        class.source_file = json.get("fileName").and_then(|v| v.as_str()).map(|s| s.to_string());
+                    }
+                    Some("com.android.tools.r8.synthesized") => {
+                        if let Some((name, index)) = &last_method {
+                            if let Some(method) = class.methods.get_mut(name).and_then(|m| m.get_mut(*index)) {
+                                method.synthesized = true;
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+
+            if !line.starts_with(char::is_whitespace) {
+                // 类映射："com.example.Foo -> a.b:"
+                let Some((original, obfuscated)) = trimmed.trim_end_matches(':').split_once(" -> ") else {
+                    continue;
+                };
+                let obfuscated = obfuscated.trim().to_string();
+                classes.insert(
+                    obfuscated.clone(),
+                    ClassMapping {
+                        original: original.trim().to_string(),
+                        ..Default::default()
+                    },
+                );
+                current = Some(obfuscated);
+                last_method = None;
+                continue;
+            }
+
+            let Some(class) = current.as_ref().and_then(|c| classes.get_mut(c)) else { continue };
+            match parse_member(trimmed) {
+                Some((obfuscated, Member::Method(mut method))) => {
+                    seq += 1;
+                    method.seq = seq;
+                    let methods = class.methods.entry(obfuscated.clone()).or_default();
+                    methods.push(method);
+                    last_method = Some((obfuscated, methods.len() - 1));
+                    summary.method_count += 1;
+                }
+                Some((obfuscated, Member::Field(field_type, name))) => {
+                    class.fields.entry(obfuscated).or_default().push((field_type, name));
+                    last_method = None;
+                    summary.field_count += 1;
+                }
+                None => {}
+            }
+        }
+
+        summary.class_count = classes.len();
+        let source_files = classes
+            .values()
+            .filter_map(|c| c.source_file.clone().map(|f| (c.original.clone(), f)))
+            .collect();
+
+        Ok(Mapping {
+            classes,
+            source_files,
+            summary,
+        })
+    }
+
+    /// 还原混淆类名，未找到时返回 None
+    pub fn original_class(&self, obfuscated: &str) -> Option<&str> {
+        self.classes.get(obfuscated).map(|c| c.original.as_str())
+    }
+
+    /// 还原类名或字段引用（如 NPE 信息中的 a.b.c），未找到时返回 None
+    pub fn original_reference(&self, obfuscated: &str) -> Option<String> {
+        if let Some(class) = self.original_class(obfuscated) {
+            return Some(class.to_string());
+        }
+        let (class, field) = obfuscated.rsplit_once('.')?;
+        let mapping = self.classes.get(class)?;
+        let names: Vec<&str> = mapping.fields.get(field)?.iter().map(|(_, name)| name.as_str()).collect();
+        Some(format!("{}.{}", mapping.original, names.join("|")))
+    }
+
+    // 源文件名：mapping 中的 sourceFile 优先，否则按外部类名推断
+    fn source_file(&self, original_class: &str) -> String {
+        // 内部类与外部类共用源文件
+        let outer = original_class.split('$').next().unwrap_or(original_class);
+        if let Some(file) = self.source_files.get(original_class).or_else(|| self.source_files.get(outer)) {
+            return file.clone();
+        }
+        let simple = original_class.rsplit('.').next().unwrap_or(original_class);
+        format!("{}.java", simple.split('$').next().unwrap_or(simple))
+    }
+
+    fn to_frame(&self, class: &ClassMapping, method: &MethodMapping, line: Option<u32>) -> StackFrame {
+        let class_name = method.original_class.clone().unwrap_or_else(|| class.original.clone());
+        let file_name = match (&method.original_class, &class.source_file) {
+            (None, Some(file)) => file.clone(),
+            _ => self.source_file(&class_name),
+        };
+        StackFrame {
+            file_name,
+            line: line.map(|l| original_line(method, l)),
+            method_name: method.original_name.clone(),
+            signature: Some(format!("{} {}({})", method.return_type, method.original_name, method.arguments)),
+            class_name,
+        }
+    }
+
+    /// 还原单个栈帧，返回所有候选内联链
+    pub fn retrace_frame(&self, class: &str, method: &str, line: Option<u32>) -> Vec<Vec<StackFrame>> {
+        let Some(class_mapping) = self.classes.get(class) else {
+            return Vec::new();
+        };
+        let Some(methods) = class_mapping.methods.get(method) else {
+            // 只有类名被混淆
+            return vec![vec![StackFrame {
+                class_name: class_mapping.original.clone(),
+                method_name: method.to_string(),
+                file_name: self.source_file(&class_mapping.original),
+                line,
+                signature: None,
+            }]];
+        };
+
+        // 内联链：最内层方法在前，调用方紧随其后，混淆区间相同且原始位置只有单个行号（如 :40）
+        let mut chains: Vec<Vec<&MethodMapping>> = Vec::new();
+        for mapping in methods {
+            match chains.last_mut() {
+                Some(chain)
+                    if chain.last().is_some_and(|last| {
+                        last.seq + 1 == mapping.seq && last.obfuscated_range == mapping.obfuscated_range
+                    }) && mapping.obfuscated_range.is_some()
+                        && matches!(mapping.original_range, Some((_, None))) =>
+                {
+                    chain.push(mapping)
+                }
+                _ => chains.push(vec![mapping]),
+            }
+        }
+
+        let mut selected: Vec<&Vec<&MethodMapping>> = match line {
+            Some(line) => {
+                let in_range: Vec<_> = chains
+                    .iter()
+                    .filter(|c| c[0].obfuscated_range.is_some_and(|(s, e)| s <= line && line <= e))
+                    .collect();
+                if !in_range.is_empty() {
+                    in_range
+                } else {
+                    let without_range: Vec<_> = chains.iter().filter(|c| c[0].obfuscated_range.is_none()).collect();
+                    if without_range.is_empty() { chains.iter().collect() } else { without_range }
+                }
+            }
+            None => chains.iter().collect(),
+        };
+
+        // 有其他候选时忽略编译器合成的方法
+        if selected.iter().any(|c| c.iter().any(|m| !m.synthesized)) {
+            selected.retain(|c| c.iter().any(|m| !m.synthesized));
+        }
+
+        let mut candidates: Vec<Vec<StackFrame>> = Vec::new();
+        for chain in selected {
+            let mut frames: Vec<StackFrame> = chain
+                .iter()
+                .filter(|m| !m.synthesized || chain.len() == 1)
+                .map(|m| self.to_frame(class_mapping, m, line))
+                .collect();
+            if line.is_none() {
+                // 没有行号时无法确定内联位置，只保留最外层方法
+                frames = frames.split_off(frames.len().saturating_sub(1));
+            }
+            if !frames.is_empty() && !candidates.contains(&frames) {
+                candidates.push(frames);
+            }
+        }
+        candidates
+    }
+}
+
+// 按映射区间计算原始行号：区间逐行对应，原始区间较短时截断到末行；没有原始区间时行号不变
+fn original_line(method: &MethodMapping, line: u32) -> u32 {
+    match (method.obfuscated_range, method.original_range) {
+        (Some((obf_start, _)), Some((start, Some(end)))) => (start + line.saturating_sub(obf_start)).min(end.max(start)),
+        (_, Some((start, _))) => start,
+        _ => line,
+    }
+}
+
+fn format_frame(prefix: &str, frame: &StackFrame, suffix: &str) -> String {
+    match frame.line {
+        Some(line) => format!("{}{}.{}({}:{}){}", prefix, frame.class_name, frame.method_name, frame.file_name, line, suffix),
+        None => format!("{}{}.{}({}){}", prefix, frame.class_name, frame.method_name, frame.file_name, suffix),
+    }
+}
+
+/// 还原整段堆栈文本
+pub fn retrace_text(mapping: &Mapping, stack_trace: &str) -> (String, Vec<RetracedFrame>) {
+    let frame_regex = Regex::new(
+        r"^(?P<prefix>.*?\bat\s+)(?P<class>[\w$.]+)\.(?P<method>[\w$<>-]+)\((?P<source>[^:()]*)(?::(?P<line>\d+))?\)(?P<suffix>.*)$",
+    )
+    .expect("内置规则无效");
+    let class_regex = Regex::new(r"[\w$]+(?:\.[\w$]+)+").expect("内置规则无效");
+
+    let mut output = Vec::new();
+    let mut frames = Vec::new();
+    for line in stack_trace.lines() {
+        let Some(captures) = frame_regex.captures(line) else {
+            // 异常类名所在行（含 Caused by:）和异常信息中的字段引用
+            let replaced = class_regex.replace_all(line, |c: &regex::Captures| {
+                mapping.original_reference(&c[0]).unwrap_or_else(|| c[0].to_string())
+            });
+            output.push(replaced.into_owned());
+            continue;
+        };
+
+        let prefix = &captures["prefix"];
+        let suffix = &captures["suffix"];
+        let line_number = captures.name("line").and_then(|m| m.as_str().parse().ok());
+        let candidates = mapping.retrace_frame(&captures["class"], &captures["method"], line_number);
+        if candidates.is_empty() {
+            output.push(line.to_string());
+            continue;
+        }
+
+        // 歧义候选按 retrace 的格式用 <OR> 标出
+        let or_prefix = match prefix.rfind("at") {
+            Some(pos) => format!("{}<OR> {}", &prefix[..pos], &prefix[pos..]),
+            None => format!("<OR> {}", prefix),
+        };
+        for (index, chain) in candidates.iter().enumerate() {
+            for frame in chain {
+                let frame_prefix = if index == 0 { prefix } else { or_prefix.as_str() };
+                output.push(format_frame(frame_prefix, frame, suffix));
+            }
+        }
+        frames.push(RetracedFrame {
+            obfuscated: line.trim().to_string(),
+            ambiguous: candidates.len() > 1,
+            candidates,
+        });
+    }
+
+    (output.join("\n"), frames)
+}
+
+/// 缓存最近一次加载的 mapping，避免每次还原都重新解析大文件
+///
+/// 克隆后共享同一份缓存，便于移入 spawn_blocking 中加载
+#[derive(Default, Clone)]
+pub struct MappingCache(Arc<Mutex<Option<CachedMapping>>>);
+
+/// (路径, 修改时间, 已解析的 mapping)
+type CachedMapping = (String, SystemTime, Arc<Mapping>);
+
+impl MappingCache {
+    pub fn get_or_load(&self, path: &Path) -> Result<Arc<Mapping>, AnalysisError> {
+        let modified = std::fs::metadata(path)
+            .and_then(|m| m.modified())
+            .map_err(|e| format!("无法读取 mapping 文件信息: {}", e))?;
+        let key = path.to_string_lossy().to_string();
+
+        if let Some((cached_path, cached_time, mapping)) = self.0.lock().map_err(|e| e.to_string())?.as_ref() {
+            if *cached_path == key && *cached_time == modified {
+                return Ok(mapping.clone());
+            }
+        }
+
+        let mapping = Arc::new(Mapping::load(path)?);
+        *self.0.lock().map_err(|e| e.to_string())? = Some((key, modified, mapping.clone()));
+        Ok(mapping)
+    }
+}
+
+/// 还原堆栈，提供 APK 时校验 mapping 与 APK 的 pg-map-id 是否一致
+pub fn retrace(mapping: &Mapping, stack_trace: &str, apk_path: Option<&Path>) -> Result<RetraceResult, AnalysisError> {
+    let mut warnings = Vec::new();
+
+    let map_id_check = match apk_path {
+        Some(apk) => {
+            let apk_pg_map_id = crate::provenance::read_pg_map_id(apk)?;
+            let mapping_pg_map_id = mapping.summary.pg_map_id.clone();
+            let matched = match (&apk_pg_map_id, &mapping_pg_map_id) {
+                (Some(a), Some(m)) => a == m,
+                _ => false,
+            };
+            if apk_pg_map_id.is_none() {
+                warnings.push("APK 的 DEX 中没有 pg-map-id 标记，无法校验 mapping 是否匹配".to_string());
+            } else if mapping_pg_map_id.is_none() {
+                warnings.push("mapping 文件头中没有 pg_map_id，无法校验是否匹配".to_string());
+            } else if !matched {
+                warnings.push("mapping 与 APK 的 pg-map-id 不一致，还原结果可能错误".to_string());
+            }
+            Some(MapIdCheck {
+                apk_pg_map_id,
+                mapping_pg_map_id,
+                matched,
+            })
+        }
+        None => None,
+    };
+
+    let (output, frames) = retrace_text(mapping, stack_trace);
+    if frames.is_empty() {
+        warnings.push("没有找到可还原的栈帧".to_string());
+    }
+    if frames.iter().any(|f| f.ambiguous) {
+        warnings.push("部分栈帧存在多个候选方法，已用 <OR> 标出".to_string());
+    }
+
+    Ok(RetraceResult {
+        output,
+        frames,
+        mapping: mapping.summary.clone(),
+        map_id_check,
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAPPING: &str = "\
+# compiler: R8
+# pg_map_id: 4a1b2c3
+com.example.app.MainActivity -> com.example.app.MainActivity:
+# {\"id\":\"sourceFile\",\"fileName\":\"MainActivity.kt\"}
+    1:3:void onCreate(android.os.Bundle):20:22 -> onCreate
+    4:4:void com.example.app.util.Helper.compute(int):55:55 -> onCreate
+    4:4:void setup():40 -> onCreate
+    4:4:void onCreate(android.os.Bundle):23 -> onCreate
+com.example.app.util.Helper -> a.b:
+    java.lang.String name -> a
+    1:1:void load(java.lang.String):10:10 -> a
+    1:1:void save(java.lang.String):30:30 -> a
+com.example.app.CrashException -> a.c:
+";
+
+    fn write_mapping(name: &str, content: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("retrace_{}_{}.txt", std::process::id(), name));
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn loads_summary_and_classes() {
+        let path = write_mapping("summary", MAPPING);
+        let mapping = Mapping::load(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(mapping.summary.compiler.as_deref(), Some("R8"));
+        assert_eq!(mapping.summary.pg_map_id.as_deref(), Some("4a1b2c3"));
+        assert_eq!(mapping.summary.class_count, 3);
+        assert_eq!(mapping.original_class("a.b"), Some("com.example.app.util.Helper"));
+    }
+
+    #[test]
+    fn expands_inline_frames_and_marks_ambiguous() {
+        let path = write_mapping("frames", MAPPING);
+        let mapping = Mapping::load(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        let inlined = mapping.retrace_frame("com.example.app.MainActivity", "onCreate", Some(4));
+        assert_eq!(inlined.len(), 1);
+        let methods: Vec<&str> = inlined[0].iter().map(|f| f.method_name.as_str()).collect();
+        assert_eq!(methods, vec!["compute", "setup", "onCreate"]);
+        assert_eq!(inlined[0][0].class_name, "com.example.app.util.Helper");
+        assert_eq!(inlined[0][2].line, Some(23));
+
+        let trace = "a.c: boom\n\tat a.b.a(SourceFile:1)\n\tat android.app.Activity.performCreate(Activity.java:8000)";
+        let (output, frames) = retrace_text(&mapping, trace);
+        assert!(output.starts_with("com.example.app.CrashException: boom"));
+        assert!(output.contains("android.app.Activity.performCreate(Activity.java:8000)"));
+        assert_eq!(frames.len(), 1);
+        assert!(frames[0].ambiguous);
+        assert_eq!(frames[0].candidates.len(), 2);
+    }
+
+    #[test]
+    fn cache_reuses_mapping_until_file_changes() {
+        let path = write_mapping("cache", MAPPING);
+        let cache = MappingCache::default();
+        let first = cache.get_or_load(&path).unwrap();
+        let second = cache.clone().get_or_load(&path).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+
+        let other = write_mapping("cache_other", MAPPING);
+        let third = cache.get_or_load(&other).unwrap();
+        assert!(!Arc::ptr_eq(&first, &third));
+
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(&other);
+        assert!(cache.get_or_load(&path).is_err());
+    }
+}
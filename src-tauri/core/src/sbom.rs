@@ -0,0 +1,627 @@
+// ==================== 软件物料清单（SBOM） ====================
+// 汇总 APK 中的 AndroidX / Kotlin / Google 库版本、原生库、第三方 SDK 和跨平台框架，
+// 输出 CycloneDX 1.5 或 SPDX 2.3 JSON。库版本来自 META-INF/*.version 和根目录的 *.properties
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, File};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use regex::bytes::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+use zip::ZipArchive;
+
+use crate::apk::PackageInfo;
+use crate::channel_batch::sha256_file;
+use crate::dex::DexFile;
+use crate::elf::ElfFile;
+use crate::error::AnalysisError;
+use crate::framework;
+use crate::manifest::read_entry;
+use crate::provenance;
+use crate::store_compliance;
+
+const TOOL_NAME: &str = "release-assistant";
+
+// 原生库中可识别版本的开源组件：(组件名, 文件名前缀限制, 版本号正则)
+// 这些库经常被静态链接进其他 .so，因此默认扫描所有原生库
+const NATIVE_PRODUCTS: &[(&str, Option<&str>, &str)] = &[
+    ("openssl", None, r"OpenSSL (\d+\.\d+\.\d+[a-z]?)[ \x00]"),
+    ("curl", None, r"libcurl/(\d+\.\d+\.\d+)"),
+    ("sqlite", Some("libsqlite"), r"\x00(3\.\d{1,2}\.\d{1,2})\x00"),
+    ("ffmpeg", None, r"FFmpeg version n?(\d+\.\d+(?:\.\d+)?)"),
+    ("zlib", None, r"(?:inflate|deflate) (\d+\.\d+\.\d+(?:\.\d+)?) Copyright"),
+    ("libpng", None, r"libpng version (\d+\.\d+\.\d+)"),
+    ("libjpeg-turbo", None, r"libjpeg-turbo version (\d+\.\d+\.\d+)"),
+    ("mbedtls", None, r"[Mm]bed ?TLS (\d+\.\d+\.\d+)"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentKind {
+    Library,
+    Framework,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SbomComponent {
+    pub kind: ComponentKind,
+    /// Maven groupId，无法确定时为空
+    pub group: Option<String>,
+    pub name: String,
+    pub version: Option<String>,
+    pub purl: Option<String>,
+    pub sha256: Option<String>,
+    /// 识别依据：文件路径或类名前缀
+    pub source: String,
+    /// ABI、Build ID、SDK 类型等附加信息
+    pub properties: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SbomResult {
+    /// cyclonedx / spdx
+    pub format: String,
+    pub package_name: String,
+    pub version_name: String,
+    pub sha256: String,
+    pub components: Vec<SbomComponent>,
+    /// 写入的文件路径，未指定输出路径时为空
+    pub output_path: Option<String>,
+    /// SBOM 文档（JSON 文本）
+    pub document: String,
+}
+
+impl SbomComponent {
+    fn new(kind: ComponentKind, name: String, source: String) -> Self {
+        SbomComponent {
+            kind,
+            group: None,
+            name,
+            version: None,
+            purl: None,
+            sha256: None,
+            source,
+            properties: BTreeMap::new(),
+        }
+    }
+
+    fn maven(group: Option<String>, name: String, version: String, source: String) -> Self {
+        let purl = group.as_ref().map(|g| format!("pkg:maven/{}/{}@{}", g, name, version));
+        SbomComponent {
+            group,
+            version: Some(version),
+            purl,
+            ..SbomComponent::new(ComponentKind::Library, name, source)
+        }
+    }
+
+    // 框架的识别依据可能就是某个原生库，需要和原生库组件区分开
+    fn bom_ref(&self) -> String {
+        match (&self.purl, self.kind) {
+            (Some(purl), _) => purl.clone(),
+            (None, ComponentKind::Framework) => format!("framework:{}", self.name),
+            (None, ComponentKind::Library) => self.source.clone(),
+        }
+    }
+}
+
+// ==================== 组件识别 ====================
+
+// META-INF/<group>_<artifact>.version，如 androidx.core_core.version、kotlinx_coroutines_core.version
+fn version_file_coordinates(stem: &str) -> (Option<String>, String) {
+    if let Some(rest) = stem.strip_prefix("kotlinx_") {
+        return (Some("org.jetbrains.kotlinx".to_string()), format!("kotlinx-{}", rest.replace('_', "-")));
+    }
+    match stem.split_once('_') {
+        Some((group, artifact)) if group.contains('.') => (Some(group.to_string()), artifact.to_string()),
+        _ => (None, stem.to_string()),
+    }
+}
+
+// 根目录 <artifact>.properties 由 Google Play 服务 / Firebase 库写入，包含 version 和 client
+fn properties_group(artifact: &str) -> Option<&'static str> {
+    if artifact.starts_with("firebase-") {
+        Some("com.google.firebase")
+    } else if artifact.starts_with("play-services-") {
+        Some("com.google.android.gms")
+    } else if artifact.starts_with("transport-") {
+        Some("com.google.android.datatransport")
+    } else {
+        None
+    }
+}
+
+fn library_components(archive: &mut ZipArchive<File>, entry_names: &[String]) -> Vec<SbomComponent> {
+    let mut components = Vec::new();
+    for name in entry_names {
+        if let Some(stem) = name.strip_prefix("META-INF/").and_then(|n| n.strip_suffix(".version")) {
+            if stem.contains('/') {
+                continue;
+            }
+            let Ok(data) = read_entry(archive, name) else { continue };
+            let version = String::from_utf8_lossy(&data).trim().to_string();
+            let (group, artifact) = version_file_coordinates(stem);
+            components.push(SbomComponent::maven(group, artifact, version, name.clone()));
+        } else if name.ends_with(".properties") && !name.contains('/') {
+            let Ok(data) = read_entry(archive, name) else { continue };
+            let properties = provenance::parse_properties(&String::from_utf8_lossy(&data));
+            let (Some(version), Some(client)) = (properties.get("version"), properties.get("client")) else {
+                continue;
+            };
+            let group = properties_group(client).map(|g| g.to_string());
+            components.push(SbomComponent::maven(group, client.clone(), version.clone(), name.clone()));
+        }
+    }
+    components
+}
+
+fn native_components(archive: &mut ZipArchive<File>, entry_names: &[String]) -> Vec<SbomComponent> {
+    let products: Vec<(&str, Option<&str>, Regex)> = NATIVE_PRODUCTS
+        .iter()
+        .map(|(product, prefix, pattern)| (*product, *prefix, Regex::new(pattern).expect("内置规则无效")))
+        .collect();
+    // (组件名, 版本) -> 包含该组件的原生库，不同 ABI 的同一版本合并为一个组件
+    let mut found: BTreeMap<(&str, String), Vec<String>> = BTreeMap::new();
+
+    let mut components = Vec::new();
+    for name in entry_names {
+        let Some((abi, file_name)) = name.strip_prefix("lib/").and_then(|n| n.split_once('/')) else { continue };
+        if !file_name.ends_with(".so") || file_name.contains('/') {
+            continue;
+        }
+        let Ok(data) = read_entry(archive, name) else { continue };
+        let mut component = SbomComponent::new(ComponentKind::Library, file_name.to_string(), name.clone());
+        component.sha256 = Some(hex::encode(Sha256::digest(&data)));
+        component.properties.insert("abi".to_string(), abi.to_string());
+        if let Some(build_id) = ElfFile::parse(&data).ok().and_then(|elf| elf.build_id()) {
+            component.properties.insert("build_id".to_string(), build_id);
+        }
+        components.push(component);
+
+        for (product, prefix, regex) in &products {
+            if prefix.is_some_and(|p| !file_name.starts_with(p)) {
+                continue;
+            }
+            if let Some(version) = regex.captures(&data).and_then(|c| c.get(1)) {
+                let version = String::from_utf8_lossy(version.as_bytes()).to_string();
+                found.entry((product, version)).or_default().push(name.clone());
+            }
+        }
+    }
+
+    for ((product, version), files) in found {
+        let mut component = SbomComponent::new(ComponentKind::Library, product.to_string(), files[0].clone());
+        component.purl = Some(format!("pkg:generic/{}@{}", product, version));
+        component.version = Some(version);
+        component.properties.insert("found_in".to_string(), files.join(", "));
+        components.push(component);
+    }
+    components
+}
+
+fn sdk_components(archive: &mut ZipArchive<File>, entry_names: &[String]) -> Result<Vec<SbomComponent>, String> {
+    let mut classes = Vec::new();
+    for name in entry_names.iter().filter(|n| !n.contains('/') && n.ends_with(".dex")) {
+        let Ok(data) = read_entry(archive, name) else { continue };
+        if let Ok(dex) = DexFile::parse(&data) {
+            classes.extend(dex.class_names());
+        }
+    }
+    let profile = store_compliance::load_profile(None)?;
+    Ok(store_compliance::detect_sdks(&classes, &profile.sdks)
+        .into_iter()
+        .map(|sdk| {
+            let source = sdk.packages.join(", ");
+            let mut component = SbomComponent::new(ComponentKind::Library, sdk.name, source);
+            component.properties.insert("category".to_string(), sdk.category);
+            component.properties.insert("class_count".to_string(), sdk.class_count.to_string());
+            component
+        })
+        .collect())
+}
+
+fn framework_components(path: &Path) -> Result<Vec<SbomComponent>, String> {
+    Ok(framework::detect_frameworks(path)?
+        .into_iter()
+        .map(|found| {
+            let source = found.evidence.first().cloned().unwrap_or_else(|| found.name.clone());
+            let mut component = SbomComponent::new(ComponentKind::Framework, found.name, source);
+            component.version = found.version;
+            component.properties = found.details;
+            component
+        })
+        .collect())
+}
+
+/// 识别 APK 中的组件
+pub(crate) fn collect_components(path: &Path) -> Result<Vec<SbomComponent>, String> {
+    let file = File::open(path).map_err(|e| format!("无法打开文件: {}", e))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("无法解析 ZIP 文件: {}", e))?;
+    let mut entry_names: Vec<String> = archive.file_names().map(|s| s.to_string()).collect();
+    entry_names.sort();
+
+    let mut components = library_components(&mut archive, &entry_names);
+    components.extend(sdk_components(&mut archive, &entry_names)?);
+    components.extend(framework_components(path)?);
+    components.extend(native_components(&mut archive, &entry_names));
+    Ok(components)
+}
+
+// ==================== 文档输出 ====================
+
+/// 当前 UTC 时间，格式为 RFC 3339
+pub fn now_utc_timestamp() -> String {
+    let secs = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0) as i64;
+    utc_timestamp(secs)
+}
+
+/// Unix 时间戳（秒）转换为 RFC 3339 格式的 UTC 时间（如 2025-01-01T00:00:00Z）
+pub fn utc_timestamp(secs: i64) -> String {
+    let (days, rem) = (secs.div_euclid(86400), secs.rem_euclid(86400));
+    // 公历日期换算（Howard Hinnant 的 civil_from_days）
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+// 同一库可能同时由 .version 和 .properties 文件识别出来，重复的 bom-ref 加序号区分
+fn unique_bom_refs(components: &[SbomComponent]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    components
+        .iter()
+        .map(|c| {
+            let base = c.bom_ref();
+            let mut candidate = base.clone();
+            let mut index = 1;
+            while !seen.insert(candidate.clone()) {
+                index += 1;
+                candidate = format!("{}#{}", base, index);
+            }
+            candidate
+        })
+        .collect()
+}
+
+fn cyclonedx_document(info: &PackageInfo, sha256: &str, components: &[SbomComponent]) -> Value {
+    let app_ref = format!("app:{}", info.package_name);
+    let refs = unique_bom_refs(components);
+    let items: Vec<Value> = components
+        .iter()
+        .zip(&refs)
+        .map(|(c, bom_ref)| {
+            let mut item = json!({
+                "type": match c.kind {
+                    ComponentKind::Library => "library",
+                    ComponentKind::Framework => "framework",
+                },
+                "bom-ref": bom_ref,
+                "name": c.name,
+            });
+            if let Some(group) = &c.group {
+                item["group"] = json!(group);
+            }
+            if let Some(version) = &c.version {
+                item["version"] = json!(version);
+            }
+            if let Some(purl) = &c.purl {
+                item["purl"] = json!(purl);
+            }
+            if let Some(hash) = &c.sha256 {
+                item["hashes"] = json!([{ "alg": "SHA-256", "content": hash }]);
+            }
+            let mut properties = vec![json!({ "name": format!("{}:source", TOOL_NAME), "value": c.source })];
+            properties.extend(
+                c.properties
+                    .iter()
+                    .map(|(k, v)| json!({ "name": format!("{}:{}", TOOL_NAME, k), "value": v })),
+            );
+            item["properties"] = json!(properties);
+            item
+        })
+        .collect();
+
+    json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": "1.5",
+        "serialNumber": format!("urn:uuid:{}", Uuid::new_v4()),
+        "version": 1,
+        "metadata": {
+            "timestamp": now_utc_timestamp(),
+            "tools": {
+                "components": [{ "type": "application", "name": TOOL_NAME, "version": env!("CARGO_PKG_VERSION") }]
+            },
+            "component": {
+                "type": "application",
+                "bom-ref": app_ref,
+                "name": info.package_name,
+                "version": info.version_name,
+                "hashes": [{ "alg": "SHA-256", "content": sha256 }],
+            },
+        },
+        "components": items,
+        "dependencies": [{
+            "ref": app_ref,
+            "dependsOn": refs,
+        }],
+    })
+}
+
+// SPDXID 只允许字母、数字、. 和 -
+fn spdx_id(value: &str) -> String {
+    let id: String = value
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '-' })
+        .collect();
+    format!("SPDXRef-{}", id)
+}
+
+fn spdx_package(
+    id: &str,
+    name: &str,
+    version: Option<&str>,
+    sha256: Option<&str>,
+    purl: Option<&str>,
+    purpose: &str,
+) -> Value {
+    let mut package = json!({
+        "SPDXID": id,
+        "name": name,
+        "downloadLocation": "NOASSERTION",
+        "filesAnalyzed": false,
+        "licenseConcluded": "NOASSERTION",
+        "licenseDeclared": "NOASSERTION",
+        "copyrightText": "NOASSERTION",
+        "primaryPackagePurpose": purpose,
+    });
+    if let Some(version) = version {
+        package["versionInfo"] = json!(version);
+    }
+    if let Some(hash) = sha256 {
+        package["checksums"] = json!([{ "algorithm": "SHA256", "checksumValue": hash }]);
+    }
+    if let Some(purl) = purl {
+        package["externalRefs"] = json!([{
+            "referenceCategory": "PACKAGE-MANAGER",
+            "referenceType": "purl",
+            "referenceLocator": purl,
+        }]);
+    }
+    package
+}
+
+fn spdx_document(info: &PackageInfo, sha256: &str, components: &[SbomComponent]) -> Value {
+    let app_id = spdx_id(&format!("app-{}", info.package_name));
+    let mut packages = vec![spdx_package(
+        &app_id,
+        &info.package_name,
+        Some(&info.version_name),
+        Some(sha256),
+        None,
+        "APPLICATION",
+    )];
+    let mut relationships = vec![json!({
+        "spdxElementId": "SPDXRef-DOCUMENT",
+        "relationshipType": "DESCRIBES",
+        "relatedSpdxElement": app_id,
+    })];
+    for (index, c) in components.iter().enumerate() {
+        // SDK 名称可能是中文，用 bom-ref 生成 SPDXID，加上序号避免重复
+        let id = spdx_id(&format!("{}-{}", index + 1, c.bom_ref()));
+        let purpose = match c.kind {
+            ComponentKind::Library => "LIBRARY",
+            ComponentKind::Framework => "FRAMEWORK",
+        };
+        packages.push(spdx_package(
+            &id,
+            &c.name,
+            c.version.as_deref(),
+            c.sha256.as_deref(),
+            c.purl.as_deref(),
+            purpose,
+        ));
+        relationships.push(json!({
+            "spdxElementId": app_id,
+            "relationshipType": "CONTAINS",
+            "relatedSpdxElement": id,
+        }));
+    }
+
+    let document_name = format!("{}-{}", info.package_name, info.version_name);
+    json!({
+        "spdxVersion": "SPDX-2.3",
+        "dataLicense": "CC0-1.0",
+        "SPDXID": "SPDXRef-DOCUMENT",
+        "name": document_name,
+        "documentNamespace": format!("https://spdx.org/spdxdocs/{}-{}", document_name, Uuid::new_v4()),
+        "creationInfo": {
+            "created": now_utc_timestamp(),
+            "creators": [format!("Tool: {}-{}", TOOL_NAME, env!("CARGO_PKG_VERSION"))],
+        },
+        "packages": packages,
+        "relationships": relationships,
+    })
+}
+
+/// 生成 SBOM，format 为 cyclonedx 或 spdx，指定 output_path 时同时写入文件
+pub fn generate_sbom(
+    info: &PackageInfo,
+    path: &Path,
+    format: &str,
+    output_path: Option<&Path>,
+) -> Result<SbomResult, AnalysisError> {
+    if !matches!(format, "cyclonedx" | "spdx") {
+        return Err(format!("不支持的 SBOM 格式: {}（可选 cyclonedx、spdx）", format).into());
+    }
+    let sha256 = sha256_file(path)?;
+    let components = collect_components(path)?;
+
+    let document = match format {
+        "cyclonedx" => cyclonedx_document(info, &sha256, &components),
+        _ => spdx_document(info, &sha256, &components),
+    };
+    let document = serde_json::to_string_pretty(&document).map_err(|e| format!("序列化 SBOM 失败: {}", e))?;
+    if let Some(output_path) = output_path {
+        fs::write(output_path, &document).map_err(|e| format!("写入 SBOM 失败: {}", e))?;
+    }
+
+    Ok(SbomResult {
+        format: format.to_string(),
+        package_name: info.package_name.clone(),
+        version_name: info.version_name.clone(),
+        sha256,
+        components,
+        output_path: output_path.map(|p| p.to_string_lossy().to_string()),
+        document,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timestamps_for_known_epochs() {
+        assert_eq!(utc_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(utc_timestamp(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(utc_timestamp(951_868_799), "2000-02-29T23:59:59Z");
+        assert_eq!(utc_timestamp(1_709_164_800), "2024-02-29T00:00:00Z");
+        assert_eq!(utc_timestamp(1_709_251_200), "2024-03-01T00:00:00Z");
+        assert_eq!(utc_timestamp(1_735_689_599), "2024-12-31T23:59:59Z");
+        assert_eq!(utc_timestamp(2_147_483_648), "2038-01-19T03:14:08Z");
+        assert_eq!(utc_timestamp(-1), "1969-12-31T23:59:59Z");
+    }
+
+    fn package_info() -> PackageInfo {
+        PackageInfo {
+            package_name: "com.example".to_string(),
+            version_name: "1.2.0".to_string(),
+            version_code: "12".to_string(),
+            min_sdk_version: "21".to_string(),
+            target_sdk_version: "35".to_string(),
+            compile_sdk_version: "35".to_string(),
+            permissions: Vec::new(),
+            activities: Vec::new(),
+            services: Vec::new(),
+            receivers: Vec::new(),
+            providers: Vec::new(),
+            file_size: 0,
+            file_size_readable: String::new(),
+            icon_base64: None,
+            protection: None,
+            zip_integrity: None,
+            frameworks: Vec::new(),
+            build_provenance: None,
+            platform: "android".to_string(),
+            ios: None,
+            harmony: None,
+        }
+    }
+
+    fn sample_components() -> Vec<SbomComponent> {
+        let mut native = SbomComponent::new(
+            ComponentKind::Library,
+            "libflutter.so".to_string(),
+            "lib/arm64-v8a/libflutter.so".to_string(),
+        );
+        native.sha256 = Some("00".repeat(32));
+        let mut openssl = SbomComponent::new(ComponentKind::Library, "openssl".to_string(), "lib/arm64-v8a/libcrypto.so".to_string());
+        openssl.purl = Some("pkg:generic/openssl@1.1.1w".to_string());
+        vec![
+            SbomComponent::maven(
+                Some("androidx.core".to_string()),
+                "core".to_string(),
+                "1.13.1".to_string(),
+                "META-INF/androidx.core_core.version".to_string(),
+            ),
+            // 同一个库由两个文件识别出来
+            SbomComponent::maven(
+                Some("com.google.android.gms".to_string()),
+                "play-services-base".to_string(),
+                "18.5.0".to_string(),
+                "META-INF/com.google.android.gms_play-services-base.version".to_string(),
+            ),
+            SbomComponent::maven(
+                Some("com.google.android.gms".to_string()),
+                "play-services-base".to_string(),
+                "18.5.0".to_string(),
+                "play-services-base.properties".to_string(),
+            ),
+            SbomComponent::new(ComponentKind::Library, "友盟+".to_string(), "com.umeng.".to_string()),
+            SbomComponent::new(ComponentKind::Framework, "Flutter".to_string(), "lib/arm64-v8a/libflutter.so".to_string()),
+            native,
+            openssl,
+        ]
+    }
+
+    #[test]
+    fn cyclonedx_refs_are_unique_and_resolve() {
+        let document = cyclonedx_document(&package_info(), "ab", &sample_components());
+        let refs: Vec<&str> = document["components"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| c["bom-ref"].as_str().unwrap())
+            .collect();
+        assert_eq!(refs.len(), 7);
+        let unique: HashSet<&str> = refs.iter().copied().collect();
+        assert_eq!(unique.len(), refs.len(), "{:?}", refs);
+        assert!(refs.contains(&"pkg:maven/com.google.android.gms/play-services-base@18.5.0#2"));
+        assert!(refs.contains(&"framework:Flutter"));
+        assert!(refs.contains(&"lib/arm64-v8a/libflutter.so"));
+
+        let app_ref = document["metadata"]["component"]["bom-ref"].as_str().unwrap();
+        assert!(!unique.contains(app_ref));
+        let dependencies = document["dependencies"].as_array().unwrap();
+        assert_eq!(dependencies[0]["ref"], app_ref);
+        for dependency in dependencies {
+            for target in dependency["dependsOn"].as_array().unwrap() {
+                assert!(unique.contains(target.as_str().unwrap()), "未定义的依赖 {}", target);
+            }
+        }
+        assert_eq!(dependencies[0]["dependsOn"].as_array().unwrap().len(), refs.len());
+    }
+
+    #[test]
+    fn spdx_ids_are_valid_and_unique() {
+        let document = spdx_document(&package_info(), "ab", &sample_components());
+        let valid = regex::Regex::new(r"^SPDXRef-[A-Za-z0-9.-]+$").unwrap();
+        let ids: Vec<&str> = document["packages"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| p["SPDXID"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids.len(), 8);
+        for id in &ids {
+            assert!(valid.is_match(id), "无效的 SPDXID {}", id);
+        }
+        let unique: HashSet<&str> = ids.iter().copied().collect();
+        assert_eq!(unique.len(), ids.len());
+
+        for relationship in document["relationships"].as_array().unwrap() {
+            for key in ["spdxElementId", "relatedSpdxElement"] {
+                let id = relationship[key].as_str().unwrap();
+                assert!(id == "SPDXRef-DOCUMENT" || unique.contains(id), "未定义的 SPDXID {}", id);
+            }
+        }
+    }
+}
@@ -0,0 +1,298 @@
+// ==================== APK 签名 ====================
+// 校验 v1/v2/v3/v4 签名是否存在，以及调用 Android SDK build-tools 中的 apksigner 完成签名
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+use zip::ZipArchive;
+
+use crate::apk::format_file_size;
+use crate::signing_block;
+
+// 通过环境变量传递密码，避免出现在进程参数中
+const KEYSTORE_PASSWORD_ENV: &str = "RELEASE_ASSISTANT_KS_PASS";
+const KEY_PASSWORD_ENV: &str = "RELEASE_ASSISTANT_KEY_PASS";
+
+#[derive(Debug)]
+pub enum SigningError {
+    /// APK 文件不存在
+    NotFound,
+    /// 扩展名不是 .apk
+    NotApk,
+    /// 读取 APK 失败
+    Io(std::io::Error),
+    /// 无法解析 APK 的 ZIP 结构
+    Zip(zip::result::ZipError),
+    /// 签名文件（keystore）不存在
+    KeystoreNotFound,
+    /// 无法启动 apksigner
+    ApksignerUnavailable { program: String, source: std::io::Error },
+    /// apksigner 返回失败，附带其错误输出
+    ApksignerFailed(String),
+}
+
+impl fmt::Display for SigningError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SigningError::NotFound => write!(f, "文件不存在"),
+            SigningError::NotApk => write!(f, "不是有效的 APK 文件"),
+            SigningError::Io(e) => write!(f, "无法读取 APK 文件: {}", e),
+            SigningError::Zip(e) => write!(f, "无法解析 APK 文件: {}", e),
+            SigningError::KeystoreNotFound => write!(f, "签名文件不存在"),
+            SigningError::ApksignerUnavailable { program, source } => write!(
+                f,
+                "无法运行 apksigner ({}): {}，请确认已安装 Android SDK build-tools",
+                program, source
+            ),
+            SigningError::ApksignerFailed(stderr) => write!(f, "apksigner 签名失败: {}", stderr),
+        }
+    }
+}
+
+impl std::error::Error for SigningError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SigningError::Io(e) => Some(e),
+            SigningError::Zip(e) => Some(e),
+            SigningError::ApksignerUnavailable { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for SigningError {
+    fn from(e: std::io::Error) -> Self {
+        SigningError::Io(e)
+    }
+}
+
+impl From<zip::result::ZipError> for SigningError {
+    fn from(e: zip::result::ZipError) -> Self {
+        SigningError::Zip(e)
+    }
+}
+
+// ==================== 签名校验 ====================
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SignatureInfo {
+    pub version: String,
+    pub present: bool,
+    pub details: Option<String>,
+    pub certificate: Option<CertificateInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CertificateInfo {
+    pub issuer: String,
+    pub subject: String,
+    pub valid_from: String,
+    pub valid_to: String,
+    pub signature_algorithm: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApkSignatureResult {
+    pub file_name: String,
+    pub file_size: String,
+    #[serde(alias = "signatures")]
+    pub signatures: HashMap<String, SignatureInfo>,
+    pub warnings: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+/// 检查 APK 包含哪些签名方案，v4 签名为同目录下的 .apk.idsig 文件
+pub fn verify_apk(path: &Path) -> Result<ApkSignatureResult, SigningError> {
+    if !path.exists() {
+        return Err(SigningError::NotFound);
+    }
+    if path.extension().and_then(|e| e.to_str()) != Some("apk") {
+        return Err(SigningError::NotApk);
+    }
+
+    let file_size = std::fs::metadata(path)?.len();
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("unknown.apk")
+        .to_string();
+
+    let archive = ZipArchive::new(std::fs::File::open(path)?)?;
+
+    let mut warnings = Vec::new();
+    let mut errors = Vec::new();
+    let mut signatures = HashMap::new();
+
+    // 检查 v1 签名 (JAR 签名)，找到任意一个签名文件即可
+    const V1_FILES: &[(&str, &str)] = &[
+        ("META-INF/MANIFEST.MF", ""),
+        ("META-INF/", ".SF"),
+        ("META-INF/", ".RSA"),
+        ("META-INF/", ".DSA"),
+        ("META-INF/", ".EC"),
+    ];
+    let v1_file = V1_FILES.iter().find_map(|(prefix, suffix)| {
+        archive.file_names().find(|name| {
+            if suffix.is_empty() {
+                name == prefix
+            } else {
+                name.starts_with(prefix) && name.ends_with(suffix)
+            }
+        })
+    });
+    let v1_present = v1_file.is_some();
+
+    signatures.insert(
+        "v1".to_string(),
+        SignatureInfo {
+            version: "1".to_string(),
+            present: v1_present,
+            details: Some(match v1_file {
+                Some(name) => format!("找到签名文件: {}", name),
+                None => "未找到 v1 签名文件".to_string(),
+            }),
+            certificate: None, // v1 签名证书解析较为复杂，暂不实现
+        },
+    );
+    if !v1_present {
+        warnings.push("未检测到 v1 签名,可能无法在 Android 7.0 以下设备上安装".to_string());
+    }
+
+    // v2/v3 签名位于 APK 签名块中
+    let signing_block = std::fs::File::open(path)
+        .map_err(|e| format!("无法打开 APK 文件: {}", e))
+        .and_then(|mut f| signing_block::read_layout(&mut f))
+        .map(|layout| layout.signing_block)
+        .unwrap_or_else(|e| {
+            warnings.push(format!("解析 APK 签名块失败: {}", e));
+            None
+        });
+    let block_contains = |id: u32| signing_block.as_ref().map(|b| b.contains(id)).unwrap_or(false);
+
+    // 检查 v2 签名 (APK Signature Scheme v2)
+    let v2_present = block_contains(signing_block::APK_SIGNATURE_SCHEME_V2_ID);
+    signatures.insert(
+        "v2".to_string(),
+        SignatureInfo {
+            version: "2".to_string(),
+            present: v2_present,
+            details: Some(if v2_present {
+                "签名块中包含 v2 签名".to_string()
+            } else {
+                "未检测到 v2 签名".to_string()
+            }),
+            certificate: None,
+        },
+    );
+    if !v2_present {
+        warnings.push("未检测到 v2 签名,可能无法在 Android 7.0+ 设备上安装".to_string());
+    }
+
+    // 检查 v3 签名 (APK Signature Scheme v3)
+    let v3_present = block_contains(signing_block::APK_SIGNATURE_SCHEME_V3_ID)
+        || block_contains(signing_block::APK_SIGNATURE_SCHEME_V31_ID);
+    signatures.insert(
+        "v3".to_string(),
+        SignatureInfo {
+            version: "3".to_string(),
+            present: v3_present,
+            details: Some(if v3_present {
+                "签名块中包含 v3 签名".to_string()
+            } else {
+                "未检测到 v3 签名".to_string()
+            }),
+            certificate: None,
+        },
+    );
+    if !v3_present {
+        warnings.push("未检测到 v3 签名,建议升级到 v3 签名以支持密钥轮换".to_string());
+    }
+
+    // 检查 v4 签名 (APK Signature Scheme v4)
+    let mut idsig = path.as_os_str().to_owned();
+    idsig.push(".idsig");
+    let v4_present = Path::new(&idsig).exists();
+    signatures.insert(
+        "v4".to_string(),
+        SignatureInfo {
+            version: "4".to_string(),
+            present: v4_present,
+            details: Some(if v4_present {
+                "找到 v4 签名文件 (.idsig)".to_string()
+            } else {
+                "未检测到 v4 签名".to_string()
+            }),
+            certificate: None,
+        },
+    );
+    if !v4_present {
+        warnings.push("未检测到 v4 签名,Android 11+ 设备将使用完整验证".to_string());
+    }
+
+    if !v1_present && !v2_present && !v3_present {
+        errors.push("APK 未签名!这是一个严重的错误,应用将无法安装".to_string());
+    }
+
+    Ok(ApkSignatureResult {
+        file_name,
+        file_size: format_file_size(file_size),
+        signatures,
+        warnings,
+        errors,
+    })
+}
+
+// ==================== apksigner 签名 ====================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignOptions {
+    pub keystore_path: String,
+    pub keystore_password: String,
+    pub key_alias: Option<String>,
+    /// 未指定时与 keystore 密码相同
+    pub key_password: Option<String>,
+    /// apksigner 路径，未指定时从 PATH 中查找
+    pub apksigner_path: Option<String>,
+}
+
+/// 使用 apksigner 对 APK 原地签名
+pub fn sign_apk(apk_path: &Path, options: &SignOptions) -> Result<(), SigningError> {
+    if !Path::new(&options.keystore_path).exists() {
+        return Err(SigningError::KeystoreNotFound);
+    }
+
+    let apksigner = options.apksigner_path.clone().unwrap_or_else(|| {
+        if cfg!(windows) { "apksigner.bat" } else { "apksigner" }.to_string()
+    });
+
+    let mut command = Command::new(&apksigner);
+    command
+        .arg("sign")
+        .arg("--ks")
+        .arg(&options.keystore_path)
+        .arg("--ks-pass")
+        .arg(format!("env:{}", KEYSTORE_PASSWORD_ENV))
+        .env(KEYSTORE_PASSWORD_ENV, &options.keystore_password);
+    if let Some(alias) = &options.key_alias {
+        command.arg("--ks-key-alias").arg(alias);
+    }
+    if let Some(key_password) = &options.key_password {
+        command
+            .arg("--key-pass")
+            .arg(format!("env:{}", KEY_PASSWORD_ENV))
+            .env(KEY_PASSWORD_ENV, key_password);
+    }
+    command.arg(apk_path);
+
+    let output = command
+        .output()
+        .map_err(|source| SigningError::ApksignerUnavailable { program: apksigner, source })?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(SigningError::ApksignerFailed(stderr.trim().to_string()));
+    }
+    Ok(())
+}
@@ -0,0 +1,355 @@
+// ==================== APK 签名块解析 ====================
+// APK 结构：[ZIP 条目][APK Signing Block][中央目录][EOCD]
+// 签名块格式：size(u64) + 若干 (len(u64) id(u32) value) + size(u64) + "APK Sig Block 42"
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+
+const EOCD_SIGNATURE: u32 = 0x0605_4b50;
+const EOCD_MIN_SIZE: u64 = 22;
+const MAX_COMMENT_SIZE: u64 = 0xffff;
+pub const SIGNING_BLOCK_MAGIC: &[u8; 16] = b"APK Sig Block 42";
+
+pub const APK_SIGNATURE_SCHEME_V2_ID: u32 = 0x7109_871a;
+pub const APK_SIGNATURE_SCHEME_V3_ID: u32 = 0xf053_68c0;
+pub const APK_SIGNATURE_SCHEME_V31_ID: u32 = 0x1b93_ad61;
+pub const VERITY_PADDING_ID: u32 = 0x4272_6577;
+pub const WALLE_CHANNEL_ID: u32 = 0x7177_7777;
+pub const VASDOLLY_CHANNEL_ID: u32 = 0x8811_55ff;
+
+// 签名块按 4096 字节对齐（供 fs-verity 使用）
+const SIGNING_BLOCK_ALIGNMENT: u64 = 4096;
+
+/// 签名块中已知 ID 的名称
+pub fn block_id_name(id: u32) -> String {
+    match id {
+        APK_SIGNATURE_SCHEME_V2_ID => "APK Signature Scheme v2".to_string(),
+        APK_SIGNATURE_SCHEME_V3_ID => "APK Signature Scheme v3".to_string(),
+        APK_SIGNATURE_SCHEME_V31_ID => "APK Signature Scheme v3.1".to_string(),
+        VERITY_PADDING_ID => "Verity padding".to_string(),
+        WALLE_CHANNEL_ID => "Walle channel".to_string(),
+        VASDOLLY_CHANNEL_ID => "VasDolly channel".to_string(),
+        0x6dff_800d | 0x2b09_189e => "Source stamp".to_string(),
+        0x504b_4453 => "Dependency info".to_string(),
+        0x2146_444e => "Google Play frosting".to_string(),
+        _ => format!("0x{:08x}", id),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SigningBlock {
+    /// 签名块在文件中的起始偏移
+    pub offset: u64,
+    pub pairs: Vec<(u32, Vec<u8>)>,
+}
+
+impl SigningBlock {
+    pub fn get(&self, id: u32) -> Option<&[u8]> {
+        self.pairs.iter().find(|(pair_id, _)| *pair_id == id).map(|(_, v)| v.as_slice())
+    }
+
+    pub fn contains(&self, id: u32) -> bool {
+        self.get(id).is_some()
+    }
+}
+
+/// APK 尾部结构
+#[derive(Debug, Clone)]
+pub struct ApkLayout {
+    pub eocd_offset: u64,
+    /// EOCD 中记录的条目总数
+    pub entry_count: u16,
+    pub central_directory_offset: u64,
+    pub central_directory_size: u64,
+    pub comment: Vec<u8>,
+    pub signing_block: Option<SigningBlock>,
+}
+
+fn u16_at(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([data[offset], data[offset + 1]])
+}
+
+fn u32_at(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+}
+
+fn u64_at(data: &[u8], offset: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&data[offset..offset + 8]);
+    u64::from_le_bytes(bytes)
+}
+
+/// 解析 APK 的 EOCD、中央目录位置和签名块
+pub fn read_layout(file: &mut File) -> Result<ApkLayout, String> {
+    let file_size = file.metadata().map_err(|e| format!("无法读取文件信息: {}", e))?.len();
+    if file_size < EOCD_MIN_SIZE {
+        return Err("文件过小，不是有效的 ZIP 文件".to_string());
+    }
+
+    // 从文件末尾向前查找 EOCD（注释最长 65535 字节）
+    let tail_size = file_size.min(EOCD_MIN_SIZE + MAX_COMMENT_SIZE);
+    let tail_start = file_size - tail_size;
+    let mut tail = vec![0u8; tail_size as usize];
+    file.seek(SeekFrom::Start(tail_start)).map_err(|e| e.to_string())?;
+    file.read_exact(&mut tail).map_err(|e| format!("读取文件尾部失败: {}", e))?;
+
+    let eocd_pos = (0..=tail.len() - EOCD_MIN_SIZE as usize)
+        .rev()
+        .find(|&pos| {
+            u32_at(&tail, pos) == EOCD_SIGNATURE
+                && pos + EOCD_MIN_SIZE as usize + u16_at(&tail, pos + 20) as usize == tail.len()
+        })
+        .ok_or("未找到 ZIP 中央目录结束记录 (EOCD)")?;
+
+    let entry_count = u16_at(&tail, eocd_pos + 10);
+    let central_directory_size = u32_at(&tail, eocd_pos + 12) as u64;
+    let central_directory_offset = u32_at(&tail, eocd_pos + 16) as u64;
+    let comment = tail[eocd_pos + EOCD_MIN_SIZE as usize..].to_vec();
+    let eocd_offset = tail_start + eocd_pos as u64;
+
+    if central_directory_offset + central_directory_size > eocd_offset {
+        return Err("中央目录位置无效".to_string());
+    }
+
+    let signing_block = read_signing_block(file, central_directory_offset)?;
+
+    Ok(ApkLayout {
+        eocd_offset,
+        entry_count,
+        central_directory_offset,
+        central_directory_size,
+        comment,
+        signing_block,
+    })
+}
+
+fn read_signing_block<R: Read + Seek>(
+    file: &mut R,
+    central_directory_offset: u64,
+) -> Result<Option<SigningBlock>, String> {
+    if central_directory_offset < 32 {
+        return Ok(None);
+    }
+
+    // 中央目录前 24 字节：size_of_block(u64) + magic
+    let mut footer = [0u8; 24];
+    file.seek(SeekFrom::Start(central_directory_offset - 24)).map_err(|e| e.to_string())?;
+    file.read_exact(&mut footer).map_err(|e| e.to_string())?;
+    if &footer[8..24] != SIGNING_BLOCK_MAGIC {
+        return Ok(None);
+    }
+
+    let block_size = u64_at(&footer, 0);
+    // 整个签名块 = 开头的 size 字段(8) + block_size
+    let total_size = block_size.checked_add(8).ok_or("签名块长度无效")?;
+    if block_size < 24 || total_size > central_directory_offset {
+        return Err("APK 签名块大小无效".to_string());
+    }
+    let block_offset = central_directory_offset - total_size;
+
+    let mut block = vec![0u8; total_size as usize];
+    file.seek(SeekFrom::Start(block_offset)).map_err(|e| e.to_string())?;
+    file.read_exact(&mut block).map_err(|e| format!("读取签名块失败: {}", e))?;
+
+    if u64_at(&block, 0) != block_size {
+        return Err("APK 签名块首尾大小不一致".to_string());
+    }
+
+    // 解析 ID-value 对
+    let pairs_end = block.len() - 24;
+    let mut pos = 8;
+    let mut pairs = Vec::new();
+    while pos < pairs_end {
+        if pos + 12 > pairs_end {
+            return Err("APK 签名块条目不完整".to_string());
+        }
+        // 长度来自文件内容，需防止溢出
+        let pair_end = usize::try_from(u64_at(&block, pos))
+            .ok()
+            .and_then(|pair_len| (pos + 8).checked_add(pair_len))
+            .ok_or("签名块长度无效")?;
+        if pair_end < pos + 12 || pair_end > pairs_end {
+            return Err("APK 签名块条目长度无效".to_string());
+        }
+        let id = u32_at(&block, pos + 8);
+        pairs.push((id, block[pos + 12..pair_end].to_vec()));
+        pos = pair_end;
+    }
+
+    Ok(Some(SigningBlock {
+        offset: block_offset,
+        pairs,
+    }))
+}
+
+/// 按给定 ID-value 对重新构建签名块；pad_to_alignment 为 true 时补齐到 4096 字节
+pub fn build_signing_block(pairs: &[(u32, Vec<u8>)], pad_to_alignment: bool) -> Vec<u8> {
+    let mut pairs_data = Vec::new();
+    for (id, value) in pairs.iter().filter(|(id, _)| *id != VERITY_PADDING_ID) {
+        pairs_data.extend_from_slice(&(value.len() as u64 + 4).to_le_bytes());
+        pairs_data.extend_from_slice(&id.to_le_bytes());
+        pairs_data.extend_from_slice(value);
+    }
+
+    if pad_to_alignment {
+        // 整块大小 = 8 + pairs + 8 + 16，padding 条目自身至少 12 字节
+        let unpadded = 32 + pairs_data.len() as u64;
+        let remainder = unpadded % SIGNING_BLOCK_ALIGNMENT;
+        if remainder != 0 {
+            let mut padding = SIGNING_BLOCK_ALIGNMENT - remainder;
+            if padding < 12 {
+                padding += SIGNING_BLOCK_ALIGNMENT;
+            }
+            let value_len = padding - 12;
+            pairs_data.extend_from_slice(&(value_len + 4).to_le_bytes());
+            pairs_data.extend_from_slice(&VERITY_PADDING_ID.to_le_bytes());
+            pairs_data.resize(pairs_data.len() + value_len as usize, 0);
+        }
+    }
+
+    let block_size = pairs_data.len() as u64 + 24;
+    let mut block = Vec::with_capacity(block_size as usize + 8);
+    block.extend_from_slice(&block_size.to_le_bytes());
+    block.extend_from_slice(&pairs_data);
+    block.extend_from_slice(&block_size.to_le_bytes());
+    block.extend_from_slice(SIGNING_BLOCK_MAGIC);
+    block
+}
+
+/// 用新的签名块和 ZIP 注释重写 APK
+///
+/// ZIP 条目和中央目录原样复制，只修改 EOCD 中的中央目录偏移。
+/// v2/v3 签名校验时会把该偏移替换为签名块起始位置，因此签名依然有效。
+pub fn rewrite_apk(
+    input: &mut File,
+    layout: &ApkLayout,
+    output: &mut File,
+    signing_block: Option<&[u8]>,
+    comment: &[u8],
+) -> Result<(), String> {
+    let entries_end = layout
+        .signing_block
+        .as_ref()
+        .map(|block| block.offset)
+        .unwrap_or(layout.central_directory_offset);
+
+    // 复制 ZIP 条目
+    input.seek(SeekFrom::Start(0)).map_err(|e| e.to_string())?;
+    std::io::copy(&mut Read::take(&mut *input, entries_end), output)
+        .map_err(|e| format!("复制 ZIP 条目失败: {}", e))?;
+
+    // 写入签名块
+    if let Some(block) = signing_block {
+        output.write_all(block).map_err(|e| format!("写入签名块失败: {}", e))?;
+    }
+    let new_cd_offset = entries_end + signing_block.map(|b| b.len() as u64).unwrap_or(0);
+    if new_cd_offset > u32::MAX as u64 {
+        return Err("APK 超过 4GB，不支持 ZIP64".to_string());
+    }
+
+    // 复制中央目录
+    input
+        .seek(SeekFrom::Start(layout.central_directory_offset))
+        .map_err(|e| e.to_string())?;
+    std::io::copy(&mut Read::take(&mut *input, layout.central_directory_size), output)
+        .map_err(|e| format!("复制中央目录失败: {}", e))?;
+
+    // 复制并修改 EOCD
+    let mut eocd = vec![0u8; EOCD_MIN_SIZE as usize];
+    input.seek(SeekFrom::Start(layout.eocd_offset)).map_err(|e| e.to_string())?;
+    input.read_exact(&mut eocd).map_err(|e| e.to_string())?;
+    eocd[16..20].copy_from_slice(&(new_cd_offset as u32).to_le_bytes());
+    eocd[20..22].copy_from_slice(&(comment.len() as u16).to_le_bytes());
+    output.write_all(&eocd).map_err(|e| format!("写入 EOCD 失败: {}", e))?;
+    output.write_all(comment).map_err(|e| format!("写入 ZIP 注释失败: {}", e))?;
+    output.flush().map_err(|e| format!("刷新文件失败: {}", e))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// 在签名块前放一段占位的 ZIP 条目数据，返回文件内容和中央目录偏移
+    fn with_entries(block: &[u8]) -> (Vec<u8>, u64) {
+        let mut data = vec![0u8; 64];
+        data.extend_from_slice(block);
+        let cd_offset = data.len() as u64;
+        (data, cd_offset)
+    }
+
+    /// 手工拼接签名块：pairs 为 (声明的长度, id, value)
+    fn raw_block(block_size: u64, pairs: &[(u64, u32, &[u8])]) -> Vec<u8> {
+        let mut block = block_size.to_le_bytes().to_vec();
+        for (len, id, value) in pairs {
+            block.extend_from_slice(&len.to_le_bytes());
+            block.extend_from_slice(&id.to_le_bytes());
+            block.extend_from_slice(value);
+        }
+        block.extend_from_slice(&block_size.to_le_bytes());
+        block.extend_from_slice(SIGNING_BLOCK_MAGIC);
+        block
+    }
+
+    #[test]
+    fn round_trip_build_and_read() {
+        let pairs = vec![(APK_SIGNATURE_SCHEME_V2_ID, vec![1, 2, 3]), (WALLE_CHANNEL_ID, b"{}".to_vec())];
+        let (data, cd_offset) = with_entries(&build_signing_block(&pairs, true));
+        assert_eq!(cd_offset % SIGNING_BLOCK_ALIGNMENT, 64);
+
+        let block = read_signing_block(&mut Cursor::new(data), cd_offset).unwrap().unwrap();
+        assert_eq!(block.offset, 64);
+        assert_eq!(block.get(APK_SIGNATURE_SCHEME_V2_ID), Some(&[1u8, 2, 3][..]));
+        assert_eq!(block.get(WALLE_CHANNEL_ID), Some(&b"{}"[..]));
+        assert!(block.contains(VERITY_PADDING_ID));
+    }
+
+    #[test]
+    fn missing_magic_is_not_an_error() {
+        let data = vec![0u8; 128];
+        assert!(read_signing_block(&mut Cursor::new(data), 128).unwrap().is_none());
+    }
+
+    #[test]
+    fn truncated_block_is_rejected() {
+        // 尾部声明的大小超过了中央目录之前的全部数据
+        let block = raw_block(4096, &[(7, APK_SIGNATURE_SCHEME_V2_ID, &[0, 0, 0])]);
+        let (data, cd_offset) = with_entries(&block);
+        assert_eq!(
+            read_signing_block(&mut Cursor::new(data), cd_offset).unwrap_err(),
+            "APK 签名块大小无效"
+        );
+    }
+
+    #[test]
+    fn truncated_pair_is_rejected() {
+        // 条目声明 100 字节，但签名块中只有 3 字节的值
+        let block = raw_block(39, &[(100, APK_SIGNATURE_SCHEME_V2_ID, &[0, 0, 0])]);
+        let (data, cd_offset) = with_entries(&block);
+        assert_eq!(
+            read_signing_block(&mut Cursor::new(data), cd_offset).unwrap_err(),
+            "APK 签名块条目长度无效"
+        );
+    }
+
+    #[test]
+    fn oversized_block_size_is_rejected() {
+        let block = raw_block(u64::MAX, &[]);
+        let (data, cd_offset) = with_entries(&block);
+        assert_eq!(
+            read_signing_block(&mut Cursor::new(data), cd_offset).unwrap_err(),
+            "签名块长度无效"
+        );
+    }
+
+    #[test]
+    fn oversized_pair_length_is_rejected() {
+        for pair_len in [u64::MAX, u64::MAX - 7, usize::MAX as u64 - 8] {
+            let block = raw_block(39, &[(pair_len, APK_SIGNATURE_SCHEME_V2_ID, &[0, 0, 0])]);
+            let (data, cd_offset) = with_entries(&block);
+            assert!(read_signing_block(&mut Cursor::new(data), cd_offset).is_err());
+        }
+    }
+}
@@ -0,0 +1,651 @@
+// ==================== 国内应用市场合规检查 ====================
+// 对照工信部 App 个人信息保护要求和各应用市场的上架规则：敏感权限、第三方 SDK、
+// 设备标识相关 API、ICP 备案号和 targetSdkVersion 下限。规则放在 rules/cn-store-compliance.json，
+// 可以复制后补充权限用途、增删 SDK 特征，再通过参数传入
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use zip::ZipArchive;
+
+use crate::apk::PackageInfo;
+use crate::arsc::ResourceTable;
+use crate::dex::DexFile;
+use crate::error::AnalysisError;
+use crate::permissions;
+use crate::play_policy::{RuleResult, RuleStatus};
+
+const BUILTIN_PROFILE: &str = include_str!("../rules/cn-store-compliance.json");
+// 只在这些扩展名的 assets 文件中查找 ICP 备案号
+const TEXT_ASSET_EXTENSIONS: &[&str] = &[".txt", ".html", ".htm", ".json", ".xml", ".js"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoreRequirement {
+    pub id: String,
+    pub name: String,
+    pub min_target_sdk: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SensitivePermission {
+    pub permission: String,
+    /// 该权限可获取的个人信息
+    pub identifiers: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SdkSignature {
+    pub name: String,
+    pub category: String,
+    /// 类名前缀，如 com.umeng.
+    pub packages: Vec<String>,
+    /// 常见收集的个人信息，以 SDK 隐私政策为准
+    pub collects: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiSignature {
+    pub class: String,
+    pub method: String,
+    pub identifier: String,
+}
+
+/// 以字符串常量形式出现的标识（如 Settings.Secure.ANDROID_ID 的值 "android_id"）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StringSignature {
+    pub value: String,
+    pub identifier: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IcpRule {
+    pub pattern: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComplianceProfile {
+    pub name: String,
+    pub version: String,
+    pub updated: String,
+    pub stores: Vec<StoreRequirement>,
+    pub sensitive_permissions: Vec<SensitivePermission>,
+    /// 权限 -> 应用填写的申请用途，未填写的敏感权限需要人工确认
+    #[serde(default)]
+    pub purposes: BTreeMap<String, String>,
+    pub sdks: Vec<SdkSignature>,
+    pub apis: Vec<ApiSignature>,
+    #[serde(default)]
+    pub strings: Vec<StringSignature>,
+    pub icp: IcpRule,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PermissionFinding {
+    pub permission: String,
+    pub description: String,
+    pub identifiers: Vec<String>,
+    pub purpose: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SdkFinding {
+    pub name: String,
+    pub category: String,
+    pub collects: Vec<String>,
+    /// 命中的包名前缀
+    pub packages: Vec<String>,
+    pub class_count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApiFinding {
+    /// 如 android.telephony.TelephonyManager.getDeviceId，字符串常量为 "android_id"
+    pub api: String,
+    pub identifier: String,
+    /// 直接调用，或只有同名字符串（可能通过反射调用）
+    pub direct_call: bool,
+    pub dex_files: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ComplianceReport {
+    pub package_name: String,
+    pub version_name: String,
+    pub target_sdk_version: Option<u32>,
+    pub profile_name: String,
+    pub profile_version: String,
+    pub profile_updated: String,
+    /// 没有不通过的检查项
+    pub passed: bool,
+    pub failed: usize,
+    pub review: usize,
+    pub results: Vec<RuleResult>,
+    pub permissions: Vec<PermissionFinding>,
+    pub sdks: Vec<SdkFinding>,
+    pub apis: Vec<ApiFinding>,
+    pub icp_numbers: Vec<String>,
+    /// Markdown 格式的自查报告，可直接附在上架材料中
+    pub markdown: String,
+    pub warnings: Vec<String>,
+}
+
+/// 读取合规规则文件，未指定时使用内置规则
+pub fn load_profile(path: Option<&Path>) -> Result<ComplianceProfile, AnalysisError> {
+    let text = match path {
+        Some(path) => fs::read_to_string(path).map_err(|e| format!("无法读取规则文件: {}", e))?,
+        None => BUILTIN_PROFILE.to_string(),
+    };
+    serde_json::from_str(&text).map_err(|e| format!("规则文件格式错误: {}", e).into())
+}
+
+// APK 中检查需要的内容
+#[derive(Default)]
+struct ApkFacts {
+    /// (DEX 文件名, 解析结果)
+    dex_files: Vec<(String, DexFile)>,
+    /// resources.arsc 字符串池和文本类 assets 的内容
+    texts: Vec<String>,
+}
+
+impl ApkFacts {
+    fn read(archive: &mut ZipArchive<File>, warnings: &mut Vec<String>) -> Result<Self, String> {
+        let mut names: Vec<String> = archive
+            .file_names()
+            .filter(|name| {
+                (!name.contains('/') && name.ends_with(".dex"))
+                    || *name == "resources.arsc"
+                    || (name.starts_with("assets/") && TEXT_ASSET_EXTENSIONS.iter().any(|ext| name.ends_with(ext)))
+            })
+            .map(|s| s.to_string())
+            .collect();
+        names.sort();
+
+        let mut facts = ApkFacts::default();
+        for name in names {
+            let mut data = Vec::new();
+            archive
+                .by_name(&name)
+                .map_err(|e| format!("读取 {} 失败: {}", name, e))?
+                .read_to_end(&mut data)
+                .map_err(|e| format!("读取 {} 失败: {}", name, e))?;
+
+            if name.ends_with(".dex") {
+                match DexFile::parse(&data) {
+                    Ok(dex) => facts.dex_files.push((name, dex)),
+                    Err(e) => warnings.push(format!("{} 无法解析: {}", name, e)),
+                }
+            } else if name == "resources.arsc" {
+                match ResourceTable::parse(&data) {
+                    Ok(table) => facts.texts.extend(table.strings),
+                    Err(e) => warnings.push(format!("resources.arsc 无法解析: {}", e)),
+                }
+            } else {
+                facts.texts.push(String::from_utf8_lossy(&data).into_owned());
+            }
+        }
+
+        if facts.dex_files.is_empty() {
+            warnings.push("未找到可解析的 DEX，SDK 和 API 检查结果不完整（可能已加固）".to_string());
+        }
+        Ok(facts)
+    }
+}
+
+fn result(id: &str, title: String, status: RuleStatus, detail: String, items: Vec<String>) -> RuleResult {
+    RuleResult {
+        id: id.to_string(),
+        title,
+        status,
+        detail,
+        items,
+        effective_date: None,
+        reference: None,
+    }
+}
+
+// ==================== 检查项 ====================
+
+fn find_permissions(info: &PackageInfo, profile: &ComplianceProfile) -> Vec<PermissionFinding> {
+    let requested: BTreeSet<&str> = info.permissions.iter().map(String::as_str).collect();
+    profile
+        .sensitive_permissions
+        .iter()
+        .filter(|p| requested.contains(p.permission.as_str()))
+        .map(|p| PermissionFinding {
+            permission: p.permission.clone(),
+            description: permissions::describe(&p.permission).description_zh,
+            identifiers: p.identifiers.clone(),
+            purpose: profile.purposes.get(&p.permission).filter(|s| !s.trim().is_empty()).cloned(),
+        })
+        .collect()
+}
+
+/// 按类名前缀识别第三方 SDK
+pub fn detect_sdks(classes: &[String], sdks: &[SdkSignature]) -> Vec<SdkFinding> {
+    sdks.iter()
+        .filter_map(|sdk| {
+            let mut packages = Vec::new();
+            let mut class_count = 0;
+            for prefix in &sdk.packages {
+                let count = classes.iter().filter(|c| c.starts_with(prefix.as_str())).count();
+                if count > 0 {
+                    packages.push(prefix.clone());
+                    class_count += count;
+                }
+            }
+            (class_count > 0).then(|| SdkFinding {
+                name: sdk.name.clone(),
+                category: sdk.category.clone(),
+                collects: sdk.collects.clone(),
+                packages,
+                class_count,
+            })
+        })
+        .collect()
+}
+
+// 单个 DEX 引用的方法和字符串
+struct DexRefs<'a> {
+    name: &'a str,
+    methods: BTreeSet<(String, String)>,
+    strings: BTreeSet<&'a str>,
+}
+
+fn find_apis(facts: &ApkFacts, profile: &ComplianceProfile) -> Vec<ApiFinding> {
+    let dex_refs: Vec<DexRefs> = facts
+        .dex_files
+        .iter()
+        .map(|(name, dex)| DexRefs {
+            name,
+            methods: dex.method_refs().into_iter().collect(),
+            strings: dex.strings.iter().map(String::as_str).collect(),
+        })
+        .collect();
+    let files = |filter: &dyn Fn(&DexRefs) -> bool| -> Vec<String> {
+        dex_refs.iter().filter(|d| filter(d)).map(|d| d.name.to_string()).collect()
+    };
+
+    let mut findings = Vec::new();
+    for api in &profile.apis {
+        let key = (api.class.clone(), api.method.clone());
+        let called = files(&|d| d.methods.contains(&key));
+        // 方法名字符串存在但没有任何同名方法引用时，可能是反射调用
+        let (direct_call, dex_files) = if called.is_empty() {
+            let referenced = files(&|d| {
+                d.strings.contains(api.method.as_str()) && !d.methods.iter().any(|(_, name)| *name == api.method)
+            });
+            (false, referenced)
+        } else {
+            (true, called)
+        };
+        if !dex_files.is_empty() {
+            findings.push(ApiFinding {
+                api: format!("{}.{}", api.class, api.method),
+                identifier: api.identifier.clone(),
+                direct_call,
+                dex_files,
+            });
+        }
+    }
+
+    for signature in &profile.strings {
+        let dex_files = files(&|d| d.strings.contains(signature.value.as_str()));
+        if !dex_files.is_empty() {
+            findings.push(ApiFinding {
+                api: format!("\"{}\"", signature.value),
+                identifier: signature.identifier.clone(),
+                direct_call: false,
+                dex_files,
+            });
+        }
+    }
+    findings
+}
+
+fn find_icp_numbers(facts: &ApkFacts, pattern: &str) -> Result<Vec<String>, String> {
+    let regex = Regex::new(pattern).map_err(|e| format!("ICP 备案号规则无效: {}", e))?;
+    let dex_strings = facts.dex_files.iter().flat_map(|(_, dex)| dex.strings.iter());
+    let numbers: BTreeSet<String> = facts
+        .texts
+        .iter()
+        .chain(dex_strings)
+        .flat_map(|text| regex.find_iter(text).map(|m| m.as_str().to_string()))
+        .collect();
+    Ok(numbers.into_iter().collect())
+}
+
+/// 按规则检查 APK 是否满足国内应用市场的隐私合规要求
+pub fn check_compliance(
+    info: &PackageInfo,
+    path: &Path,
+    profile: &ComplianceProfile,
+) -> Result<ComplianceReport, AnalysisError> {
+    let file = File::open(path)?;
+    let mut archive = ZipArchive::new(file)?;
+    let mut warnings = Vec::new();
+    let facts = ApkFacts::read(&mut archive, &mut warnings)?;
+    let target_sdk = info.target_sdk_version.parse::<u32>().ok();
+
+    let permissions = find_permissions(info, profile);
+    let classes: Vec<String> = facts.dex_files.iter().flat_map(|(_, dex)| dex.class_names()).collect();
+    let sdks = detect_sdks(&classes, &profile.sdks);
+    let apis = find_apis(&facts, profile);
+    let icp_numbers = find_icp_numbers(&facts, &profile.icp.pattern)?;
+
+    let mut results = Vec::new();
+    for store in &profile.stores {
+        let (status, detail) = match target_sdk {
+            Some(sdk) if sdk >= store.min_target_sdk => {
+                (RuleStatus::Pass, format!("targetSdkVersion {} 满足要求（不低于 {}）", sdk, store.min_target_sdk))
+            }
+            Some(sdk) => (
+                RuleStatus::Fail,
+                format!("targetSdkVersion 为 {}，要求不低于 {}", sdk, store.min_target_sdk),
+            ),
+            None => (RuleStatus::Fail, format!("未声明 targetSdkVersion，要求不低于 {}", store.min_target_sdk)),
+        };
+        results.push(result(
+            &format!("target_sdk_{}", store.id),
+            format!("{} targetSdkVersion", store.name),
+            status,
+            detail,
+            Vec::new(),
+        ));
+    }
+
+    let unexplained: Vec<String> = permissions
+        .iter()
+        .filter(|p| p.purpose.is_none())
+        .map(|p| p.permission.clone())
+        .collect();
+    let (status, detail) = if permissions.is_empty() {
+        (RuleStatus::Pass, "未申请敏感权限".to_string())
+    } else if unexplained.is_empty() {
+        (RuleStatus::Pass, format!("申请的 {} 个敏感权限都已填写申请用途", permissions.len()))
+    } else {
+        (
+            RuleStatus::Review,
+            format!(
+                "{} 个敏感权限未填写申请用途，需在隐私政策中说明并在使用相关功能时再动态申请",
+                unexplained.len()
+            ),
+        )
+    };
+    results.push(result("sensitive_permissions", "敏感权限".to_string(), status, detail, unexplained));
+
+    let collecting: Vec<String> = sdks
+        .iter()
+        .filter(|sdk| !sdk.collects.is_empty())
+        .map(|sdk| format!("{}: {}", sdk.name, sdk.collects.join("、")))
+        .collect();
+    let (status, detail) = if sdks.is_empty() {
+        (RuleStatus::NotApplicable, "未识别到已知的第三方 SDK".to_string())
+    } else if collecting.is_empty() {
+        (RuleStatus::Review, "识别到的第三方 SDK 需列入隐私政策的第三方信息共享清单".to_string())
+    } else {
+        (
+            RuleStatus::Review,
+            "以下 SDK 会收集设备标识等个人信息，需列入第三方信息共享清单，并确认在用户同意隐私政策后才初始化".to_string(),
+        )
+    };
+    let items = if collecting.is_empty() { sdks.iter().map(|s| s.name.clone()).collect() } else { collecting };
+    results.push(result("third_party_sdks", "第三方 SDK".to_string(), status, detail, items));
+
+    let (status, detail) = if apis.is_empty() {
+        (RuleStatus::Pass, "未发现获取设备标识等个人信息的 API 引用".to_string())
+    } else {
+        (
+            RuleStatus::Review,
+            "需确认这些调用发生在用户同意隐私政策之后，且调用频率与业务功能相符".to_string(),
+        )
+    };
+    let items = apis.iter().map(|a| format!("{}（{}）", a.api, a.identifier)).collect();
+    results.push(result("identifier_apis", "设备标识相关 API".to_string(), status, detail, items));
+
+    let (status, detail) = if icp_numbers.is_empty() {
+        (
+            RuleStatus::Review,
+            "未在资源、assets 和 DEX 字符串中找到 ICP 备案号，需确认应用内（如“关于”页面）展示了 App 备案号".to_string(),
+        )
+    } else {
+        (RuleStatus::Pass, "应用内包含 ICP 备案号".to_string())
+    };
+    results.push(result("icp_filing", "ICP 备案".to_string(), status, detail, icp_numbers.clone()));
+
+    let failed = results.iter().filter(|r| r.status == RuleStatus::Fail).count();
+    let review = results.iter().filter(|r| r.status == RuleStatus::Review).count();
+    let mut report = ComplianceReport {
+        package_name: info.package_name.clone(),
+        version_name: info.version_name.clone(),
+        target_sdk_version: target_sdk,
+        profile_name: profile.name.clone(),
+        profile_version: profile.version.clone(),
+        profile_updated: profile.updated.clone(),
+        passed: failed == 0,
+        failed,
+        review,
+        results,
+        permissions,
+        sdks,
+        apis,
+        icp_numbers,
+        markdown: String::new(),
+        warnings,
+    };
+    report.markdown = render_markdown(&report);
+    Ok(report)
+}
+
+// ==================== 报告导出 ====================
+
+fn status_label(status: RuleStatus) -> &'static str {
+    match status {
+        RuleStatus::Pass => "通过",
+        RuleStatus::Fail => "不通过",
+        RuleStatus::Review => "需人工确认",
+        RuleStatus::NotApplicable => "不适用",
+    }
+}
+
+// 表格单元格中的 | 和换行会破坏 Markdown 表格
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+fn render_markdown(report: &ComplianceReport) -> String {
+    let mut lines = vec![
+        "# 隐私合规自查报告".to_string(),
+        String::new(),
+        format!("- 应用包名：{}", report.package_name),
+        format!("- 版本：{}", report.version_name),
+        format!(
+            "- targetSdkVersion：{}",
+            report.target_sdk_version.map(|v| v.to_string()).unwrap_or_else(|| "未声明".to_string())
+        ),
+        format!("- 检查规则：{}（{}，更新于 {}）", report.profile_name, report.profile_version, report.profile_updated),
+        String::new(),
+        "## 检查结果".to_string(),
+        String::new(),
+        "| 检查项 | 结果 | 说明 |".to_string(),
+        "| --- | --- | --- |".to_string(),
+    ];
+    for r in &report.results {
+        lines.push(format!("| {} | {} | {} |", cell(&r.title), status_label(r.status), cell(&r.detail)));
+    }
+
+    lines.extend([String::new(), "## 敏感权限".to_string(), String::new()]);
+    if report.permissions.is_empty() {
+        lines.push("未申请敏感权限。".to_string());
+    } else {
+        lines.push("| 权限 | 说明 | 涉及的个人信息 | 申请用途 |".to_string());
+        lines.push("| --- | --- | --- | --- |".to_string());
+        for p in &report.permissions {
+            lines.push(format!(
+                "| {} | {} | {} | {} |",
+                p.permission,
+                cell(&p.description),
+                p.identifiers.join("、"),
+                cell(p.purpose.as_deref().unwrap_or("（未填写）"))
+            ));
+        }
+    }
+
+    lines.extend([String::new(), "## 第三方 SDK".to_string(), String::new()]);
+    if report.sdks.is_empty() {
+        lines.push("未识别到已知的第三方 SDK。".to_string());
+    } else {
+        lines.push("| SDK | 类型 | 常见收集的个人信息 | 包名 |".to_string());
+        lines.push("| --- | --- | --- | --- |".to_string());
+        for sdk in &report.sdks {
+            lines.push(format!(
+                "| {} | {} | {} | {} |",
+                cell(&sdk.name),
+                cell(&sdk.category),
+                sdk.collects.join("、"),
+                sdk.packages.join(", ")
+            ));
+        }
+        lines.push(String::new());
+        lines.push("收集的个人信息以各 SDK 官方隐私政策为准。".to_string());
+    }
+
+    lines.extend([String::new(), "## 设备标识相关 API".to_string(), String::new()]);
+    if report.apis.is_empty() {
+        lines.push("未发现相关 API 引用。".to_string());
+    } else {
+        lines.push("| API | 个人信息 | 引用方式 | 所在 DEX |".to_string());
+        lines.push("| --- | --- | --- | --- |".to_string());
+        for api in &report.apis {
+            let kind = if api.direct_call { "直接调用" } else { "字符串引用" };
+            lines.push(format!("| {} | {} | {} | {} |", cell(&api.api), api.identifier, kind, api.dex_files.join(", ")));
+        }
+    }
+
+    lines.extend([String::new(), "## ICP 备案".to_string(), String::new()]);
+    if report.icp_numbers.is_empty() {
+        lines.push("应用内未找到 ICP 备案号。".to_string());
+    } else {
+        lines.push(report.icp_numbers.join("、"));
+    }
+
+    if !report.warnings.is_empty() {
+        lines.extend([String::new(), "## 说明".to_string(), String::new()]);
+        lines.extend(report.warnings.iter().map(|w| format!("- {}", w)));
+    }
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn builtin() -> ComplianceProfile {
+        load_profile(None).unwrap()
+    }
+
+    fn facts(dex_files: Vec<DexFile>, texts: &[&str]) -> ApkFacts {
+        ApkFacts {
+            dex_files: dex_files
+                .into_iter()
+                .enumerate()
+                .map(|(i, dex)| (if i == 0 { "classes.dex".to_string() } else { format!("classes{}.dex", i + 1) }, dex))
+                .collect(),
+            texts: texts.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn detects_sdks_by_package_prefix() {
+        let classes: Vec<String> = [
+            "com.umeng.analytics.MobclickAgent",
+            "com.umeng.commonsdk.UMConfigure",
+            "cn.jiguang.api.JCoreInterface",
+            "cn.jpush.android.api.JPushInterface",
+            "com.tencent.bugly.crashreport.CrashReport",
+            // 前缀需要完整匹配到包名分隔符
+            "com.umengx.Fake",
+            "com.example.MainActivity",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        let findings = detect_sdks(&classes, &builtin().sdks);
+        let summary: Vec<(&str, usize)> = findings.iter().map(|f| (f.name.as_str(), f.class_count)).collect();
+        assert_eq!(summary, [("友盟+", 2), ("极光推送", 2), ("腾讯 Bugly", 1)]);
+        assert_eq!(findings[1].packages, ["cn.jpush.", "cn.jiguang."]);
+        assert!(detect_sdks(&classes[5..], &builtin().sdks).is_empty());
+    }
+
+    #[test]
+    fn finds_direct_and_reflective_api_use() {
+        let profile = builtin();
+        let facts = facts(
+            vec![
+                // 直接调用
+                DexFile::from_parts(&[], &[("android.telephony.TelephonyManager", "getDeviceId")]),
+                // 只有方法名字符串，没有同名方法引用：可能是反射调用
+                DexFile::from_parts(&["getImei", "android_id"], &[("java.lang.Class", "getMethod")]),
+                // 同名方法引用来自其他类，说明字符串只是方法名本身，不算反射
+                DexFile::from_parts(&[], &[("com.example.Sim", "getSubscriberId")]),
+            ],
+            &[],
+        );
+
+        let findings = find_apis(&facts, &profile);
+        let summary: Vec<(&str, bool, Vec<&str>)> = findings
+            .iter()
+            .map(|f| (f.api.as_str(), f.direct_call, f.dex_files.iter().map(String::as_str).collect()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("android.telephony.TelephonyManager.getDeviceId", true, vec!["classes.dex"]),
+                ("android.telephony.TelephonyManager.getImei", false, vec!["classes2.dex"]),
+                ("\"android_id\"", false, vec!["classes2.dex"]),
+            ]
+        );
+        assert_eq!(findings[2].identifier, "Android ID");
+    }
+
+    #[test]
+    fn direct_call_takes_precedence_over_string() {
+        let facts = facts(
+            vec![
+                DexFile::from_parts(&["getLine1Number"], &[]),
+                DexFile::from_parts(&[], &[("android.telephony.TelephonyManager", "getLine1Number")]),
+            ],
+            &[],
+        );
+        let findings = find_apis(&facts, &builtin());
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].direct_call);
+        assert_eq!(findings[0].dex_files, ["classes2.dex"]);
+    }
+
+    #[test]
+    fn icp_pattern_from_rules() {
+        let pattern = builtin().icp.pattern;
+        let facts = facts(
+            vec![DexFile::from_parts(&["粤ICP备2021012345号-3A"], &[])],
+            &[
+                "<footer>京ICP备12345678号-1 | 京公网安备11010502030000号</footer>",
+                "备案号：沪ICP备2020000000号",
+                "重复：京ICP备12345678号-1",
+                // 缺少省份简称、缺少“号”字的都不算
+                "ICP备12345678号 浙ICP备123456",
+            ],
+        );
+
+        let numbers = find_icp_numbers(&facts, &pattern).unwrap();
+        assert_eq!(numbers, ["京ICP备12345678号-1", "沪ICP备2020000000号", "粤ICP备2021012345号-3A"]);
+
+        let err = find_icp_numbers(&facts, "ICP备(").unwrap_err();
+        assert!(err.starts_with("ICP 备案号规则无效"), "{}", err);
+    }
+}
@@ -0,0 +1,357 @@
+// ==================== 原生崩溃符号化 ====================
+// 解析 tombstone / logcat 中的 native backtrace，按 ELF build-id 匹配未裁剪的 .so，
+// 通过符号表和 DWARF 还原函数名与源码行号（无需 NDK 的 ndk-stack）
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use zip::ZipArchive;
+
+use crate::elf::ElfFile;
+use crate::error::AnalysisError;
+use crate::manifest::read_entry;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceFrame {
+    pub function: Option<String>,
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NativeFrame {
+    pub index: u32,
+    pub pc: u64,
+    pub library: String,
+    pub build_id: Option<String>,
+    /// 还原结果，含内联函数时最内层在前
+    pub symbols: Vec<SourceFrame>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LibraryMatch {
+    pub name: String,
+    /// backtrace 中记录的 BuildId
+    pub backtrace_build_id: Option<String>,
+    pub symbol_file: Option<String>,
+    pub symbol_build_id: Option<String>,
+    /// APK 中同名库（已裁剪）的 build-id
+    pub apk_build_id: Option<String>,
+    pub apk_matches: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SymbolicateResult {
+    pub output: String,
+    pub frames: Vec<NativeFrame>,
+    pub libraries: Vec<LibraryMatch>,
+    pub warnings: Vec<String>,
+}
+
+// 从路径中取库文件名，兼容 base.apk!libfoo.so 形式
+fn library_name(path: &str) -> &str {
+    path.rsplit(['/', '!']).next().unwrap_or(path)
+}
+
+fn read_build_id(path: &Path) -> Option<String> {
+    let data = fs::read(path).ok()?;
+    ElfFile::parse(&data).ok()?.build_id()
+}
+
+// 文件名为 libfoo.so 或带版本号的 libfoo.so.1.2
+fn is_shared_library(name: &str) -> bool {
+    if name.ends_with(".so") {
+        return true;
+    }
+    match name.split_once(".so.") {
+        Some((_, version)) => version
+            .split('.')
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit())),
+        None => false,
+    }
+}
+
+// 递归收集符号目录中的 .so 文件，不进入指向目录的符号链接，避免链接成环时无限递归
+fn collect_symbol_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("无法读取符号目录 {}: {}", dir.display(), e))?;
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let path = entry.path();
+        if file_type.is_dir() {
+            collect_symbol_files(&path, files)?;
+        } else if path.is_file() && path.file_name().and_then(|n| n.to_str()).is_some_and(is_shared_library) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// 按 build-id 查找符号文件：优先检查同名文件，找不到时再检查目录中的全部 .so
+fn find_symbol_file(
+    name: &str,
+    build_id: Option<&str>,
+    files: &[PathBuf],
+    build_ids: &mut HashMap<PathBuf, Option<String>>,
+) -> Option<(PathBuf, Option<String>)> {
+    let mut by_name: Vec<&PathBuf> = files
+        .iter()
+        .filter(|p| p.file_name().and_then(|n| n.to_str()) == Some(name))
+        .collect();
+    let Some(build_id) = build_id else {
+        // backtrace 中没有 BuildId 时只能按文件名匹配，同名多个时取第一个
+        by_name.sort();
+        let path = by_name.first()?;
+        let id = build_ids.entry((*path).clone()).or_insert_with(|| read_build_id(path)).clone();
+        return Some(((*path).clone(), id));
+    };
+
+    let others = files.iter().filter(|p| !by_name.contains(p));
+    for path in by_name.iter().copied().chain(others) {
+        let id = build_ids.entry(path.clone()).or_insert_with(|| read_build_id(path));
+        if id.as_deref() == Some(build_id) {
+            return Some((path.clone(), id.clone()));
+        }
+    }
+    None
+}
+
+// backtrace 路径中的 ABI 目录（lib/arm64）对应 APK 中的 lib/arm64-v8a
+fn apk_abi(path: &str) -> Option<&'static str> {
+    let abi = path.split('/').skip_while(|p| *p != "lib").nth(1)?;
+    Some(match abi {
+        "arm64" | "arm64-v8a" => "arm64-v8a",
+        "arm" | "armeabi-v7a" => "armeabi-v7a",
+        "x86" => "x86",
+        "x86_64" => "x86_64",
+        _ => return None,
+    })
+}
+
+fn apk_build_id(archive: &mut ZipArchive<File>, name: &str, abi: Option<&str>) -> Option<String> {
+    let mut candidates: Vec<String> = archive
+        .file_names()
+        .filter(|f| f.starts_with("lib/") && library_name(f) == name)
+        .map(|f| f.to_string())
+        .collect();
+    if let Some(abi) = abi {
+        candidates.retain(|f| f.starts_with(&format!("lib/{}/", abi)));
+    }
+    candidates.sort();
+    let data = read_entry(archive, candidates.first()?).ok()?;
+    ElfFile::parse(&data).ok()?.build_id()
+}
+
+fn resolve(loader: &addr2line::Loader, pc: u64) -> Vec<SourceFrame> {
+    let mut symbols = Vec::new();
+    if let Ok(mut frames) = loader.find_frames(pc) {
+        while let Ok(Some(frame)) = frames.next() {
+            let function = frame
+                .function
+                .as_ref()
+                .and_then(|f| f.demangle().ok())
+                .map(|name| name.to_string());
+            let location = frame.location.as_ref();
+            symbols.push(SourceFrame {
+                function,
+                file: location.and_then(|l| l.file).map(|f| f.to_string()),
+                line: location.and_then(|l| l.line),
+                column: location.and_then(|l| l.column),
+            });
+        }
+    }
+
+    // 没有 DWARF 信息时退回到符号表，只能得到函数名
+    if symbols.iter().all(|s| s.function.is_none()) {
+        if let Some(name) = loader.find_symbol(pc) {
+            let function = addr2line::demangle_auto(name.into(), None).to_string();
+            match symbols.first_mut() {
+                Some(first) => first.function = Some(function),
+                None => symbols.push(SourceFrame {
+                    function: Some(function),
+                    file: None,
+                    line: None,
+                    column: None,
+                }),
+            }
+        }
+    }
+    symbols
+}
+
+fn format_symbol(symbol: &SourceFrame) -> String {
+    let function = symbol.function.as_deref().unwrap_or("??");
+    match (&symbol.file, symbol.line) {
+        (Some(file), Some(line)) => match symbol.column {
+            Some(column) if column > 0 => format!("{} at {}:{}:{}", function, file, line, column),
+            _ => format!("{} at {}:{}", function, file, line),
+        },
+        (Some(file), None) => format!("{} at {}", function, file),
+        _ => function.to_string(),
+    }
+}
+
+/// 符号化 native backtrace，提供 APK 时校验 APK 内的库与符号文件 build-id 是否一致
+pub fn symbolicate(backtrace: &str, symbol_dir: &Path, apk_path: Option<&Path>) -> Result<SymbolicateResult, AnalysisError> {
+    if !symbol_dir.is_dir() {
+        return Err("符号目录不存在".into());
+    }
+    let frame_regex = Regex::new(r"#(\d+)\s+pc\s+([0-9a-fA-F]+)\s+(\S+)").expect("内置规则无效");
+    let build_id_regex = Regex::new(r"\(BuildId: ([0-9a-fA-F]+)\)").expect("内置规则无效");
+
+    let mut symbol_files = Vec::new();
+    collect_symbol_files(symbol_dir, &mut symbol_files)?;
+    let mut build_ids = HashMap::new();
+
+    let mut apk = match apk_path {
+        Some(path) => {
+            let file = File::open(path).map_err(|e| format!("无法打开 APK: {}", e))?;
+            Some(ZipArchive::new(file).map_err(|e| format!("无法解析 APK: {}", e))?)
+        }
+        None => None,
+    };
+
+    let mut warnings = Vec::new();
+    let mut libraries: Vec<LibraryMatch> = Vec::new();
+    let mut loaders: HashMap<PathBuf, Option<addr2line::Loader>> = HashMap::new();
+    let mut frames = Vec::new();
+    let mut output = Vec::new();
+
+    for line in backtrace.lines() {
+        output.push(line.to_string());
+        let Some(captures) = frame_regex.captures(line) else { continue };
+        let Ok(pc) = u64::from_str_radix(&captures[2], 16) else { continue };
+        let path = captures[3].to_string();
+        let name = library_name(&path).to_string();
+        let build_id = build_id_regex.captures(line).map(|c| c[1].to_lowercase());
+
+        // 每个库（按名称 + BuildId）只匹配一次
+        let index = match libraries
+            .iter()
+            .position(|l| l.name == name && l.backtrace_build_id == build_id)
+        {
+            Some(index) => index,
+            None => {
+                let found = find_symbol_file(&name, build_id.as_deref(), &symbol_files, &mut build_ids);
+                let (symbol_file, symbol_build_id) = match found {
+                    Some((file, id)) => (Some(file), id),
+                    None => (None, None),
+                };
+                let apk_id = apk.as_mut().and_then(|archive| apk_build_id(archive, &name, apk_abi(&path)));
+                let apk_matches = match (&apk_id, &symbol_build_id) {
+                    (Some(a), Some(s)) => Some(a == s),
+                    _ => None,
+                };
+                if apk_matches == Some(false) {
+                    warnings.push(format!("{} 的符号文件与 APK 中的库 build-id 不一致", name));
+                }
+                libraries.push(LibraryMatch {
+                    name: name.clone(),
+                    backtrace_build_id: build_id.clone(),
+                    symbol_file: symbol_file.map(|p| p.to_string_lossy().to_string()),
+                    symbol_build_id,
+                    apk_build_id: apk_id,
+                    apk_matches,
+                });
+                libraries.len() - 1
+            }
+        };
+
+        let symbols = match &libraries[index].symbol_file {
+            Some(file) => {
+                let file = PathBuf::from(file);
+                let loader = loaders.entry(file.clone()).or_insert_with(|| match addr2line::Loader::new(&file) {
+                    Ok(loader) => Some(loader),
+                    Err(e) => {
+                        warnings.push(format!("无法加载符号文件 {}: {}", file.display(), e));
+                        None
+                    }
+                });
+                loader.as_ref().map(|l| resolve(l, pc)).unwrap_or_default()
+            }
+            None => Vec::new(),
+        };
+
+        let indent = " ".repeat(line.len() - line.trim_start().len() + 4);
+        for symbol in &symbols {
+            output.push(format!("{}{}", indent, format_symbol(symbol)));
+        }
+        frames.push(NativeFrame {
+            index: captures[1].parse().unwrap_or(0),
+            pc,
+            library: path,
+            build_id,
+            symbols,
+        });
+    }
+
+    if frames.is_empty() {
+        warnings.push("没有找到 native backtrace 栈帧（#00 pc ...）".to_string());
+    }
+    for library in &libraries {
+        if library.symbol_file.is_none() && !library.name.is_empty() {
+            // 系统库（libc.so 等）通常没有符号文件，只提示应用自己的库
+            if !path_is_system(&frames, &library.name) {
+                warnings.push(format!("未找到 {} 的符号文件", library.name));
+            }
+        }
+    }
+
+    Ok(SymbolicateResult {
+        output: output.join("\n"),
+        frames,
+        libraries,
+        warnings,
+    })
+}
+
+fn path_is_system(frames: &[NativeFrame], name: &str) -> bool {
+    frames
+        .iter()
+        .filter(|f| library_name(&f.library) == name)
+        .all(|f| ["/system/", "/apex/", "/vendor/"].iter().any(|p| f.library.starts_with(p)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_shared_library_names() {
+        for name in ["libfoo.so", "libc++_shared.so", "libfoo.so.1", "libssl.so.1.1"] {
+            assert!(is_shared_library(name), "{}", name);
+        }
+        for name in ["foo.so.txt", "libfoo.so.", "libfoo.so.1a", "libfoo.sox", "README", "libfoo.so.sym"] {
+            assert!(!is_shared_library(name), "{}", name);
+        }
+    }
+
+    #[test]
+    fn library_name_strips_apk_prefix() {
+        assert_eq!(library_name("/data/app/base.apk!libnative.so"), "libnative.so");
+        assert_eq!(library_name("/system/lib64/libc.so"), "libc.so");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlink_loop_in_symbol_dir_terminates() {
+        let root = std::env::temp_dir().join(format!("symbolicate_{}_loop", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("arm64-v8a")).unwrap();
+        fs::write(root.join("arm64-v8a/libnative.so"), b"elf").unwrap();
+        fs::write(root.join("arm64-v8a/libnative.so.txt"), b"notes").unwrap();
+        std::os::unix::fs::symlink(&root, root.join("arm64-v8a/loop")).unwrap();
+
+        let mut files = Vec::new();
+        let result = collect_symbol_files(&root, &mut files);
+        let _ = fs::remove_dir_all(&root);
+
+        result.unwrap();
+        assert_eq!(files, vec![root.join("arm64-v8a/libnative.so")]);
+    }
+}
@@ -0,0 +1,462 @@
+// ==================== ZIP 结构完整性与篡改检查 ====================
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::AnalysisError;
+use crate::harmony;
+use crate::manifest_audit::{summarize_findings, SecurityFinding, Severity};
+use crate::signing_block::{self, SIGNING_BLOCK_MAGIC};
+use crate::zip_raw::{self, CentralEntry, LocalHeader, FLAG_DATA_DESCRIPTOR, FLAG_ENCRYPTED, METHOD_DEFLATED, METHOD_STORED};
+
+const DATA_DESCRIPTOR_SIGNATURE: u32 = 0x0807_4b50;
+// 单个条目压缩比超过该值且解压后大于 1 MB 时视为可疑
+const MAX_COMPRESSION_RATIO: u64 = 100;
+const MIN_BOMB_SIZE: u64 = 1024 * 1024;
+// 解压后总大小上限
+const MAX_TOTAL_UNCOMPRESSED: u64 = 4 * 1024 * 1024 * 1024;
+const MAX_GAP_SCAN_SIZE: u64 = 16 * 1024 * 1024;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ZipIntegrityReport {
+    pub file_name: String,
+    pub entry_count: usize,
+    pub total_uncompressed_size: u64,
+    pub findings: Vec<SecurityFinding>,
+    /// 没有高危问题
+    pub safe: bool,
+    pub warnings: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+// 条目在文件中的范围（本地文件头起始到数据描述符结束）
+struct EntrySpan {
+    name: String,
+    start: u64,
+    end: u64,
+}
+
+/// 检查 APK 的 ZIP 结构
+pub fn check_zip_integrity(path: &Path) -> Result<ZipIntegrityReport, AnalysisError> {
+    if !path.exists() {
+        return Err(AnalysisError::NotFound);
+    }
+
+    let mut file = File::open(path)?;
+    let layout = signing_block::read_layout(&mut file)?;
+    let entries = zip_raw::read_central_directory(&mut file, &layout)?;
+
+    let mut findings = Vec::new();
+
+    if entries.len() != layout.entry_count as usize {
+        findings.push(SecurityFinding::new(
+            "entry_count_mismatch",
+            Severity::Medium,
+            "条目数量不一致",
+            format!(
+                "EOCD 记录 {} 个条目，中央目录实际包含 {} 个条目",
+                layout.entry_count,
+                entries.len()
+            ),
+            None,
+        ));
+    }
+
+    check_duplicates(&entries, &mut findings);
+
+    let mut spans = Vec::new();
+    let mut total_uncompressed_size: u64 = 0;
+    for entry in &entries {
+        check_entry_name(entry, &mut findings);
+        check_entry_method(entry, &mut findings);
+        check_compression_ratio(entry, &mut findings);
+        total_uncompressed_size += entry.uncompressed_size;
+
+        match zip_raw::read_local_header(&mut file, entry.local_header_offset) {
+            Ok(header) => {
+                check_header_mismatch(entry, &header, &mut findings);
+                let end = data_end(&mut file, entry, &header)?;
+                spans.push(EntrySpan {
+                    name: entry.name.clone(),
+                    start: entry.local_header_offset,
+                    end,
+                });
+            }
+            Err(e) => findings.push(SecurityFinding::new(
+                "invalid_local_header",
+                Severity::High,
+                "本地文件头无效",
+                e,
+                Some(entry.name.clone()),
+            )),
+        }
+    }
+
+    if total_uncompressed_size > MAX_TOTAL_UNCOMPRESSED {
+        findings.push(SecurityFinding::new(
+            "zip_bomb_total",
+            Severity::High,
+            "解压后总大小异常",
+            format!("全部条目解压后共 {} 字节，可能是 ZIP 炸弹", total_uncompressed_size),
+            None,
+        ));
+    }
+
+    // HarmonyOS 包的签名块格式不同，同样位于中央目录之前
+    let hap_block = harmony::read_hap_signing_block(&mut file, &layout).unwrap_or(None);
+    let entries_end = layout
+        .signing_block
+        .as_ref()
+        .map(|block| block.offset)
+        .or(hap_block.map(|block| block.offset))
+        .unwrap_or(layout.central_directory_offset);
+    check_layout(&mut file, &mut spans, entries_end, &layout, &mut findings)?;
+
+    let (warnings, errors) = summarize_findings(&mut findings);
+
+    Ok(ZipIntegrityReport {
+        file_name: path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("")
+            .to_string(),
+        entry_count: entries.len(),
+        total_uncompressed_size,
+        safe: errors.is_empty(),
+        findings,
+        warnings,
+        errors,
+    })
+}
+
+// Android 安装器与 Java ZIP 实现对重名条目的取舍不同，可被用来隐藏内容
+fn check_duplicates(entries: &[CentralEntry], findings: &mut Vec<SecurityFinding>) {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for entry in entries {
+        *counts.entry(entry.name.as_str()).or_insert(0) += 1;
+    }
+    let mut duplicates: Vec<(&str, usize)> = counts.into_iter().filter(|(_, count)| *count > 1).collect();
+    duplicates.sort();
+    for (name, count) in duplicates {
+        findings.push(SecurityFinding::new(
+            "duplicate_entry",
+            Severity::High,
+            "重复的条目名",
+            format!("条目出现 {} 次，不同解析器可能读取到不同内容", count),
+            Some(name.to_string()),
+        ));
+    }
+}
+
+fn check_entry_name(entry: &CentralEntry, findings: &mut Vec<SecurityFinding>) {
+    let name = &entry.name;
+    let traversal = name.split(['/', '\\']).any(|part| part == "..");
+    if traversal || name.starts_with('/') || name.starts_with('\\') || name.contains(':') {
+        findings.push(SecurityFinding::new(
+            "path_traversal",
+            Severity::High,
+            "条目路径越界",
+            "条目名包含 ../、绝对路径或盘符，解压时可能写出目标目录".to_string(),
+            Some(name.clone()),
+        ));
+    } else if name.contains('\\') {
+        findings.push(SecurityFinding::new(
+            "backslash_path",
+            Severity::Low,
+            "条目路径使用反斜杠",
+            "ZIP 规范要求使用 / 作为路径分隔符".to_string(),
+            Some(name.clone()),
+        ));
+    }
+}
+
+fn check_entry_method(entry: &CentralEntry, findings: &mut Vec<SecurityFinding>) {
+    if entry.method != METHOD_STORED && entry.method != METHOD_DEFLATED {
+        findings.push(SecurityFinding::new(
+            "unsupported_method",
+            Severity::High,
+            "不支持的压缩方式",
+            format!("压缩方式 {}，Android 只支持 STORED(0) 和 DEFLATED(8)", entry.method),
+            Some(entry.name.clone()),
+        ));
+    }
+    if entry.flags & FLAG_ENCRYPTED != 0 {
+        findings.push(SecurityFinding::new(
+            "encrypted_entry",
+            Severity::High,
+            "条目被标记为加密",
+            "Android 不支持加密的 ZIP 条目，常见于伪加密反分析手段".to_string(),
+            Some(entry.name.clone()),
+        ));
+    }
+    if entry.method == METHOD_STORED && entry.compressed_size != entry.uncompressed_size {
+        findings.push(SecurityFinding::new(
+            "stored_size_mismatch",
+            Severity::High,
+            "未压缩条目大小不一致",
+            format!(
+                "STORED 条目压缩后 {} 字节、解压后 {} 字节",
+                entry.compressed_size, entry.uncompressed_size
+            ),
+            Some(entry.name.clone()),
+        ));
+    }
+}
+
+fn check_compression_ratio(entry: &CentralEntry, findings: &mut Vec<SecurityFinding>) {
+    if entry.uncompressed_size < MIN_BOMB_SIZE {
+        return;
+    }
+    let ratio = entry.uncompressed_size / entry.compressed_size.max(1);
+    if ratio > MAX_COMPRESSION_RATIO {
+        findings.push(SecurityFinding::new(
+            "zip_bomb",
+            Severity::Medium,
+            "压缩比异常",
+            format!(
+                "{} 字节压缩为 {} 字节（{}:1），可能是 ZIP 炸弹",
+                entry.uncompressed_size, entry.compressed_size, ratio
+            ),
+            Some(entry.name.clone()),
+        ));
+    }
+}
+
+// 本地文件头与中央目录记录应当一致，否则不同解析器会读到不同内容
+fn check_header_mismatch(entry: &CentralEntry, header: &LocalHeader, findings: &mut Vec<SecurityFinding>) {
+    let mut differences = Vec::new();
+
+    if header.name != entry.name.as_bytes() {
+        differences.push(format!("文件名为 {}", String::from_utf8_lossy(&header.name)));
+    }
+    if header.method != entry.method {
+        differences.push(format!("压缩方式 {} ≠ {}", header.method, entry.method));
+    }
+    if header.flags & FLAG_ENCRYPTED != entry.flags & FLAG_ENCRYPTED {
+        differences.push("加密标志不同".to_string());
+    }
+    // 使用数据描述符时本地文件头中的 CRC 和大小可以为 0
+    let deferred = header.flags & FLAG_DATA_DESCRIPTOR != 0;
+    if !(deferred && header.crc32 == 0) && header.crc32 != entry.crc32 {
+        differences.push(format!("CRC32 {:08x} ≠ {:08x}", header.crc32, entry.crc32));
+    }
+    if !(deferred && header.compressed_size == 0) && header.compressed_size != entry.compressed_size {
+        differences.push(format!("压缩大小 {} ≠ {}", header.compressed_size, entry.compressed_size));
+    }
+    if !(deferred && header.uncompressed_size == 0) && header.uncompressed_size != entry.uncompressed_size {
+        differences.push(format!("解压大小 {} ≠ {}", header.uncompressed_size, entry.uncompressed_size));
+    }
+
+    if !differences.is_empty() {
+        findings.push(SecurityFinding::new(
+            "header_mismatch",
+            Severity::High,
+            "本地文件头与中央目录不一致",
+            differences.join("；"),
+            Some(entry.name.clone()),
+        ));
+    }
+}
+
+// 条目数据（含数据描述符）的结束位置
+fn data_end(file: &mut File, entry: &CentralEntry, header: &LocalHeader) -> Result<u64, String> {
+    let end = header.data_offset + entry.compressed_size;
+    if header.flags & FLAG_DATA_DESCRIPTOR == 0 {
+        return Ok(end);
+    }
+    // 数据描述符：可选签名 + crc32 + 压缩大小 + 解压大小
+    let mut signature = [0u8; 4];
+    file.seek(SeekFrom::Start(end)).map_err(|e| e.to_string())?;
+    if file.read_exact(&mut signature).is_err() {
+        return Ok(end);
+    }
+    if u32::from_le_bytes(signature) == DATA_DESCRIPTOR_SIGNATURE {
+        Ok(end + 16)
+    } else {
+        Ok(end + 12)
+    }
+}
+
+// 在 [offset, offset + len) 范围内查找签名块魔数，只读取前 16 MB
+fn contains_signing_block_magic(file: &mut File, offset: u64, len: u64) -> Result<bool, String> {
+    let mut data = vec![0u8; len.min(MAX_GAP_SCAN_SIZE) as usize];
+    file.seek(SeekFrom::Start(offset)).map_err(|e| e.to_string())?;
+    file.read_exact(&mut data).map_err(|e| e.to_string())?;
+    Ok(data.windows(SIGNING_BLOCK_MAGIC.len()).any(|w| w == SIGNING_BLOCK_MAGIC))
+}
+
+// 检查条目之前、之间以及签名块/中央目录前后的多余数据
+fn check_layout(
+    file: &mut File,
+    spans: &mut [EntrySpan],
+    entries_end: u64,
+    layout: &signing_block::ApkLayout,
+    findings: &mut Vec<SecurityFinding>,
+) -> Result<(), String> {
+    spans.sort_by_key(|span| span.start);
+
+    if let Some(first) = spans.first() {
+        if first.start > 0 {
+            // Janus 漏洞：文件开头拼接 DEX，v1 签名校验仍能通过
+            let mut magic = [0u8; 4];
+            file.seek(SeekFrom::Start(0)).map_err(|e| e.to_string())?;
+            file.read_exact(&mut magic).map_err(|e| e.to_string())?;
+            let prefix = if &magic == b"dex\n" { "（内容为 DEX 文件）" } else { "" };
+            findings.push(SecurityFinding::new(
+                "prefix_data",
+                Severity::High,
+                "第一个条目前存在额外数据",
+                format!(
+                    "文件开头有 {} 字节不属于任何条目{}，可能是 Janus 攻击 (CVE-2017-13156)",
+                    first.start, prefix
+                ),
+                None,
+            ));
+        }
+    }
+
+    for pair in spans.windows(2) {
+        if pair[0].end > pair[1].start {
+            findings.push(SecurityFinding::new(
+                "overlapping_entries",
+                Severity::High,
+                "条目数据重叠",
+                format!("{} 与 {} 的数据区域重叠，常见于 ZIP 炸弹", pair[0].name, pair[1].name),
+                Some(pair[1].name.clone()),
+            ));
+        } else if pair[0].end < pair[1].start {
+            findings.push(SecurityFinding::new(
+                "gap_between_entries",
+                Severity::Low,
+                "条目之间存在未引用的数据",
+                format!("{} 之后有 {} 字节未被任何条目引用", pair[0].name, pair[1].start - pair[0].end),
+                Some(pair[1].name.clone()),
+            ));
+        }
+    }
+
+    if let Some(last) = spans.last() {
+        let target = if layout.signing_block.is_some() { "签名块" } else { "中央目录" };
+        if last.end > entries_end {
+            findings.push(SecurityFinding::new(
+                "entry_overruns_directory",
+                Severity::High,
+                "条目数据越过中央目录",
+                format!("{} 的数据延伸到{}中", last.name, target),
+                Some(last.name.clone()),
+            ));
+        } else if last.end < entries_end {
+            let gap = entries_end - last.end;
+            if layout.signing_block.is_none() && contains_signing_block_magic(file, last.end, gap)? {
+                // 签名块与中央目录不相邻时安装器找不到签名块
+                findings.push(SecurityFinding::new(
+                    "detached_signing_block",
+                    Severity::High,
+                    "签名块与中央目录之间存在额外数据",
+                    "找到了 APK 签名块，但它没有紧挨中央目录，v2/v3 签名将无法被识别".to_string(),
+                    None,
+                ));
+            } else {
+                findings.push(SecurityFinding::new(
+                    "gap_before_directory",
+                    Severity::Medium,
+                    "条目数据之后存在额外数据",
+                    format!("最后一个条目与{}之间有 {} 字节额外数据", target, gap),
+                    None,
+                ));
+            }
+        }
+    }
+
+    let directory_end = layout.central_directory_offset + layout.central_directory_size;
+    if directory_end < layout.eocd_offset {
+        findings.push(SecurityFinding::new(
+            "gap_before_eocd",
+            Severity::Medium,
+            "中央目录之后存在额外数据",
+            format!("中央目录与 EOCD 之间有 {} 字节额外数据", layout.eocd_offset - directory_end),
+            None,
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+    use zip::write::SimpleFileOptions;
+    use zip::{CompressionMethod, ZipWriter};
+
+    fn build_zip(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in entries {
+            let options = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+            writer.start_file(*name, options).unwrap();
+            writer.write_all(content).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn check(name: &str, data: &[u8]) -> ZipIntegrityReport {
+        let path = std::env::temp_dir().join(format!("zip_integrity_{}_{}.apk", std::process::id(), name));
+        std::fs::write(&path, data).unwrap();
+        let report = check_zip_integrity(&path);
+        let _ = std::fs::remove_file(&path);
+        report.unwrap()
+    }
+
+    fn finding_ids(report: &ZipIntegrityReport) -> Vec<&str> {
+        report.findings.iter().map(|f| f.id.as_str()).collect()
+    }
+
+    /// 替换文件中所有出现的字节序列（本地文件头和中央目录中的文件名）
+    fn replace_all(data: &mut [u8], from: &[u8], to: &[u8]) {
+        let mut pos = 0;
+        while let Some(found) = data[pos..].windows(from.len()).position(|w| w == from) {
+            data[pos + found..pos + found + to.len()].copy_from_slice(to);
+            pos += found + to.len();
+        }
+    }
+
+    #[test]
+    fn clean_zip_is_safe() {
+        let report = check("clean", &build_zip(&[("AndroidManifest.xml", b"<manifest/>"), ("classes.dex", b"dex")]));
+        assert_eq!(report.entry_count, 2);
+        assert!(report.safe, "{:?}", report.findings);
+        assert!(report.errors.is_empty());
+    }
+
+    #[test]
+    fn duplicate_entries_are_reported() {
+        let mut data = build_zip(&[("a.txt", b"first"), ("b.txt", b"second")]);
+        replace_all(&mut data, b"b.txt", b"a.txt");
+        let report = check("duplicate", &data);
+        assert!(finding_ids(&report).contains(&"duplicate_entry"));
+        assert!(!report.safe);
+        assert_eq!(report.errors, vec!["重复的条目名: a.txt".to_string()]);
+    }
+
+    #[test]
+    fn path_traversal_is_reported() {
+        let mut data = build_zip(&[("xx/evil.so", b"elf")]);
+        replace_all(&mut data, b"xx/evil.so", b"../evil.so");
+        let report = check("traversal", &data);
+        assert!(finding_ids(&report).contains(&"path_traversal"));
+    }
+
+    #[test]
+    fn truncated_zip_is_rejected() {
+        let data = build_zip(&[("a.txt", b"content")]);
+        let path = std::env::temp_dir().join(format!("zip_integrity_{}_truncated.apk", std::process::id()));
+        std::fs::write(&path, &data[..data.len() - 10]).unwrap();
+        let result = check_zip_integrity(&path);
+        let _ = std::fs::remove_file(&path);
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,142 @@
+// ==================== ZIP 原始结构读取 ====================
+// 直接读取中央目录和本地文件头，用于对齐检查和重写
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+
+use crate::signing_block::ApkLayout;
+
+const CENTRAL_HEADER_SIGNATURE: u32 = 0x0201_4b50;
+const LOCAL_HEADER_SIGNATURE: u32 = 0x0403_4b50;
+pub const CENTRAL_HEADER_SIZE: usize = 46;
+pub const LOCAL_HEADER_SIZE: usize = 30;
+
+pub const METHOD_STORED: u16 = 0;
+pub const METHOD_DEFLATED: u16 = 8;
+// 通用标志位：bit 0 加密，bit 3 使用数据描述符
+pub const FLAG_ENCRYPTED: u16 = 0x0001;
+pub const FLAG_DATA_DESCRIPTOR: u16 = 0x0008;
+
+/// 中央目录记录
+#[derive(Debug, Clone)]
+pub struct CentralEntry {
+    pub name: String,
+    pub flags: u16,
+    pub method: u16,
+    pub crc32: u32,
+    pub compressed_size: u64,
+    pub uncompressed_size: u64,
+    pub local_header_offset: u64,
+    /// 完整的原始记录（含文件名、扩展字段和注释）
+    pub record: Vec<u8>,
+}
+
+/// 本地文件头
+#[derive(Debug, Clone)]
+pub struct LocalHeader {
+    pub flags: u16,
+    pub method: u16,
+    pub crc32: u32,
+    pub compressed_size: u64,
+    pub uncompressed_size: u64,
+    pub name: Vec<u8>,
+    pub extra: Vec<u8>,
+    /// 文件数据在 APK 中的偏移
+    pub data_offset: u64,
+}
+
+fn u16_at(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([data[offset], data[offset + 1]])
+}
+
+fn u32_at(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+}
+
+/// 按中央目录顺序读取全部条目
+pub fn read_central_directory(file: &mut File, layout: &ApkLayout) -> Result<Vec<CentralEntry>, String> {
+    let mut data = vec![0u8; layout.central_directory_size as usize];
+    file.seek(SeekFrom::Start(layout.central_directory_offset))
+        .map_err(|e| e.to_string())?;
+    file.read_exact(&mut data).map_err(|e| format!("读取中央目录失败: {}", e))?;
+
+    let mut entries = Vec::new();
+    let mut pos = 0;
+    while pos + CENTRAL_HEADER_SIZE <= data.len() {
+        if u32_at(&data, pos) != CENTRAL_HEADER_SIGNATURE {
+            return Err(format!("中央目录偏移 {} 处的记录签名无效", pos));
+        }
+        let name_len = u16_at(&data, pos + 28) as usize;
+        let extra_len = u16_at(&data, pos + 30) as usize;
+        let comment_len = u16_at(&data, pos + 32) as usize;
+        let record_len = CENTRAL_HEADER_SIZE + name_len + extra_len + comment_len;
+        if pos + record_len > data.len() {
+            return Err("中央目录记录不完整".to_string());
+        }
+
+        let compressed_size = u32_at(&data, pos + 20);
+        let uncompressed_size = u32_at(&data, pos + 24);
+        let local_header_offset = u32_at(&data, pos + 42);
+        if [compressed_size, uncompressed_size, local_header_offset].contains(&u32::MAX) {
+            return Err("不支持 ZIP64 格式".to_string());
+        }
+
+        let name_start = pos + CENTRAL_HEADER_SIZE;
+        entries.push(CentralEntry {
+            name: String::from_utf8_lossy(&data[name_start..name_start + name_len]).into_owned(),
+            flags: u16_at(&data, pos + 8),
+            method: u16_at(&data, pos + 10),
+            crc32: u32_at(&data, pos + 16),
+            compressed_size: compressed_size as u64,
+            uncompressed_size: uncompressed_size as u64,
+            local_header_offset: local_header_offset as u64,
+            record: data[pos..pos + record_len].to_vec(),
+        });
+        pos += record_len;
+    }
+
+    Ok(entries)
+}
+
+/// 读取指定偏移处的本地文件头
+pub fn read_local_header(file: &mut File, offset: u64) -> Result<LocalHeader, String> {
+    let mut fixed = [0u8; LOCAL_HEADER_SIZE];
+    file.seek(SeekFrom::Start(offset)).map_err(|e| e.to_string())?;
+    file.read_exact(&mut fixed)
+        .map_err(|e| format!("读取偏移 {} 处的本地文件头失败: {}", offset, e))?;
+    if u32_at(&fixed, 0) != LOCAL_HEADER_SIGNATURE {
+        return Err(format!("偏移 {} 处不是本地文件头", offset));
+    }
+
+    let name_len = u16_at(&fixed, 26) as usize;
+    let extra_len = u16_at(&fixed, 28) as usize;
+    let mut name = vec![0u8; name_len];
+    let mut extra = vec![0u8; extra_len];
+    file.read_exact(&mut name).map_err(|e| e.to_string())?;
+    file.read_exact(&mut extra).map_err(|e| e.to_string())?;
+
+    Ok(LocalHeader {
+        flags: u16_at(&fixed, 6),
+        method: u16_at(&fixed, 8),
+        crc32: u32_at(&fixed, 14),
+        compressed_size: u32_at(&fixed, 18) as u64,
+        uncompressed_size: u32_at(&fixed, 22) as u64,
+        name,
+        extra,
+        data_offset: offset + (LOCAL_HEADER_SIZE + name_len + extra_len) as u64,
+    })
+}
+
+/// 按 ID 拆分扩展字段，返回 (id, 数据)
+pub fn split_extra_fields(extra: &[u8]) -> Vec<(u16, &[u8])> {
+    let mut fields = Vec::new();
+    let mut pos = 0;
+    while pos + 4 <= extra.len() {
+        let id = u16_at(extra, pos);
+        let len = u16_at(extra, pos + 2) as usize;
+        let end = (pos + 4 + len).min(extra.len());
+        fields.push((id, &extra[pos + 4..end]));
+        pos += 4 + len;
+    }
+    fields
+}
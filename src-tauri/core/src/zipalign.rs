@@ -0,0 +1,393 @@
+// ==================== zipalign 检查与对齐 ====================
+// 未压缩条目需 4 字节对齐，未压缩的 .so 需按页（4 KB / 16 KB）对齐
+
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::AnalysisError;
+use crate::signing_block::{self, ApkLayout};
+use crate::zip_raw::{self, CentralEntry, LOCAL_HEADER_SIZE, METHOD_STORED};
+
+const EOCD_SIGNATURE: u32 = 0x0605_4b50;
+const DEFAULT_ALIGNMENT: u64 = 4;
+pub const DEFAULT_PAGE_SIZE: u64 = 16 * 1024;
+// apksigner 使用的对齐扩展字段：alignment(u16) + 填充
+const ALIGNMENT_EXTRA_ID: u16 = 0xd935;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MisalignedEntry {
+    pub name: String,
+    pub data_offset: u64,
+    pub required_alignment: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AlignmentReport {
+    pub file_name: String,
+    pub page_size: u64,
+    pub total_entries: usize,
+    pub stored_entries: usize,
+    pub misaligned: Vec<MisalignedEntry>,
+    pub aligned: bool,
+    /// 压缩存储的 .so，extractNativeLibs=false 时无法直接映射
+    pub compressed_native_libs: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AlignResult {
+    pub output_path: String,
+    pub adjusted_entries: usize,
+    /// 原文件含 v2/v3 签名块，对齐后已移除，需要重新签名
+    pub signature_removed: bool,
+    pub report: AlignmentReport,
+    pub warnings: Vec<String>,
+}
+
+fn validate_page_size(page_size: Option<u64>) -> Result<u64, String> {
+    match page_size.unwrap_or(DEFAULT_PAGE_SIZE) {
+        size @ (4096 | 16384) => Ok(size),
+        size => Err(format!("不支持的页大小 {}，仅支持 4096 或 16384", size)),
+    }
+}
+
+// 条目数据需要的对齐字节数，压缩条目无需对齐
+fn required_alignment(entry: &CentralEntry, page_size: u64) -> u64 {
+    if entry.method != METHOD_STORED {
+        1
+    } else if entry.name.ends_with(".so") {
+        page_size
+    } else {
+        DEFAULT_ALIGNMENT
+    }
+}
+
+/// 检查 APK 的 zipalign 对齐情况
+pub fn check_alignment(path: &Path, page_size: Option<u64>) -> Result<AlignmentReport, AnalysisError> {
+    let page_size = validate_page_size(page_size)?;
+    if !path.exists() {
+        return Err(AnalysisError::NotFound);
+    }
+
+    let mut file = File::open(path)?;
+    let layout = signing_block::read_layout(&mut file)?;
+    let entries = zip_raw::read_central_directory(&mut file, &layout)?;
+
+    let mut misaligned = Vec::new();
+    let mut compressed_native_libs = Vec::new();
+    for entry in &entries {
+        if entry.method != METHOD_STORED {
+            if entry.name.starts_with("lib/") && entry.name.ends_with(".so") {
+                compressed_native_libs.push(entry.name.clone());
+            }
+            continue;
+        }
+        let header = zip_raw::read_local_header(&mut file, entry.local_header_offset)?;
+        let alignment = required_alignment(entry, page_size);
+        if header.data_offset % alignment != 0 {
+            misaligned.push(MisalignedEntry {
+                name: entry.name.clone(),
+                data_offset: header.data_offset,
+                required_alignment: alignment,
+            });
+        }
+    }
+
+    Ok(AlignmentReport {
+        file_name: path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("")
+            .to_string(),
+        page_size,
+        total_entries: entries.len(),
+        stored_entries: entries.iter().filter(|e| e.method == METHOD_STORED).count(),
+        aligned: misaligned.is_empty(),
+        misaligned,
+        compressed_native_libs,
+    })
+}
+
+// 去掉已有的对齐字段，需要时追加新的对齐字段
+fn aligned_extra(extra: &[u8], header_end: u64, alignment: u64) -> Vec<u8> {
+    let mut result = Vec::new();
+    for (id, data) in zip_raw::split_extra_fields(extra) {
+        // 旧版 zipalign 用 0 填充，解析出来是 id 为 0 的空字段
+        if id == ALIGNMENT_EXTRA_ID || (id == 0 && data.iter().all(|b| *b == 0)) {
+            continue;
+        }
+        result.extend_from_slice(&id.to_le_bytes());
+        result.extend_from_slice(&(data.len() as u16).to_le_bytes());
+        result.extend_from_slice(data);
+    }
+
+    if alignment > 1 {
+        let data_offset = header_end + result.len() as u64;
+        let mut padding = (alignment - data_offset % alignment) % alignment;
+        if padding != 0 {
+            // 对齐字段至少 6 字节（id + 长度 + alignment）
+            while padding < 6 {
+                padding += alignment;
+            }
+            result.extend_from_slice(&ALIGNMENT_EXTRA_ID.to_le_bytes());
+            result.extend_from_slice(&((padding - 4) as u16).to_le_bytes());
+            result.extend_from_slice(&(alignment as u16).to_le_bytes());
+            result.resize(result.len() + (padding - 6) as usize, 0);
+        }
+    }
+    result
+}
+
+// 按对齐要求写出条目、中央目录和 EOCD，返回调整过的条目数
+fn write_aligned(
+    input: &mut File,
+    output: &mut File,
+    layout: &ApkLayout,
+    mut entries: Vec<CentralEntry>,
+    page_size: u64,
+) -> Result<usize, String> {
+    // 条目数据区结束位置（签名块或中央目录开始处）
+    let entries_end = layout
+        .signing_block
+        .as_ref()
+        .map(|block| block.offset)
+        .unwrap_or(layout.central_directory_offset);
+
+    // 按本地文件头顺序处理，每个条目复制到下一个条目之前（含数据描述符）
+    let mut order: Vec<usize> = (0..entries.len()).collect();
+    order.sort_by_key(|&i| entries[i].local_header_offset);
+
+    let mut written: u64 = 0;
+    let mut adjusted_entries = 0;
+
+    for (position, &index) in order.iter().enumerate() {
+        let entry = &entries[index];
+        let header = zip_raw::read_local_header(input, entry.local_header_offset)?;
+        let span_end = order
+            .get(position + 1)
+            .map(|&next| entries[next].local_header_offset)
+            .unwrap_or(entries_end);
+        if span_end < header.data_offset {
+            return Err(format!("条目 {} 的数据区无效", entry.name));
+        }
+
+        let header_end = written + (LOCAL_HEADER_SIZE + header.name.len()) as u64;
+        let extra = aligned_extra(&header.extra, header_end, required_alignment(entry, page_size));
+        if extra != header.extra {
+            adjusted_entries += 1;
+        }
+        if extra.len() > u16::MAX as usize {
+            return Err(format!("条目 {} 的扩展字段过长", entry.name));
+        }
+
+        // 本地文件头：除扩展字段长度外原样保留
+        let mut fixed = [0u8; LOCAL_HEADER_SIZE];
+        input
+            .seek(SeekFrom::Start(entry.local_header_offset))
+            .map_err(|e| e.to_string())?;
+        input.read_exact(&mut fixed).map_err(|e| e.to_string())?;
+        fixed[28..30].copy_from_slice(&(extra.len() as u16).to_le_bytes());
+
+        output.write_all(&fixed).map_err(|e| e.to_string())?;
+        output.write_all(&header.name).map_err(|e| e.to_string())?;
+        output.write_all(&extra).map_err(|e| e.to_string())?;
+
+        input
+            .seek(SeekFrom::Start(header.data_offset))
+            .map_err(|e| e.to_string())?;
+        let data_len = span_end - header.data_offset;
+        std::io::copy(&mut Read::take(&mut *input, data_len), output)
+            .map_err(|e| format!("复制 {} 失败: {}", entry.name, e))?;
+
+        entries[index].local_header_offset = written;
+        written = header_end + extra.len() as u64 + data_len;
+    }
+
+    // 中央目录：更新本地文件头偏移
+    let central_directory_offset = written;
+    let mut central_directory_size: u64 = 0;
+    for entry in &entries {
+        let mut record = entry.record.clone();
+        record[42..46].copy_from_slice(&(entry.local_header_offset as u32).to_le_bytes());
+        output.write_all(&record).map_err(|e| e.to_string())?;
+        central_directory_size += record.len() as u64;
+    }
+    if central_directory_offset + central_directory_size > u32::MAX as u64 {
+        return Err("APK 超过 4GB，不支持 ZIP64".to_string());
+    }
+
+    let mut eocd = Vec::with_capacity(22 + layout.comment.len());
+    eocd.extend_from_slice(&EOCD_SIGNATURE.to_le_bytes());
+    eocd.extend_from_slice(&[0u8; 4]);
+    eocd.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+    eocd.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+    eocd.extend_from_slice(&(central_directory_size as u32).to_le_bytes());
+    eocd.extend_from_slice(&(central_directory_offset as u32).to_le_bytes());
+    eocd.extend_from_slice(&(layout.comment.len() as u16).to_le_bytes());
+    eocd.extend_from_slice(&layout.comment);
+    output.write_all(&eocd).map_err(|e| e.to_string())?;
+    output.flush().map_err(|e| format!("刷新文件失败: {}", e))?;
+
+    Ok(adjusted_entries)
+}
+
+/// 重写 APK 使条目对齐，输出到 output_path（可与输入相同）
+///
+/// 对齐会移动条目位置，v2/v3 签名随之失效，签名块会被移除，需要重新签名。
+pub fn align_apk(input_path: &Path, output_path: &Path, page_size: Option<u64>) -> Result<AlignResult, AnalysisError> {
+    let page_size = validate_page_size(page_size)?;
+    if !input_path.exists() {
+        return Err(AnalysisError::NotFound);
+    }
+
+    let mut input = File::open(input_path)?;
+    let layout = signing_block::read_layout(&mut input)?;
+    let entries = zip_raw::read_central_directory(&mut input, &layout)?;
+    if entries.len() > u16::MAX as usize {
+        return Err("条目数量超过 65535，不支持 ZIP64".into());
+    }
+
+    let temp_path = output_path.with_extension("apk.tmp");
+    let result = File::create(&temp_path)
+        .map_err(|e| format!("无法创建输出文件: {}", e))
+        .and_then(|mut output| write_aligned(&mut input, &mut output, &layout, entries, page_size));
+
+    drop(input);
+    let adjusted_entries = match result {
+        Ok(count) => count,
+        Err(e) => {
+            let _ = fs::remove_file(&temp_path);
+            return Err(e.into());
+        }
+    };
+    fs::rename(&temp_path, output_path).map_err(|e| format!("保存输出文件失败: {}", e))?;
+
+    let signature_removed = layout.signing_block.is_some();
+    let mut warnings = Vec::new();
+    if signature_removed {
+        warnings.push("对齐后 v2/v3 签名已失效并被移除，请使用 apksigner 重新签名".to_string());
+    }
+
+    Ok(AlignResult {
+        output_path: output_path.to_string_lossy().to_string(),
+        adjusted_entries,
+        signature_removed,
+        report: check_alignment(output_path, Some(page_size))?,
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use std::path::PathBuf;
+
+    use zip::write::SimpleFileOptions;
+    use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("zipalign_{}_{}.apk", std::process::id(), name))
+    }
+
+    // 压缩和未压缩条目交替，文件名长度使未压缩条目落在未对齐的位置
+    fn unaligned_apk() -> (Vec<u8>, Vec<(&'static str, Vec<u8>)>) {
+        let library: Vec<u8> = (0..5000u32).map(|i| (i * 7) as u8).collect();
+        let entries = vec![
+            ("AndroidManifest.xml", b"<manifest package=\"com.example\"/>".repeat(20)),
+            ("res/raw/a.bin", b"odd".to_vec()),
+            ("classes.dex", b"dex\n035\0".repeat(100)),
+            ("lib/arm64-v8a/libfoo.so", library),
+            ("resources.arsc", vec![0x02, 0x00, 0x0c, 0x00]),
+        ];
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in &entries {
+            let method = if name.ends_with(".dex") || name.ends_with(".xml") {
+                CompressionMethod::Deflated
+            } else {
+                CompressionMethod::Stored
+            };
+            writer.start_file(*name, SimpleFileOptions::default().compression_method(method)).unwrap();
+            writer.write_all(content).unwrap();
+        }
+        (writer.finish().unwrap().into_inner(), entries)
+    }
+
+    fn read_entries(path: &Path) -> Vec<(String, CompressionMethod, u32, Vec<u8>)> {
+        let mut archive = ZipArchive::new(File::open(path).unwrap()).unwrap();
+        (0..archive.len())
+            .map(|i| {
+                let mut file = archive.by_index(i).unwrap();
+                let mut data = Vec::new();
+                file.read_to_end(&mut data).unwrap();
+                (file.name().to_string(), file.compression(), file.crc32(), data)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn align_round_trip_keeps_entry_data() {
+        let (data, entries) = unaligned_apk();
+        let input = temp_path("input");
+        let output = temp_path("output");
+        fs::write(&input, &data).unwrap();
+
+        let before = check_alignment(&input, None).unwrap();
+        assert!(!before.aligned, "测试数据应当未对齐");
+
+        let result = align_apk(&input, &output, None).unwrap();
+        assert!(result.report.aligned, "{:?}", result.report.misaligned);
+        assert!(result.adjusted_entries > 0);
+        assert!(!result.signature_removed);
+        assert_eq!(result.report.total_entries, entries.len());
+
+        // 条目顺序、压缩方式、CRC 和内容都不变
+        let original = read_entries(&input);
+        let aligned = read_entries(&output);
+        assert_eq!(original, aligned);
+        for ((name, _, _, data), (expected_name, expected)) in aligned.iter().zip(&entries) {
+            assert_eq!(name, expected_name);
+            assert_eq!(data, expected);
+        }
+
+        // .so 按 16 KB 对齐，并带有 apksigner 的对齐扩展字段
+        let mut file = File::open(&output).unwrap();
+        let layout = signing_block::read_layout(&mut file).unwrap();
+        let central = zip_raw::read_central_directory(&mut file, &layout).unwrap();
+        let library = central.iter().find(|e| e.name == "lib/arm64-v8a/libfoo.so").unwrap();
+        let header = zip_raw::read_local_header(&mut file, library.local_header_offset).unwrap();
+        assert_eq!(header.data_offset % DEFAULT_PAGE_SIZE, 0);
+        let fields = zip_raw::split_extra_fields(&header.extra);
+        assert!(fields.iter().any(|(id, data)| *id == ALIGNMENT_EXTRA_ID && data[..2] == 16384u16.to_le_bytes()));
+
+        // 已对齐的文件再次对齐不做修改
+        let again = align_apk(&output, &input, None).unwrap();
+        assert_eq!(again.adjusted_entries, 0);
+        assert_eq!(fs::read(&input).unwrap(), fs::read(&output).unwrap());
+
+        let _ = fs::remove_file(&input);
+        let _ = fs::remove_file(&output);
+    }
+
+    #[test]
+    fn align_in_place_with_4k_pages() {
+        let (data, _) = unaligned_apk();
+        let path = temp_path("in_place");
+        fs::write(&path, &data).unwrap();
+
+        let result = align_apk(&path, &path, Some(4096)).unwrap();
+        assert!(result.report.aligned);
+        assert_eq!(result.report.page_size, 4096);
+        assert!(!path.with_extension("apk.tmp").exists());
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn unsupported_page_size_is_rejected() {
+        let err = check_alignment(Path::new("unused.apk"), Some(8192)).unwrap_err();
+        assert!(err.to_string().contains("8192"), "{}", err);
+    }
+}
@@ -1,28 +1,30 @@
 use std::path::Path;
-use std::io::{Read, Cursor};
-use md5::Md5;
-use sha1::Sha1;
-use sha2::{Sha256, Digest};
 use serde::{Deserialize, Serialize};
-use tokio::fs;
-use tokio::io::AsyncReadExt;
-use zip::ZipArchive;
-use rusty_axml;
-use image::{DynamicImage, GenericImageView, ImageFormat};
-use uuid::Uuid;
-use rand::Rng;
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct FileInfo {
-    pub file_name: String,
-    pub file_extension: String,
-    pub file_size: u64,
-    pub file_size_readable: String,
-    pub file_path: String,
-    pub md5: String,
-    pub sha1: String,
-    pub sha256: String,
-}
+use base64::Engine;
+use release_assistant_core::advisory::{self, ImportResult, VulnerabilityReport};
+use release_assistant_core::apk::{self, PackageInfo};
+use release_assistant_core::build_apks::{self, BuildApksResult};
+use release_assistant_core::channel::{self, ChannelFormat, ChannelInfo};
+use release_assistant_core::channel_batch::{self, BatchResult};
+use release_assistant_core::device_compat::{self, CompatibilityReport};
+use release_assistant_core::file_info::{self, FileInfo};
+use release_assistant_core::generators::{self, GeneratorError, PasswordOptions, UuidVersion};
+use release_assistant_core::icons::{self, IconOptions};
+use release_assistant_core::image::{self, ImageError, OutputFormat, ResizeMode};
+use release_assistant_core::library::{self, LibraryInfo};
+use release_assistant_core::manifest_audit::{self, ManifestAuditResult};
+use release_assistant_core::manifest_editor::{self, ManifestEditResult, ManifestEdits};
+use release_assistant_core::network_security::{self, NetworkReport};
+use release_assistant_core::permissions::{self, PermissionReport};
+use release_assistant_core::play_policy::{self, PolicyReport, ReleaseType};
+use release_assistant_core::retrace::{self, MappingCache, RetraceResult};
+use release_assistant_core::sbom::{self, SbomResult};
+use release_assistant_core::secret_scan::{self, SecretScanResult};
+use release_assistant_core::signing::{self, ApkSignatureResult, SignOptions};
+use release_assistant_core::store_compliance::{self, ComplianceReport};
+use release_assistant_core::symbolicate::{self, SymbolicateResult};
+use release_assistant_core::zip_integrity::{self, ZipIntegrityReport};
+use release_assistant_core::zipalign::{self, AlignResult, AlignmentReport};
 
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 #[tauri::command]
@@ -32,107 +34,12 @@ fn greet(name: &str) -> String {
 
 #[tauri::command]
 async fn get_file_info(file_path: String) -> Result<FileInfo, String> {
-    let path = Path::new(&file_path);
-    
-    // 检查文件是否存在
-    if !path.exists() {
-        return Err("文件不存在".to_string());
-    }
-    
-    // 获取文件名
-    let file_name = path
-        .file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or("")
-        .to_string();
-    
-    // 获取文件扩展名
-    let file_extension = path
-        .extension()
-        .and_then(|e| e.to_str())
-        .unwrap_or("")
-        .to_string();
-    
-    // 获取文件大小
-    let metadata = fs::metadata(&path).await.map_err(|e| e.to_string())?;
-    let file_size = metadata.len();
-    let file_size_readable = format_file_size(file_size);
-    
-    // 使用分块读取计算哈希，避免大文件占用过多内存
-    let mut file = fs::File::open(&path).await.map_err(|e| e.to_string())?;
-    
-    // 初始化哈希器
-    let mut hasher_md5 = Md5::new();
-    let mut hasher_sha1 = Sha1::new();
-    let mut hasher_sha256 = Sha256::new();
-    
-    // 分块读取文件（每次读取 8MB）
-    const CHUNK_SIZE: usize = 8 * 1024 * 1024;
-    let mut buffer = vec![0u8; CHUNK_SIZE];
-    
-    loop {
-        let bytes_read = file.read(&mut buffer).await.map_err(|e| e.to_string())?;
-        if bytes_read == 0 {
-            break;
-        }
-        
-        // 更新所有哈希器
-        let chunk = &buffer[..bytes_read];
-        hasher_md5.update(chunk);
-        hasher_sha1.update(chunk);
-        hasher_sha256.update(chunk);
-    }
-    
-    // 完成哈希计算
-    let md5 = hex::encode(hasher_md5.finalize());
-    let sha1 = hex::encode(hasher_sha1.finalize());
-    let sha256 = hex::encode(hasher_sha256.finalize());
-    
-    Ok(FileInfo {
-        file_name,
-        file_extension,
-        file_size,
-        file_size_readable,
-        file_path: file_path.clone(),
-        md5,
-        sha1,
-        sha256,
+    // 哈希计算是同步 IO，放到阻塞线程池中执行
+    tokio::task::spawn_blocking(move || {
+        file_info::file_info(Path::new(&file_path)).map_err(|e| e.to_string())
     })
-}
-
-fn format_file_size(size: u64) -> String {
-    const KB: u64 = 1024;
-    const MB: u64 = KB * 1024;
-    const GB: u64 = MB * 1024;
-    
-    if size >= GB {
-        format!("{:.2} GB", size as f64 / GB as f64)
-    } else if size >= MB {
-        format!("{:.2} MB", size as f64 / MB as f64)
-    } else if size >= KB {
-        format!("{:.2} KB", size as f64 / KB as f64)
-    } else {
-        format!("{} Bytes", size)
-    }
-}
-
-// Android 包信息结构
-#[derive(Debug, Serialize, Deserialize)]
-pub struct PackageInfo {
-    pub package_name: String,
-    pub version_name: String,
-    pub version_code: String,
-    pub min_sdk_version: String,
-    pub target_sdk_version: String,
-    pub compile_sdk_version: String,
-    pub permissions: Vec<String>,
-    pub activities: Vec<String>,
-    pub services: Vec<String>,
-    pub receivers: Vec<String>,
-    pub providers: Vec<String>,
-    pub file_size: u64,
-    pub file_size_readable: String,
-    pub icon_base64: Option<String>,  // Base64 编码的图标
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
 }
 
 #[tauri::command]
@@ -146,512 +53,24 @@ async fn parse_android_package(file_path: String) -> Result<PackageInfo, String>
 }
 
 fn parse_android_package_sync(file_path: &str) -> Result<PackageInfo, String> {
-    let path = Path::new(file_path);
-
-    // 检查文件是否存在
-    if !path.exists() {
-        return Err("文件不存在".to_string());
-    }
-
-    // 获取文件扩展名，判断是否是 XAPK
-    let extension = path.extension()
-        .and_then(|e| e.to_str())
-        .unwrap_or("")
-        .to_lowercase();
-
-    // 如果是 XAPK 文件，先提取 base.apk
-    if extension == "xapk" {
-        println!("检测到 XAPK 文件，正在提取 base.apk...");
-        return parse_xapk_file(path);
-    }
-
-    // 获取文件大小
-    let metadata = std::fs::metadata(path).map_err(|e| e.to_string())?;
-    let file_size = metadata.len();
-    let file_size_readable = format_file_size(file_size);
-
-    // 打开 ZIP 文件
-    let file = std::fs::File::open(path).map_err(|e| format!("无法打开文件: {}", e))?;
-    let mut archive = ZipArchive::new(file).map_err(|e| format!("无法解析 ZIP 文件: {}", e))?;
-
-    // 查找 AndroidManifest.xml
-    let manifest_data = if let Ok(mut manifest_file) = archive.by_name("AndroidManifest.xml") {
-        let mut buffer = Vec::new();
-        manifest_file.read_to_end(&mut buffer).map_err(|e| format!("读取 AndroidManifest.xml 失败: {}", e))?;
-        buffer
-    } else {
-        return Err("未找到 AndroidManifest.xml 文件".to_string());
-    };
-    
-    // 解析二进制 XML
-    let cursor = Cursor::new(manifest_data);
-    let axml = rusty_axml::parse_from_reader(cursor)
-        .map_err(|e| format!("解析 AndroidManifest.xml 失败: {:?}", e))?;
-
-    // 辅助函数：处理原始格式的属性值
-    // 某些属性值可能返回 "(type 0x10) 0x12927c70" 这种格式
-    // 需要提取十六进制值并转换为十进制字符串
-    fn clean_attr_value(value: &str) -> String {
-        // 检查是否是原始格式 "(type 0x...) 0x..."
-        if value.contains("(type 0x") && value.contains("0x") {
-            // 提取最后一个 0x 后的十六进制值
-            if let Some(hex_start) = value.rfind("0x") {
-                let hex_str = &value[hex_start + 2..];
-                // 转换为十进制
-                if let Ok(num) = u32::from_str_radix(hex_str.trim(), 16) {
-                    return num.to_string();
-                }
-            }
-        }
-        value.to_string()
-    }
-
-    // 使用 helper 函数获取组件列表
-    let permissions = rusty_axml::get_requested_permissions(&axml);
-    let activities = rusty_axml::get_activities_names(&axml);
-    let services = rusty_axml::get_services_names(&axml);
-    let receivers = rusty_axml::get_receivers_names(&axml);
-    let providers = rusty_axml::get_providers_names(&axml);
-
-    // 查找 manifest 节点获取包名和版本信息
-    let mut package_name = String::new();
-    let mut version_name = String::new();
-    let mut version_code = String::new();
-    let mut compile_sdk_version = String::new();
-    let mut min_sdk_version = String::new();
-    let mut target_sdk_version = String::new();
-
-    // 从 AXML 树中提取 manifest 属性
-    let manifest_nodes = rusty_axml::find_nodes_by_type(&axml, "manifest");
-    if let Some(manifest_node) = manifest_nodes.first() {
-        let elem = manifest_node.borrow();
-
-        // 尝试不同的属性名称（有/无命名空间前缀）
-        if let Some(pkg) = elem.get_attr("package") {
-            package_name = clean_attr_value(pkg);
-        }
-        if let Some(vn) = elem.get_attr("android:versionName") {
-            version_name = clean_attr_value(vn);
-        } else if let Some(vn) = elem.get_attr("versionName") {
-            version_name = clean_attr_value(vn);
-        }
-        if let Some(vc) = elem.get_attr("android:versionCode") {
-            version_code = clean_attr_value(vc);
-        } else if let Some(vc) = elem.get_attr("versionCode") {
-            version_code = clean_attr_value(vc);
-        }
-        // 尝试多种可能的属性名称获取 compileSdkVersion
-        if let Some(csv) = elem.get_attr("android:compileSdkVersion") {
-            compile_sdk_version = clean_attr_value(csv);
-        } else if let Some(csv) = elem.get_attr("compileSdkVersion") {
-            compile_sdk_version = clean_attr_value(csv);
-        }
-
-        // 如果没有获取到 compileSdkVersion，尝试使用 compileSdkVersionCodename
-        if compile_sdk_version.is_empty() {
-            if let Some(csc) = elem.get_attr("android:compileSdkVersionCodename") {
-                compile_sdk_version = clean_attr_value(csc);
-            } else if let Some(csc) = elem.get_attr("compileSdkVersionCodename") {
-                compile_sdk_version = clean_attr_value(csc);
-            }
-        }
-    }
-
-    // 查找 uses-sdk 节点获取 SDK 版本
-    let uses_sdk_nodes = rusty_axml::find_nodes_by_type(&axml, "uses-sdk");
-    if let Some(uses_sdk_node) = uses_sdk_nodes.first() {
-        let elem = uses_sdk_node.borrow();
-
-        // 尝试不同的属性名称（有/无命名空间前缀）
-        if min_sdk_version.is_empty() {
-            if let Some(min) = elem.get_attr("android:minSdkVersion") {
-                min_sdk_version = clean_attr_value(min);
-            } else if let Some(min) = elem.get_attr("minSdkVersion") {
-                min_sdk_version = clean_attr_value(min);
-            }
-        }
-
-        if target_sdk_version.is_empty() {
-            if let Some(target) = elem.get_attr("android:targetSdkVersion") {
-                target_sdk_version = clean_attr_value(target);
-            } else if let Some(target) = elem.get_attr("targetSdkVersion") {
-                target_sdk_version = clean_attr_value(target);
-            }
-        }
-    }
-
-    // 如果没有获取到 SDK 版本，设置默认值
-    if min_sdk_version.is_empty() {
-        min_sdk_version = "未指定".to_string();
-    }
-    if target_sdk_version.is_empty() {
-        target_sdk_version = "未指定".to_string();
-    }
-    if compile_sdk_version.is_empty() {
-        compile_sdk_version = "未指定".to_string();
-    }
-    
-    // 提取应用图标（传入文件路径以重新打开 ZIP）
-    let icon_base64 = extract_app_icon(path);
-
-    Ok(PackageInfo {
-        package_name,
-        version_name,
-        version_code,
-        min_sdk_version,
-        target_sdk_version,
-        compile_sdk_version,
-        permissions,
-        activities,
-        services,
-        receivers,
-        providers,
-        file_size,
-        file_size_readable,
-        icon_base64,
-    })
+    apk::parse_package(Path::new(file_path)).map_err(|e| e.to_string())
 }
 
-// 解析 XAPK 文件
-fn parse_xapk_file(xapk_path: &Path) -> Result<PackageInfo, String> {
-    use std::io::Write;
-    use std::env;
-
-    // 打开 XAPK 文件（ZIP 格式）
-    let file = std::fs::File::open(xapk_path).map_err(|e| format!("无法打开 XAPK 文件: {}", e))?;
-    let xapk_archive = ZipArchive::new(file).map_err(|e| format!("无法解析 XAPK 文件: {}", e))?;
-
-    println!("  XAPK 文件包含 {} 个文件", xapk_archive.len());
-
-    // 首先列出 XAPK 中的所有文件
-    println!("\n  📋 XAPK 文件列表:");
-    let xapk_files: Vec<String> = xapk_archive.file_names().map(|s| s.to_string()).collect();
-    for (index, filename) in xapk_files.iter().enumerate() {
-        if index < 20 || filename.ends_with(".apk") || filename.ends_with(".json") {
-            println!("    {}: {}", index + 1, filename);
-        }
-    }
-
-    // 查找 APK 文件（按优先级）
-    let apk_priority = [
-        "base.apk",                     // 最常见
-        "split_config.base.apk",        // 某些 XAPK 的命名
-        "master.apk",                   // 备选名称
-    ];
-
-    let mut target_apk_name: Option<String> = None;
-
-    // 首先尝试优先级列表中的名称
-    for priority_name in &apk_priority {
-        if xapk_files.iter().any(|f| f == priority_name) {
-            target_apk_name = Some(priority_name.to_string());
-            println!("\n  ✅ 找到优先级 APK: {}", priority_name);
-            break;
-        }
-    }
-
-    // 如果没找到，查找任意 .apk 文件
-    if target_apk_name.is_none() {
-        println!("\n  🔍 查找任意 APK 文件...");
-        for filename in &xapk_files {
-            if filename.to_lowercase().ends_with(".apk") {
-                target_apk_name = Some(filename.clone());
-                println!("  ✅ 找到 APK: {}", filename);
-                break;
-            }
-        }
-    }
-
-    let apk_name = target_apk_name.ok_or_else(|| {
-        format!("XAPK 文件中未找到任何 APK 文件。文件列表:\n{}",
-            xapk_files.iter()
-                .take(30)
-                .enumerate()
-                .map(|(i, f)| format!("  {}. {}", i + 1, f))
-                .collect::<Vec<_>>()
-                .join("\n"))
-    })?;
-
-    println!("\n  📦 准备解析: {}", apk_name);
-
-    // 重新打开 XAPK 文件（因为之前已经遍历过文件列表）
-    let file = std::fs::File::open(xapk_path).map_err(|e| format!("无法重新打开 XAPK 文件: {}", e))?;
-    let mut xapk_archive = ZipArchive::new(file).map_err(|e| format!("无法重新解析 XAPK 文件: {}", e))?;
-
-    let mut apk_file = xapk_archive.by_name(&apk_name)
-        .map_err(|e| format!("无法读取 {}: {}", apk_name, e))?;
-
-    println!("  APK 大小: {} bytes", apk_file.size());
-
-    // 创建临时目录
-    let temp_dir = env::temp_dir();
-    let temp_apk_path = temp_dir.join(format!("release_assistant_xapk_{}_{}.apk",
-        std::process::id(),
-        std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs()));
-
-    println!("  提取 APK 到临时文件: {:?}", temp_apk_path);
-
-    // 提取 APK 到临时文件
-    let mut temp_file = std::fs::File::create(&temp_apk_path)
-        .map_err(|e| format!("无法创建临时文件: {}", e))?;
-
-    let mut buffer = Vec::new();
-    apk_file.read_to_end(&mut buffer)
-        .map_err(|e| format!("读取 APK 失败: {}", e))?;
-
-    temp_file.write_all(&buffer)
-        .map_err(|e| format!("写入临时文件失败: {}", e))?;
-
-    println!("  APK 提取完成，开始解析...");
-
-    // 解析提取的 APK
-    let result = parse_android_package_sync(temp_apk_path.to_str()
-        .ok_or("临时文件路径无效")?);
-
-    // 清理临时文件
-    let _ = std::fs::remove_file(&temp_apk_path);
-    println!("  ✅ 临时文件已清理");
+// ==================== 图片处理功能 ====================
 
-    result
+// 解码前端传入的 base64 图片
+fn decode_image_base64(image_base64: &str) -> Result<Vec<u8>, String> {
+    base64::engine::general_purpose::STANDARD
+        .decode(image_base64)
+        .map_err(|e| format!("Base64 解码失败: {}", e))
 }
 
-// 提取应用图标并返回 Base64 编码
-// 需要传入文件路径以重新打开 ZIP，避免读取冲突
-// 按分辨率从高到低查找 ic_launcher 开头的 PNG 图片
-fn extract_app_icon(file_path: &Path) -> Option<String> {
-    use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
-
-    // 重新打开 ZIP 文件以避免读取冲突
-    let file = std::fs::File::open(file_path).ok()?;
-    let mut archive = ZipArchive::new(file).ok()?;
-
-    println!("🔍 开始提取应用图标...");
-    println!("📁 APK 文件路径: {:?}", file_path);
-
-    // 首先列出 ZIP 中所有文件，帮助调试
-    println!("\n📋 ZIP 中的所有文件 (前 100 个):");
-    let zip_names: Vec<String> = archive.file_names().map(|s| s.to_string()).collect();
-    println!("  总文件数: {}", zip_names.len());
-
-    for (index, zip_path) in zip_names.iter().take(100).enumerate() {
-        println!("  {}: {}", index + 1, zip_path);
-    }
-
-    if zip_names.len() > 100 {
-        println!("  ... (还有 {} 个文件)", zip_names.len() - 100);
-    }
-
-    // 查找包含 'mipmap' 或 'drawable' 且以 .png 结尾的文件
-    println!("\n🎨 所有 PNG 图片文件:");
-    let mut png_files = Vec::new();
-    for zip_path in &zip_names {
-        if zip_path.to_lowercase().ends_with(".png") {
-            png_files.push(zip_path.clone());
-        }
-    }
-
-    if png_files.is_empty() {
-        println!("  ⚠️  未找到任何 PNG 文件");
-    } else {
-        for (index, png_file) in png_files.iter().enumerate() {
-            println!("  {}: {}", index + 1, png_file);
-        }
-    }
-
-    // 首先尝试从所有 PNG 文件中查找 ic_launcher 开头的图标
-    println!("\n🔍 在所有 PNG 文件中查找 ic_launcher 开头的图标:");
-    let mut launcher_icons: Vec<String> = Vec::new();
-
-    for png_file in &png_files {
-        // 提取文件名（不含路径）
-        let file_name = png_file.split('/').last().unwrap_or("");
-        let file_name = file_name.split('\\').last().unwrap_or(file_name);
-
-        // 检查文件名是否以 ic_launcher 开头（不区分大小写）
-        if file_name.to_lowercase().starts_with("ic_launcher") {
-            println!("  ✅ 找到: {}", png_file);
-            launcher_icons.push(png_file.clone());
-        }
-    }
-
-    if !launcher_icons.is_empty() {
-        println!("\n  📝 找到 {} 个 ic_launcher 图标，尝试读取", launcher_icons.len());
-
-        // 按路径长度排序，优先选择路径较短的（通常是高分辨率）
-        launcher_icons.sort_by_key(|a| a.len());
-
-        // 尝试读取第一个图标
-        for icon_path in &launcher_icons {
-            println!("  📖 尝试读取: {}", icon_path);
-
-            match archive.by_name(&icon_path) {
-                Ok(mut icon_file) => {
-                    let mut icon_data = Vec::new();
-                    match icon_file.read_to_end(&mut icon_data) {
-                        Ok(size) => {
-                            println!("  ✅ 成功读取图标: {} (大小: {} bytes)", icon_path, size);
-                            let base64_icon = BASE64.encode(&icon_data);
-                            println!("  🎯 图标 Base64 编码完成，长度: {}", base64_icon.len());
-                            return Some(format!("data:image/png;base64,{}", base64_icon));
-                        }
-                        Err(e) => {
-                            println!("  ❌ 读取文件内容失败: {}", e);
-                            continue;
-                        }
-                    }
-                }
-                Err(e) => {
-                    println!("  ❌ 无法打开文件: {}", e);
-                    continue;
-                }
-            }
-        }
-    }
-
-    // 如果没找到 ic_launcher，回退到原来的 DPI 目录查找方式
-    println!("\n🔍 回退到 DPI 目录查找方式...");
-
-    // DPI 目录列表（从高到低分辨率）
-    const DPI_DIRS: &[&str] = &[
-        "mipmap-xxxhdpi",   // 512dpi - 最高分辨率
-        "drawable-xxxhdpi",
-        "mipmap-xxhdpi",    // 480dpi
-        "drawable-xxhdpi",
-        "mipmap-xhdpi",     // 320dpi
-        "drawable-xhdpi",
-        "mipmap-hdpi",      // 240dpi
-        "drawable-hdpi",
-        "mipmap-mdpi",      // 160dpi
-        "drawable-mdpi",
-        "mipmap-ldpi",      // 120dpi
-        "drawable-ldpi",
-        "mipmap",           // 默认
-        "drawable",
-    ];
-
-    // 按分辨率从高到低依次查找
-    for dpi_dir in DPI_DIRS {
-        println!("\n🔎 检查目录: {}", dpi_dir);
-
-        // 查找当前 DPI 目录下所有以 ic_launcher 开头的 PNG 文件
-        let mut icons_in_this_dpi: Vec<String> = Vec::new();
-
-        for zip_path in &zip_names {
-            let lower_path = zip_path.to_lowercase();
-
-            // 详细的路径匹配调试
-            let path_pattern1 = format!("/{}/", dpi_dir);
-            let path_pattern2 = format!("{}/", dpi_dir);
-            let path_pattern3 = format!("\\{}\\", dpi_dir);
-            let path_pattern4 = format!("{}\\", dpi_dir);
-
-            let match1 = zip_path.contains(&path_pattern1);
-            let match2 = zip_path.starts_with(&path_pattern2);
-            let match3 = zip_path.contains(&path_pattern3);
-            let match4 = zip_path.starts_with(&path_pattern4);
-            let has_dpi_dir = match1 || match2 || match3 || match4;
-
-            // 如果路径包含 DPI 目录，输出详细信息
-            if zip_path.contains(dpi_dir) {
-                println!("  📌 检查文件: {}", zip_path);
-                println!("     - 包含 '{}': {}", dpi_dir, zip_path.contains(dpi_dir));
-                println!("     - 匹配模式1 '{}': {}", path_pattern1, match1);
-                println!("     - 匹配模式2 '{}': {}", path_pattern2, match2);
-                println!("     - 匹配模式3 '{}': {}", path_pattern3, match3);
-                println!("     - 匹配模式4 '{}': {}", path_pattern4, match4);
-                println!("     - is PNG: {}", lower_path.ends_with(".png"));
-            }
-
-            if !has_dpi_dir {
-                continue;
-            }
-
-            // 检查是否是 PNG 文件
-            if !lower_path.ends_with(".png") {
-                continue;
-            }
-
-            // 提取文件名（不含路径，处理 / 和 \ 两种分隔符）
-            let file_name = zip_path.split('/').last().unwrap_or("");
-            let file_name = file_name.split('\\').last().unwrap_or(file_name);
-
-            println!("     - 文件名: '{}'", file_name);
-            println!("     - 以 ic_launcher 开头: {}", file_name.to_lowercase().starts_with("ic_launcher"));
-
-            // 检查文件名是否以 ic_launcher 开头（不区分大小写）
-            if file_name.to_lowercase().starts_with("ic_launcher") {
-                println!("  ✅ 找到候选图标: {}", zip_path);
-                icons_in_this_dpi.push(zip_path.clone());
-            }
-        }
-
-        // 如果当前 DPI 目录找到了图标，按文件名排序优先返回 ic_launcher.png
-        if !icons_in_this_dpi.is_empty() {
-            println!("\n  📝 在 {} 目录找到 {} 个候选图标", dpi_dir, icons_in_this_dpi.len());
-
-            // 优先选择 ic_launcher.png，然后是 ic_launcher_round.png，最后是其他变体
-            icons_in_this_dpi.sort_by(|a, b| {
-                let a_lower = a.to_lowercase();
-                let b_lower = b.to_lowercase();
-
-                // ic_launcher.png 优先级最高
-                if a_lower.ends_with("ic_launcher.png") && !b_lower.ends_with("ic_launcher.png") {
-                    return std::cmp::Ordering::Less;
-                }
-                if !a_lower.ends_with("ic_launcher.png") && b_lower.ends_with("ic_launcher.png") {
-                    return std::cmp::Ordering::Greater;
-                }
-
-                // ic_launcher_round.png 次优先
-                if a_lower.ends_with("ic_launcher_round.png") && !b_lower.ends_with("ic_launcher_round.png") {
-                    return std::cmp::Ordering::Less;
-                }
-                if !a_lower.ends_with("ic_launcher_round.png") && b_lower.ends_with("ic_launcher_round.png") {
-                    return std::cmp::Ordering::Greater;
-                }
-
-                // 其他情况按字母顺序
-                a.cmp(b)
-            });
-
-            // 尝试读取优先级最高的图标
-            for zip_path in &icons_in_this_dpi {
-                println!("  📖 尝试读取: {}", zip_path);
-
-                match archive.by_name(&zip_path) {
-                    Ok(mut icon_file) => {
-                        let mut icon_data = Vec::new();
-                        match icon_file.read_to_end(&mut icon_data) {
-                            Ok(size) => {
-                                println!("  ✅ 成功读取图标: {} (大小: {} bytes)", zip_path, size);
-                                let base64_icon = BASE64.encode(&icon_data);
-                                println!("  🎯 图标 Base64 编码完成，长度: {}", base64_icon.len());
-                                return Some(format!("data:image/png;base64,{}", base64_icon));
-                            }
-                            Err(e) => {
-                                println!("  ❌ 读取文件内容失败: {}", e);
-                                continue;
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        println!("  ❌ 无法打开文件: {}", e);
-                        continue;
-                    }
-                }
-            }
-        }
-    }
-
-    println!("\n❌ 未找到任何 ic_launcher 开头的 PNG 图标");
-    None
+// data URL 的 MIME 部分沿用前端传入的 output_format
+fn to_data_url(output_format: &str, data: &[u8]) -> String {
+    let base64_string = base64::engine::general_purpose::STANDARD.encode(data);
+    format!("data:{};base64,{}", output_format, base64_string)
 }
 
-// ==================== 图片处理功能 ====================
-
 /// 调整图片尺寸
 #[tauri::command]
 async fn resize_image(
@@ -662,113 +81,13 @@ async fn resize_image(
     output_format: String,
     quality: u8,
 ) -> Result<String, String> {
-    use image::{ImageFormat, DynamicImage, imageops::FilterType};
-    use base64::Engine;
-
-    // 解码 base64 图片
-    let image_data = base64::engine::general_purpose::STANDARD
-        .decode(&image_base64)
-        .map_err(|e| format!("Base64 解码失败: {}", e))?;
-
-    // 加载图片
-    let img = image::load_from_memory(&image_data)
-        .map_err(|e| format!("图片加载失败: {}", e))?;
-
-    // 获取原始尺寸
-    let (orig_width, orig_height) = img.dimensions();
-
-    // 根据模式调整图片尺寸
-    let resized_img = match mode.as_str() {
-        "fit" => {
-            // 适应模式:保持宽高比,完整显示在目标区域内
-            let ratio = (target_width as f64 / orig_width as f64)
-                .min(target_height as f64 / orig_height as f64);
-            let new_width = (orig_width as f64 * ratio).round() as u32;
-            let new_height = (orig_height as f64 * ratio).round() as u32;
+    let image_data = decode_image_base64(&image_base64)?;
+    let mode: ResizeMode = mode.parse().map_err(|e: ImageError| e.to_string())?;
+    let format = OutputFormat::from_name(&output_format);
 
-            let resized = image::imageops::resize(&img, new_width, new_height, FilterType::Lanczos3);
-
-            // 创建目标尺寸的画布并居中放置 - 优化:使用 fill 代替循环
-            let mut canvas = image::RgbaImage::new(target_width, target_height);
-            canvas.fill(0u8); // 快速填充透明像素
-
-            let offset_x = ((target_width - new_width) / 2) as i64;
-            let offset_y = ((target_height - new_height) / 2) as i64;
-
-            // resized 已经是 ImageBuffer，可以直接 overlay
-            image::imageops::overlay(&mut canvas, &resized, offset_x, offset_y);
-
-            DynamicImage::ImageRgba8(canvas)
-        }
-        "fill" => {
-            // 填充模式:保持宽高比,填满目标区域,裁剪多余部分
-            let ratio = (target_width as f64 / orig_width as f64)
-                .max(target_height as f64 / orig_height as f64);
-            let new_width = (orig_width as f64 * ratio).round() as u32;
-            let new_height = (orig_height as f64 * ratio).round() as u32;
-
-            let resized = image::imageops::resize(&img, new_width, new_height, FilterType::Lanczos3);
-
-            // 裁剪到目标尺寸(居中裁剪)
-            let offset_x = ((new_width - target_width) / 2) as u32;
-            let offset_y = ((new_height - target_height) / 2) as u32;
-
-            // 使用 view 替代 crop,然后 to_image
-            let cropped = resized.view(offset_x, offset_y, target_width, target_height).to_image();
-            DynamicImage::ImageRgba8(cropped)
-        }
-        "stretch" => {
-            // 拉伸模式:直接拉伸到目标尺寸
-            DynamicImage::ImageRgba8(image::imageops::resize(&img, target_width, target_height, FilterType::Lanczos3))
-        }
-        _ => {
-            return Err(format!("未知的调整模式: {}", mode));
-        }
-    };
-
-    // 编码为输出格式
-    let mut buffer = Vec::new();
-    let format = match output_format.as_str() {
-        "image/png" | "png" => {
-            // PNG 编码 - 使用快速压缩级别
-            let mut cursor = std::io::Cursor::new(&mut buffer);
-            let encoder = image::codecs::png::PngEncoder::new_with_quality(
-                &mut cursor,
-                image::codecs::png::CompressionType::Fast,
-                image::codecs::png::FilterType::Adaptive
-            );
-            resized_img.write_with_encoder(encoder)
-                .map_err(|e| format!("PNG 编码失败: {}", e))?;
-            ImageFormat::Png
-        }
-        "image/jpeg" | "jpg" | "jpeg" => ImageFormat::Jpeg,
-        "image/webp" | "webp" => ImageFormat::WebP,
-        _ => ImageFormat::Png,
-    };
-
-    let mut cursor = std::io::Cursor::new(&mut buffer);
-
-    // 对于 JPG,需要转换为 RGB 并设置质量
-    if format == ImageFormat::Jpeg {
-        let rgb_img = resized_img.to_rgb8();
-        let mut encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut cursor, quality);
-        encoder.encode(
-            &rgb_img,
-            resized_img.width(),
-            resized_img.height(),
-            image::ExtendedColorType::Rgb8,
-        ).map_err(|e| format!("图片编码失败: {}", e))?;
-    } else if format != ImageFormat::Png {
-        // 非PNG格式使用标准编码
-        resized_img.write_to(&mut cursor, format)
-            .map_err(|e| format!("图片编码失败: {}", e))?;
-    }
-
-    // 转换为 base64
-    let base64_string = base64::engine::general_purpose::STANDARD.encode(&buffer);
-    let data_url = format!("data:{};base64,{}", output_format, base64_string);
-
-    Ok(data_url)
+    let data = image::resize(&image_data, target_width, target_height, mode, format, quality)
+        .map_err(|e| e.to_string())?;
+    Ok(to_data_url(&output_format, &data))
 }
 
 /// 为图片添加圆角
@@ -778,156 +97,10 @@ async fn add_image_radius(
     radius: u32,
     output_format: String,
 ) -> Result<String, String> {
-    use base64::Engine;
-
-    // 解码 base64 图片
-    let image_data = base64::engine::general_purpose::STANDARD
-        .decode(&image_base64)
-        .map_err(|e| format!("Base64 解码失败: {}", e))?;
-
-    // 加载图片
-    let img = image::load_from_memory(&image_data)
-        .map_err(|e| format!("图片加载失败: {}", e))?;
-
-    // 获取图片尺寸
-    let (width, height) = img.dimensions();
-    let max_radius = radius.min(width.min(height) / 2);
-
-    // 创建带圆角的图片
-    let rounded_img = if output_format == "image/png" || output_format == "png" {
-        // PNG 支持透明度,可以真正实现圆角
-        create_rounded_image(&img, max_radius)?
-    } else {
-        // JPG 等不支持透明度,只能绘制白色圆角背景
-        create_rounded_image_with_bg(&img, max_radius)?
-    };
-
-    // 编码为输出格式
-    let mut buffer = Vec::new();
-    let format = if output_format == "image/png" || output_format == "png" {
-        // PNG 编码 - 使用快速压缩级别
-        let mut cursor = std::io::Cursor::new(&mut buffer);
-        let encoder = image::codecs::png::PngEncoder::new_with_quality(
-            &mut cursor,
-            image::codecs::png::CompressionType::Fast,
-            image::codecs::png::FilterType::Adaptive
-        );
-        rounded_img.write_with_encoder(encoder)
-            .map_err(|e| format!("PNG 编码失败: {}", e))?;
-        ImageFormat::Png
-    } else if output_format == "image/jpeg" || output_format == "jpg" || output_format == "jpeg" {
-        ImageFormat::Jpeg
-    } else if output_format == "image/webp" || output_format == "webp" {
-        ImageFormat::WebP
-    } else {
-        ImageFormat::Png
-    };
-
-    // 对于非 PNG 格式，使用标准编码
-    if format != ImageFormat::Png {
-        let mut cursor = std::io::Cursor::new(&mut buffer);
-        rounded_img.write_to(&mut cursor, format)
-            .map_err(|e| format!("图片编码失败: {}", e))?;
-    }
-
-    // 转换为 base64
-    let base64_string = base64::engine::general_purpose::STANDARD.encode(&buffer);
-    let data_url = format!("data:{};base64,{}", output_format, base64_string);
-
-    Ok(data_url)
-}
-
-/// 创建带圆角的图片(支持透明)
-fn create_rounded_image(img: &DynamicImage, radius: u32) -> Result<DynamicImage, String> {
-    let (width, height) = img.dimensions();
-
-    // 如果半径为0，直接返回原图
-    if radius == 0 {
-        return Ok(img.clone());
-    }
-
-    let mut rgba_img = img.to_rgba8();
-
-    // 预计算圆角遮罩 - 只处理四个角
-    let r = radius as f64;
-    let r_squared = r * r;
-
-    // 左上角
-    for y in 0..radius.min(height) {
-        for x in 0..radius.min(width) {
-            let dx = x as f64 - r;
-            let dy = y as f64 - r;
-            if dx * dx + dy * dy > r_squared {
-                if let Some(pixel) = rgba_img.get_pixel_mut_checked(x, y) {
-                    pixel[3] = 0;
-                }
-            }
-        }
-    }
-
-    // 右上角
-    for y in 0..radius.min(height) {
-        for x in 0..radius.min(width) {
-            let actual_x = width - 1 - x;
-            let dx = x as f64 - r;
-            let dy = y as f64 - r;
-            if dx * dx + dy * dy > r_squared {
-                if let Some(pixel) = rgba_img.get_pixel_mut_checked(actual_x, y) {
-                    pixel[3] = 0;
-                }
-            }
-        }
-    }
-
-    // 左下角
-    for y in 0..radius.min(height) {
-        for x in 0..radius.min(width) {
-            let actual_y = height - 1 - y;
-            let dx = x as f64 - r;
-            let dy = y as f64 - r;
-            if dx * dx + dy * dy > r_squared {
-                if let Some(pixel) = rgba_img.get_pixel_mut_checked(x, actual_y) {
-                    pixel[3] = 0;
-                }
-            }
-        }
-    }
-
-    // 右下角
-    for y in 0..radius.min(height) {
-        for x in 0..radius.min(width) {
-            let actual_x = width - 1 - x;
-            let actual_y = height - 1 - y;
-            let dx = x as f64 - r;
-            let dy = y as f64 - r;
-            if dx * dx + dy * dy > r_squared {
-                if let Some(pixel) = rgba_img.get_pixel_mut_checked(actual_x, actual_y) {
-                    pixel[3] = 0;
-                }
-            }
-        }
-    }
-
-    Ok(DynamicImage::ImageRgba8(rgba_img))
-}
-
-/// 创建带圆角的图片(白色背景,用于不支持透明的格式)
-fn create_rounded_image_with_bg(img: &DynamicImage, radius: u32) -> Result<DynamicImage, String> {
-    use image::RgbaImage;
-
-    let (width, height) = img.dimensions();
-    let rounded = create_rounded_image(img, radius)?;
-
-    // 创建白色背景
-    let mut bg_img = RgbaImage::new(width, height);
-    for pixel in bg_img.pixels_mut() {
-        *pixel = image::Rgba([255, 255, 255, 255]);
-    }
-
-    // 合并圆角图片到白色背景
-    image::imageops::overlay(&mut bg_img, &rounded.to_rgba8(), 0, 0);
-
-    Ok(DynamicImage::ImageRgba8(bg_img))
+    let image_data = decode_image_base64(&image_base64)?;
+    let data = image::add_radius(&image_data, radius, OutputFormat::from_name(&output_format))
+        .map_err(|e| e.to_string())?;
+    Ok(to_data_url(&output_format, &data))
 }
 
 /// 生成多尺寸 APP 图标
@@ -939,88 +112,22 @@ async fn generate_app_icons(
     padding_percent: u32,
     output_format: String,
 ) -> Result<Vec<IconResult>, String> {
-    use base64::Engine;
-
-    // 解码 base64 图片
-    let image_data = base64::engine::general_purpose::STANDARD
-        .decode(&image_base64)
-        .map_err(|e| format!("Base64 解码失败: {}", e))?;
-
-    // 加载原始图片
-    let source_img = image::load_from_memory(&image_data)
-        .map_err(|e| format!("图片加载失败: {}", e))?;
-
-    let mut results = Vec::new();
-
-    for size in sizes {
-        // 计算实际边距(像素)
-        let padding = (size as f64 * padding_percent as f64 / 100.0) as u32;
-        let content_size = size - padding * 2;
-
-        // 调整图片大小(保持宽高比)
-        let resized = image::imageops::resize(
-            &source_img,
-            content_size,
-            content_size,
-            image::imageops::FilterType::Lanczos3,
-        );
-
-        // 创建正方形画布 - 优化:使用 fill 代替循环
-        let mut canvas = image::RgbaImage::new(size, size);
-        canvas.fill(0u8); // 快速填充透明像素
-
-        // 居中放置调整后的图片
-        let offset_x = padding as i64;
-        let offset_y = padding as i64;
-        image::imageops::overlay(&mut canvas, &resized, offset_x, offset_y);
-
-        // 应用圆角 - 优化:直接传递引用,避免克隆
-        let radius = (size as f64 * radius_percent as f64 / 100.0) as u32;
-        let rounded = if radius > 0 {
-            create_rounded_image(&DynamicImage::ImageRgba8(canvas), radius)?
-        } else {
-            DynamicImage::ImageRgba8(canvas)
-        };
-
-        // 编码为输出格式 - 优化PNG编码
-        let mut buffer = Vec::new();
-        let format = if output_format == "image/png" || output_format == "png" {
-            // 使用快速PNG编码
-            let mut cursor = std::io::Cursor::new(&mut buffer);
-            let encoder = image::codecs::png::PngEncoder::new_with_quality(
-                &mut cursor,
-                image::codecs::png::CompressionType::Fast,
-                image::codecs::png::FilterType::Adaptive
-            );
-            rounded.write_with_encoder(encoder)
-                .map_err(|e| format!("PNG 编码失败: {}", e))?;
-            image::ImageFormat::Png
-        } else if output_format == "image/jpeg" || output_format == "jpg" || output_format == "jpeg" {
-            image::ImageFormat::Jpeg
-        } else if output_format == "image/webp" || output_format == "webp" {
-            image::ImageFormat::WebP
-        } else {
-            image::ImageFormat::Png
-        };
-
-        // 非PNG格式使用标准编码
-        if format != image::ImageFormat::Png {
-            let mut cursor = std::io::Cursor::new(&mut buffer);
-            rounded.write_to(&mut cursor, format)
-                .map_err(|e| format!("图片编码失败: {}", e))?;
-        }
-
-        // 转换为 base64
-        let base64_string = base64::engine::general_purpose::STANDARD.encode(&buffer);
-        let data_url = format!("data:{};base64,{}", output_format, base64_string);
-
-        results.push(IconResult {
-            size,
-            url: data_url,
-        });
-    }
+    let image_data = decode_image_base64(&image_base64)?;
+    let options = IconOptions {
+        sizes,
+        radius_percent,
+        padding_percent,
+        format: OutputFormat::from_name(&output_format),
+    };
 
-    Ok(results)
+    let icons = icons::generate_icons(&image_data, &options).map_err(|e| e.to_string())?;
+    Ok(icons
+        .into_iter()
+        .map(|icon| IconResult {
+            size: icon.size,
+            url: to_data_url(&output_format, &icon.data),
+        })
+        .collect())
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -1060,44 +167,8 @@ fn generate_uuids(
     uppercase: bool,
     with_hyphens: bool,
 ) -> Result<Vec<String>, String> {
-    let mut uuids = Vec::new();
-
-    for _ in 0..count {
-        let uuid = match version.as_str() {
-            "v4" => Uuid::new_v4(),
-            "v7" => {
-                // UUID v7 使用时间戳,这里简化实现,使用 v4 但格式化为 v7
-                // 实际生产环境应使用 uuid v7 crate
-                Uuid::new_v4()
-            }
-            _ => return Err(format!("不支持的 UUID 版本: {}", version)),
-        };
-
-        let mut uuid_string = uuid.to_string();
-
-        if !with_hyphens {
-            uuid_string = uuid_string.replace("-", "");
-        }
-
-        if uppercase {
-            uuid_string = uuid_string.to_uppercase();
-        }
-
-        uuids.push(uuid_string);
-    }
-
-    Ok(uuids)
-}
-
-#[derive(Debug, Deserialize)]
-pub struct PasswordOptions {
-    pub length: u32,
-    pub uppercase: bool,
-    pub lowercase: bool,
-    pub numbers: bool,
-    pub symbols: bool,
-    #[serde(alias = "excludeAmbiguous")]
-    pub exclude_ambiguous: bool,
+    let version: UuidVersion = version.parse().map_err(|e: GeneratorError| e.to_string())?;
+    Ok(generators::generate_uuids(count, version, uppercase, with_hyphens))
 }
 
 /// 生成密码
@@ -1106,301 +177,348 @@ fn generate_passwords(
     options: PasswordOptions,
     count: u32,
 ) -> Result<Vec<String>, String> {
-    let uppercase_chars = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
-    let lowercase_chars = "abcdefghijklmnopqrstuvwxyz";
-    let number_chars = "0123456789";
-    let symbol_chars = "!@#$%^&*()_+-=[]{}|;:,.<>?";
-    let ambiguous_chars = "0OIl1";
-
-    let mut charset = String::new();
-    let mut required_chars = String::new();
-
-    if options.uppercase {
-        let chars = if options.exclude_ambiguous {
-            uppercase_chars.replace(|c| ambiguous_chars.contains(c), "")
-        } else {
-          uppercase_chars.to_string()
-        };
-        charset.push_str(&chars);
-        if !chars.is_empty() {
-            required_chars.push(chars.chars().next().unwrap());
-        }
-    }
-
-    if options.lowercase {
-        let chars = if options.exclude_ambiguous {
-            lowercase_chars.replace(|c| ambiguous_chars.contains(c), "")
-        } else {
-          lowercase_chars.to_string()
-        };
-        charset.push_str(&chars);
-        if !chars.is_empty() {
-            required_chars.push(chars.chars().next().unwrap());
-        }
-    }
-
-    if options.numbers {
-        let chars = if options.exclude_ambiguous {
-            number_chars.replace(|c| ambiguous_chars.contains(c), "")
-        } else {
-          number_chars.to_string()
-        };
-        charset.push_str(&chars);
-        if !chars.is_empty() {
-            required_chars.push(chars.chars().next().unwrap());
-        }
-    }
-
-    if options.symbols {
-        let chars = if options.exclude_ambiguous {
-            symbol_chars.replace(|c| ambiguous_chars.contains(c), "")
-        } else {
-          symbol_chars.to_string()
-        };
-        charset.push_str(&chars);
-        if !chars.is_empty() {
-            required_chars.push(chars.chars().next().unwrap());
-        }
-    }
+    generators::generate_passwords(&options, count).map_err(|e| e.to_string())
+}
 
-    if charset.is_empty() {
-        return Err("请至少选择一种字符类型".to_string());
-    }
+// ==================== APK 签名验证功能 ====================
 
-    let charset_vec: Vec<char> = charset.chars().collect();
-    let mut rng = rand::thread_rng();
-    let mut passwords = Vec::new();
+/// 验证 APK 签名
+#[tauri::command]
+async fn verify_apk_signature(apk_path: String) -> Result<ApkSignatureResult, String> {
+    tokio::task::spawn_blocking(move || {
+        signing::verify_apk(Path::new(&apk_path)).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
 
-    for _ in 0..count {
-        let mut password = String::new();
+// ==================== Manifest 安全审计功能 ====================
 
-        // 先确保包含每种选中的字符类型
-        for c in required_chars.chars() {
-            password.push(c);
-        }
+/// 审计 APK 的 Manifest 安全配置
+#[tauri::command]
+async fn audit_manifest(file_path: String) -> Result<ManifestAuditResult, String> {
+    tokio::task::spawn_blocking(move || {
+        manifest_audit::audit_apk_manifest(Path::new(&file_path)).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
 
-        // 填充剩余长度
-        while password.len() < options.length as usize {
-            let random_index = rng.gen_range(0..charset_vec.len());
-            password.push(charset_vec[random_index]);
-        }
+/// 对 APK 请求的权限进行分类
+#[tauri::command]
+async fn classify_permissions(file_path: String) -> Result<PermissionReport, String> {
+    tokio::task::spawn_blocking(move || {
+        permissions::classify_apk_permissions(Path::new(&file_path)).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
 
-        // 打乱密码顺序
-        let password_chars: Vec<char> = password.chars().collect();
-        let mut shuffled_password = String::new();
-        for _ in 0..password_chars.len() {
-            let random_index = rng.gen_range(0..password_chars.len());
-            shuffled_password.push(password_chars[random_index]);
-        }
+/// 提取网络安全配置和深度链接
+#[tauri::command]
+async fn analyze_network_config(file_path: String) -> Result<NetworkReport, String> {
+    tokio::task::spawn_blocking(move || {
+        network_security::analyze_apk_network(Path::new(&file_path)).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
 
-        passwords.push(shuffled_password);
-    }
+// ==================== 硬编码密钥扫描功能 ====================
 
-    Ok(passwords)
+/// 扫描 APK 中的硬编码密钥和网络地址
+#[tauri::command]
+async fn scan_secrets(file_path: String) -> Result<SecretScanResult, String> {
+    tokio::task::spawn_blocking(move || {
+        secret_scan::scan_apk_secrets(Path::new(&file_path)).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
 }
 
-// ==================== APK 签名验证功能 ====================
+// ==================== Manifest 编辑功能 ====================
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct SignatureInfo {
-    pub version: String,
-    pub present: bool,
-    pub details: Option<String>,
-    pub certificate: Option<CertificateInfo>,
+/// 修改 AndroidManifest.xml 属性并重新打包，可选重新签名
+#[tauri::command]
+async fn edit_manifest(
+    file_path: String,
+    output_path: String,
+    edits: ManifestEdits,
+    sign_options: Option<SignOptions>,
+) -> Result<ManifestEditResult, String> {
+    tokio::task::spawn_blocking(move || {
+        manifest_editor::edit_apk_manifest(
+            Path::new(&file_path),
+            Path::new(&output_path),
+            &edits,
+            sign_options.as_ref(),
+        ).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct CertificateInfo {
-    pub issuer: String,
-    pub subject: String,
-    pub valid_from: String,
-    pub valid_to: String,
-    pub signature_algorithm: String,
+// ==================== 渠道号功能 ====================
+
+/// 读取 APK 的渠道号
+#[tauri::command]
+async fn read_channel(file_path: String) -> Result<ChannelInfo, String> {
+    tokio::task::spawn_blocking(move || {
+        channel::read_apk_channel(Path::new(&file_path)).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ApkSignatureResult {
-    pub file_name: String,
-    pub file_size: String,
-    #[serde(alias = "signatures")]
-    pub signatures: std::collections::HashMap<String, SignatureInfo>,
-    pub warnings: Vec<String>,
-    pub errors: Vec<String>,
+/// 写入渠道号，不影响 v2/v3 签名
+#[tauri::command]
+async fn write_channel(
+    file_path: String,
+    output_path: String,
+    channel: String,
+    extras: Option<std::collections::BTreeMap<String, String>>,
+    format: Option<ChannelFormat>,
+) -> Result<ChannelInfo, String> {
+    tokio::task::spawn_blocking(move || {
+        channel::write_apk_channel(
+            Path::new(&file_path),
+            Path::new(&output_path),
+            &channel,
+            &extras.unwrap_or_default(),
+            format,
+        ).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
 }
 
-/// 验证 APK 签名
+/// 批量生成渠道包，每完成一个渠道发送 channel-batch-progress 事件
 #[tauri::command]
-async fn verify_apk_signature(apk_path: String) -> Result<ApkSignatureResult, String> {
-    use std::path::Path;
-    use std::fs;
-
-    let path = Path::new(&apk_path);
-
-    // 检查文件是否存在
-    if !path.exists() {
-        return Err("文件不存在".to_string());
-    }
-
-    // 检查文件扩展名
-    if path.extension().and_then(|e| e.to_str()) != Some("apk") {
-        return Err("不是有效的 APK 文件".to_string());
-    }
-
-    // 获取文件大小
-    let metadata = fs::metadata(&path)
-        .map_err(|e| format!("无法读取文件信息: {}", e))?;
-    let file_size = metadata.len();
-    let file_size_readable = if file_size < 1024 {
-        format!("{} B", file_size)
-    } else if file_size < 1024 * 1024 {
-        format!("{:.2} KB", file_size as f64 / 1024.0)
-    } else {
-        format!("{:.2} MB", file_size as f64 / (1024.0 * 1024.0))
-    };
+async fn package_channels(
+    app: tauri::AppHandle,
+    base_apk: String,
+    channel_list_path: String,
+    output_dir: String,
+    name_template: Option<String>,
+    format: Option<ChannelFormat>,
+) -> Result<BatchResult, String> {
+    use tauri::Emitter;
 
-    let file_name = path.file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or("unknown.apk")
-        .to_string();
-
-    // 打开 APK 文件(ZIP 格式)
-    let file = std::fs::File::open(&path)
-        .map_err(|e| format!("无法打开 APK 文件: {}", e))?;
-    let mut archive = ZipArchive::new(file)
-        .map_err(|e| format!("无法解析 APK 文件: {}", e))?;
-
-    let mut warnings = Vec::new();
-    let mut errors = Vec::new();
-    let mut signatures: std::collections::HashMap<String, SignatureInfo> = std::collections::HashMap::new();
-
-    // 检查 v1 签名 (JAR 签名)
-    let v1_files = [
-        "META-INF/MANIFEST.MF",
-        "META-INF/*.SF",
-        "META-INF/*.RSA",
-        "META-INF/*.DSA",
-        "META-INF/*.EC",
-    ];
-
-    let mut v1_present = false;
-    let mut v1_details = Vec::new();
-    for pattern in &v1_files {
-        if pattern.contains('*') {
-            let prefix = &pattern[..pattern.find('*').unwrap()];
-            let suffix = &pattern[pattern.find('*').unwrap() + 1..];
-            for name in archive.file_names() {
-                if name.starts_with(prefix) && name.ends_with(suffix) {
-                    v1_present = true;
-                    v1_details.push(name.to_string());
-                    break;
-                }
-            }
-        } else {
-            for name in archive.file_names() {
-                if name == *pattern {
-                    v1_present = true;
-                    v1_details.push(pattern.to_string());
-                    break;
+    tokio::task::spawn_blocking(move || {
+        channel_batch::package_channels(
+            Path::new(&base_apk),
+            Path::new(&channel_list_path),
+            Path::new(&output_dir),
+            name_template.as_deref(),
+            format,
+            |progress| {
+                if let Err(e) = app.emit("channel-batch-progress", progress) {
+                    log::warn!("发送进度事件失败: {}", e);
                 }
-            }
+            },
+        ).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
+
+// ==================== ZIP 完整性检查功能 ====================
+
+/// 检查 APK 的 ZIP 结构完整性和篡改痕迹
+#[tauri::command]
+async fn check_zip_integrity(file_path: String) -> Result<ZipIntegrityReport, String> {
+    tokio::task::spawn_blocking(move || {
+        zip_integrity::check_zip_integrity(Path::new(&file_path)).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
+
+// ==================== zipalign 功能 ====================
+
+/// 检查 APK 条目对齐（page_size 为 .so 对齐要求，默认 16 KB）
+#[tauri::command]
+async fn check_zipalign(file_path: String, page_size: Option<u64>) -> Result<AlignmentReport, String> {
+    tokio::task::spawn_blocking(move || {
+        zipalign::check_alignment(Path::new(&file_path), page_size).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
+
+/// 重新对齐 APK，会使 v2 及以上签名失效
+#[tauri::command]
+async fn zipalign_apk(file_path: String, output_path: String, page_size: Option<u64>) -> Result<AlignResult, String> {
+    tokio::task::spawn_blocking(move || {
+        zipalign::align_apk(Path::new(&file_path), Path::new(&output_path), page_size).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
+
+// ==================== AAR / JAR 库检查功能 ====================
+
+/// 检查 SDK 库文件（.aar / .jar）
+#[tauri::command]
+async fn inspect_library(file_path: String) -> Result<LibraryInfo, String> {
+    tokio::task::spawn_blocking(move || {
+        library::inspect_library(Path::new(&file_path)).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
+
+// ==================== 堆栈还原功能 ====================
+
+/// 使用 mapping.txt 还原混淆堆栈，提供 apk_path 时校验 pg-map-id
+#[tauri::command]
+async fn retrace_stack_trace(
+    mapping_path: String,
+    stack_trace: String,
+    apk_path: Option<String>,
+    cache: State<'_, MappingCache>,
+) -> Result<RetraceResult, String> {
+    // mapping 文件可能很大，解析结果按路径和修改时间缓存
+    let cache = cache.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        let mapping = cache.get_or_load(Path::new(&mapping_path))?;
+        retrace::retrace(&mapping, &stack_trace, apk_path.as_deref().map(Path::new)).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
+
+// ==================== 原生崩溃符号化功能 ====================
+
+/// 使用未裁剪的 .so 符号化 native backtrace，提供 apk_path 时校验 build-id
+#[tauri::command]
+async fn symbolicate_native(
+    backtrace: String,
+    symbol_dir: String,
+    apk_path: Option<String>,
+) -> Result<SymbolicateResult, String> {
+    tokio::task::spawn_blocking(move || {
+        symbolicate::symbolicate(&backtrace, Path::new(&symbol_dir), apk_path.as_deref().map(Path::new)).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
+
+// ==================== 设备兼容性检查功能 ====================
+
+/// 检查 APK / 拆分集合 / AAB 能否安装到指定设备，device_spec 为 JSON（兼容 bundletool device-spec.json）
+#[tauri::command]
+async fn check_device_compatibility(file_path: String, device_spec: String) -> Result<CompatibilityReport, String> {
+    tokio::task::spawn_blocking(move || {
+        device_compat::check_compatibility(Path::new(&file_path), &device_spec).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
+
+// ==================== AAB 生成 APK 功能 ====================
+
+/// 从 AAB 生成 APK，mode 为 device（按设备描述生成拆分 APK）或 universal
+#[tauri::command]
+async fn build_apks_from_bundle(
+    file_path: String,
+    output_dir: String,
+    mode: String,
+    device_spec: Option<String>,
+    sign_options: Option<SignOptions>,
+) -> Result<BuildApksResult, String> {
+    tokio::task::spawn_blocking(move || {
+        build_apks::build_apks(
+            Path::new(&file_path),
+            Path::new(&output_dir),
+            &mode,
+            device_spec.as_deref(),
+            sign_options.as_ref(),
+        ).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
+
+// ==================== Google Play 政策检查功能 ====================
+
+/// 按规则文件检查 APK 是否满足 Google Play 上架要求，rules_path 为空时使用内置规则，
+/// release_type 为空时按应用更新检查
+#[tauri::command]
+async fn check_play_policy(
+    file_path: String,
+    rules_path: Option<String>,
+    release_type: Option<ReleaseType>,
+) -> Result<PolicyReport, String> {
+    tokio::task::spawn_blocking(move || {
+        let rules = play_policy::load_rules(rules_path.as_deref().map(Path::new))?;
+        let info = parse_android_package_sync(&file_path)?;
+        if info.platform != "android" {
+            return Err("仅支持 Android APK".to_string());
         }
-        if v1_present { break; }
-    }
-
-    let v1_info = SignatureInfo {
-        version: "1".to_string(),
-        present: v1_present,
-        details: Some(if v1_present {
-            format!("找到签名文件: {}", v1_details.join(", "))
-        } else {
-            "未找到 v1 签名文件".to_string()
-        }),
-        certificate: None, // v1 签名证书解析较为复杂,暂不实现
-    };
-    signatures.insert("v1".to_string(), v1_info);
-
-    if !v1_present {
-        warnings.push("未检测到 v1 签名,可能无法在 Android 7.0 以下设备上安装".to_string());
-    }
-
-    // 检查 v2 签名 (APK Signature Scheme v2)
-    let v2_block_found = archive.by_name("META-INF/MANIFEST.MF").is_ok() ||
-                        archive.by_name("META-INF/SIGNATURE.RSA").is_ok() ||
-                        archive.by_name("META-INF/SIGNATURE.SF").is_ok();
-
-    // v2 签名在 APK 的签名块中,这里做简化检测
-    let v2_present = v2_block_found || archive.by_name("AndroidManifest.xml").is_ok();
-
-    let v2_info = SignatureInfo {
-        version: "2".to_string(),
-        present: v2_present,
-        details: Some(if v2_present {
-            "检测到 v2 签名特征".to_string()
-        } else {
-            "未检测到 v2 签名".to_string()
-        }),
-        certificate: None,
-    };
-    signatures.insert("v2".to_string(), v2_info);
-
-    if !v2_present {
-        warnings.push("未检测到 v2 签名,可能无法在 Android 7.0+ 设备上安装".to_string());
-    }
-
-    // 检查 v3 签名 (APK Signature Scheme v3)
-    // v3 签名同样在签名块中,这里做简化检测
-    let v3_present = v2_present; // 简化:如果有 v2 签名,很可能也有 v3
-
-    let v3_info = SignatureInfo {
-        version: "3".to_string(),
-        present: v3_present,
-        details: Some(if v3_present {
-            "检测到 v3 签名特征(基于签名块检测)".to_string()
-        } else {
-            "未检测到 v3 签名".to_string()
-        }),
-        certificate: None,
-    };
-    signatures.insert("v3".to_string(), v3_info);
-
-    if !v3_present {
-        warnings.push("未检测到 v3 签名,建议升级到 v3 签名以支持密钥轮换".to_string());
-    }
-
-    // 检查 v4 签名 (APK Signature Scheme v4)
-    // v4 签名使用流式验证,通常会有 V4Signature 文件
-    let v4_present = archive.by_name("apex_v1.vm").is_ok() || // APEX 相关
-                     archive.by_name("apex_info.pb").is_ok() || // APEX 相关
-                     v3_present; // 简化:如果有 v3,可能也有 v4
-
-    let v4_info = SignatureInfo {
-        version: "4".to_string(),
-        present: v4_present,
-        details: Some(if v4_present {
-            "检测到 v4 签名特征(流式验证)".to_string()
-        } else {
-            "未检测到 v4 签名".to_string()
-        }),
-        certificate: None,
-    };
-    signatures.insert("v4".to_string(), v4_info);
-
-    if !v4_present {
-        warnings.push("未检测到 v4 签名,Android 11+ 设备将使用完整验证".to_string());
-    }
-
-    // 生成总体建议
-    if !v1_present && !v2_present && !v3_present {
-        errors.push("APK 未签名!这是一个严重的错误,应用将无法安装".to_string());
-    }
-
-    Ok(ApkSignatureResult {
-        file_name,
-        file_size: file_size_readable,
-        signatures,
-        warnings,
-        errors,
+        play_policy::check_policy(&info, Path::new(&file_path), &rules, release_type.unwrap_or_default()).map_err(|e| e.to_string())
     })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
+
+// ==================== 国内应用市场合规检查功能 ====================
+
+/// 按工信部和国内应用市场要求检查 APK，生成可附在上架材料中的自查报告，profile_path 为空时使用内置规则
+#[tauri::command]
+async fn check_store_compliance(
+    file_path: String,
+    profile_path: Option<String>,
+) -> Result<ComplianceReport, String> {
+    tokio::task::spawn_blocking(move || {
+        let profile = store_compliance::load_profile(profile_path.as_deref().map(Path::new))?;
+        let info = parse_android_package_sync(&file_path)?;
+        if info.platform != "android" {
+            return Err("仅支持 Android APK".to_string());
+        }
+        store_compliance::check_compliance(&info, Path::new(&file_path), &profile).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
+
+// ==================== SBOM 生成功能 ====================
+
+/// 生成 APK 的软件物料清单（CycloneDX / SPDX JSON），output_path 不为空时写入文件
+#[tauri::command]
+async fn generate_sbom(file_path: String, format: String, output_path: Option<String>) -> Result<SbomResult, String> {
+    tokio::task::spawn_blocking(move || {
+        let info = parse_android_package_sync(&file_path)?;
+        if info.platform != "android" {
+            return Err("仅支持 Android APK".to_string());
+        }
+        sbom::generate_sbom(&info, Path::new(&file_path), &format, output_path.as_deref().map(Path::new)).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
+
+// ==================== 离线漏洞匹配功能 ====================
+
+/// 导入 OSV 漏洞数据（all.zip、目录或 JSON 文件）到本地数据库
+#[tauri::command]
+async fn import_advisories(source_paths: Vec<String>, database_path: String) -> Result<ImportResult, String> {
+    tokio::task::spawn_blocking(move || {
+        let sources: Vec<&Path> = source_paths.iter().map(Path::new).collect();
+        advisory::import_advisories(&sources, Path::new(&database_path)).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("任务执行失败: {}", e))?
+}
+
+/// 用本地漏洞数据库检查 APK 中的 Maven 库和原生库组件
+#[tauri::command]
+async fn scan_vulnerabilities(file_path: String, database_path: String) -> Result<VulnerabilityReport, String> {
+    tokio::task::spawn_blocking(move || {
+        advisory::scan(Path::new(&file_path), Path::new(&database_path)).map_err(|e| e.to_string())
+    })
+        .await
+        .map_err(|e| format!("任务执行失败: {}", e))?
 }
 
 // ========== 认证相关命令 ==========
@@ -1450,6 +568,7 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .manage(store)
+        .manage(MappingCache::default())
         .invoke_handler(tauri::generate_handler![
             greet,
             get_file_info,
@@ -1461,6 +580,27 @@ pub fn run() {
             generate_uuids,
             generate_passwords,
             verify_apk_signature,
+            audit_manifest,
+            classify_permissions,
+            analyze_network_config,
+            scan_secrets,
+            read_channel,
+            write_channel,
+            package_channels,
+            check_zipalign,
+            zipalign_apk,
+            check_zip_integrity,
+            edit_manifest,
+            inspect_library,
+            retrace_stack_trace,
+            symbolicate_native,
+            check_device_compatibility,
+            build_apks_from_bundle,
+            check_play_policy,
+            check_store_compliance,
+            generate_sbom,
+            import_advisories,
+            scan_vulnerabilities,
             set_store,
             get_store,
             delete_store,
@@ -32,11 +32,11 @@ export function PackageParsePage() {
       const selected = await open({
         multiple: false,
         directory: false,
-        title: "选择 APK/AAB/XAPK 文件",
+        title: "选择安装包文件",
         filters: [
           {
-            name: "Android Package",
-            extensions: ["apk", "aab", "xapk"],
+            name: "App Package",
+            extensions: ["apk", "aab", "xapk", "ipa", "hap", "app"],
           },
         ],
       });